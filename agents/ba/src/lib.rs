@@ -1,7 +1,8 @@
 //! # Buyer Agent (BA) — the book-buy conversation (mobile / wasm)
 //!
-//! BA drives the whole purchase as a small state machine: find a seller (DF),
-//! resolve it (AMS), browse (BS), reserve payment (PA), and take delivery. It is
+//! BA drives the whole purchase as a small state machine: find the sellers (DF),
+//! put the book out to tender among them (a `fipa-contract-net`, lowest price
+//! wins), resolve the winner (AMS), reserve payment (PA), and take delivery. It is
 //! the **mobile** agent — written once here and compiled to **wasm32**
 //! (sandboxed, migratable) via [`unl_agent::export_agent!`]; the same code also
 //! builds native (rlib) so a node can run it in-process.
@@ -9,49 +10,52 @@
 //! Conversation (replies dispatched by the UNL verb + BA's state):
 //! ```text
 //! start    → seek bookselling → df
-//! provide  → locate <seller>  → ams
-//! at       → catalog          → <seller>
-//! catalog  → reserve LtG       → pa
+//! provide  → cfp LtG          → every seller (contract-net)
+//! awarded  → locate <winner>  → ams
+//! at       → reserve LtG       → pa   (at the winning bid's price)
 //! receipt(held)  → await delivery
 //! deliver  → result: bought
-//! deny / empty → result: failed (with the reason)
+//! deny / empty / no bid → result: failed (with the reason)
 //! ```
 //! BA emits its final verdict to `result` (a non-agent), where the node picks it
-//! up. BA never needs `ctx.from()` — it addresses fixed roles and the seller it
-//! learned from DF — so it works even before wasm sender-threading lands.
+//! up. The tender closes once every seller has bid or refused, or after
+//! [`TENDER_MS`] on the conversation clock, whichever is first.
 
-use serde::Deserialize;
+use serde_json::json;
 use unl_agent::{Agent, Ctx};
 use unl_core::{NodeRef, Uci};
+use unl_fipa::{lowest_price, ContractNet, Conversations, Outcome};
 use unl_parser::parse_sentence;
 
+/// How long the tender waits for the sellers' bids.
+pub const TENDER_MS: u64 = 2_000;
+
+/// Timer id of the conversation clock.
+const CONVERSATIONS: u64 = 1;
+
 #[derive(Clone, Copy, PartialEq)]
 enum St {
     Init,
     Provider,
+    Tender,
     Address,
-    Catalog,
     Held,
     Delivery,
     Done,
     Failed,
 }
 
-#[derive(Deserialize)]
-struct Book {
-    title: String,
-    price: u64,
-}
-
 /// The buyer agent.
 pub struct Buyer {
     st: St,
     seller: String,
+    price: u64,
+    convs: Conversations,
 }
 
 impl Default for Buyer {
     fn default() -> Self {
-        Buyer { st: St::Init, seller: String::new() }
+        Buyer { st: St::Init, seller: String::new(), price: 0, convs: Conversations::new(CONVERSATIONS) }
     }
 }
 
@@ -64,10 +68,32 @@ impl Buyer {
         ctx.send("result", "obj(failed, x)", why.as_bytes().to_vec());
         self.st = St::Failed;
     }
+
+    /// The tender closed: go after the winner, or give up.
+    fn awarded(&mut self, outcome: Outcome, ctx: &mut Ctx) {
+        if self.st != St::Tender {
+            return;
+        }
+        match outcome.result {
+            Ok(award) => {
+                self.seller = award["winner"].as_str().unwrap_or_default().to_string();
+                self.price = award["bid"]["price"].as_u64().unwrap_or_default();
+                // the seller is a UUID → ask AMS with it in the body.
+                let q = json!({ "agent": self.seller });
+                ctx.send("ams", "obj(locate, agent)", serde_json::to_vec(&q).unwrap());
+                self.st = St::Address;
+            }
+            Err(why) if why == "no-proposals" => self.fail(ctx, "book-not-found"),
+            Err(why) => self.fail(ctx, &why),
+        }
+    }
 }
 
 impl Agent for Buyer {
     fn on_message(&mut self, unl: &str, body: &[u8], ctx: &mut Ctx) {
+        if let Some(done) = self.convs.on_message(unl, body, &mut (), ctx) {
+            return done.into_iter().for_each(|o| self.awarded(o, ctx));
+        }
         let Some((verb, _subject)) = verb_subject(unl) else { return };
 
         // Any deny aborts the purchase, whatever state we're in.
@@ -83,35 +109,23 @@ impl Agent for Buyer {
             }
             (St::Provider, "provide") => {
                 let providers: Vec<String> = serde_json::from_slice(body).unwrap_or_default();
-                match providers.first() {
-                    Some(p) => {
-                        self.seller = p.clone();
-                        // the seller is a UUID → ask AMS with it in the body.
-                        let q = serde_json::json!({ "agent": p });
-                        ctx.send("ams", "obj(locate, agent)", serde_json::to_vec(&q).unwrap());
-                        self.st = St::Address;
-                    }
-                    None => self.fail(ctx, "no-provider"),
+                if providers.is_empty() {
+                    return self.fail(ctx, "no-provider");
                 }
+                let tender = ContractNet::new(providers, "LtG", json!({ "title": "LtG" }), TENDER_MS, lowest_price);
+                let tender = tender.award_only(); // the sale itself settles through PA
+                self.st = St::Tender;
+                let (_, done) = self.convs.start(tender.pid(), Box::new(tender), ctx);
+                done.into_iter().for_each(|o| self.awarded(o, ctx));
             }
             (St::Address, "at") => match jfield(body, "address") {
                 Some(_addr) => {
-                    ctx.send(&self.seller, "obj(catalog, systemdynamics)", vec![]);
-                    self.st = St::Catalog;
+                    let terms = json!({ "seller": self.seller, "amount": self.price });
+                    ctx.send("pa", "obj(reserve, LtG)", serde_json::to_vec(&terms).unwrap());
+                    self.st = St::Held;
                 }
                 None => self.fail(ctx, "no-address"),
             },
-            (St::Catalog, "catalog") => {
-                let books: Vec<Book> = serde_json::from_slice(body).unwrap_or_default();
-                match books.iter().find(|b| b.title == "LtG") {
-                    Some(b) => {
-                        let terms = serde_json::json!({ "seller": self.seller, "amount": b.price });
-                        ctx.send("pa", "obj(reserve, LtG)", serde_json::to_vec(&terms).unwrap());
-                        self.st = St::Held;
-                    }
-                    None => self.fail(ctx, "book-not-found"),
-                }
-            }
             (St::Held, "receipt") => {
                 if jstatus(body) == "held" {
                     self.st = St::Delivery; // payment secured; await the book
@@ -124,6 +138,12 @@ impl Agent for Buyer {
             _ => {}
         }
     }
+
+    fn on_tick(&mut self, timer_id: u64, now_ms: u64, ctx: &mut Ctx) {
+        if let Some(done) = self.convs.on_tick(timer_id, now_ms, &mut (), ctx) {
+            done.into_iter().for_each(|o| self.awarded(o, ctx));
+        }
+    }
 }
 
 fn verb_subject(unl: &str) -> Option<(String, String)> {
@@ -176,4 +196,54 @@ mod tests {
         assert_eq!(out[0].to, "result");
         assert_eq!(out[0].body, b"insufficient");
     }
+
+    /// Feed `unl`/`body` from `from`; what BA sent.
+    fn hear(ba: &mut Buyer, from: &str, unl: &str, body: &[u8]) -> Vec<unl_agent::Outgoing> {
+        let mut ctx = Ctx::new();
+        ctx.set_from(from);
+        ba.on_message(unl, body, &mut ctx);
+        ctx.take()
+    }
+
+    /// A seller's answer to `cfp`: `price` bid, or refused.
+    fn bid(cfp: &unl_agent::Outgoing, price: Option<u64>) -> (String, Vec<u8>) {
+        let (acl, _) = unl_fipa::Acl::split(&cfp.body).unwrap();
+        match price {
+            Some(p) => ("obj(propose, LtG)".into(), acl.reply("propose").join(json!({ "bid": { "price": p } }))),
+            None => ("obj(refuse, LtG)".into(), acl.reply("refuse").join(json!({ "reason": "book-not-found" }))),
+        }
+    }
+
+    #[test]
+    fn the_cheapest_seller_wins_the_tender() {
+        let mut ba = Buyer::new();
+        hear(&mut ba, "boot", "obj(start, buy)", b"");
+        let cfps = hear(&mut ba, "df", "obj(provide, bookselling)", br#"["s1","s2","s3"]"#);
+        assert_eq!(cfps.iter().map(|m| m.to.as_str()).collect::<Vec<_>>(), ["s1", "s2", "s3"]);
+        assert!(cfps.iter().all(|m| m.unl == "obj(cfp, LtG)"));
+        for (seller, price) in [("s1", Some(1_200)), ("s3", None)] {
+            let (unl, body) = bid(&cfps[if seller == "s1" { 0 } else { 2 }], price);
+            assert!(hear(&mut ba, seller, &unl, &body).is_empty());
+        }
+        let (unl, body) = bid(&cfps[1], Some(999));
+        let out = hear(&mut ba, "s2", &unl, &body);
+        let sent: Vec<_> = out.iter().map(|m| (m.to.as_str(), m.unl.as_str())).collect();
+        assert_eq!(
+            sent,
+            [("s1", "obj(reject, LtG)"), ("s2", "obj(accept, LtG)"), ("ams", "obj(locate, agent)")]
+        );
+        assert_eq!(out[2].body, br#"{"agent":"s2"}"#);
+        let out = hear(&mut ba, "ams", "obj(at, agent)", br#"{"address":"127.0.0.1:9001"}"#);
+        assert_eq!(out[0].body, br#"{"amount":999,"seller":"s2"}"#);
+    }
+
+    #[test]
+    fn no_bid_means_the_book_was_not_found() {
+        let mut ba = Buyer::new();
+        hear(&mut ba, "boot", "obj(start, buy)", b"");
+        let cfps = hear(&mut ba, "df", "obj(provide, bookselling)", br#"["s1"]"#);
+        let (unl, body) = bid(&cfps[0], None);
+        let out = hear(&mut ba, "s1", &unl, &body);
+        assert_eq!((out[0].to.as_str(), out[0].body.as_slice()), ("result", b"book-not-found".as_slice()));
+    }
 }
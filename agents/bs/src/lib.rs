@@ -1,12 +1,14 @@
 //! # Seller Agent (BS) — catalog + fulfilment around PA's escrow
 //!
-//! BS answers catalog queries, and around a purchase mirrors PA's escrow: on
-//! PA's `held` notice it reserves the book and `accept`s payment; on `paid` it
-//! ships to the buyer; on `cancelled` it releases the reservation.
+//! BS answers catalog queries, bids in buyers' tenders, and around a purchase
+//! mirrors PA's escrow: on PA's `held` notice it reserves the book and `accept`s
+//! payment; on `paid` it ships to the buyer; on `cancelled` it releases the
+//! reservation.
 //!
 //! | in `unl` / `body` | BS does |
 //! |---|---|
 //! | `obj(catalog, <topic>)` | reply `obj(catalog, <topic>)` + `[{title,price}…]` to the asker |
+//! | `obj(cfp, <title>)` `{_acl, task:{title}}` | `propose {bid:{price}}`, or `refuse` if not stocked (contract-net) |
 //! | `obj(receipt, <order>)` `{status:"held", buyer}` | reserve; `obj(accept, <order>)` → pa |
 //! | `obj(receipt, <order>)` `{status:"paid"}` | `obj(deliver, <order>)` → buyer (ship) |
 //! | `obj(receipt, <order>)` `{status:"cancelled"}` | release the reservation |
@@ -16,20 +18,57 @@
 
 use std::collections::BTreeMap;
 
+use serde_json::{json, Value};
 use unl_agent::{Agent, Ctx};
 use unl_core::{NodeRef, Uci};
+use unl_fipa::{Contractor, Conversations, Msg, Participant, Refusal, FIPA_CONTRACT_NET};
 use unl_parser::parse_sentence;
 
+/// Timer id of the conversation clock.
+const CONVERSATIONS: u64 = 1;
+
+/// The books on offer.
+struct Shelf {
+    has_ltg: bool,
+}
+
+impl Shelf {
+    fn catalog(&self) -> Value {
+        if self.has_ltg {
+            json!([{"title":"LtG","price":999},{"title":"Other","price":500}])
+        } else {
+            json!([{"title":"Other","price":500}])
+        }
+    }
+}
+
+impl Contractor for Shelf {
+    fn propose(&mut self, cfp: &Msg) -> Result<Value, Refusal> {
+        let title = cfp.content["task"]["title"].as_str().unwrap_or_default();
+        let books = self.catalog();
+        let book = books.as_array().into_iter().flatten().find(|b| b["title"] == title);
+        book.map(|b| json!({ "price": b["price"] })).ok_or_else(|| Refusal::Refuse("book-not-found".into()))
+    }
+
+    /// Winning only clears the way; the sale itself goes through PA's escrow.
+    fn perform(&mut self, _accepted: &Msg, bid: &Value) -> Result<Value, String> {
+        Ok(bid.clone())
+    }
+}
+
 /// The seller. `has_ltg` controls whether "Limits to Growth" is in the catalog
 /// (so a node can simulate a seller that lacks the book).
 pub struct Seller {
-    has_ltg: bool,
+    shelf: Shelf,
+    convs: Conversations<Shelf>,
     buyers: BTreeMap<String, String>, // order -> buyer id
 }
 
 impl Seller {
     pub fn new(has_ltg: bool) -> Self {
-        Seller { has_ltg, buyers: BTreeMap::new() }
+        let mut convs = Conversations::new(CONVERSATIONS);
+        convs.respond(FIPA_CONTRACT_NET, Participant::factory());
+        Seller { shelf: Shelf { has_ltg }, convs, buyers: BTreeMap::new() }
     }
 }
 
@@ -41,16 +80,14 @@ impl Default for Seller {
 
 impl Agent for Seller {
     fn on_message(&mut self, unl: &str, body: &[u8], ctx: &mut Ctx) {
+        if self.convs.on_message(unl, body, &mut self.shelf, ctx).is_some() {
+            return; // a tender: the participant FSM answered
+        }
         let Some((verb, subject)) = verb_subject(unl) else { return };
         match verb.as_str() {
             "catalog" => {
-                let books = if self.has_ltg {
-                    serde_json::json!([{"title":"LtG","price":999},{"title":"Other","price":500}])
-                } else {
-                    serde_json::json!([{"title":"Other","price":500}])
-                };
                 let from = ctx.from().to_string();
-                ctx.send(from, "obj(catalog, systemdynamics)", serde_json::to_vec(&books).unwrap());
+                ctx.send(from, "obj(catalog, systemdynamics)", serde_json::to_vec(&self.shelf.catalog()).unwrap());
             }
             "receipt" => match jstatus(body).as_str() {
                 "held" => {
@@ -72,6 +109,10 @@ impl Agent for Seller {
             _ => {}
         }
     }
+
+    fn on_tick(&mut self, timer_id: u64, now_ms: u64, ctx: &mut Ctx) {
+        self.convs.on_tick(timer_id, now_ms, &mut self.shelf, ctx);
+    }
 }
 
 fn verb_subject(unl: &str) -> Option<(String, String)> {
@@ -114,6 +155,17 @@ mod tests {
         assert!(String::from_utf8_lossy(&out[0].body).contains("LtG"));
     }
 
+    #[test]
+    fn a_cfp_is_bid_on_or_refused() {
+        let cfp = unl_fipa::Acl::new("c1", FIPA_CONTRACT_NET, "cfp").join(json!({ "task": { "title": "LtG" } }));
+        let out = run(&mut Seller::new(true), "BA", "obj(cfp, LtG)", &cfp);
+        assert_eq!((out[0].to.as_str(), out[0].unl.as_str()), ("BA", "obj(propose, LtG)"));
+        let (_, content) = unl_fipa::Acl::split(&out[0].body).unwrap();
+        assert_eq!(content, json!({ "bid": { "price": 999 } }));
+        let out = run(&mut Seller::new(false), "BA", "obj(cfp, LtG)", &cfp);
+        assert_eq!(out[0].unl, "obj(refuse, LtG)");
+    }
+
     #[test]
     fn held_reserves_and_accepts() {
         let mut bs = Seller::new(true);
@@ -10,7 +10,7 @@ use crate::actor::messages::*;
 use crate::observability::{record_agent_spawned, record_agent_stopped, record_message_received};
 use crate::proto;
 use crate::wasm::WasmRuntime;
-use crate::protocol::ProtocolStateMachine;
+use crate::protocol::{ProtocolConfig, ProtocolStateMachine};
 
 /// Actor wrapping a WASM agent instance
 pub struct AgentActor {
@@ -23,6 +23,9 @@ pub struct AgentActor {
     /// Active protocol conversations
     conversations: HashMap<String, Box<dyn ProtocolStateMachine>>,
 
+    /// Node settings the conversations are built with
+    protocols: ProtocolConfig,
+
     /// Incoming message queue
     mailbox: VecDeque<proto::AclMessage>,
 
@@ -81,6 +84,7 @@ impl AgentActor {
             agent_id: config.id,
             runtime,
             conversations: HashMap::new(),
+            protocols: ProtocolConfig::default(),
             mailbox: VecDeque::new(),
             capabilities: config.capabilities,
             supervisor: None,
@@ -135,6 +139,12 @@ impl AgentActor {
         self
     }
 
+    /// Build conversations under the node's protocol settings (lease ceilings).
+    pub fn with_protocol_config(mut self, protocols: ProtocolConfig) -> Self {
+        self.protocols = protocols;
+        self
+    }
+
     /// Provide the startup seed (the agent's own UNL + DATA blocks). When
     /// non-empty, the agent is `config()`'d once with it after init.
     pub fn with_seed(mut self, unl: Vec<u8>, data: Vec<u8>) -> Self {
@@ -272,6 +282,18 @@ impl AgentActor {
         Ok(())
     }
 
+    /// Give every conversation its clock step (subscription leases expire or
+    /// renew).
+    fn poll_conversations(&mut self, ctx: &mut Context<Self>) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let results: Vec<_> = self.conversations.values_mut().map(|c| c.poll(now)).collect();
+        for result in results {
+            if let Err(e) = self.handle_protocol_result(result, ctx) {
+                warn!(agent = %self.agent_id.name, "conversation poll failed: {}", e);
+            }
+        }
+    }
+
     /// Send an ACL message. Routes through the supervisor, which delivers to
     /// local agents and forwards non-local receivers to the network. Falls back
     /// to the network directly when no supervisor is attached.
@@ -384,6 +406,7 @@ impl Actor for AgentActor {
 
             // Process pending messages
             actor.process_mailbox(ctx);
+            actor.poll_conversations(ctx);
 
             // Call agent run tick
             match actor.call_run_tick() {
@@ -568,7 +591,7 @@ impl Handler<StartConversation> for AgentActor {
         let conv_id = uuid::Uuid::new_v4().to_string();
 
         // Create protocol state machine
-        let state_machine = crate::protocol::create_state_machine(msg.protocol)
+        let state_machine = crate::protocol::create_state_machine_with(msg.protocol, &self.protocols)
             .map_err(|e| AgentError::InvalidState(e.to_string()))?;
 
         self.conversations.insert(conv_id.clone(), state_machine);
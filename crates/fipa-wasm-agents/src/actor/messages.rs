@@ -324,6 +324,17 @@ pub struct AgentStatus {
     pub memory_used: usize,
 }
 
+impl AgentStatus {
+    /// The load this agent puts on its node
+    pub fn load(&self) -> proto::LoadMetrics {
+        proto::LoadMetrics {
+            active_conversations: self.active_conversations,
+            memory_usage_bytes: self.memory_used,
+            ..Default::default()
+        }
+    }
+}
+
 impl<A, M> actix::dev::MessageResponse<A, M> for AgentStatus
 where
     A: actix::Actor,
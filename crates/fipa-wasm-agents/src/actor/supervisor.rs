@@ -12,6 +12,7 @@ use crate::content::block::{BlockFile, TAG_DATA, TAG_UNL, TAG_WASM};
 use crate::content::unl::{vocabulary_from_bundle, UnlPackager, UnlVerifier, VocabRegistry};
 use crate::content::verify::{ContentVerifier, OutboundPackager};
 use crate::proto;
+use crate::protocol::ProtocolConfig;
 use crate::wasm::WasmRuntime;
 
 /// Supervisor actor managing agent lifecycle
@@ -40,6 +41,9 @@ pub struct Supervisor {
     /// Outbound packager shared by spawned agents (validate + package sends).
     outbound: Arc<dyn OutboundPackager>,
 
+    /// Node protocol settings spawned agents build their conversations with.
+    protocols: ProtocolConfig,
+
     /// Node ID for this supervisor
     node_id: String,
 }
@@ -79,6 +83,7 @@ impl Supervisor {
             agent_verifiers: HashMap::new(),
             vocab_registry,
             outbound,
+            protocols: ProtocolConfig::default(),
             node_id,
         }
     }
@@ -95,6 +100,12 @@ impl Supervisor {
         self
     }
 
+    /// Set the protocol settings (e.g. the subscription lease ceiling) for spawned agents.
+    pub fn with_protocol_config(mut self, protocols: ProtocolConfig) -> Self {
+        self.protocols = protocols;
+        self
+    }
+
     /// Set the default content verifier applied to spawned agents.
     pub fn with_content_verifier(mut self, verifier: Arc<dyn ContentVerifier>) -> Self {
         self.default_verifier = Some(verifier);
@@ -180,7 +191,8 @@ impl Supervisor {
 
         // Create and start actor
         let mut actor = AgentActor::new(config.clone(), runtime)
-            .with_supervisor(ctx.address());
+            .with_supervisor(ctx.address())
+            .with_protocol_config(self.protocols.clone());
 
         if let Some(network) = &self.network {
             actor = actor.with_network(network.clone());
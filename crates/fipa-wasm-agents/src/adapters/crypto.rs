@@ -10,6 +10,7 @@ use std::path::Path;
 
 use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 use rand::RngCore;
+use sha2::{Digest, Sha256};
 
 /// A node's Ed25519 keypair. Secret-side only.
 #[derive(Clone)]
@@ -54,6 +55,12 @@ impl NodeCrypto {
         self.key.sign(msg).to_bytes()
     }
 
+    /// A one-way subkey for `tag` (SHA-256 over the tag and the seed): a MAC key
+    /// for node-private data, such as the module cache. The seed stays here.
+    pub fn derive(&self, tag: &[u8]) -> [u8; 32] {
+        Sha256::new().chain_update(tag).chain_update(self.key.to_bytes()).finalize().into()
+    }
+
     /// A fresh 16-byte anti-replay nonce.
     pub fn nonce(&self) -> [u8; 16] {
         let mut n = [0u8; 16];
@@ -102,6 +109,14 @@ mod tests {
         assert!(!verify(&other.public_key(), b"hello", &sig)); // wrong key
     }
 
+    #[test]
+    fn derived_keys_are_per_tag_and_per_node() {
+        let k = NodeCrypto::generate();
+        assert_eq!(k.derive(b"a"), k.derive(b"a"));
+        assert_ne!(k.derive(b"a"), k.derive(b"b"));
+        assert_ne!(k.derive(b"a"), NodeCrypto::generate().derive(b"a"));
+    }
+
     #[test]
     fn seed_persists_across_load() {
         let dir = std::env::temp_dir().join(format!("nodekey-{}", std::process::id()));
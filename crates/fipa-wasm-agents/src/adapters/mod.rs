@@ -70,6 +70,8 @@ pub trait StateStore {
     fn get(&self, ns: &str, key: &str) -> Result<Option<Vec<u8>>>;
     fn put(&self, ns: &str, key: &str, val: &[u8]) -> Result<()>;
     fn del(&self, ns: &str, key: &str) -> Result<()>;
+    /// `(key, value_len)` of every entry in `ns` whose key starts with `prefix`.
+    fn scan(&self, ns: &str, prefix: &str) -> Result<Vec<(String, u64)>>;
 }
 
 #[cfg(test)]
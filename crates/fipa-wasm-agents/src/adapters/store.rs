@@ -44,6 +44,15 @@ impl StateStore for SledStore {
         self.db.flush()?;
         Ok(())
     }
+    fn scan(&self, ns: &str, prefix: &str) -> Result<Vec<(String, u64)>> {
+        let head = Self::scoped(ns, "").len();
+        let mut out = Vec::new();
+        for kv in self.db.scan_prefix(Self::scoped(ns, prefix)) {
+            let (k, v) = kv?;
+            out.push((String::from_utf8_lossy(&k[head..]).into_owned(), v.len() as u64));
+        }
+        Ok(out)
+    }
 }
 
 #[cfg(test)]
@@ -74,4 +83,17 @@ mod tests {
         assert_eq!(s.get("", "ax").unwrap(), None);
         assert_eq!(s.get("ax", "").unwrap(), None);
     }
+
+    #[test]
+    fn scan_lists_only_the_namespace() {
+        let s = temp_store();
+        s.put("a", "cfg/x", b"12").unwrap();
+        s.put("a", "cfg/y", b"3").unwrap();
+        s.put("a", "other", b"").unwrap();
+        s.put("ab", "cfg/z", b"4").unwrap(); // a longer ns sharing the prefix
+        let mut cfg = s.scan("a", "cfg/").unwrap();
+        cfg.sort();
+        assert_eq!(cfg, vec![("cfg/x".into(), 2), ("cfg/y".into(), 1)]);
+        assert_eq!(s.scan("a", "").unwrap().len(), 3);
+    }
 }
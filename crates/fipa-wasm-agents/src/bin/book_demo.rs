@@ -12,14 +12,13 @@ use fipa_wasm_agents::identity::{AgentId, Header};
 use fipa_wasm_agents::process::Router;
 use fipa_wasm_agents::proto;
 use fipa_wasm_agents::wasm::{AgentRuntime, NativeRuntime, WasmRuntime};
-use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use unl_agent::{Agent, Ctx};
 use unl_core::{NodeRef, Uci};
 use unl_parser::parse_sentence;
 use uuid::Uuid;
 
-// ── message helpers (for BS + reading BA's verdict) ─────────────────────
+// ── message helpers (for reading BA's verdict) ──────────────────────────
 
 fn verb_subject(unl: &str) -> Option<(String, String)> {
     let g = parse_sentence(unl).ok()?;
@@ -34,59 +33,6 @@ fn word(n: &NodeRef) -> Option<String> {
     }
     None
 }
-fn jstatus(body: &[u8]) -> String {
-    jfield(body, "status").unwrap_or_default()
-}
-fn jfield(body: &[u8], key: &str) -> Option<String> {
-    let v: serde_json::Value = serde_json::from_slice(body).ok()?;
-    v.get(key)?.as_str().map(str::to_string)
-}
-
-// ── BS: the seller (native) ─────────────────────────────────────────────
-
-struct Seller {
-    has_ltg: bool,
-    buyers: BTreeMap<String, String>, // order -> buyer
-}
-impl Seller {
-    fn new(has_ltg: bool) -> Self {
-        Seller { has_ltg, buyers: BTreeMap::new() }
-    }
-}
-impl Agent for Seller {
-    fn on_message(&mut self, unl: &str, body: &[u8], ctx: &mut Ctx) {
-        let Some((verb, subject)) = verb_subject(unl) else { return };
-        match verb.as_str() {
-            "catalog" => {
-                let books = if self.has_ltg {
-                    serde_json::json!([{"title":"LtG","price":999},{"title":"Other","price":500}])
-                } else {
-                    serde_json::json!([{"title":"Other","price":500}])
-                };
-                let from = ctx.from().to_string();
-                ctx.send(from, "obj(catalog, systemdynamics)", serde_json::to_vec(&books).unwrap());
-            }
-            "receipt" => match jstatus(body).as_str() {
-                "held" => {
-                    if let Some(buyer) = jfield(body, "buyer") {
-                        self.buyers.insert(subject.clone(), buyer);
-                        ctx.send("pa", format!("obj(accept, {subject})"), vec![]);
-                    }
-                }
-                "paid" => {
-                    if let Some(buyer) = self.buyers.get(&subject) {
-                        ctx.send(buyer.clone(), format!("obj(deliver, {subject})"), vec![]);
-                    }
-                }
-                "cancelled" => {
-                    self.buyers.remove(&subject);
-                }
-                _ => {}
-            },
-            _ => {}
-        }
-    }
-}
 
 // ── BA: load the wasm agent (fallback to native) ────────────────────────
 
@@ -157,7 +103,7 @@ fn run(s: &Scenario) -> String {
     r.add(df.id(), Box::new(NativeRuntime::new(df_agent)));
     r.add(ams.id(), Box::new(NativeRuntime::new(ams_agent)));
     r.add(pa.id(), Box::new(NativeRuntime::new(pa_agent)));
-    r.add(seller.id(), Box::new(NativeRuntime::new(Seller::new(s.bs_has_book))));
+    r.add(seller.id(), Box::new(NativeRuntime::new(bs_agent::Seller::new(s.bs_has_book))));
     r.add(ba.id(), ba_runtime()); // ← the buyer, as wasm (or native fallback)
 
     r.send("boot", &ba.id(), b"obj(start, buy)", b"");
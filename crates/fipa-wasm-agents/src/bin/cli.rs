@@ -18,12 +18,18 @@
 //!
 //! # Search for services
 //! fipa-cli services search calculator
+//!
+//! # Replay a recorded agent trace offline
+//! fipa-cli replay ./agent.wasm ./traces/<uuid>.<code>.trace
+//!
+//! # Check a sniffer trace against its interaction protocols
+//! fipa-cli conform ./trace.json
 //! ```
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use colored::Colorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tonic::transport::Channel;
 
@@ -93,6 +99,25 @@ enum Commands {
 
     /// Interactive shell mode
     Shell,
+
+    /// Replay a recorded agent trace against its module, offline
+    Replay {
+        /// Path to the WASM module the trace was recorded against
+        wasm: PathBuf,
+
+        /// Path to the trace (a node's `FIPA_RECORD` directory holds `<uuid>.<code>.trace`)
+        trace: PathBuf,
+
+        /// Replay on the wasmi interpreter (IoT profile) instead of wasmtime
+        #[arg(long)]
+        iot: bool,
+    },
+
+    /// Check a recorded message trace against the protocols it claims
+    Conform {
+        /// Path to a sniffer trace exported as JSON
+        trace: PathBuf,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -294,6 +319,8 @@ async fn main() -> Result<()> {
         Commands::Services(cmd) => cmd_services(&args, cmd).await,
         Commands::Messages(cmd) => cmd_messages(&args, cmd).await,
         Commands::Nodes(cmd) => cmd_nodes(&args, cmd).await,
+        Commands::Replay { wasm, trace, iot } => cmd_replay(&args, wasm, trace, *iot),
+        Commands::Conform { trace } => cmd_conform(&args, trace),
     }
 }
 
@@ -316,6 +343,75 @@ async fn connect(node: &str, timeout: u64) -> Result<FipaAgentServiceClient<Chan
 // Command Handlers
 // =============================================================================
 
+fn cmd_replay(args: &Args, wasm: &Path, trace: &Path, iot: bool) -> Result<()> {
+    use fipa_wasm_agents::wasm::{replay, WasmiEngine, WasmtimeEngine};
+
+    let code = std::fs::read(wasm).with_context(|| format!("read {}", wasm.display()))?;
+    let file = std::fs::File::open(trace).with_context(|| format!("open {}", trace.display()))?;
+    let trace = std::io::BufReader::new(file);
+    let outcome = if iot {
+        replay(&WasmiEngine, &code, trace)?
+    } else {
+        replay(&WasmtimeEngine, &code, trace)?
+    };
+
+    if args.format == OutputFormat::Json {
+        let divergence = outcome.divergence.as_ref().map(|d| {
+            serde_json::json!({ "step": d.step, "input": d.input, "reason": d.reason })
+        });
+        println!("{}", serde_json::json!({ "steps": outcome.steps, "divergence": divergence }));
+    } else {
+        match &outcome.divergence {
+            None => println!("{} {} steps reproduced", "✓".green(), outcome.steps),
+            Some(d) => {
+                println!("{} diverged at step {}: {}", "✗".red(), d.step, d.reason);
+                println!("  {} {:?}", "Input:".bold(), d.input);
+            }
+        }
+    }
+    if outcome.divergence.is_some() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn cmd_conform(args: &Args, trace: &Path) -> Result<()> {
+    use fipa_wasm_agents::tools::{check_trace, MessageTrace};
+
+    let json = std::fs::read_to_string(trace).with_context(|| format!("read {}", trace.display()))?;
+    let trace = MessageTrace::from_json(&json).context("Failed to parse trace")?;
+    let report = check_trace(&trace);
+
+    if args.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for conv in &report.conversations {
+            let protocol = conv.protocol.as_deref().unwrap_or("-");
+            if let Some(reason) = &conv.skipped {
+                println!("{} {} ({}): skipped, {}", "-".yellow(), conv.conversation_id, protocol, reason);
+            } else if conv.is_conformant() {
+                let state = conv.final_state.as_deref().unwrap_or("");
+                let status = if conv.complete { state.normal() } else { state.yellow() };
+                println!("{} {} ({}): {} messages, {}", "✓".green(), conv.conversation_id, protocol, conv.messages, status);
+            } else {
+                println!("{} {} ({}):", "✗".red(), conv.conversation_id, protocol);
+                for v in &conv.violations {
+                    println!("  {} #{} {} from {} in state {}", "Message:".bold(), v.entry_id, v.performative, v.sender, v.state);
+                    println!("    {} {}", "Expected:".bold(), v.expected.join(", "));
+                    println!("    {} {}", "Error:".bold(), v.error);
+                }
+            }
+        }
+        if report.unscoped > 0 {
+            println!("{} {} messages without a conversation ID", "-".yellow(), report.unscoped);
+        }
+    }
+    if !report.is_conformant() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 async fn cmd_status(args: &Args) -> Result<()> {
     println!("{}", "FIPA Platform Status".bold().cyan());
     println!("{}", "─".repeat(40));
@@ -12,6 +12,9 @@
 //   FIPA_SEED       {"ledger":{...}}                   (PA ledger seed)
 //   FIPA_KICK       2                                  (BA: seconds before kickoff)
 //   FIPA_BOOT_DELAY 1                                  (seconds before registering)
+//   FIPA_PROFILE    normal | iot                       (node profile; default normal)
+//   FIPA_POOL       1000                               (pooled wasm instance slots; off if unset)
+//   FIPA_RECORD     /data/traces                       (record wasm agents for replay; off if unset)
 
 use std::net::TcpListener;
 use std::sync::atomic::AtomicBool;
@@ -21,8 +24,9 @@ use std::thread;
 use std::time::Duration;
 
 use fipa_wasm_agents::identity::{AgentId, Header};
+use fipa_wasm_agents::manifest::NodeProfile;
 use fipa_wasm_agents::process::Node;
-use fipa_wasm_agents::wasm::{AgentRuntime, NativeRuntime};
+use fipa_wasm_agents::wasm::{enable_pooling, AgentRuntime, ModuleCache, NativeRuntime, Pooling};
 use unl_agent::{Agent, Ctx};
 use uuid::Uuid;
 
@@ -87,6 +91,26 @@ fn main() {
     // Persisted node identities: Ed25519 signing key (R1) + Noise static key (R2).
     let _ = node.load_key(format!("{data}/node_key"));
     let _ = node.load_noise(format!("{data}/noise_key"));
+    match env_or("FIPA_PROFILE", "normal").as_str() {
+        "normal" => {}
+        "iot" => node.set_profile(NodeProfile::iot()),
+        other => panic!("unknown FIPA_PROFILE '{other}' (normal|iot)"),
+    }
+    // Pooling allocator for nodes churning through ephemeral agents — must be set
+    // before anything builds the shared engine (the module cache below does).
+    if let Some(slots) = env("FIPA_POOL").and_then(|v| v.parse().ok())
+        && let Err(e) = enable_pooling(Pooling::for_profile(node.profile(), slots))
+    {
+        eprintln!("[{name}] FIPA_POOL ignored: {e}");
+    }
+    // Precompiled wasm artifacts, so restarts and inbound migrations skip compilation.
+    match ModuleCache::open(format!("{data}/modules"), node.cache_key()) {
+        Ok(cache) => cache.install(),
+        Err(e) => eprintln!("[{name}] module cache disabled: {e}"),
+    }
+    if let Some(dir) = env("FIPA_RECORD") {
+        node.set_record_dir(dir);
+    }
     if let Some(a) = env("FIPA_AMS") {
         node.add_route("ams", &a);
         node.set_ams(&a);
@@ -66,7 +66,7 @@ pub use observability::{
     init_metrics, init_tracing, record_agent_spawned, record_agent_stopped,
     record_consensus_commit, record_consensus_election, record_message_latency,
     record_message_received, record_message_sent, record_migration, record_wasm_execution,
-    MetricsConfig, MetricsHandle, TracingConfig, TracingFormat,
+    record_agent_usage, record_fuel_throttled, forget_agent, MetricsConfig, MetricsHandle, TracingConfig, TracingFormat,
 };
 
 pub use security::{
@@ -65,6 +65,15 @@ pub struct Budget {
     pub state_kb: u64,
     pub timers: u32,
     pub msg_per_s: u32,
+    /// Wall-clock deadline per entry-point call, in ms (audit L3). Fuel bounds guest
+    /// instructions; this bounds the call's wall-clock time, checked in guest code
+    /// only — a host call in progress is not cut short, it trips on return.
+    #[serde(default = "default_wall_ms")]
+    pub wall_ms: u64,
+    /// Cumulative fuel an agent may burn per minute across all its calls, on top of
+    /// the per-call `fuel`; 0 = unmetered. The node drops inputs past it.
+    #[serde(default)]
+    pub fuel_per_min: u64,
     /// Network scope: `"none"` | `"platform"` | `"any"` | `"node:<id>,…"`.
     pub net: String,
 }
@@ -77,11 +86,49 @@ impl Default for Budget {
             state_kb: 256,
             timers: 4,
             msg_per_s: 50,
+            wall_ms: default_wall_ms(),
+            fuel_per_min: 0,
             net: "platform".into(),
         }
     }
 }
 
+fn default_wall_ms() -> u64 {
+    1_000
+}
+
+impl Budget {
+    /// Whether every field stays within `ceiling`, else the first that doesn't.
+    pub fn within(&self, ceiling: &Budget) -> Result<(), FitError> {
+        if self.mem_kb > ceiling.mem_kb {
+            return Err(FitError::OverBudget("mem_kb"));
+        }
+        if self.fuel > ceiling.fuel {
+            return Err(FitError::OverBudget("fuel"));
+        }
+        if self.state_kb > ceiling.state_kb {
+            return Err(FitError::OverBudget("state_kb"));
+        }
+        if self.timers > ceiling.timers {
+            return Err(FitError::OverBudget("timers"));
+        }
+        if self.msg_per_s > ceiling.msg_per_s {
+            return Err(FitError::OverBudget("msg_per_s"));
+        }
+        if self.wall_ms > ceiling.wall_ms {
+            return Err(FitError::OverBudget("wall_ms"));
+        }
+        // a metered ceiling admits neither an unmetered request nor a larger one
+        if ceiling.fuel_per_min != 0 && (self.fuel_per_min == 0 || self.fuel_per_min > ceiling.fuel_per_min) {
+            return Err(FitError::OverBudget("fuel_per_min"));
+        }
+        if net_rank(&self.net) > net_rank(&ceiling.net) {
+            return Err(FitError::OverBudget("net")); // requested scope is broader than the ceiling
+        }
+        Ok(())
+    }
+}
+
 /// The agent manifest — the bundle `HEAD`. Extends the identity header with the
 /// profile, brain, requested grants, and budgets.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -97,6 +144,19 @@ pub struct Manifest {
     pub grants: Vec<Capability>,
     #[serde(default)]
     pub budget: Budget,
+    /// Built from the `stateless` template: the agent keeps nothing between
+    /// lifetimes, so the node may recycle a reset instance of the same code for it.
+    #[serde(default)]
+    pub stateless: bool,
+    /// The owner's Ed25519 public key (hex): the root of the agent's attestation
+    /// chain (`MOBILITY.md` §7). An owned agent moves only under a chain the owner
+    /// delegated, and its messages carry that chain.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Instance UUIDs this agent was cloned from, the original first and the
+    /// direct parent last; empty for an agent that was deployed, not cloned.
+    #[serde(default)]
+    pub lineage: Vec<String>,
 }
 
 impl Manifest {
@@ -106,6 +166,13 @@ impl Manifest {
     pub fn to_json(&self) -> Vec<u8> {
         serde_json::to_vec(self).unwrap_or_default()
     }
+
+    /// The owner key, if the manifest names a well-formed one.
+    pub fn owner_key(&self) -> Option<[u8; 32]> {
+        let mut key = [0u8; 32];
+        hex::decode_to_slice(self.owner.as_deref()?, &mut key).ok()?;
+        Some(key)
+    }
 }
 
 /// The effective, post-fit authority granted to an admitted agent.
@@ -131,6 +198,16 @@ impl Grant {
             budget: Budget::default(),
         }
     }
+
+    /// Whether this grant is no wider than `held` — every capability already held
+    /// and every budget within the held one — so replacing an agent's code under it
+    /// cannot escalate the agent's authority.
+    pub fn within(&self, held: &Grant) -> Result<(), FitError> {
+        if let Some(&c) = self.caps.iter().find(|c| !held.caps.contains(c)) {
+            return Err(FitError::Ungranted(c));
+        }
+        self.budget.within(&held.budget)
+    }
 }
 
 /// Why a manifest does not fit a node profile (load-time, operator-facing — the
@@ -176,6 +253,8 @@ impl NodeProfile {
                 state_kb: 1 << 20,      // 1 GiB
                 timers: 1024,
                 msg_per_s: 100_000,
+                wall_ms: 60_000,
+                fuel_per_min: 0,
                 net: "any".into(),
             },
         }
@@ -193,6 +272,8 @@ impl NodeProfile {
                 state_kb: 64,
                 timers: 4,
                 msg_per_s: 50,
+                wall_ms: 1_000,
+                fuel_per_min: 0,
                 net: "platform".into(),
             },
         }
@@ -208,25 +289,7 @@ impl NodeProfile {
                 return Err(FitError::Ungranted(c));
             }
         }
-        let b = &m.budget;
-        if b.mem_kb > self.ceiling.mem_kb {
-            return Err(FitError::OverBudget("mem_kb"));
-        }
-        if b.fuel > self.ceiling.fuel {
-            return Err(FitError::OverBudget("fuel"));
-        }
-        if b.state_kb > self.ceiling.state_kb {
-            return Err(FitError::OverBudget("state_kb"));
-        }
-        if b.timers > self.ceiling.timers {
-            return Err(FitError::OverBudget("timers"));
-        }
-        if b.msg_per_s > self.ceiling.msg_per_s {
-            return Err(FitError::OverBudget("msg_per_s"));
-        }
-        if net_rank(&b.net) > net_rank(&self.ceiling.net) {
-            return Err(FitError::OverBudget("net")); // requested scope is broader than the ceiling
-        }
+        m.budget.within(&self.ceiling)?;
         let mut caps: HashSet<Capability> = m.grants.iter().copied().collect();
         caps.insert(Capability::Messaging); // core
         caps.insert(Capability::Log); // core
@@ -247,6 +310,9 @@ mod tests {
             brain: Brain::Wasm,
             grants: grants.to_vec(),
             budget,
+            stateless: false,
+            owner: None,
+            lineage: Vec::new(),
         }
     }
 
@@ -279,14 +345,39 @@ mod tests {
     fn net_scope_broader_than_the_ceiling_is_rejected() {
         // IoT confines agents to "platform"; a request for "any" must not fit (H4).
         // Every other field is within the IoT ceiling so only `net` can trip.
-        let wide = Budget { mem_kb: 64, fuel: 1, state_kb: 1, timers: 1, msg_per_s: 1, net: "any".into() };
+        let wide = Budget { mem_kb: 64, fuel: 1, state_kb: 1, timers: 1, msg_per_s: 1, wall_ms: 1, fuel_per_min: 0, net: "any".into() };
         let err = NodeProfile::iot().fit(&manifest(&[Capability::State], wide)).unwrap_err();
         assert_eq!(err, FitError::OverBudget("net"));
         // "none" fits the platform ceiling.
-        let ok = Budget { mem_kb: 64, fuel: 1, state_kb: 1, timers: 1, msg_per_s: 1, net: "none".into() };
+        let ok = Budget { mem_kb: 64, fuel: 1, state_kb: 1, timers: 1, msg_per_s: 1, wall_ms: 1, fuel_per_min: 0, net: "none".into() };
         assert!(NodeProfile::iot().fit(&manifest(&[Capability::State], ok)).is_ok());
     }
 
+    #[test]
+    fn a_grant_within_another_adds_no_capability_or_budget() {
+        let held = NodeProfile::normal().fit(&manifest(&[Capability::State], Budget::default())).unwrap();
+        let same = NodeProfile::normal().fit(&manifest(&[Capability::State], Budget::default())).unwrap();
+        assert!(same.within(&held).is_ok());
+        let narrower = NodeProfile::normal().fit(&manifest(&[], Budget { fuel: 1, ..Budget::default() })).unwrap();
+        assert!(narrower.within(&held).is_ok());
+        let more_caps = NodeProfile::normal().fit(&manifest(&[Capability::Spawn], Budget::default())).unwrap();
+        assert_eq!(more_caps.within(&held).unwrap_err(), FitError::Ungranted(Capability::Spawn));
+        let more_mem = Budget { mem_kb: 8192, ..Budget::default() };
+        let bigger = NodeProfile::normal().fit(&manifest(&[Capability::State], more_mem)).unwrap();
+        assert_eq!(bigger.within(&held).unwrap_err(), FitError::OverBudget("mem_kb"));
+    }
+
+    #[test]
+    fn a_metered_fuel_ceiling_admits_no_unmetered_or_larger_rate() {
+        let ceiling = Budget { fuel_per_min: 1_000, ..Budget::default() };
+        assert!(Budget { fuel_per_min: 500, ..Budget::default() }.within(&ceiling).is_ok());
+        assert_eq!(Budget::default().within(&ceiling).unwrap_err(), FitError::OverBudget("fuel_per_min"));
+        let over = Budget { fuel_per_min: 2_000, ..Budget::default() };
+        assert_eq!(over.within(&ceiling).unwrap_err(), FitError::OverBudget("fuel_per_min"));
+        // an unmetered ceiling bounds nothing
+        assert!(over.within(&Budget::default()).is_ok());
+    }
+
     #[test]
     fn manifest_json_roundtrips() {
         let m = manifest(&[Capability::Discovery, Capability::Llm], Budget::default());
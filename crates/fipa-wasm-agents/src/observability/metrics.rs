@@ -2,7 +2,10 @@
 
 use metrics::{counter, gauge, histogram, describe_counter, describe_gauge, describe_histogram};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
 use std::net::SocketAddr;
+use std::sync::Mutex;
 use std::time::Duration;
 
 /// Configuration for metrics
@@ -41,10 +44,74 @@ pub struct MetricsHandle {
 impl MetricsHandle {
     /// Render metrics in Prometheus text format
     pub fn render(&self) -> String {
-        self.handle.render()
+        let mut out = self.handle.render();
+        out.push_str(&render_agent_usage());
+        out
     }
 }
 
+/// What one mounted agent has cost so far. Kept outside the recorder because the
+/// `metrics` facade cannot drop a label set: an agent's series must go when it
+/// leaves the node, or the label count grows with every uuid ever mounted.
+#[derive(Default)]
+struct AgentUsage {
+    /// Fuel burned, by entry point.
+    fuel: BTreeMap<String, u64>,
+    /// Linear-memory high-water mark across every instance the agent has run in,
+    /// so an upgrade (a fresh, smaller instance) does not lower it.
+    mem_peak_bytes: u64,
+    throttled: u64,
+}
+
+static AGENT_USAGE: Mutex<BTreeMap<String, AgentUsage>> = Mutex::new(BTreeMap::new());
+
+fn with_agent_usage<R>(f: impl FnOnce(&mut BTreeMap<String, AgentUsage>) -> R) -> R {
+    f(&mut AGENT_USAGE.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Escape a Prometheus label value.
+fn label(v: &str) -> String {
+    v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// The per-agent series in Prometheus text format.
+fn render_agent_usage() -> String {
+    with_agent_usage(|usage| {
+        let mut out = String::new();
+        if usage.is_empty() {
+            return out;
+        }
+        let _ = writeln!(out, "# HELP {} Fuel burned per agent and entry point", WasmMetrics::AGENT_FUEL_TOTAL);
+        let _ = writeln!(out, "# TYPE {} counter", WasmMetrics::AGENT_FUEL_TOTAL);
+        for (agent, u) in usage.iter() {
+            for (entry, fuel) in &u.fuel {
+                let _ = writeln!(
+                    out,
+                    "{}{{agent=\"{}\",entry=\"{}\"}} {fuel}",
+                    WasmMetrics::AGENT_FUEL_TOTAL,
+                    label(agent),
+                    label(entry)
+                );
+            }
+        }
+        let _ = writeln!(out, "# HELP {} Linear-memory high-water mark per agent in bytes", WasmMetrics::AGENT_MEMORY_PEAK_BYTES);
+        let _ = writeln!(out, "# TYPE {} gauge", WasmMetrics::AGENT_MEMORY_PEAK_BYTES);
+        for (agent, u) in usage.iter() {
+            let _ = writeln!(out, "{}{{agent=\"{}\"}} {}", WasmMetrics::AGENT_MEMORY_PEAK_BYTES, label(agent), u.mem_peak_bytes);
+        }
+        let _ = writeln!(
+            out,
+            "# HELP {} Inputs dropped because an agent exhausted its fuel-per-minute budget",
+            WasmMetrics::AGENT_FUEL_THROTTLED_TOTAL
+        );
+        let _ = writeln!(out, "# TYPE {} counter", WasmMetrics::AGENT_FUEL_THROTTLED_TOTAL);
+        for (agent, u) in usage.iter() {
+            let _ = writeln!(out, "{}{{agent=\"{}\"}} {}", WasmMetrics::AGENT_FUEL_THROTTLED_TOTAL, label(agent), u.throttled);
+        }
+        out
+    })
+}
+
 /// Agent-related metrics
 pub struct AgentMetrics;
 
@@ -80,6 +147,9 @@ pub struct WasmMetrics;
 impl WasmMetrics {
     pub const EXECUTION_SECONDS: &'static str = "fipa_wasm_execution_seconds";
     pub const FUEL_CONSUMED: &'static str = "fipa_wasm_fuel_consumed";
+    pub const AGENT_FUEL_TOTAL: &'static str = "fipa_wasm_agent_fuel_total";
+    pub const AGENT_MEMORY_PEAK_BYTES: &'static str = "fipa_wasm_agent_memory_peak_bytes";
+    pub const AGENT_FUEL_THROTTLED_TOTAL: &'static str = "fipa_wasm_agent_fuel_throttled_total";
 }
 
 /// Initialize the metrics system
@@ -98,11 +168,11 @@ pub fn init_metrics(config: MetricsConfig) -> Result<MetricsHandle, Box<dyn std:
         )?;
 
     let handle = builder.install_recorder()?;
-    let metrics_handle = MetricsHandle { handle: handle.clone() };
+    let metrics_handle = MetricsHandle { handle };
 
     // Start HTTP server for metrics endpoint
     let listen_addr = config.listen_addr;
-    let shared_handle = std::sync::Arc::new(handle);
+    let shared_handle = std::sync::Arc::new(metrics_handle.clone());
 
     tokio::spawn(async move {
         use axum::{routing::get, Router, Json, http::StatusCode};
@@ -289,6 +359,26 @@ pub fn record_wasm_execution(duration: Duration, function: &str, fuel: Option<u6
     }
 }
 
+/// Record what one entry-point call of an agent cost
+pub fn record_agent_usage(agent: &str, entry: &str, fuel: u64, mem_bytes: u64) {
+    with_agent_usage(|usage| {
+        let u = usage.entry(agent.to_string()).or_default();
+        let total = u.fuel.entry(entry.to_string()).or_default();
+        *total = total.saturating_add(fuel);
+        u.mem_peak_bytes = u.mem_peak_bytes.max(mem_bytes);
+    });
+}
+
+/// Record an input dropped by an agent's fuel-per-minute budget
+pub fn record_fuel_throttled(agent: &str) {
+    with_agent_usage(|usage| usage.entry(agent.to_string()).or_default().throttled += 1);
+}
+
+/// Drop every per-agent series of an agent that has left the node
+pub fn forget_agent(agent: &str) {
+    with_agent_usage(|usage| usage.remove(agent));
+}
+
 /// Record an agent migration
 pub fn record_migration(from_node: &str, to_node: &str, success: bool) {
     counter!(
@@ -315,5 +405,21 @@ mod tests {
         assert!(AgentMetrics::SPAWNED_TOTAL.starts_with("fipa_"));
         assert!(MessageMetrics::SENT_TOTAL.starts_with("fipa_"));
         assert!(ConsensusMetrics::COMMITS_TOTAL.starts_with("fipa_"));
+        assert!(WasmMetrics::AGENT_FUEL_TOTAL.starts_with("fipa_"));
+    }
+
+    #[test]
+    fn test_agent_usage_keeps_its_peak_and_is_forgotten() {
+        let agent = "metrics-test-agent";
+        record_agent_usage(agent, "init", 10, 4096);
+        record_agent_usage(agent, "step", 5, 1024); // e.g. a fresh instance after an upgrade
+        record_fuel_throttled(agent);
+        let text = render_agent_usage();
+        assert!(text.contains(&format!("{}{{agent=\"{agent}\",entry=\"init\"}} 10", WasmMetrics::AGENT_FUEL_TOTAL)));
+        assert!(text.contains(&format!("{}{{agent=\"{agent}\"}} 4096", WasmMetrics::AGENT_MEMORY_PEAK_BYTES)));
+        assert!(text.contains(&format!("{}{{agent=\"{agent}\"}} 1", WasmMetrics::AGENT_FUEL_THROTTLED_TOTAL)));
+
+        forget_agent(agent);
+        assert!(!render_agent_usage().contains(agent));
     }
 }
@@ -25,8 +25,9 @@ mod tracing_setup;
 pub use metrics::{
     init_metrics, record_agent_spawned, record_agent_stopped, record_message_sent,
     record_message_received, record_message_latency, record_consensus_commit,
-    record_consensus_election, record_wasm_execution, record_migration, AgentMetrics,
-    ConsensusMetrics, MessageMetrics, MetricsConfig, MetricsHandle,
+    record_consensus_election, record_wasm_execution, record_migration, record_agent_usage,
+    record_fuel_throttled, forget_agent, AgentMetrics, ConsensusMetrics, MessageMetrics, MetricsConfig,
+    MetricsHandle, WasmMetrics,
 };
 
 pub use tracing_setup::{init_tracing, TracingConfig, TracingFormat};
@@ -6,7 +6,8 @@
 //! - Yellow pages service (agents register/search services)
 //! - Multi-criteria search (by name, protocol, ontology, properties)
 //! - DF federation (multiple DFs sharing catalogs)
-//! - Subscription to DF changes (notify on register/deregister)
+//! - Subscription to DF changes (notify on register/deregister), leased: a
+//!   subscriber renews before its lease runs out or is dropped
 //!
 //! # FIPA Compliance
 //!
@@ -14,10 +15,11 @@
 
 use actix::prelude::*;
 use std::collections::{HashMap, HashSet};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
 use crate::proto;
+use crate::protocol::{clamp_lease, DEFAULT_MAX_LEASE_MS};
 
 /// DF configuration
 #[derive(Debug, Clone)]
@@ -33,6 +35,9 @@ pub struct DFConfig {
 
     /// Federated DFs
     pub federated_dfs: Vec<String>,
+
+    /// Longest subscription lease granted; longer requests are clamped to it
+    pub max_subscription_lease: Duration,
 }
 
 impl Default for DFConfig {
@@ -42,6 +47,7 @@ impl Default for DFConfig {
             max_services_per_agent: 100,
             max_total_services: 10000,
             federated_dfs: vec![],
+            max_subscription_lease: Duration::from_millis(DEFAULT_MAX_LEASE_MS),
         }
     }
 }
@@ -94,6 +100,12 @@ pub struct DFSubscription {
 
     /// Created timestamp
     pub created_at: Instant,
+
+    /// Lease granted (as last renewed)
+    pub lease: Duration,
+
+    /// When the lease runs out unless renewed
+    pub expires_at: Instant,
 }
 
 /// DF statistics
@@ -104,6 +116,7 @@ pub struct DFStats {
     pub searches: u64,
     pub search_results: u64,
     pub notifications_sent: u64,
+    pub subscriptions_expired: u64,
 }
 
 impl DF {
@@ -294,22 +307,60 @@ impl DF {
         true
     }
 
-    /// Subscribe to DF changes
-    fn subscribe(&mut self, request: DFSubscribe) -> Result<(), DFError> {
+    /// The lease granted for `requested`, clamped to the configured maximum
+    fn grant_lease(&self, requested: Option<Duration>) -> Duration {
+        let max_ms = self.config.max_subscription_lease.as_millis() as u64;
+        Duration::from_millis(clamp_lease(requested.map(|d| d.as_millis() as u64), max_ms))
+    }
+
+    /// Subscribe to DF changes; returns the lease granted
+    fn subscribe(&mut self, request: DFSubscribe) -> Result<Duration, DFError> {
         let subscriber_name = request.subscriber.name.clone();
+        let lease = self.grant_lease(request.lease);
+        let now = Instant::now();
 
         self.subscriptions.insert(subscriber_name.clone(), DFSubscription {
             subscriber: request.subscriber,
             filter: request.filter,
-            created_at: Instant::now(),
+            created_at: now,
+            lease,
+            expires_at: now + lease,
         });
 
-        info!("DF: Agent '{}' subscribed to DF changes", subscriber_name);
-        Ok(())
+        info!("DF: Agent '{}' subscribed to DF changes for {:?}", subscriber_name, lease);
+        Ok(lease)
+    }
+
+    /// Renew a subscription's lease; returns the lease granted
+    fn renew(&mut self, request: DFRenew) -> Result<Duration, DFError> {
+        let lease = self.grant_lease(request.lease);
+        let subscription = self.subscriptions
+            .get_mut(&request.subscriber.name)
+            .filter(|s| s.expires_at > Instant::now())
+            .ok_or(DFError::SubscriptionNotFound)?;
+        subscription.lease = lease;
+        subscription.expires_at = Instant::now() + lease;
+        Ok(lease)
+    }
+
+    /// Drop subscriptions whose lease ran out by `now`
+    fn expire_subscriptions(&mut self, now: Instant) -> usize {
+        let before = self.subscriptions.len();
+        self.subscriptions.retain(|name, s| {
+            let live = s.expires_at > now;
+            if !live {
+                info!("DF: Subscription of '{}' expired", name);
+            }
+            live
+        });
+        let expired = before - self.subscriptions.len();
+        self.stats.subscriptions_expired += expired as u64;
+        expired
     }
 
     /// Notify subscribers of a registration
     fn notify_subscribers(&mut self, service: &proto::ServiceDescription, notification_type: DFNotificationType) {
+        self.expire_subscriptions(Instant::now());
         for (_, subscription) in &self.subscriptions {
             // Check if subscriber is interested
             let dummy_owner = proto::AgentId {
@@ -331,6 +382,7 @@ impl DF {
 
     /// Notify subscribers of a deregistration
     fn notify_subscribers_deregister(&mut self, service_name: &str, _agent_name: &str) {
+        self.expire_subscriptions(Instant::now());
         for (_, subscription) in &self.subscriptions {
             // Simple name-based check for deregistrations
             if subscription.filter.name.as_ref().map(|n| service_name.contains(n)).unwrap_or(true) {
@@ -352,8 +404,13 @@ impl DF {
 impl Actor for DF {
     type Context = Context<Self>;
 
-    fn started(&mut self, _ctx: &mut Self::Context) {
+    fn started(&mut self, ctx: &mut Self::Context) {
         info!("DF started for platform: {}", self.config.platform_name);
+
+        // Sweep lapsed subscriptions even when nothing changes
+        ctx.run_interval(Duration::from_secs(1), |df, _ctx| {
+            df.expire_subscriptions(Instant::now());
+        });
     }
 }
 
@@ -408,15 +465,29 @@ pub struct DFSearch {
     pub max_results: Option<usize>,
 }
 
-/// Subscribe to DF changes
+/// Subscribe to DF changes; answered with the lease granted
 #[derive(Debug, Clone, Message)]
-#[rtype(result = "Result<(), DFError>")]
+#[rtype(result = "Result<Duration, DFError>")]
 pub struct DFSubscribe {
     /// Subscriber agent ID
     pub subscriber: proto::AgentId,
 
     /// Filter for notifications
     pub filter: DFSearchFilter,
+
+    /// Lease requested (`None` = the configured maximum)
+    pub lease: Option<Duration>,
+}
+
+/// Renew a subscription before its lease runs out; answered with the lease granted
+#[derive(Debug, Clone, Message)]
+#[rtype(result = "Result<Duration, DFError>")]
+pub struct DFRenew {
+    /// Subscriber agent ID
+    pub subscriber: proto::AgentId,
+
+    /// Lease requested (`None` = the configured maximum)
+    pub lease: Option<Duration>,
 }
 
 /// Unsubscribe from DF changes
@@ -541,13 +612,21 @@ impl Handler<DFSearch> for DF {
 }
 
 impl Handler<DFSubscribe> for DF {
-    type Result = Result<(), DFError>;
+    type Result = Result<Duration, DFError>;
 
     fn handle(&mut self, msg: DFSubscribe, _ctx: &mut Self::Context) -> Self::Result {
         self.subscribe(msg)
     }
 }
 
+impl Handler<DFRenew> for DF {
+    type Result = Result<Duration, DFError>;
+
+    fn handle(&mut self, msg: DFRenew, _ctx: &mut Self::Context) -> Self::Result {
+        self.renew(msg)
+    }
+}
+
 impl Handler<DFUnsubscribe> for DF {
     type Result = Result<(), DFError>;
 
@@ -611,4 +690,40 @@ mod tests {
         };
         assert!(!df.matches_filter(&service, &owner, &filter));
     }
+
+    #[test]
+    fn test_subscription_lease_clamped_renewed_and_expired() {
+        let config = DFConfig {
+            max_subscription_lease: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let mut df = DF::new(config);
+        let subscriber = proto::AgentId {
+            name: "watcher".to_string(),
+            addresses: vec![],
+            resolvers: vec![],
+        };
+
+        // An unbounded request gets the configured maximum
+        let lease = df.subscribe(DFSubscribe {
+            subscriber: subscriber.clone(),
+            filter: DFSearchFilter::default(),
+            lease: Some(Duration::from_secs(86_400)),
+        }).unwrap();
+        assert_eq!(lease, Duration::from_secs(60));
+
+        // Renewing re-arms it
+        let lease = df.renew(DFRenew { subscriber: subscriber.clone(), lease: Some(Duration::from_secs(30)) }).unwrap();
+        assert_eq!(lease, Duration::from_secs(30));
+        let expires_at = df.subscriptions["watcher"].expires_at;
+
+        // Still live just before the deadline, gone at it
+        assert_eq!(df.expire_subscriptions(expires_at - Duration::from_millis(1)), 0);
+        assert_eq!(df.expire_subscriptions(expires_at), 1);
+        assert_eq!(df.get_stats().subscriptions_expired, 1);
+        assert!(matches!(
+            df.renew(DFRenew { subscriber, lease: None }),
+            Err(DFError::SubscriptionNotFound)
+        ));
+    }
 }
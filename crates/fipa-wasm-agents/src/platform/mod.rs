@@ -26,4 +26,4 @@ pub mod ams;
 pub mod df;
 
 pub use ams::{AMS, AMSConfig, AMSCreateAgent, AMSDestroyAgent, AMSQueryAgents, AMSSuspendAgent, AMSResumeAgent};
-pub use df::{DF, DFConfig, DFRegister, DFDeregister, DFSearch, DFSubscribe};
+pub use df::{DF, DFConfig, DFRegister, DFDeregister, DFSearch, DFSubscribe, DFRenew};
@@ -0,0 +1,319 @@
+//! The attestation chain — who may act for a mobile agent (`docs/MOBILITY.md` §7).
+//!
+//! Identity is the agent UUID; the authority to sign *as* the agent is a chain
+//! rooted at its **owner**, the key the manifest (`HEAD`) names in `owner`: an
+//! owner-signed [`Delegation`] to the first node, then one [`Handoff`] per
+//! migration, each signed by the node the agent left. [`Attestation::verify`]
+//! walks the chain and yields the node it ends at — the only node that may sign
+//! for the agent. Verifiers walk every link, so the chain is bounded
+//! ([`MAX_CHAIN_LINKS`], THREAT_MODEL M3): the owner collapses it with a fresh
+//! Delegation to the current node (compaction), and revokes a node by delegating
+//! past it at a higher epoch.
+//!
+//! Time windows: the Delegation must be valid whenever the chain is verified, so
+//! its `naf` bounds how long a chain stays usable without the owner. A Handoff's
+//! window bounds when the *move* may be accepted ([`Attestation::extend`]); once
+//! accepted it stays part of the history and is only checked not to be from the
+//! future.
+
+use serde::{Deserialize, Serialize};
+
+use super::migrate::{in_window, put, Handoff, CLOCK_SKEW_MS};
+use crate::adapters::{self, NodeCrypto};
+
+/// Longest chain a verifier walks: the Delegation plus at most 15 handoffs. An
+/// agent that has moved more often needs its owner to compact the chain.
+pub const MAX_CHAIN_LINKS: usize = 16;
+
+/// Why an attestation chain does not authorize a node. Links are numbered from the
+/// Delegation (0) along the handoffs (1..).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ChainError {
+    #[error("malformed attestation")]
+    Malformed,
+    #[error("chain of {0} links exceeds the bound of {MAX_CHAIN_LINKS}")]
+    TooLong(usize),
+    #[error("bad signature on link {0}")]
+    BadSignature(usize),
+    #[error("link {0} is for another agent")]
+    WrongAgent(usize),
+    #[error("not rooted at the agent's owner")]
+    WrongOwner,
+    #[error("the owner delegated different code")]
+    WrongCode,
+    #[error("link {0} is outside its validity window")]
+    Expired(usize),
+    #[error("link {0} is not signed by the node the chain authorized")]
+    Broken(usize),
+    #[error("link {0} does not advance the epoch")]
+    Stale(usize),
+    #[error("superseded by the owner at epoch {0}")]
+    Superseded(u64),
+    #[error("the chain does not end at the signing node")]
+    NotHolder,
+}
+
+/// The root link: the owner authorizes a node to act for the agent, running the
+/// code it names, from `epoch` on.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Delegation {
+    pub agent: String,
+    /// The owner's Ed25519 public key (must match the manifest's `owner`).
+    pub owner_pub: Vec<u8>,
+    /// The node key being authorized.
+    pub to_node: Vec<u8>,
+    /// Content address of the code the owner authorizes — stands in for the bundle
+    /// `SIG` over `HEAD‖wasm_hash`, so no node on the chain can swap the code.
+    pub code_hash: String,
+    pub epoch: u64,
+    /// Validity window (Unix ms).
+    pub nbf: u64,
+    pub naf: u64,
+    /// Signature by `owner_pub` over the fields above.
+    pub sig: Vec<u8>,
+}
+
+impl Delegation {
+    pub fn sealed(
+        agent: &str,
+        to_node: Vec<u8>,
+        code_hash: &str,
+        epoch: u64,
+        nbf: u64,
+        naf: u64,
+        owner: &NodeCrypto,
+    ) -> Self {
+        let mut d = Delegation {
+            agent: agent.into(),
+            owner_pub: owner.public_key().to_vec(),
+            to_node,
+            code_hash: code_hash.into(),
+            epoch,
+            nbf,
+            naf,
+            sig: Vec::new(),
+        };
+        d.sig = owner.sign(&d.signing_bytes()).to_vec();
+        d
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut b = Vec::new();
+        b.extend_from_slice(b"fipa:delegation:v1\0");
+        put(&mut b, self.agent.as_bytes());
+        put(&mut b, &self.owner_pub);
+        put(&mut b, &self.to_node);
+        put(&mut b, self.code_hash.as_bytes());
+        b.extend_from_slice(&self.epoch.to_be_bytes());
+        b.extend_from_slice(&self.nbf.to_be_bytes());
+        b.extend_from_slice(&self.naf.to_be_bytes());
+        b
+    }
+
+    /// Verify the delegation is signed by `owner_pub`.
+    pub fn verify(&self) -> bool {
+        let (Ok(pk), Ok(sg)) = (<[u8; 32]>::try_from(self.owner_pub.as_slice()), <[u8; 64]>::try_from(self.sig.as_slice()))
+        else {
+            return false;
+        };
+        adapters::verify(&pk, &self.signing_bytes(), &sg)
+    }
+
+    /// Whether the delegation is in force at `now`.
+    pub fn valid_at(&self, now: u64) -> bool {
+        in_window(self.nbf, self.naf, now)
+    }
+}
+
+/// An owned agent's chain of custody: the owner's [`Delegation`] followed by the
+/// [`Handoff`] of every migration since.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attestation {
+    pub delegation: Delegation,
+    #[serde(default)]
+    pub handoffs: Vec<Handoff>,
+}
+
+impl Attestation {
+    pub fn new(delegation: Delegation) -> Self {
+        Attestation { delegation, handoffs: Vec::new() }
+    }
+
+    pub fn agent(&self) -> &str {
+        &self.delegation.agent
+    }
+
+    pub fn owner(&self) -> &[u8] {
+        &self.delegation.owner_pub
+    }
+
+    /// The epoch the chain has reached (its last link's).
+    pub fn epoch(&self) -> u64 {
+        self.handoffs.last().map_or(self.delegation.epoch, |h| h.epoch)
+    }
+
+    /// The node the last link authorizes.
+    pub fn holder(&self) -> &[u8] {
+        self.handoffs.last().map_or(&self.delegation.to_node, |h| &h.to_pub)
+    }
+
+    /// Walk the chain at `now` and return the node it authorizes. The length is
+    /// checked before any signature, so an oversized chain costs nothing (M3).
+    pub fn verify(&self, now: u64) -> Result<[u8; 32], ChainError> {
+        let links = 1 + self.handoffs.len();
+        if links > MAX_CHAIN_LINKS {
+            return Err(ChainError::TooLong(links));
+        }
+        let d = &self.delegation;
+        if !d.verify() {
+            return Err(ChainError::BadSignature(0));
+        }
+        if !d.valid_at(now) {
+            return Err(ChainError::Expired(0));
+        }
+        let (mut holder, mut epoch) = (d.to_node.as_slice(), d.epoch);
+        for (i, h) in self.handoffs.iter().enumerate() {
+            let link = i + 1;
+            if h.agent != d.agent {
+                return Err(ChainError::WrongAgent(link));
+            }
+            if h.from_pub != holder {
+                return Err(ChainError::Broken(link));
+            }
+            if h.epoch <= epoch {
+                return Err(ChainError::Stale(link));
+            }
+            if h.nbf > now.saturating_add(CLOCK_SKEW_MS) {
+                return Err(ChainError::Expired(link));
+            }
+            if !h.verify() {
+                return Err(ChainError::BadSignature(link));
+            }
+            (holder, epoch) = (&h.to_pub, h.epoch);
+        }
+        <[u8; 32]>::try_from(holder).map_err(|_| ChainError::Malformed)
+    }
+
+    /// Append the handoff of a move accepted at `now`; the chain is unchanged if
+    /// the result does not verify.
+    pub fn extend(&mut self, handoff: Handoff, now: u64) -> Result<(), ChainError> {
+        if !handoff.valid_at(now) {
+            return Err(ChainError::Expired(self.handoffs.len() + 1));
+        }
+        self.handoffs.push(handoff);
+        let verified = self.verify(now);
+        if verified.is_err() {
+            self.handoffs.pop();
+        }
+        verified.map(drop)
+    }
+
+    /// Replace the chain with a fresh owner Delegation to its current holder
+    /// (compaction). The Delegation must come from the same owner and must not
+    /// go back in epoch.
+    pub fn compact(&mut self, delegation: Delegation, now: u64) -> Result<(), ChainError> {
+        let fresh = Attestation::new(delegation);
+        fresh.verify(now)?;
+        if fresh.agent() != self.agent() {
+            return Err(ChainError::WrongAgent(0));
+        }
+        if fresh.owner() != self.owner() {
+            return Err(ChainError::WrongOwner);
+        }
+        if fresh.holder() != self.holder() {
+            return Err(ChainError::NotHolder);
+        }
+        if fresh.epoch() < self.epoch() {
+            return Err(ChainError::Stale(0));
+        }
+        *self = fresh;
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_000_000;
+
+    fn delegated(owner: &NodeCrypto, node: &NodeCrypto) -> Attestation {
+        Attestation::new(Delegation::sealed("CTR", node.public_key().to_vec(), "c0de", 0, NOW, NOW + 60_000, owner))
+    }
+
+    /// Move the agent from `from` to `to` at `epoch`.
+    fn hop(chain: &mut Attestation, from: &NodeCrypto, to: &NodeCrypto, epoch: u64) -> Result<(), ChainError> {
+        chain.extend(Handoff::sealed("CTR", to.public_key().to_vec(), epoch, "", NOW, from), NOW)
+    }
+
+    #[test]
+    fn a_chain_authorizes_the_node_it_ends_at() {
+        let owner = NodeCrypto::generate();
+        let nodes: Vec<_> = (0..3).map(|_| NodeCrypto::generate()).collect();
+        let mut chain = delegated(&owner, &nodes[0]);
+        assert_eq!(chain.verify(NOW), Ok(nodes[0].public_key()));
+        hop(&mut chain, &nodes[0], &nodes[1], 1).unwrap();
+        hop(&mut chain, &nodes[1], &nodes[2], 2).unwrap();
+        assert_eq!(chain.verify(NOW), Ok(nodes[2].public_key()));
+        assert_eq!(chain.epoch(), 2);
+
+        let back = Attestation::decode(&chain.encode()).unwrap();
+        assert_eq!(back.verify(NOW), Ok(nodes[2].public_key()));
+        assert_eq!(chain.verify(NOW + 61_000 + CLOCK_SKEW_MS), Err(ChainError::Expired(0)));
+    }
+
+    #[test]
+    fn a_broken_or_replayed_link_is_refused() {
+        let owner = NodeCrypto::generate();
+        let (a, b, c) = (NodeCrypto::generate(), NodeCrypto::generate(), NodeCrypto::generate());
+        let mut chain = delegated(&owner, &a);
+        // b was never authorized, so it cannot hand the agent on
+        assert_eq!(hop(&mut chain, &b, &c, 1), Err(ChainError::Broken(1)));
+        hop(&mut chain, &a, &b, 1).unwrap();
+        // a handed the agent away: a second handoff from a forks the chain
+        assert_eq!(hop(&mut chain, &a, &c, 2), Err(ChainError::Broken(2)));
+        // the epoch must advance
+        assert_eq!(hop(&mut chain, &b, &c, 1), Err(ChainError::Stale(2)));
+        assert_eq!(chain.handoffs.len(), 1); // refused links are not kept
+
+        let stale = Handoff::sealed("CTR", c.public_key().to_vec(), 2, "", NOW - 400_000, &b);
+        assert_eq!(chain.extend(stale, NOW), Err(ChainError::Expired(2)));
+
+        let mut forged = chain.clone();
+        forged.delegation.to_node = c.public_key().to_vec();
+        assert_eq!(forged.verify(NOW), Err(ChainError::BadSignature(0)));
+    }
+
+    #[test]
+    fn chain_length_is_bounded_until_the_owner_compacts_it() {
+        let owner = NodeCrypto::generate();
+        let nodes: Vec<_> = (0..=MAX_CHAIN_LINKS).map(|_| NodeCrypto::generate()).collect();
+        let mut chain = delegated(&owner, &nodes[0]);
+        for i in 1..MAX_CHAIN_LINKS {
+            hop(&mut chain, &nodes[i - 1], &nodes[i], i as u64).unwrap();
+        }
+        let last = MAX_CHAIN_LINKS as u64;
+        let (tail, next) = (&nodes[MAX_CHAIN_LINKS - 1], &nodes[MAX_CHAIN_LINKS]);
+        assert_eq!(hop(&mut chain, tail, next, last), Err(ChainError::TooLong(MAX_CHAIN_LINKS + 1)));
+
+        // a Delegation to another node, or from another key, is not a compaction
+        let elsewhere = Delegation::sealed("CTR", next.public_key().to_vec(), "c0de", last, NOW, NOW + 60_000, &owner);
+        assert_eq!(chain.compact(elsewhere, NOW), Err(ChainError::NotHolder));
+        let intruder = NodeCrypto::generate();
+        let forged = Delegation::sealed("CTR", tail.public_key().to_vec(), "c0de", last, NOW, NOW + 60_000, &intruder);
+        assert_eq!(chain.compact(forged, NOW), Err(ChainError::WrongOwner));
+
+        let fresh = Delegation::sealed("CTR", tail.public_key().to_vec(), "c0de", last - 1, NOW, NOW + 60_000, &owner);
+        chain.compact(fresh, NOW).unwrap();
+        assert!(chain.handoffs.is_empty());
+        hop(&mut chain, tail, next, last).unwrap();
+        assert_eq!(chain.verify(NOW), Ok(next.public_key()));
+    }
+}
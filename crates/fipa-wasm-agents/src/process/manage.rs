@@ -43,6 +43,9 @@ pub fn build_wasm(path: &Path) -> Result<Box<dyn AgentRuntime>> {
         storage_quota_bytes: 1024 * 1024,
         ..Default::default()
     };
+    if crate::wasm::is_component(&wasm) {
+        return Ok(Box::new(crate::wasm::ComponentRuntime::new(&wasm, &caps)?));
+    }
     Ok(Box::new(WasmRuntime::new(&wasm, &caps)?))
 }
 
@@ -7,14 +7,16 @@
 //! at AMS with a **higher epoch** so the move is the single forward step — a
 //! replayed or forked snapshot at a lower/equal epoch cannot double-bind (H1).
 //!
-//! This module is the move payload + its signing. The orchestration (the two-phase
-//! commit, the attestation chain for the destination's key, content-addressed code
-//! transfer when the destination lacks the agent) is the remaining M5 hardening;
-//! the happy-path state transfer + signed snapshot + epoch arbiter are implemented.
+//! This module is the move payload + its signing, plus the records of the
+//! **two-phase move** (§6): the source's signed COMMIT/ABORT [`MoveDecision`] and
+//! the [`StagedMove`] each side keeps in its durable staging log while a move is
+//! in flight, so a restarted node can finish or undo it. The orchestration lives
+//! in `node.rs`.
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use super::attest::Attestation;
 use crate::adapters::{self, NodeCrypto};
 
 /// SHA-256 of `bytes` as lowercase hex — the content address of a wasm module.
@@ -27,7 +29,7 @@ pub fn code_hash(bytes: &[u8]) -> String {
 /// Length-prefix a field into the signing buffer so adjacent variable-length
 /// fields cannot be re-split to forge an equivalent message (audit L1 — canonical
 /// encoding). Every signed field is framed `[u32 BE len][bytes]`.
-fn put(b: &mut Vec<u8>, field: &[u8]) {
+pub(super) fn put(b: &mut Vec<u8>, field: &[u8]) {
     b.extend_from_slice(&(field.len() as u32).to_be_bytes());
     b.extend_from_slice(field);
 }
@@ -51,6 +53,10 @@ pub struct AgentSnapshot {
     pub manifest: Vec<u8>,
     /// The agent's serialized state (from the guest's `snapshot` export).
     pub state: Vec<u8>,
+    /// Timers and async requests the agent is waiting on, re-armed at the
+    /// destination (MOBILITY §8).
+    #[serde(default)]
+    pub pending: Pending,
     /// Anti-replay nonce.
     pub nonce: Vec<u8>,
     /// The origin node's Ed25519 public key.
@@ -69,6 +75,7 @@ impl AgentSnapshot {
         code: Vec<u8>,
         state: Vec<u8>,
         manifest: Vec<u8>,
+        pending: Pending,
         key: &NodeCrypto,
     ) -> Self {
         let mut s = AgentSnapshot {
@@ -78,6 +85,7 @@ impl AgentSnapshot {
             code,
             manifest,
             state,
+            pending,
             nonce: key.nonce().to_vec(),
             origin_pub: key.public_key().to_vec(),
             sig: Vec::new(),
@@ -92,12 +100,13 @@ impl AgentSnapshot {
     /// share one signature (audit L1).
     fn signing_bytes(&self) -> Vec<u8> {
         let mut b = Vec::new();
-        b.extend_from_slice(b"fipa:migrate:v2\0");
+        b.extend_from_slice(b"fipa:migrate:v3\0");
         put(&mut b, self.uuid.as_bytes());
         b.extend_from_slice(&self.epoch.to_be_bytes());
         put(&mut b, self.code_hash.as_bytes());
         put(&mut b, &self.manifest);
         put(&mut b, &self.state);
+        self.pending.put_into(&mut b);
         put(&mut b, &self.nonce);
         put(&mut b, &self.origin_pub);
         b
@@ -119,6 +128,12 @@ impl AgentSnapshot {
         adapters::verify(&pk, &self.signing_bytes(), &sg)
     }
 
+    /// SHA-256 (hex) of the signed fields — what a [`Handoff`] names as the
+    /// snapshot it moves.
+    pub fn digest(&self) -> String {
+        hex::encode(Sha256::digest(self.signing_bytes()))
+    }
+
     pub fn encode(&self) -> Vec<u8> {
         serde_json::to_vec(self).unwrap_or_default()
     }
@@ -127,11 +142,53 @@ impl AgentSnapshot {
     }
 }
 
+/// Work an agent is waiting on when it is snapshotted: its armed timer slots and
+/// its outstanding async requests. Timers travel as **remaining** ms, so the
+/// destination re-arms them against its own clock and no clock is shared between
+/// the nodes (MOBILITY §8).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pending {
+    /// `(timer_id, remaining_ms)` per armed slot.
+    pub timers: Vec<(u64, u64)>,
+    /// `(request_id, prompt)` per inference the agent awaits a reply to; the
+    /// destination issues each again, and a reply to any other id is stale.
+    pub requests: Vec<(u64, String)>,
+}
+
+impl Pending {
+    fn put_into(&self, b: &mut Vec<u8>) {
+        b.extend_from_slice(&(self.timers.len() as u32).to_be_bytes());
+        for (id, remaining_ms) in &self.timers {
+            b.extend_from_slice(&id.to_be_bytes());
+            b.extend_from_slice(&remaining_ms.to_be_bytes());
+        }
+        b.extend_from_slice(&(self.requests.len() as u32).to_be_bytes());
+        for (id, prompt) in &self.requests {
+            b.extend_from_slice(&id.to_be_bytes());
+            put(b, prompt.as_bytes());
+        }
+    }
+}
+
+/// How long a handoff stays usable after it is sealed (ms). It must outlast the
+/// destination's staging window, since a restarted destination re-stages a move
+/// from the same payload.
+pub const HANDOFF_TTL_MS: u64 = 300_000;
+
+/// Tolerance for clock skew between nodes when checking `nbf`/`naf` windows (ms).
+pub const CLOCK_SKEW_MS: u64 = 30_000;
+
+/// Whether `now` falls in `[nbf, naf]`, widened by [`CLOCK_SKEW_MS`] either side.
+pub(super) fn in_window(nbf: u64, naf: u64, now: u64) -> bool {
+    nbf <= now.saturating_add(CLOCK_SKEW_MS) && now <= naf.saturating_add(CLOCK_SKEW_MS)
+}
+
 /// A key handoff: the origin node authorizes a destination node to act for an
-/// agent at a new epoch (`docs/MOBILITY.md` §7). The AMS node verifies this against
-/// the agent's current TOFU key before moving the binding — so a legitimately
-/// migrated agent can re-bind under the destination's key without breaking the R3
-/// impersonation defense.
+/// agent at a new epoch (`docs/MOBILITY.md` §7). For an owned agent it is one link
+/// of the agent's [`Attestation`](super::attest::Attestation); for an unowned one
+/// the AMS node verifies it against the agent's current TOFU key before moving the
+/// binding — so a legitimately migrated agent can re-bind under the destination's
+/// key without breaking the R3 impersonation defense.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Handoff {
     pub agent: String,
@@ -140,17 +197,37 @@ pub struct Handoff {
     /// The destination node key being authorized.
     pub to_pub: Vec<u8>,
     pub epoch: u64,
+    /// The [`AgentSnapshot::digest`] of the move this handoff authorizes (empty for
+    /// a bare key change), so the link cannot be replayed onto another snapshot.
+    #[serde(default)]
+    pub snapshot_hash: String,
+    /// Validity window (Unix ms): the destination must accept the move within it.
+    #[serde(default)]
+    pub nbf: u64,
+    #[serde(default)]
+    pub naf: u64,
     /// Signature by `from_pub` (the origin node) over the fields above.
     pub sig: Vec<u8>,
 }
 
 impl Handoff {
-    pub fn sealed(agent: &str, to_pub: Vec<u8>, epoch: u64, from_key: &NodeCrypto) -> Self {
+    /// Seal a handoff valid from `now` for [`HANDOFF_TTL_MS`].
+    pub fn sealed(
+        agent: &str,
+        to_pub: Vec<u8>,
+        epoch: u64,
+        snapshot_hash: &str,
+        now: u64,
+        from_key: &NodeCrypto,
+    ) -> Self {
         let mut h = Handoff {
             agent: agent.into(),
             from_pub: from_key.public_key().to_vec(),
             to_pub,
             epoch,
+            snapshot_hash: snapshot_hash.into(),
+            nbf: now,
+            naf: now.saturating_add(HANDOFF_TTL_MS),
             sig: Vec::new(),
         };
         h.sig = from_key.sign(&h.signing_bytes()).to_vec();
@@ -159,14 +236,22 @@ impl Handoff {
 
     fn signing_bytes(&self) -> Vec<u8> {
         let mut b = Vec::new();
-        b.extend_from_slice(b"fipa:handoff:v2\0");
+        b.extend_from_slice(b"fipa:handoff:v3\0");
         put(&mut b, self.agent.as_bytes());
         put(&mut b, &self.from_pub);
         put(&mut b, &self.to_pub);
         b.extend_from_slice(&self.epoch.to_be_bytes());
+        put(&mut b, self.snapshot_hash.as_bytes());
+        b.extend_from_slice(&self.nbf.to_be_bytes());
+        b.extend_from_slice(&self.naf.to_be_bytes());
         b
     }
 
+    /// Whether the handoff may be used at `now`.
+    pub fn valid_at(&self, now: u64) -> bool {
+        in_window(self.nbf, self.naf, now)
+    }
+
     /// Verify the handoff is signed by `from_pub`.
     pub fn verify(&self) -> bool {
         if self.sig.len() != 64 || self.from_pub.len() != 32 {
@@ -189,6 +274,10 @@ pub struct MigratePayload {
     /// the snapshot left out.
     #[serde(default)]
     pub from_addr: String,
+    /// An owned agent's attestation chain as the origin holds it; the destination
+    /// appends `handoff` to it. `None` for an unowned agent.
+    #[serde(default)]
+    pub chain: Option<Attestation>,
 }
 
 impl MigratePayload {
@@ -200,6 +289,169 @@ impl MigratePayload {
     }
 }
 
+/// Which end of a move a [`StagedMove`] records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveRole {
+    Source,
+    Destination,
+    /// The source of a clone: `uuid` is the copy's and `origin` the original's,
+    /// which keeps running — settling the entry never touches it.
+    Clone,
+}
+
+/// How far an in-flight move has got on one side (`docs/MOBILITY.md` §6). A
+/// settled move (COMMITTED or aborted) leaves the log, so there is no terminal
+/// phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovePhase {
+    /// Source: PREPARE sent, no PREPARED yet — the move can still be aborted and
+    /// the local copy resumed.
+    Preparing,
+    /// Destination: the agent is staged (mounted, suspended) awaiting the source's
+    /// decision; discarded when the staging window closes.
+    Prepared,
+    /// Source: PREPARED received and COMMIT decided — past the point of no return,
+    /// the local copy is never resumed unless the destination reports an abort.
+    Committing,
+}
+
+/// One entry of a node's durable staging log: an in-flight move, with the signed
+/// payload so a restarted source can re-mount its agent after an abort and a
+/// restarted destination can re-stage it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StagedMove {
+    pub role: MoveRole,
+    pub phase: MovePhase,
+    pub uuid: String,
+    pub epoch: u64,
+    /// The other end's address (the destination for a source entry, the origin for
+    /// a destination entry).
+    pub peer_addr: String,
+    /// The encoded [`MigratePayload`].
+    pub payload: Vec<u8>,
+    /// When the entry entered its phase (Unix ms).
+    pub since_ms: u64,
+    /// The agent's local alias on this node, so a copy re-mounted from the log
+    /// keeps it (empty: the uuid).
+    #[serde(default)]
+    pub alias: String,
+    /// For a clone entry, the uuid of the agent the copy was made from (empty
+    /// otherwise).
+    #[serde(default)]
+    pub origin: String,
+}
+
+impl StagedMove {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
+
+/// The source's COMMIT or ABORT for a staged move, carried by `KIND_MIGRATE_COMMIT`
+/// / `KIND_MIGRATE_ABORT`. Signed by the origin node so that only the node which
+/// prepared a move can settle it — including from a fresh connection after a
+/// restart, when the Noise session that carried the PREPARE is gone.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MoveDecision {
+    pub uuid: String,
+    pub epoch: u64,
+    pub commit: bool,
+    /// Why the move was aborted (empty on commit), for the destination's audit log.
+    #[serde(default)]
+    pub reason: String,
+    /// Signature by the origin node over the fields above.
+    pub sig: Vec<u8>,
+}
+
+impl MoveDecision {
+    pub fn sealed(uuid: &str, epoch: u64, commit: bool, reason: &str, key: &NodeCrypto) -> Self {
+        let mut d = MoveDecision { uuid: uuid.into(), epoch, commit, reason: reason.into(), sig: Vec::new() };
+        d.sig = key.sign(&d.signing_bytes()).to_vec();
+        d
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut b = Vec::new();
+        b.extend_from_slice(b"fipa:move-decision:v1\0");
+        put(&mut b, self.uuid.as_bytes());
+        b.extend_from_slice(&self.epoch.to_be_bytes());
+        b.push(self.commit as u8);
+        put(&mut b, self.reason.as_bytes());
+        b
+    }
+
+    /// Verify the decision is signed by `origin_pub` (the staged snapshot's origin).
+    pub fn verify(&self, origin_pub: &[u8]) -> bool {
+        let (Ok(pk), Ok(sg)) = (<[u8; 32]>::try_from(origin_pub), <[u8; 64]>::try_from(self.sig.as_slice())) else {
+            return false;
+        };
+        adapters::verify(&pk, &self.signing_bytes(), &sg)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
+
+/// How long a source keeps forwarding for an agent it handed on (ms, MOBILITY
+/// §8): long enough for the AMS re-bind to reach the agent's correspondents.
+pub const FORWARD_TTL_MS: u64 = 30_000;
+
+/// How many forwarders may relay one message, so a trail of forwarding entries
+/// left by an agent that keeps moving cannot bounce a message around for ever.
+pub const MAX_FORWARD_HOPS: u8 = 3;
+
+/// A forwarder's stamp on a relayed message (`NodeMsg::relay`): how many nodes
+/// have relayed it so far, signed by the last of them together with the original
+/// envelope's signature, so the count is bound to that one message and to a node
+/// that answers for it in the audit trail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Relay {
+    pub hops: u8,
+    /// The relaying node's key.
+    pub via_pub: Vec<u8>,
+    /// Signature by `via_pub` over the hop count and the envelope signature.
+    pub sig: Vec<u8>,
+}
+
+impl Relay {
+    pub fn sealed(hops: u8, envelope_sig: &[u8], key: &NodeCrypto) -> Self {
+        let mut r = Relay { hops, via_pub: key.public_key().to_vec(), sig: Vec::new() };
+        r.sig = key.sign(&r.signing_bytes(envelope_sig)).to_vec();
+        r
+    }
+
+    fn signing_bytes(&self, envelope_sig: &[u8]) -> Vec<u8> {
+        let mut b = Vec::new();
+        b.extend_from_slice(b"fipa:relay:v1\0");
+        b.push(self.hops);
+        put(&mut b, &self.via_pub);
+        put(&mut b, envelope_sig);
+        b
+    }
+
+    /// Verify the stamp belongs to the envelope signed with `envelope_sig`.
+    pub fn verify(&self, envelope_sig: &[u8]) -> bool {
+        let (Ok(pk), Ok(sg)) = (<[u8; 32]>::try_from(self.via_pub.as_slice()), <[u8; 64]>::try_from(self.sig.as_slice())) else {
+            return false;
+        };
+        adapters::verify(&pk, &self.signing_bytes(envelope_sig), &sg)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,7 +459,7 @@ mod tests {
     #[test]
     fn snapshot_sign_verify_roundtrip() {
         let k = NodeCrypto::generate();
-        let snap = AgentSnapshot::sealed("CTR", 1, vec![0xaa, 0xbb], vec![0, 0, 0, 7], b"{}".to_vec(), &k);
+        let snap = AgentSnapshot::sealed("CTR", 1, vec![0xaa, 0xbb], vec![0, 0, 0, 7], b"{}".to_vec(), Pending::default(), &k);
         assert!(snap.verify());
         let back = AgentSnapshot::decode(&snap.encode()).unwrap();
         assert!(back.verify());
@@ -219,14 +471,80 @@ mod tests {
     #[test]
     fn tampered_snapshot_is_rejected() {
         let k = NodeCrypto::generate();
-        let mut snap = AgentSnapshot::sealed("CTR", 1, vec![0xaa], vec![0, 0, 0, 7], b"{}".to_vec(), &k);
+        let mut snap = AgentSnapshot::sealed("CTR", 1, vec![0xaa], vec![0, 0, 0, 7], b"{}".to_vec(), Pending::default(), &k);
         snap.state = vec![9, 9, 9, 9]; // tamper with the state after signing
         assert!(!snap.verify());
-        let mut snap2 = AgentSnapshot::sealed("CTR", 1, vec![0xaa], vec![7], b"{}".to_vec(), &k);
+        let mut snap2 = AgentSnapshot::sealed("CTR", 1, vec![0xaa], vec![7], b"{}".to_vec(), Pending::default(), &k);
         snap2.code = vec![0xff]; // tamper with the code after signing
         assert!(!snap2.verify());
-        let mut snap3 = AgentSnapshot::sealed("CTR", 1, vec![0xaa], vec![7], b"{\"grants\":[]}".to_vec(), &k);
+        let mut snap3 = AgentSnapshot::sealed("CTR", 1, vec![0xaa], vec![7], b"{\"grants\":[]}".to_vec(), Pending::default(), &k);
         snap3.manifest = b"{\"grants\":[\"crypto\"]}".to_vec(); // tamper with the manifest
         assert!(!snap3.verify());
+        let pending = Pending { timers: vec![(7, 5_000)], requests: vec![(42, "hello".into())] };
+        let mut snap4 = AgentSnapshot::sealed("CTR", 1, vec![0xaa], vec![7], b"{}".to_vec(), pending, &k);
+        assert!(AgentSnapshot::decode(&snap4.encode()).unwrap().verify());
+        snap4.pending.timers[0].1 = 60_000; // stretch a carried deadline
+        assert!(!snap4.verify());
+    }
+
+    #[test]
+    fn handoff_binds_its_snapshot_and_window() {
+        let (k, dest) = (NodeCrypto::generate(), NodeCrypto::generate());
+        let snap = AgentSnapshot::sealed("CTR", 2, vec![0xaa], vec![7], b"{}".to_vec(), Pending::default(), &k);
+        let ho = Handoff::sealed("CTR", dest.public_key().to_vec(), 2, &snap.digest(), 1_000, &k);
+        assert!(ho.verify());
+        assert!(ho.valid_at(1_000) && ho.valid_at(1_000 + HANDOFF_TTL_MS));
+        assert!(!ho.valid_at(1_000 + HANDOFF_TTL_MS + CLOCK_SKEW_MS + 1)); // expired
+        let mut other = ho.clone();
+        other.snapshot_hash = AgentSnapshot::sealed("CTR", 2, vec![0xbb], vec![7], b"{}".to_vec(), Pending::default(), &k).digest();
+        assert!(!other.verify()); // cannot be re-pointed at another snapshot
+        let mut longer = ho.clone();
+        longer.naf = u64::MAX;
+        assert!(!longer.verify());
+    }
+
+    #[test]
+    fn move_decision_binds_origin_and_verdict() {
+        let (k, other) = (NodeCrypto::generate(), NodeCrypto::generate());
+        let d = MoveDecision::sealed("CTR", 2, true, "", &k);
+        assert!(d.verify(&k.public_key()));
+        assert!(!d.verify(&other.public_key())); // only the origin may settle the move
+        let mut flipped = MoveDecision::decode(&d.encode()).unwrap();
+        flipped.commit = false; // a COMMIT cannot be replayed as an ABORT, or vice versa
+        assert!(!flipped.verify(&k.public_key()));
+        let mut later = d.clone();
+        later.epoch = 3;
+        assert!(!later.verify(&k.public_key()));
+    }
+
+    #[test]
+    fn a_staged_clone_keeps_its_origin_apart_from_its_alias() {
+        let e = StagedMove {
+            role: MoveRole::Clone,
+            phase: MovePhase::Committing,
+            uuid: "CTR-2".into(),
+            epoch: 0,
+            peer_addr: "127.0.0.1:1".into(),
+            payload: Vec::new(),
+            since_ms: 1,
+            alias: "CTR-2".into(),
+            origin: "CTR".into(),
+        };
+        let back = StagedMove::decode(&e.encode()).unwrap();
+        assert_eq!((back.alias.as_str(), back.origin.as_str()), ("CTR-2", "CTR"));
+        // an entry logged before `origin` existed still reads back
+        let old = br#"{"role":"Source","phase":"Preparing","uuid":"u","epoch":1,"peer_addr":"a","payload":[],"since_ms":0}"#;
+        assert!(StagedMove::decode(old).unwrap().origin.is_empty());
+    }
+
+    #[test]
+    fn relay_stamp_binds_its_envelope_and_hop_count() {
+        let k = NodeCrypto::generate();
+        let r = Relay::sealed(1, &[7; 64], &k);
+        assert!(Relay::decode(&r.encode()).unwrap().verify(&[7; 64]));
+        assert!(!r.verify(&[8; 64])); // cannot be moved onto another message
+        let mut reset = r.clone();
+        reset.hops = 0;
+        assert!(!reset.verify(&[7; 64])); // nor its count wound back
     }
 }
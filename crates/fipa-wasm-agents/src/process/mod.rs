@@ -22,14 +22,16 @@ use anyhow::{anyhow, Result};
 use crate::wasm::{AgentRuntime, OutboundIntent};
 
 mod agents;
+mod attest;
 mod manage;
 mod migrate;
 mod node;
 mod resolve;
 mod router;
 pub use agents::native_agent;
+pub use attest::{Attestation, ChainError, Delegation, MAX_CHAIN_LINKS};
 pub use manage::{build_runtime, build_wasm, ManagedAgent, Profile, Recipe};
-pub use migrate::{AgentSnapshot, Handoff, MigratePayload};
+pub use migrate::{code_hash, AgentSnapshot, Handoff, MigratePayload, MoveDecision, MovePhase, MoveRole, StagedMove};
 pub use node::{AuditEvent, AuditSink, LlmBackend, Node, NodeMsg};
 pub use resolve::{resolve, Resolution};
 pub use router::{Envelope, Router};
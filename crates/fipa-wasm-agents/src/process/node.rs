@@ -33,34 +33,68 @@
 
 use std::collections::HashMap;
 use std::io;
-use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, Sender, SyncSender};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::adapters::{self, Engine, HostHooks, Limits, NodeCrypto, NodeNoise, NoiseSession, SledStore, StateStore};
-use crate::manifest::{Capability, Grant, Manifest, NodeProfile, Profile};
-use crate::wasm::{AgentRuntime, OutboundIntent, WasmRuntime, WasmiEngine};
+use crate::identity::{AgentId, Header};
+use crate::manifest::{Budget, Capability, Grant, Manifest, NodeProfile, Profile};
+use crate::wasm::{AgentRuntime, CallUsage, DeadlineExceeded, GuestLog, OutboundIntent, Recorder, WasmRuntime, WasmiEngine};
 use rand::RngCore;
 use std::collections::HashSet;
 use unl_agent::{InferReq, SpawnReq, TimerOp};
 
-use super::migrate::{code_hash, AgentSnapshot, Handoff, MigratePayload};
+use super::attest::{Attestation, ChainError, Delegation};
+use super::migrate::{
+    code_hash, AgentSnapshot, Handoff, MigratePayload, MoveDecision, MovePhase, MoveRole, Pending, Relay, StagedMove,
+    FORWARD_TTL_MS, MAX_FORWARD_HOPS,
+};
 
 const KIND_MSG: u8 = 1;
 const KIND_RESOLVE_REQ: u8 = 2;
 const KIND_RESOLVE_RESP: u8 = 3;
-const KIND_MIGRATE: u8 = 4;
+const KIND_MIGRATE: u8 = 4; // PREPARE: the signed move payload
 const KIND_CODE_FETCH: u8 = 5; // request a wasm module by content hash
 const KIND_CODE_BLOB: u8 = 6; // the module bytes (empty = unknown hash)
-const KIND_MIGRATE_ACK: u8 = 7; // destination confirms it mounted the migrated agent (prepared)
-const KIND_MIGRATE_COMMIT: u8 = 8; // source activates the prepared agent once it has tombstoned
+const KIND_MIGRATE_ACK: u8 = 7; // PREPARED: destination staged the agent (mounted, suspended)
+const KIND_MIGRATE_COMMIT: u8 = 8; // source's signed COMMIT decision
+const KIND_MIGRATE_COMMITTED: u8 = 9; // destination activated the staged agent
+const KIND_MIGRATE_ABORT: u8 = 10; // source: signed ABORT; destination: refused / rolled back
+const KIND_DELEGATION: u8 = 11; // an owner's signed Delegation: chain compaction or revocation
+const KIND_DELEGATION_ACK: u8 = 12; // [1] applied, [0] refused
+
+/// How long a destination keeps a staged agent waiting for the source's COMMIT
+/// before discarding it (MOBILITY §6). Generous next to the in-line exchange, so a
+/// source that restarts mid-move can still settle from its staging log.
+const STAGING_TIMEOUT_MS: u64 = 30_000;
+
+/// How often a source re-sends a COMMIT it logged but never saw confirmed, so an
+/// agent suspended by a lost COMMITTED is settled without waiting for a restart.
+const COMMIT_RETRY_MS: u64 = 5_000;
+
+/// The span of an agent's `fuel_per_min` budget window.
+const FUEL_WINDOW_MS: u64 = 60_000;
+
+/// How long an agent waits on an inference before the node expires the request and
+/// answers it with an error, so a backend that never replies cannot pile up
+/// outstanding requests (M5).
+const INFER_TIMEOUT_MS: u64 = 120_000;
 
 /// A short dial timeout bounds connect/read/write so a slow or hostile peer cannot
 /// stall a handler (R4; partial mitigation of `THREAT_MODEL.md` H3). The frame-size
 /// cap now lives in the Noise transport ([`crate::adapters::noise`]).
 const DIAL_TIMEOUT: Duration = Duration::from_secs(2);
+/// Reset runtimes kept warm per code hash for stateless agents.
+const MAX_WARM_PER_CODE: usize = 32;
+/// A reset stateless runtime kept warm, with the budget it was instantiated under.
+type WarmRuntime = (Budget, Box<dyn AgentRuntime + Send>);
+/// The UNL of a forwarder's redirect hint (MOBILITY §8), handled by the node
+/// rather than delivered to an agent.
+const MOVED_UNL: &[u8] = b"obj(moved, agent)";
 
 /// A message in flight between nodes. `from_addr` is the sender's return address;
 /// `nonce`/`sig`/`sender_pub` authenticate it (R1).
@@ -77,6 +111,16 @@ pub struct NodeMsg {
     pub sig: Vec<u8>,
     /// The signing node's public key (32 bytes when signed).
     pub sender_pub: Vec<u8>,
+    /// The sending agent's encoded [`Attestation`] when it has an owner (empty
+    /// otherwise): the chain that authorizes `sender_pub` to sign as `from`.
+    pub attest: Vec<u8>,
+    /// A forwarder's encoded [`Relay`] stamp when the message was relayed for an
+    /// agent that moved away (empty otherwise). Outside `sig`: each hop re-stamps it.
+    pub relay: Vec<u8>,
+    /// The address the sender dialed (empty for a sink-bound message). Signed, so a
+    /// message that arrives elsewhere is known to be relayed and must carry its
+    /// stamp — stripping the stamp cannot pass it off as direct.
+    pub to_addr: String,
 }
 
 // ── length-prefixed wire codec ──────────────────────────────────────────
@@ -111,11 +155,17 @@ fn encode_msg(m: &NodeMsg) -> Vec<u8> {
     put(&mut b, &m.nonce);
     put(&mut b, &m.sig);
     put(&mut b, &m.sender_pub);
+    put(&mut b, &m.attest);
+    put(&mut b, &m.relay);
+    put(&mut b, m.to_addr.as_bytes());
     b
 }
+/// Decode a `KIND_MSG` frame. The fields after `sender_pub` came later and are
+/// optional: a frame from an older node simply ends there, and decodes with them
+/// empty.
 fn decode_msg(p: &[u8]) -> Option<NodeMsg> {
     let mut i = 0;
-    Some(NodeMsg {
+    let mut m = NodeMsg {
         to: String::from_utf8(get(p, &mut i)?).ok()?,
         from: String::from_utf8(get(p, &mut i)?).ok()?,
         from_addr: String::from_utf8(get(p, &mut i)?).ok()?,
@@ -124,10 +174,18 @@ fn decode_msg(p: &[u8]) -> Option<NodeMsg> {
         nonce: get(p, &mut i)?,
         sig: get(p, &mut i)?,
         sender_pub: get(p, &mut i)?,
-    })
+        ..Default::default()
+    };
+    let mut optional = || if i == p.len() { Some(Vec::new()) } else { get(p, &mut i) };
+    m.attest = optional()?;
+    m.relay = optional()?;
+    m.to_addr = String::from_utf8(optional()?).ok()?;
+    Some(m)
 }
 
-/// The exact bytes covered by the signature: every field **except** `sig`.
+/// The exact bytes covered by the signature: every field **except** `sig` and the
+/// per-hop `relay` stamp. The later fields are bound only when set, so an older
+/// node's envelope (which has none) still verifies.
 fn signing_bytes(m: &NodeMsg) -> Vec<u8> {
     let mut b = Vec::new();
     put(&mut b, m.to.as_bytes());
@@ -137,6 +195,11 @@ fn signing_bytes(m: &NodeMsg) -> Vec<u8> {
     put(&mut b, &m.body);
     put(&mut b, &m.nonce);
     put(&mut b, &m.sender_pub);
+    if !m.attest.is_empty() || !m.to_addr.is_empty() {
+        b.extend_from_slice(b"fipa:msg:v2\0");
+        put(&mut b, &m.attest);
+        put(&mut b, m.to_addr.as_bytes());
+    }
     b
 }
 
@@ -152,6 +215,33 @@ fn dial(addr: &str) -> io::Result<TcpStream> {
     Ok(s)
 }
 
+/// Settle a staged move at `dest_addr` on a fresh connection (recovery).
+/// `Ok(true)` if the destination reports the agent live there, `Ok(false)` if it
+/// holds no staging for that epoch. Blocks for up to a dial plus the 8s reply
+/// wait, so the node only calls it off its serve loop.
+fn send_decision(noise: &NodeNoise, dest_addr: &str, decision: &MoveDecision) -> io::Result<bool> {
+    let mut s = dial(dest_addr)?;
+    s.set_read_timeout(Some(Duration::from_secs(8))).ok();
+    let mut sess = noise.connect(&mut s)?;
+    let kind = if decision.commit { KIND_MIGRATE_COMMIT } else { KIND_MIGRATE_ABORT };
+    sess.send(&mut s, kind, &decision.encode())?;
+    match sess.recv(&mut s)? {
+        (KIND_MIGRATE_COMMITTED, _) => Ok(true),
+        (KIND_MIGRATE_ABORT, _) => Ok(false),
+        _ => Err(io::Error::other("unexpected reply to a move decision")),
+    }
+}
+
+/// `addr` as the socket address a dial reaches, so a receiver compares addresses
+/// rather than spellings (a hostname, a bracketed IPv6). Left as is when it does
+/// not resolve.
+fn canonical_addr(addr: &str) -> String {
+    if let Ok(sa) = addr.parse::<SocketAddr>() {
+        return sa.to_string();
+    }
+    addr.to_socket_addrs().ok().and_then(|mut a| a.next()).map_or_else(|| addr.to_string(), |sa| sa.to_string())
+}
+
 /// A namespaced state handle: an agent's [`unl_agent::Kv`] confined to its own
 /// namespace (R8 — keys cannot escape) and bounded by a byte quota (M4).
 struct ScopedKv {
@@ -182,6 +272,15 @@ impl unl_agent::Kv for ScopedKv {
             self.used.store(self.used.load(Relaxed).saturating_sub(old), Relaxed);
         }
     }
+    fn keys(&self, prefix: &str) -> Vec<String> {
+        self.store.scan(&self.ns, prefix).map(|e| e.into_iter().map(|(k, _)| k).collect()).unwrap_or_default()
+    }
+    fn usage(&self) -> u64 {
+        self.used.load(std::sync::atomic::Ordering::Relaxed)
+    }
+    fn quota(&self) -> u64 {
+        self.quota
+    }
 }
 
 /// Domain tag separating agent-app signatures from the node's own envelope /
@@ -250,6 +349,12 @@ fn now_ms() -> u64 {
         .unwrap_or(0)
 }
 
+/// The engine caps a grant's budget implies (H3/R7): per-call fuel and the
+/// linear-memory ceiling.
+fn engine_limits(grant: &Grant) -> Limits {
+    Limits { fuel: grant.budget.fuel, mem_bytes: grant.budget.mem_kb.saturating_mul(1024) as usize }
+}
+
 /// Handle one accepted connection in its own thread (so a slow or hostile peer
 /// cannot stall the accept loop or the single-threaded agent executor, H3/R7):
 /// run the Noise handshake, read one frame, and hand it to the node's main loop —
@@ -261,7 +366,8 @@ fn handle_conn(
     in_tx: &SyncSender<NodeMsg>,
     rz_tx: &Sender<(String, Sender<String>)>,
     mg_tx: &Sender<(Vec<u8>, Sender<bool>)>,
-    mg_fin_tx: &Sender<(String, bool)>,
+    mg_fin_tx: &Sender<(MoveDecision, Sender<Option<bool>>)>,
+    dl_tx: &Sender<(Delegation, Sender<bool>)>,
     code_store: &Arc<Mutex<HashMap<String, Vec<u8>>>>,
     allow: Option<Arc<HashSet<Vec<u8>>>>,
 ) {
@@ -308,22 +414,31 @@ fn handle_conn(
                 // keep reading — the channel is persistent
             }
             KIND_MIGRATE => {
-                // Decode the uuid up front (payload is moved into the channel) so the
-                // finalize step can name the prepared agent.
-                let uuid = MigratePayload::decode(&payload).map(|mp| mp.snapshot.uuid).unwrap_or_default();
+                // PREPARE: the main loop verifies and stages the agent. PREPARED only if
+                // THIS payload staged a mount (H2); anything else is refused with ABORT.
                 let (resp_tx, resp_rx) = std::sync::mpsc::channel();
-                if mg_tx.send((payload, resp_tx)).is_ok() {
-                    if let Ok(true) = resp_rx.recv_timeout(Duration::from_secs(5)) {
-                        // Prepared — confirm so the source tombstones, then await the
-                        // COMMIT that activates us. No COMMIT (timeout/EOF) → abort.
-                        let _ = sess.send(&mut s, KIND_MIGRATE_ACK, b"");
-                        s.set_read_timeout(Some(Duration::from_secs(8))).ok();
-                        let commit = matches!(sess.recv(&mut s), Ok((KIND_MIGRATE_COMMIT, _)));
-                        let _ = mg_fin_tx.send((uuid, commit));
-                    }
+                let staged = mg_tx.send((payload, resp_tx)).is_ok()
+                    && matches!(resp_rx.recv_timeout(Duration::from_secs(5)), Ok(true));
+                if !staged {
+                    let _ = sess.send(&mut s, KIND_MIGRATE_ABORT, b"");
+                    return;
+                }
+                let _ = sess.send(&mut s, KIND_MIGRATE_ACK, b"");
+                // The decision normally follows on this connection. If it does not (the
+                // source crashed, the link dropped), the agent stays staged until the
+                // source settles it on a new connection or the staging window closes.
+                s.set_read_timeout(Some(Duration::from_secs(8))).ok();
+                if let Ok((kind @ (KIND_MIGRATE_COMMIT | KIND_MIGRATE_ABORT), body)) = sess.recv(&mut s) {
+                    settle_conn(&mut s, &mut sess, kind, &body, mg_fin_tx);
                 }
                 return; // one-shot
             }
+            KIND_MIGRATE_COMMIT | KIND_MIGRATE_ABORT => {
+                // A decision on a move staged earlier — a restarted source finishing
+                // (or undoing) it from its staging log.
+                settle_conn(&mut s, &mut sess, kind, &payload, mg_fin_tx);
+                return; // one-shot
+            }
             KIND_RESOLVE_REQ => {
                 let uuid = String::from_utf8_lossy(&payload).to_string();
                 let (resp_tx, resp_rx) = std::sync::mpsc::channel();
@@ -334,6 +449,16 @@ fn handle_conn(
                 }
                 return; // one-shot
             }
+            KIND_DELEGATION => {
+                // An owner's compaction or revocation: the main loop checks it
+                // against the chains and owners it knows and answers whether it took.
+                let (resp_tx, resp_rx) = std::sync::mpsc::channel();
+                let applied = serde_json::from_slice::<Delegation>(&payload).is_ok_and(|d| {
+                    dl_tx.send((d, resp_tx)).is_ok() && matches!(resp_rx.recv_timeout(DIAL_TIMEOUT), Ok(true))
+                });
+                let _ = sess.send(&mut s, KIND_DELEGATION_ACK, &[applied as u8]);
+                return; // one-shot
+            }
             KIND_CODE_FETCH => {
                 let hash = String::from_utf8_lossy(&payload).to_string();
                 let code = code_store.lock().unwrap_or_else(|e| e.into_inner()).get(&hash).cloned().unwrap_or_default();
@@ -345,6 +470,31 @@ fn handle_conn(
     }
 }
 
+/// Hand a source's COMMIT/ABORT to the main loop and answer with the outcome:
+/// COMMITTED if the agent is (now, or already) live here, ABORT if nothing is
+/// staged for that epoch. A malformed or mislabelled decision, or one not signed
+/// by the move's origin, gets no answer.
+fn settle_conn(
+    s: &mut TcpStream,
+    sess: &mut NoiseSession,
+    kind: u8,
+    body: &[u8],
+    mg_fin_tx: &Sender<(MoveDecision, Sender<Option<bool>>)>,
+) {
+    let Some(decision) = MoveDecision::decode(body) else { return };
+    if decision.commit != (kind == KIND_MIGRATE_COMMIT) {
+        return;
+    }
+    let (resp_tx, resp_rx) = std::sync::mpsc::channel();
+    if mg_fin_tx.send((decision, resp_tx)).is_err() {
+        return;
+    }
+    if let Ok(Some(committed)) = resp_rx.recv_timeout(Duration::from_secs(5)) {
+        let reply = if committed { KIND_MIGRATE_COMMITTED } else { KIND_MIGRATE_ABORT };
+        let _ = sess.send(s, reply, b"");
+    }
+}
+
 // ── the node ────────────────────────────────────────────────────────────
 
 /// One mounted agent: its identity, friendly alias, runtime, and offered service.
@@ -360,11 +510,21 @@ struct Mounted {
     active: bool,          // false while a migrated agent is prepared but not yet committed (H3/H4)
 }
 
+/// Where an agent this node handed on now lives (MOBILITY §8): stray messages for
+/// it are relayed there until `until_ms`, and each sender's node is hinted once.
+struct Forward {
+    addr: String,
+    handoff: Handoff,          // the move's handoff, proof for the redirect hint
+    until_ms: u64,
+    hinted: HashSet<String>,   // sender addresses already sent a redirect hint
+}
+
 /// A node: one **or more** local agents, a TCP address, a routing table, and the
 /// node's signing + Noise identities. Co-located agents exchange messages through
 /// an in-process work queue (the executor); only cross-node hops touch the wire.
 pub struct Node {
     addr: String,                        // my bind address (return address)
+    own_addrs: Vec<SocketAddr>,          // `addr` resolved, plus where `serve` listens: what a dialed `to_addr` may name
     label: String,                       // node label for logs (the primary alias)
     primary: String,                     // first-mounted agent uuid (kick/inject target)
     agents: HashMap<String, Mounted>,    // uuid -> mounted agent
@@ -373,31 +533,46 @@ pub struct Node {
     ams_addr: Option<String>,            // where to RESOLVE unknown UUIDs
     sink: Option<Sender<NodeMsg>>,       // undeliverable (e.g. "result")
     key: NodeCrypto,                     // this node's Ed25519 identity (signs/verifies)
+    key_persisted: bool,                 // `key` was loaded from disk, so it survives a restart
     keys: HashMap<String, [u8; 32]>,     // R3: from-uuid -> authorized node pubkey (TOFU)
+    chains: HashMap<String, Attestation>, // owned local agents: uuid -> attestation chain we sign under (mirrored to the store)
+    owners: HashMap<String, ([u8; 32], u64)>, // owned agents seen: uuid -> (pinned owner key, highest chain epoch)
     noise: NodeNoise,                    // R2: static Noise identity (encrypts the channel)
     kick_rx: Option<Receiver<(Vec<u8>, Vec<u8>)>>, // local, trusted kickoff injections
     seen: HashMap<String, u64>,          // migration replay guard: uuid -> last epoch
+    origins: HashMap<String, (u64, Vec<u8>)>, // uuid -> (epoch, origin key) of the last move staged here
     profile: NodeProfile,                // M2: which capabilities this node offers
     timers: HashMap<String, HashMap<u64, u64>>, // M3: uuid -> timer_id -> deadline_ms
     store: Option<Arc<SledStore>>,       // M4: durable state backend (state capability)
     llm: Option<Arc<dyn LlmBackend>>,    // M5: inference backend (llm capability)
     pending_infers: Vec<(String, u64, String)>, // M5: (agent, req_id, prompt) to run
+    outstanding: HashMap<String, HashMap<u64, (String, u64)>>, // M5: uuid -> req_id -> (prompt, expiry ms) awaiting a reply (moves with the agent)
     audit: Option<Arc<dyn AuditSink>>,   // M6: forensic event sink (log rich)
     faults: HashMap<String, u32>,        // M6: consecutive fault count per agent
     quarantined: HashSet<String>,        // M6: agents stopped after repeated faults
     conns: HashMap<String, (TcpStream, NoiseSession)>, // persistent KIND_MSG channels per peer
     code_store: Arc<Mutex<HashMap<String, Vec<u8>>>>,  // content-addressed wasm (CODE_FETCH)
     noise_allow: Option<HashSet<Vec<u8>>>, // C2a: if set, only these peer static keys may connect
-    prepared: HashMap<String, Handoff>, // migrated agents mounted-but-suspended, awaiting commit (H3)
+    prepared: HashMap<String, (Handoff, Option<Attestation>, Pending)>, // migrated agents mounted-but-suspended, awaiting commit (H3)
+    staging: HashMap<String, StagedMove>, // two-phase move log: uuid -> in-flight move (mirrored to the store)
+    resending: HashSet<String>,          // moves whose COMMIT re-send is out on a worker thread
+    resend_tx: Sender<(StagedMove, io::Result<bool>)>, // worker -> node: a re-sent COMMIT's answer
+    resend_rx: Receiver<(StagedMove, io::Result<bool>)>,
+    forwards: HashMap<String, Forward>,  // agents handed on: uuid -> forwarding entry (grace window)
     msg_window: HashMap<String, (u64, u32)>, // H5: per-agent egress rate window (start_ms, count)
+    fuel_window: HashMap<String, (u64, u64, bool)>, // per-agent fuel-per-minute window (start_ms, fuel spent, throttle audited)
     nonce_seen: HashSet<(String, Vec<u8>)>, // M5: (from, nonce) replay guard for wire messages
     nonce_order: std::collections::VecDeque<(String, Vec<u8>)>, // M5: eviction order for nonce_seen
+    warm: HashMap<String, Vec<WarmRuntime>>, // reset stateless runtimes, by code hash
+    record_dir: Option<PathBuf>,         // if set, wasm agents are recorded here for offline replay
 }
 
 impl Node {
     pub fn new(uuid: &str, alias: &str, addr: &str, agent: Box<dyn AgentRuntime + Send>) -> Self {
+        let (resend_tx, resend_rx) = std::sync::mpsc::channel();
         let mut node = Node {
             addr: addr.into(),
+            own_addrs: addr.to_socket_addrs().map(Iterator::collect).unwrap_or_default(),
             label: alias.into(),
             primary: uuid.into(),
             agents: HashMap::new(),
@@ -406,15 +581,20 @@ impl Node {
             ams_addr: None,
             sink: None,
             key: NodeCrypto::generate(),
+            key_persisted: false,
             keys: HashMap::new(),
+            chains: HashMap::new(),
+            owners: HashMap::new(),
             noise: NodeNoise::generate(),
             kick_rx: None,
             seen: HashMap::new(),
+            origins: HashMap::new(),
             profile: NodeProfile::normal(),
             timers: HashMap::new(),
             store: None,
             llm: None,
             pending_infers: Vec::new(),
+            outstanding: HashMap::new(),
             audit: None,
             faults: HashMap::new(),
             quarantined: HashSet::new(),
@@ -422,9 +602,17 @@ impl Node {
             code_store: Arc::new(Mutex::new(HashMap::new())),
             noise_allow: None,
             prepared: HashMap::new(),
+            staging: HashMap::new(),
+            resending: HashSet::new(),
+            resend_tx,
+            resend_rx,
+            forwards: HashMap::new(),
             msg_window: HashMap::new(),
+            fuel_window: HashMap::new(),
             nonce_seen: HashSet::new(),
             nonce_order: std::collections::VecDeque::new(),
+            warm: HashMap::new(),
+            record_dir: None,
         };
         node.mount(uuid, alias, agent, None);
         node
@@ -474,6 +662,11 @@ impl Node {
         self.profile = profile;
     }
 
+    /// This node's profile.
+    pub fn profile(&self) -> &NodeProfile {
+        &self.profile
+    }
+
     /// Whether `uuid`'s agent holds `cap` — the gate every host-call consults
     /// (M2). A `false` is the uniform, opaque `denied` at runtime.
     pub fn granted(&self, uuid: &str, cap: Capability) -> bool {
@@ -488,24 +681,47 @@ impl Node {
         for u in uuids {
             self.provision_state(&u);
         }
+        for chain in self.persisted_chains() {
+            self.chains.entry(chain.agent().to_string()).or_insert(chain);
+        }
     }
 
     /// If `uuid` holds the `State` capability and the node has a store, hand the
     /// agent a namespace-confined Kv handle (M4).
     fn provision_state(&mut self, uuid: &str) {
-        if !self.granted(uuid, Capability::State) {
-            return;
+        let Some(kv) = self.agents.get(uuid).and_then(|m| self.scoped_kv(uuid, &m.grant)) else { return };
+        if let Some(m) = self.agents.get_mut(uuid) {
+            m.runtime.set_state(kv);
+        }
+    }
+
+    /// The state store an agent under `grant` sees: its own namespace of the node
+    /// store, within its `state_kb` quota. `None` without the `state` capability or
+    /// a store.
+    fn scoped_kv(&self, uuid: &str, grant: &Grant) -> Option<Arc<ScopedKv>> {
+        if !grant.granted(Capability::State) {
+            return None;
         }
-        let Some(store) = self.store.clone() else { return };
-        let quota = self.agents.get(uuid).map(|m| m.grant.budget.state_kb.saturating_mul(1024)).unwrap_or(0);
-        let kv = Arc::new(ScopedKv {
+        let store = self.store.clone()?;
+        // Count what the namespace already holds (a restart or a migrated-in agent),
+        // so the quota bounds the durable total rather than this mount's writes.
+        let used: u64 = store.scan(uuid, "").map(|e| e.iter().map(|(_, n)| n).sum()).unwrap_or(0);
+        Some(Arc::new(ScopedKv {
             store,
             ns: uuid.to_string(),
-            used: Arc::new(std::sync::atomic::AtomicU64::new(0)),
-            quota,
-        });
-        if let Some(m) = self.agents.get_mut(uuid) {
-            m.runtime.set_state(kv);
+            used: Arc::new(std::sync::atomic::AtomicU64::new(used)),
+            quota: grant.budget.state_kb.saturating_mul(1024),
+        }))
+    }
+
+    /// Hand a not-yet-started runtime what `grant` entitles it to — its state store
+    /// and keyring — so its `init` and `restore` already see them.
+    fn provision(&self, uuid: &str, grant: &Grant, runtime: &mut (dyn AgentRuntime + Send)) {
+        if let Some(kv) = self.scoped_kv(uuid, grant) {
+            runtime.set_state(kv);
+        }
+        if grant.granted(Capability::Crypto) {
+            runtime.set_keyring(Arc::new(NodeKeyring { key: self.key.clone() }));
         }
     }
 
@@ -527,22 +743,110 @@ impl Node {
     }
 
     /// Queue an agent's inference requests (M5), gated by the `Llm` capability; the
-    /// serve loop runs them off-thread and delivers each result as a message.
-    fn apply_infer_reqs(&mut self, uuid: &str, reqs: Vec<InferReq>) {
+    /// serve loop runs them off-thread and delivers each result as a message, or an
+    /// error once [`INFER_TIMEOUT_MS`] passes without one. Returns the requests it
+    /// refused.
+    fn apply_infer_reqs(&mut self, uuid: &str, mut reqs: Vec<InferReq>) -> Vec<InferReq> {
         if reqs.is_empty() {
-            return;
+            return reqs;
         }
         if !self.granted(uuid, Capability::Llm) {
             crate::flow!("[{}] ⛔ infer denied for '{}' (no Llm grant)", self.label, uuid);
-            return;
+            return reqs;
         }
         const MAX_INFER_PER_CALL: usize = 16; // M2: bound a single agent's burst
         if reqs.len() > MAX_INFER_PER_CALL {
             self.audit(uuid, "denied:infer-burst", &format!("{} requests", reqs.len()));
         }
-        for r in reqs.into_iter().take(MAX_INFER_PER_CALL) {
+        let refused = reqs.split_off(reqs.len().min(MAX_INFER_PER_CALL));
+        let until = now_ms() + INFER_TIMEOUT_MS;
+        for r in reqs {
+            self.outstanding.entry(uuid.to_string()).or_default().insert(r.req_id, (r.prompt.clone(), until));
             self.pending_infers.push((uuid.to_string(), r.req_id, r.prompt));
         }
+        refused
+    }
+
+    /// Deliver the outcome of `uuid`'s inference `req_id`, if the agent still awaits
+    /// it. A reply the agent no longer awaits is stale: it moved on (the request
+    /// went with it), was unmounted (MOBILITY §8), or the request expired.
+    fn answer_infer(&mut self, uuid: &str, req_id: u64, text: &str) {
+        if self.outstanding.get_mut(uuid).and_then(|reqs| reqs.remove(&req_id)).is_none() {
+            crate::flow!("[{}] ⛔ stale reply {} for '{}' dropped", self.label, req_id, uuid);
+            return;
+        }
+        self.reply_infer(uuid, req_id, text);
+    }
+
+    /// Deliver an inference outcome as a message from `llm` (the async
+    /// reply-by-message model — the agent correlates by request_id).
+    fn reply_infer(&mut self, uuid: &str, req_id: u64, text: &str) {
+        let body = serde_json::json!({ "request_id": req_id, "text": text }).to_string();
+        self.pump(NodeMsg {
+            to: uuid.into(),
+            from: "llm".into(),
+            unl: b"obj(inferred, x)".to_vec(),
+            body: body.into_bytes(),
+            ..Default::default()
+        });
+    }
+
+    /// Answer every inference outstanding past its expiry with an error, so the
+    /// agent stops waiting and the entry is freed. A suspended agent's requests
+    /// wait — they travel in its snapshot.
+    fn expire_infers(&mut self, now: u64) {
+        let mut expired: Vec<(String, u64)> = Vec::new();
+        for (uuid, reqs) in &self.outstanding {
+            if !self.agents.get(uuid).is_some_and(|m| m.active) {
+                continue;
+            }
+            expired.extend(reqs.iter().filter(|(_, (_, until))| *until <= now).map(|(&id, _)| (uuid.clone(), id)));
+        }
+        for (uuid, req_id) in expired {
+            self.pending_infers.retain(|(agent, id, _)| !(*agent == uuid && *id == req_id)); // never ran
+            self.answer_infer(&uuid, req_id, "error: timed out");
+        }
+    }
+
+    /// The timers and requests `uuid` is waiting on, for its snapshot: each armed
+    /// timer as the ms it has left at `now`, each outstanding request with its
+    /// prompt (MOBILITY §8).
+    fn pending_of(&self, uuid: &str, now: u64) -> Pending {
+        let mut timers: Vec<(u64, u64)> = self
+            .timers
+            .get(uuid)
+            .map(|slots| slots.iter().map(|(&id, &deadline)| (id, deadline.saturating_sub(now))).collect())
+            .unwrap_or_default();
+        timers.sort_unstable();
+        let mut requests: Vec<(u64, String)> = self
+            .outstanding
+            .get(uuid)
+            .map(|reqs| reqs.iter().map(|(&id, (prompt, _))| (id, prompt.clone())).collect())
+            .unwrap_or_default();
+        requests.sort_unstable();
+        Pending { timers, requests }
+    }
+
+    /// Re-arm what an agent carried in its snapshot: each timer fires after its
+    /// remaining ms on this node's clock, and each outstanding request is issued
+    /// again — through the same grant and budget checks as the agent's own calls.
+    /// A request refused here was accepted where the agent made it, so the agent is
+    /// told with an error reply rather than left waiting.
+    fn rearm(&mut self, uuid: &str, pending: &Pending) {
+        let ops = pending.timers.iter().map(|&(id, delay_ms)| TimerOp::Set { id, delay_ms }).collect();
+        self.apply_timer_ops(uuid, ops);
+        let reqs = pending.requests.iter().map(|(id, prompt)| InferReq { req_id: *id, prompt: prompt.clone() }).collect();
+        for r in self.apply_infer_reqs(uuid, reqs) {
+            self.reply_infer(uuid, r.req_id, "error: denied");
+        }
+    }
+
+    /// Forget the timers and requests of an agent that left or was unmounted; an
+    /// inference still running for it finds no outstanding entry and is dropped.
+    fn drop_pending(&mut self, uuid: &str) {
+        self.timers.remove(uuid);
+        self.outstanding.remove(uuid);
+        self.pending_infers.retain(|(agent, _, _)| agent != uuid);
     }
 
     /// Set a forensic audit sink (M6). Events are recorded node-side; the agent
@@ -558,8 +862,27 @@ impl Node {
         }
     }
 
+    /// Emit an agent's log lines (already rate-limited by its runtime) as `tracing`
+    /// events and audit records, stamped with the agent's UUID — the `log`
+    /// capability is node-attributed, so lines cannot be forged or suppressed.
+    fn apply_logs(&self, uuid: &str, logs: Vec<GuestLog>) {
+        for line in logs {
+            line.emit(uuid);
+            let mut detail = line.message.clone();
+            if let Some(cid) = &line.conversation {
+                detail = format!("[{cid}] {detail}");
+            }
+            if !line.fields.is_empty() {
+                detail = format!("{detail} {}", line.fields_text());
+            }
+            self.audit(uuid, &format!("log:{}", line.level.as_str()), &detail);
+        }
+    }
+
     /// Supervisor (M6): track per-agent faults and quarantine an agent that faults
-    /// repeatedly, so a misbehaving agent is isolated, not the node.
+    /// repeatedly, so a misbehaving agent is isolated, not the node. A call cut off
+    /// at its wall-clock deadline (L3) is audited as `deadline` rather than `fault`,
+    /// but counts toward the same threshold.
     fn supervise(&mut self, uuid: &str, result: &anyhow::Result<()>) {
         const MAX_FAULTS: u32 = 3;
         match result {
@@ -572,7 +895,8 @@ impl Node {
                     *n += 1;
                     *n
                 };
-                self.audit(uuid, "fault", &e.to_string());
+                let kind = if e.downcast_ref::<DeadlineExceeded>().is_some() { "deadline" } else { "fault" };
+                self.audit(uuid, kind, &e.to_string());
                 if count >= MAX_FAULTS {
                     self.quarantined.insert(uuid.to_string());
                     self.audit(uuid, "quarantined", "fault threshold exceeded");
@@ -640,6 +964,62 @@ impl Node {
         true
     }
 
+    /// Cumulative CPU budget (`fuel_per_min`), on top of the per-call fuel cap: a
+    /// fixed one-minute window of the fuel an agent has burned. Once spent, the
+    /// agent's messages are dropped — and its ticks deferred — until the window
+    /// rolls over. Native/infra agents and a zero budget are unmetered.
+    fn fuel_allows(&mut self, uuid: &str) -> bool {
+        let limit = match self.agents.get(uuid) {
+            Some(m) if m.manifest.is_some() => m.grant.budget.fuel_per_min,
+            _ => return true,
+        };
+        if limit == 0 {
+            return true;
+        }
+        let now = now_ms();
+        let slot = self.fuel_window.entry(uuid.to_string()).or_insert((now, 0, false));
+        if now.saturating_sub(slot.0) >= FUEL_WINDOW_MS {
+            *slot = (now, 0, false);
+        }
+        slot.1 < limit
+    }
+
+    /// Bill an agent's drained call costs: per-agent metrics, and the fuel window
+    /// [`Self::fuel_allows`] checks.
+    fn apply_usage(&mut self, uuid: &str, usage: Vec<CallUsage>) {
+        let now = now_ms();
+        for u in usage {
+            crate::observability::record_agent_usage(uuid, u.entry, u.fuel, u.mem_bytes);
+            let slot = self.fuel_window.entry(uuid.to_string()).or_insert((now, 0, false));
+            slot.1 = slot.1.saturating_add(u.fuel);
+        }
+    }
+
+    /// Forget what an agent that left this node cost: its fuel window and its
+    /// per-agent metric series.
+    fn drop_usage(&mut self, uuid: &str) {
+        self.fuel_window.remove(uuid);
+        crate::observability::forget_agent(uuid);
+    }
+
+    /// Whether an agent has spent its fuel budget for the minute, in which case the
+    /// caller holds its input back. Every held input is counted; the throttle is
+    /// audited once per window, so a flood of input cannot flood the audit trail.
+    fn fuel_denied(&mut self, uuid: &str, what: &str) -> bool {
+        if self.fuel_allows(uuid) {
+            return false;
+        }
+        crate::observability::record_fuel_throttled(uuid);
+        if let Some(slot) = self.fuel_window.get_mut(uuid)
+            && !slot.2
+        {
+            slot.2 = true;
+            self.audit(uuid, "denied:fuel-budget", what);
+            crate::flow!("[{}] ⛔ fuel budget spent for '{}' — holding its input for the minute", self.label, uuid);
+        }
+        true
+    }
+
     /// Mount a **mobile wasm agent** from its module bytes + `manifest` (HEAD) — only
     /// wasm agents move (native agents are stationary, host-instantiated templates).
     /// The manifest is fit against the node profile (M2 load-time gate); on success
@@ -657,7 +1037,16 @@ impl Node {
             .profile
             .fit(manifest)
             .map_err(|e| anyhow::anyhow!("manifest does not fit node profile: {e:?}"))?;
-        let runtime = self.instantiate_agent(&code, &grant)?;
+        let runtime = match self.take_warm(&code, &grant, manifest) {
+            Some(rt) => rt,
+            None => self.instantiate_agent(&code, &grant)?,
+        };
+        let mut runtime = self.recorded(uuid, &code, &grant, runtime);
+        self.provision(uuid, &grant, &mut *runtime);
+        let init = runtime.init();
+        self.apply_logs(uuid, runtime.take_logs());
+        self.apply_usage(uuid, runtime.take_usage());
+        init?;
         self.aliases.insert(alias.into(), uuid.into());
         self.agents.insert(
             uuid.into(),
@@ -673,24 +1062,145 @@ impl Node {
                 active: true,
             },
         );
-        self.provision_state(uuid);
-        self.provision_crypto(uuid);
         Ok(())
     }
 
-    /// Instantiate (and `init`) a wasm agent under `grant`, selecting the engine by
+    /// Replace a running agent's code in place, carrying its state over: the current
+    /// runtime is snapshotted, the new module is instantiated, `init`ed and handed the
+    /// snapshot via `restore`, and only then swapped in. `manifest` is fit against the
+    /// node profile and must be [`Grant::within`] the agent's current grant — an
+    /// upgrade never widens authority. Any failure leaves the old runtime mounted and
+    /// untouched. A native agent (DF/AMS/PA) may be upgraded to a wasm build of the
+    /// same `Agent`, whose snapshot format it shares.
+    ///
+    /// An owned agent keeps its owner, and its chain names the code it runs: its
+    /// upgrade needs `delegation`, the owner's [`Delegation`] of the new code to this
+    /// node, which compacts the chain once the new code is in. An unowned agent
+    /// takes none, and an upgrade cannot give it an owner.
+    pub fn upgrade(
+        &mut self,
+        uuid: &str,
+        code: Vec<u8>,
+        manifest: &Manifest,
+        delegation: Option<Delegation>,
+    ) -> anyhow::Result<()> {
+        let Some(current) = self.agents.get(uuid) else {
+            anyhow::bail!("no local agent '{uuid}'");
+        };
+        if !current.active {
+            anyhow::bail!("'{uuid}' is not live (a migration is pending)");
+        }
+        let owner = current.manifest.as_ref().and_then(Manifest::owner_key);
+        let hash = code_hash(&code);
+        let chain = match (owner, delegation) {
+            (Some(o), _) if manifest.owner_key() != Some(o) => {
+                self.audit(uuid, "upgrade:wrong-owner", manifest.owner.as_deref().unwrap_or(""));
+                anyhow::bail!("upgrade names another owner for '{uuid}'");
+            }
+            (Some(_), None) => anyhow::bail!("'{uuid}' is owned: upgrading needs its owner's delegation"),
+            (Some(o), Some(d)) => match self.delegated_chain(uuid, d, Some(o), Some(&hash)) {
+                Ok(chain) => Some(chain),
+                Err(e) => {
+                    self.audit(uuid, "upgrade:chain", &e.to_string());
+                    anyhow::bail!("upgrade of '{uuid}' not delegated: {e}");
+                }
+            },
+            (None, None) if manifest.owner_key().is_none() => None,
+            (None, _) => anyhow::bail!("'{uuid}' is not owned, and an upgrade cannot give it an owner"),
+        };
+        let grant = self
+            .profile
+            .fit(manifest)
+            .map_err(|e| anyhow::anyhow!("manifest does not fit node profile: {e:?}"))?;
+        if let Err(e) = grant.within(&current.grant) {
+            self.audit(uuid, "upgrade:escalation", &format!("{e:?}"));
+            anyhow::bail!("upgrade would widen the agent's grant: {e:?}");
+        }
+        let state = match self.agents.get_mut(uuid) {
+            Some(m) => m.runtime.snapshot(),
+            None => Vec::new(),
+        };
+        let started = self.instantiate_agent(&code, &grant).and_then(|rt| {
+            let mut rt = self.recorded(uuid, &code, &grant, rt);
+            self.provision(uuid, &grant, &mut *rt);
+            let started = rt.init().and_then(|()| rt.restore(&state));
+            self.apply_logs(uuid, rt.take_logs());
+            self.apply_usage(uuid, rt.take_usage());
+            started.map(|()| rt)
+        });
+        let runtime = match started {
+            Ok(rt) => rt,
+            Err(e) => {
+                self.audit(uuid, "upgrade:failed", &e.to_string());
+                return Err(e.context("upgrade rolled back"));
+            }
+        };
+        if let Some(m) = self.agents.get_mut(uuid) {
+            m.runtime = runtime;
+            m.code = Some(code);
+            m.grant = grant;
+            m.manifest = Some(manifest.clone());
+        }
+        if let Some(chain) = chain {
+            self.adopt_chain(chain);
+        }
+        self.faults.remove(uuid);
+        self.audit(uuid, "upgraded", &hash);
+        Ok(())
+    }
+
+    /// Remove a local agent whose work is done. The runtime of a `stateless` wasm
+    /// agent is reset and kept warm, so the next mount of the same code under the
+    /// same budget skips instantiation; anything else is dropped. The primary agent
+    /// cannot be unmounted.
+    pub fn unmount(&mut self, uuid: &str) -> bool {
+        if uuid == self.primary {
+            return false;
+        }
+        let Some(mut m) = self.agents.remove(uuid) else {
+            return false;
+        };
+        self.aliases.remove(&m.alias);
+        self.drop_pending(uuid);
+        self.faults.remove(uuid);
+        self.msg_window.remove(uuid);
+        self.drop_usage(uuid);
+        self.drop_chain(uuid);
+        // a quarantined agent's instance is never handed to the next tenant
+        let reusable = !self.quarantined.remove(uuid) && m.manifest.as_ref().is_some_and(|man| man.stateless);
+        if let (true, Some(code)) = (reusable, &m.code) {
+            let pool = self.warm.entry(code_hash(code)).or_default();
+            if pool.len() < MAX_WARM_PER_CODE && matches!(m.runtime.reset(), Ok(true)) {
+                pool.push((m.grant.budget.clone(), m.runtime));
+            }
+        }
+        self.audit(uuid, "unmounted", &m.alias);
+        true
+    }
+
+    /// A warm runtime for a stateless agent with this code and budget, if one is pooled.
+    fn take_warm(&mut self, code: &[u8], grant: &Grant, manifest: &Manifest) -> Option<Box<dyn AgentRuntime + Send>> {
+        if !manifest.stateless {
+            return None;
+        }
+        let pool = self.warm.get_mut(&code_hash(code))?;
+        let i = pool.iter().position(|(budget, _)| *budget == grant.budget)?;
+        Some(pool.swap_remove(i).1)
+    }
+
+    /// Instantiate a wasm agent under `grant` (not yet `init`ed), selecting the engine by
     /// node profile: the wasmi interpreter on an IoT node, wasmtime otherwise — the
     /// same agent ABI runs on either (E2). The wasm engine caps are derived from the
     /// granted budget, so both `mount_wasm` and the migration path are sandboxed by
-    /// the *fitted* budget rather than defaults (audit H1).
+    /// the *fitted* budget rather than defaults (audit H1). wasmtime also enforces the
+    /// per-call `wall_ms` deadline (L3); the wasmi interpreter is bounded by fuel alone.
     fn instantiate_agent(&self, code: &[u8], grant: &Grant) -> anyhow::Result<Box<dyn AgentRuntime + Send>> {
+        let component = crate::wasm::is_component(code);
         if self.profile.profile == Profile::Iot {
-            let limits = Limits {
-                fuel: grant.budget.fuel,
-                mem_bytes: grant.budget.mem_kb.saturating_mul(1024) as usize,
-            };
-            let mut m = WasmiEngine.instantiate(code, limits, HostHooks::default())?;
-            m.init()?;
+            if component {
+                anyhow::bail!("component agents need the wasmtime (normal) profile");
+            }
+            let m = WasmiEngine.instantiate(code, engine_limits(grant), HostHooks::default())?;
             Ok(Box::new(m))
         } else {
             let caps = crate::proto::AgentCapabilities {
@@ -699,16 +1209,55 @@ impl Node {
                 storage_quota_bytes: grant.budget.state_kb.saturating_mul(1024),
                 ..Default::default()
             };
+            if component {
+                let mut rt = crate::wasm::ComponentRuntime::new(code, &caps)?;
+                rt.set_deadline(grant.budget.wall_ms);
+                return Ok(Box::new(rt));
+            }
             let mut rt = WasmRuntime::new(code, &caps)?;
-            rt.call_init()?;
+            rt.set_deadline(grant.budget.wall_ms);
             Ok(Box::new(rt))
         }
     }
 
+    /// Record every wasm agent mounted from now on to `dir/<uuid>.<code>.trace` (the
+    /// first 12 hex digits of its module hash, so an upgraded agent starts a new
+    /// trace) — its inputs, host-call results and emitted intents — so a misbehaving
+    /// agent can be replayed offline against the same module (`fipa-cli replay`). Recording costs
+    /// a trace write per call and keeps the agent's runtime out of the warm pool.
+    pub fn set_record_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.record_dir = Some(dir.into());
+    }
+
+    /// Wrap a freshly instantiated core-module runtime in a [`Recorder`] when
+    /// recording is on. A trace that can't be opened is audited and the agent runs
+    /// unrecorded; components are not recordable.
+    fn recorded(
+        &self,
+        uuid: &str,
+        code: &[u8],
+        grant: &Grant,
+        runtime: Box<dyn AgentRuntime + Send>,
+    ) -> Box<dyn AgentRuntime + Send> {
+        let Some(dir) = &self.record_dir else { return runtime };
+        if crate::wasm::is_component(code) {
+            return runtime;
+        }
+        let path = dir.join(format!("{uuid}.{}.trace", &code_hash(code)[..12]));
+        match std::fs::create_dir_all(dir).and_then(|()| std::fs::File::create(&path)) {
+            Ok(f) => Box::new(Recorder::new(runtime, code, engine_limits(grant), Box::new(io::BufWriter::new(f)))),
+            Err(e) => {
+                self.audit(uuid, "record:failed", &format!("{}: {e}", path.display()));
+                runtime
+            }
+        }
+    }
+
     /// Use a persisted node key at `path` (mint+persist on first run) instead of an
     /// ephemeral one — so a node keeps its signing identity across restarts.
     pub fn load_key(&mut self, path: impl AsRef<std::path::Path>) -> io::Result<()> {
         self.key = NodeCrypto::load_or_mint(path)?;
+        self.key_persisted = true;
         Ok(())
     }
 
@@ -771,6 +1320,119 @@ impl Node {
         self.key.public_key()
     }
 
+    /// The key the module cache seals artifacts under — derived from the node
+    /// key, so a file planted in the cache directory can't pass as ours.
+    pub fn cache_key(&self) -> [u8; 32] {
+        self.key.derive(b"fipa:modcache:v1")
+    }
+
+    /// Install an owner's [`Delegation`] for a mounted agent (MOBILITY §7): the root
+    /// of its attestation chain, or — at an epoch no lower than the chain has
+    /// reached — a compaction that replaces the chain. The agent's manifest must
+    /// name the delegating owner, and the delegation must cover the agent's current
+    /// code and authorize this node. From then on the agent's messages and moves
+    /// carry the chain.
+    pub fn delegate(&mut self, delegation: Delegation) -> Result<(), ChainError> {
+        let agent = delegation.agent.clone();
+        let Some(m) = self.agents.get(&agent) else { return Err(ChainError::WrongAgent(0)) };
+        let owner = m.manifest.as_ref().and_then(Manifest::owner_key);
+        let code = m.code.as_deref().map(code_hash);
+        let chain = self.delegated_chain(&agent, delegation, owner, code.as_deref())?;
+        self.adopt_chain(chain);
+        Ok(())
+    }
+
+    /// The chain `delegation` gives `agent`, owned by `owner` and running the code
+    /// hashed `code` here: checked against both and this node, then compacted into
+    /// the chain held, or its new root.
+    fn delegated_chain(
+        &self,
+        agent: &str,
+        delegation: Delegation,
+        owner: Option<[u8; 32]>,
+        code: Option<&str>,
+    ) -> Result<Attestation, ChainError> {
+        if delegation.agent != agent {
+            return Err(ChainError::WrongAgent(0));
+        }
+        if owner.is_none_or(|o| o.as_slice() != delegation.owner_pub)
+            || self.owners.get(agent).is_some_and(|(o, _)| o.as_slice() != delegation.owner_pub)
+        {
+            return Err(ChainError::WrongOwner);
+        }
+        if code != Some(delegation.code_hash.as_str()) {
+            return Err(ChainError::WrongCode);
+        }
+        if delegation.to_node != self.key.public_key() {
+            return Err(ChainError::NotHolder);
+        }
+        match self.chains.get(agent).cloned() {
+            Some(mut chain) => {
+                chain.compact(delegation, now_ms())?;
+                Ok(chain)
+            }
+            None => {
+                let chain = Attestation::new(delegation);
+                chain.verify(now_ms())?;
+                Ok(chain)
+            }
+        }
+    }
+
+    /// Sign as the agent under `chain` from now on.
+    fn adopt_chain(&mut self, chain: Attestation) {
+        if let Some(m) = self.agents.get_mut(chain.agent()) {
+            m.epoch = m.epoch.max(chain.epoch()); // the next move must advance past it
+        }
+        self.note_owner(chain.agent(), chain.owner(), chain.epoch());
+        self.install_chain(chain);
+    }
+
+    /// Record an owner's [`Delegation`] published for an agent, wherever it runs
+    /// (MOBILITY §7 revocation): from now on chains for the agent below its epoch
+    /// are refused, so a node the owner delegated past can no longer sign as it.
+    pub fn revoke(&mut self, delegation: &Delegation) -> Result<(), ChainError> {
+        if !delegation.verify() {
+            return Err(ChainError::BadSignature(0));
+        }
+        if self.owners.get(&delegation.agent).is_some_and(|(o, _)| o.as_slice() != delegation.owner_pub) {
+            return Err(ChainError::WrongOwner);
+        }
+        self.note_owner(&delegation.agent, &delegation.owner_pub, delegation.epoch);
+        Ok(())
+    }
+
+    /// An owner's [`Delegation`] published to this node (`KIND_DELEGATION`): one to
+    /// this node for an agent mounted here compacts its chain ([`Node::delegate`]);
+    /// any other is recorded as a revocation ([`Node::revoke`]).
+    pub fn accept_delegation(&mut self, delegation: Delegation) -> Result<(), ChainError> {
+        let agent = delegation.agent.clone();
+        let outcome = if self.agents.contains_key(&agent) && delegation.to_node == self.key.public_key() {
+            self.delegate(delegation).map(|()| "compacted")
+        } else {
+            self.revoke(&delegation).map(|()| "revoked")
+        };
+        match &outcome {
+            Ok(kind) => self.audit(&agent, kind, ""),
+            Err(e) => self.audit(&agent, "delegation:refused", &e.to_string()),
+        }
+        outcome.map(|_| ())
+    }
+
+    /// Publish an owner's [`Delegation`] to the node at `addr` — the node holding
+    /// the agent's chain (a compaction), or any node that may hear from it (a
+    /// revocation). `Ok(true)` if the node applied it, `Ok(false)` if it refused it.
+    pub fn publish_delegation(&self, addr: &str, delegation: &Delegation) -> io::Result<bool> {
+        let mut s = dial(addr)?;
+        let mut sess = self.noise.connect(&mut s)?;
+        let body = serde_json::to_vec(delegation).map_err(io::Error::other)?;
+        sess.send(&mut s, KIND_DELEGATION, &body)?;
+        match sess.recv(&mut s)? {
+            (KIND_DELEGATION_ACK, ack) => Ok(ack == [1]),
+            _ => Err(io::Error::other("unexpected reply to a delegation")),
+        }
+    }
+
     /// Cache a wasm module by its content hash (so this node can serve CODE_FETCH).
     pub fn cache_code(&self, code: Vec<u8>) {
         self.code_store.lock().unwrap_or_else(|e| e.into_inner()).insert(code_hash(&code), code);
@@ -791,8 +1453,9 @@ impl Node {
     }
 
     /// Build the signed move payload (snapshot of code+state at epoch+1, plus a
-    /// handoff authorizing `dest_pub`) for a mobile agent — without sending it. The
-    /// code is cached so the destination can CODE_FETCH it.
+    /// handoff authorizing `dest_pub` and, for an owned agent, its attestation chain)
+    /// for a mobile agent — without sending it. The code is cached so the
+    /// destination can CODE_FETCH it.
     pub fn build_migrate_payload(&mut self, uuid: &str, dest_pub: &[u8]) -> Option<Vec<u8>> {
         let (code, epoch, state, manifest_json) = {
             let m = self.agents.get_mut(uuid)?;
@@ -803,74 +1466,260 @@ impl Node {
             (code, epoch, m.runtime.snapshot(), manifest_json)
         };
         self.cache_code(code.clone());
-        let snapshot = AgentSnapshot::sealed(uuid, epoch, code, state, manifest_json, &self.key);
-        let handoff = Handoff::sealed(uuid, dest_pub.to_vec(), epoch, &self.key);
-        Some(MigratePayload { snapshot, handoff, from_addr: self.addr.clone() }.encode())
+        let pending = self.pending_of(uuid, now_ms());
+        let snapshot = AgentSnapshot::sealed(uuid, epoch, code, state, manifest_json, pending, &self.key);
+        let handoff = Handoff::sealed(uuid, dest_pub.to_vec(), epoch, &snapshot.digest(), now_ms(), &self.key);
+        let chain = self.chains.get(uuid).cloned();
+        Some(MigratePayload { snapshot, handoff, from_addr: self.addr.clone(), chain }.encode())
     }
 
     /// Migrate a mobile (wasm) agent to `dest_addr`, authorizing `dest_pub` to act
-    /// for it: send the signed snapshot + handoff over Noise, then tombstone the
-    /// local copy. The epoch arbiter (R6) + handoff prevent forking (H1); full
-    /// two-phase crash-safety is the remaining hardening.
+    /// for it, as a two-phase move (MOBILITY §6): PREPARE (signed snapshot + handoff)
+    /// → PREPARED → COMMIT → COMMITTED, then tombstone the local copy. Each phase is
+    /// written to the staging log before it is acted on, so
+    /// [`Node::recover_migrations`] can finish or undo the move after a crash. The
+    /// agent is suspended throughout and live on exactly one node: before the COMMIT
+    /// decision any failure resumes the local copy; after it, the copy stays
+    /// suspended until the destination reports the outcome (H4).
     pub fn migrate(&mut self, uuid: &str, dest_addr: &str, dest_pub: &[u8]) -> io::Result<()> {
         let payload = self
             .build_migrate_payload(uuid, dest_pub)
             .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "agent not mobile / absent"))?;
-        // Suspend the local agent for the whole handoff so it never runs concurrently
-        // with the destination's (prepared) copy — no double execution / fork (H4).
-        if let Some(m) = self.agents.get_mut(uuid) {
-            m.active = false;
-        }
-        match self.send_migration(dest_addr, &payload) {
-            Ok(()) => {
-                // The destination has prepared the agent and we have told it to
-                // commit; tombstone the local copy (no loss — see send_migration).
-                if let Some(m) = self.agents.remove(uuid) {
-                    self.aliases.remove(&m.alias);
-                }
+        let (epoch, alias) = match self.agents.get_mut(uuid) {
+            Some(m) => {
+                m.active = false;
+                (m.epoch, m.alias.clone())
+            }
+            None => (0, uuid.to_string()),
+        };
+        self.log_move(StagedMove {
+            role: MoveRole::Source,
+            phase: MovePhase::Preparing,
+            uuid: uuid.into(),
+            epoch,
+            peer_addr: dest_addr.into(),
+            payload: payload.clone(),
+            since_ms: now_ms(),
+            alias,
+            origin: String::new(),
+        });
+        let result = self.send_migration(uuid, epoch, dest_addr, &payload);
+        let decided = self.staging.get(uuid).is_some_and(|e| e.phase == MovePhase::Committing);
+        match result {
+            Ok(true) => {
+                self.start_forwarding(uuid, dest_addr, &payload);
+                self.tombstone(uuid);
                 Ok(())
             }
+            Ok(false) => {
+                // Refused, or the staging window closed before our COMMIT: the
+                // destination holds no live copy, so ours resumes.
+                self.revive(uuid);
+                Err(io::Error::other("migration aborted by the destination"))
+            }
+            Err(e) if decided => {
+                // COMMIT sent but unconfirmed: the destination may already run the
+                // agent, so ours stays suspended until a COMMIT retry from the serve
+                // loop (or recover_migrations) learns the outcome.
+                crate::flow!("[{}] ⚠ migrate: commit of '{}' unconfirmed: {e}", self.label, uuid);
+                Err(e)
+            }
             Err(e) => {
-                // The move did not complete: resume the local agent (no loss, no fork —
-                // the destination never activates without our COMMIT).
-                if let Some(m) = self.agents.get_mut(uuid) {
-                    m.active = true;
-                }
+                // No COMMIT decided — the destination never activates; resume ours.
+                self.revive(uuid);
+                Err(e)
+            }
+        }
+    }
+
+    /// Clone a mobile (wasm) agent to `dest_addr` (MOBILITY §2–§3): the copy starts
+    /// from a snapshot of the agent's current state under a freshly minted instance
+    /// UUID of the same type, with the original appended to its manifest's
+    /// `lineage`, and registers itself with AMS when the destination activates it.
+    /// The copy takes none of the original's timers or outstanding requests, and
+    /// the original keeps running. Returns the clone's UUID.
+    ///
+    /// The copy travels the same PREPARE/PREPARED/COMMIT exchange as a move, staged
+    /// in the log under the copy's UUID; the original is never suspended. A clone
+    /// that fails before the COMMIT leaves nothing behind. One whose COMMITTED is
+    /// lost may already be live at the destination, so its COMMIT stays logged and
+    /// is retried like a move's, and a further clone of the same agent is refused
+    /// until it settles — a retry cannot mint a second instance. An owned agent is
+    /// refused — a new identity needs its own Delegation, so its owner clones it.
+    pub fn clone_agent(&mut self, uuid: &str, dest_addr: &str, dest_pub: &[u8]) -> io::Result<String> {
+        let m = self
+            .agents
+            .get(uuid)
+            .filter(|m| m.code.is_some())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "agent not mobile / absent"))?;
+        if m.manifest.as_ref().is_some_and(|man| man.owner.is_some()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "an owned agent is cloned by its owner"));
+        }
+        if let Some(e) = self.staging.values().find(|e| e.role == MoveRole::Clone && e.origin == uuid) {
+            return Err(io::Error::other(format!("clone '{}' of '{}' is still unconfirmed", e.uuid, uuid)));
+        }
+        let (clone, payload) = self
+            .build_clone_payload(uuid, dest_pub)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "agent not mobile / absent"))?;
+        self.log_move(StagedMove {
+            role: MoveRole::Clone,
+            phase: MovePhase::Preparing,
+            uuid: clone.clone(),
+            epoch: 0,
+            peer_addr: dest_addr.into(),
+            payload: payload.clone(),
+            since_ms: now_ms(),
+            alias: clone.clone(),
+            origin: uuid.into(),
+        });
+        let result = self.send_migration(&clone, 0, dest_addr, &payload);
+        let decided = self.staging.get(&clone).is_some_and(|e| e.phase == MovePhase::Committing);
+        match result {
+            Ok(true) => {
+                self.unlog_move(&clone);
+                self.note_cloned(uuid, &clone, dest_addr);
+                Ok(clone)
+            }
+            Ok(false) => {
+                self.unlog_move(&clone);
+                Err(io::Error::other("clone refused by the destination"))
+            }
+            Err(e) if decided => {
+                // The copy may already run at the destination: keep the COMMIT logged
+                // for the serve loop (or recover_migrations) to settle.
+                crate::flow!("[{}] ⚠ clone: commit of '{}' unconfirmed: {e}", self.label, clone);
+                Err(io::Error::other(format!("clone '{clone}' of '{uuid}' committed but unconfirmed: {e}")))
+            }
+            Err(e) => {
+                self.unlog_move(&clone);
                 Err(e)
             }
         }
     }
 
-    /// The migration exchange (source side). Send the signed payload, await the
-    /// destination's prepare-ACK with a timeout **strictly greater** than the
-    /// destination's mount budget so a busy destination cannot time us out into a
-    /// fork (H3), then send the COMMIT that activates the prepared agent. Returns
-    /// `Ok` only once the destination has acknowledged the prepared mount; the
-    /// caller then tombstones. On any error the caller resumes the local agent, and
-    /// the destination — having received no COMMIT — aborts its prepared copy.
-    fn send_migration(&self, dest_addr: &str, payload: &[u8]) -> io::Result<()> {
+    /// Record that `uuid` now has a live copy `clone` at `dest_addr`.
+    fn note_cloned(&mut self, uuid: &str, clone: &str, dest_addr: &str) {
+        self.audit(uuid, "cloned", clone);
+        crate::flow!("[{}] ⎘ '{}' cloned as '{}' to {}", self.label, uuid, clone, dest_addr);
+    }
+
+    /// Build the signed payload for a clone of `uuid`: its code and current state
+    /// under a fresh instance UUID at epoch 0, the manifest extended with the
+    /// lineage, and a handoff authorizing `dest_pub`. Returns the clone's UUID too.
+    fn build_clone_payload(&mut self, uuid: &str, dest_pub: &[u8]) -> Option<(String, Vec<u8>)> {
+        let m = self.agents.get_mut(uuid)?;
+        let code = m.code.clone()?;
+        let mut manifest = m.manifest.clone()?;
+        let state = m.runtime.snapshot();
+        let header = Header { type_id: manifest.type_id, desc: manifest.desc.clone(), name: manifest.name.clone() };
+        let clone = AgentId::spawn(&header).id();
+        manifest.lineage.push(uuid.to_string());
+        self.cache_code(code.clone());
+        let snapshot = AgentSnapshot::sealed(&clone, 0, code, state, manifest.to_json(), Pending::default(), &self.key);
+        let handoff = Handoff::sealed(&clone, dest_pub.to_vec(), 0, &snapshot.digest(), now_ms(), &self.key);
+        let payload = MigratePayload { snapshot, handoff, from_addr: self.addr.clone(), chain: None };
+        Some((clone, payload.encode()))
+    }
+
+    /// The two-phase exchange (source side). Send PREPARE and await PREPARED with a
+    /// timeout **strictly greater** than the destination's mount budget so a busy
+    /// destination cannot time us out into a fork (H3); then log the COMMIT decision
+    /// and send it (a clone has no log entry to update). `Ok(true)` on COMMITTED,
+    /// `Ok(false)` if the destination refused the PREPARE or rolled back; an error
+    /// leaves the outcome to the caller.
+    fn send_migration(&mut self, uuid: &str, epoch: u64, dest_addr: &str, payload: &[u8]) -> io::Result<bool> {
         let mut s = dial(dest_addr)?;
         s.set_read_timeout(Some(Duration::from_secs(8))).ok(); // > the destination's 5s wait (H3)
         let mut sess = self.noise.connect(&mut s)?;
         sess.send(&mut s, KIND_MIGRATE, payload)?;
-        let (kind, _) = sess.recv(&mut s)?;
-        if kind != KIND_MIGRATE_ACK {
-            return Err(io::Error::new(io::ErrorKind::Other, "migration not acknowledged"));
+        match sess.recv(&mut s) {
+            Ok((KIND_MIGRATE_ACK, _)) => {}
+            Ok((KIND_MIGRATE_ABORT, _)) => return Ok(false),
+            other => {
+                // No PREPARED: roll back whatever the destination may have staged
+                // (best effort — its staging window covers a dead link).
+                let abort = MoveDecision::sealed(uuid, epoch, false, "no PREPARED", &self.key);
+                let _ = sess.send(&mut s, KIND_MIGRATE_ABORT, &abort.encode());
+                return Err(other.err().unwrap_or_else(|| io::Error::other("migration not acknowledged")));
+            }
         }
-        sess.send(&mut s, KIND_MIGRATE_COMMIT, b"")?;
-        Ok(())
+        // The point of no return: the decision is durable before it is sent, so a
+        // crash from here on retries the COMMIT rather than reviving our copy.
+        self.set_move_phase(uuid, MovePhase::Committing);
+        let commit = MoveDecision::sealed(uuid, epoch, true, "", &self.key);
+        sess.send(&mut s, KIND_MIGRATE_COMMIT, &commit.encode())?;
+        match sess.recv(&mut s)? {
+            (KIND_MIGRATE_COMMITTED, _) => Ok(true),
+            (KIND_MIGRATE_ABORT, _) => Ok(false),
+            _ => Err(io::Error::other("commit not confirmed")),
+        }
+    }
+
+    /// Send a best-effort decision from a worker thread: nothing here waits on, or
+    /// changes with, its answer.
+    fn post_decision(&self, dest_addr: &str, decision: MoveDecision) {
+        let (noise, dest) = (self.noise.clone(), dest_addr.to_string());
+        std::thread::spawn(move || {
+            let _ = send_decision(&noise, &dest, &decision);
+        });
+    }
+
+    /// Drop the local copy of an agent that now lives elsewhere, and its log entry.
+    fn tombstone(&mut self, uuid: &str) {
+        self.unlog_move(uuid);
+        self.drop_chain(uuid);
+        self.drop_pending(uuid);
+        self.drop_usage(uuid);
+        if let Some(m) = self.agents.remove(uuid) {
+            self.aliases.remove(&m.alias);
+        }
+    }
+
+    /// Undo a source-side move: resume the suspended local copy — or, if a restart
+    /// lost it, re-mount it from the snapshot in the staging log — and drop the log
+    /// entry. Only called once the destination cannot hold a live copy.
+    fn revive(&mut self, uuid: &str) {
+        let entry = self.staging.get(uuid).cloned();
+        self.unlog_move(uuid);
+        if let Some(m) = self.agents.get_mut(uuid) {
+            m.active = true;
+            return;
+        }
+        let Some(entry) = entry else { return };
+        let Some(snap) = MigratePayload::decode(&entry.payload).map(|mp| mp.snapshot) else { return };
+        let Some(manifest) = Manifest::from_json(&snap.manifest) else { return };
+        let alias = if entry.alias.is_empty() { uuid } else { entry.alias.as_str() };
+        if let Err(e) = self.mount_wasm(uuid, alias, snap.code.clone(), &manifest, None) {
+            self.audit(uuid, "migrate:revive-failed", &e.to_string());
+            return;
+        }
+        if let Some(m) = self.agents.get_mut(uuid) {
+            m.epoch = snap.epoch; // a retried move must advance past the aborted epoch
+            let restored = m.runtime.restore(&snap.state);
+            let (logs, usage) = (m.runtime.take_logs(), m.runtime.take_usage());
+            self.apply_logs(uuid, logs);
+            self.apply_usage(uuid, usage);
+            if let Err(e) = restored {
+                self.audit(uuid, "migrate:revive-failed", &e.to_string());
+                return;
+            }
+        }
+        self.rearm(uuid, &snap.pending);
+        crate::flow!("[{}] ↺ '{}' revived from the staging log (epoch {})", self.label, uuid, snap.epoch);
     }
 
     /// Receive a migrated agent: verify the snapshot + handoff, confirm it is for
-    /// this node, guard against replay (epoch must advance), instantiate the wasm
-    /// from the carried code, restore state, mount it, and re-bind at AMS carrying
-    /// the handoff so the AMS node can move the agent's authorized key.
+    /// this node and — for an owned agent — that its attestation chain extends to
+    /// us, guard against replay (epoch must advance), instantiate the wasm from the
+    /// carried code, restore state, mount it, and re-bind at AMS carrying the
+    /// handoff so the AMS node can move the agent's authorized key.
     /// Returns `true` only when *this* payload mounts (prepares) an agent, so the
     /// ACK reflects an actual mount rather than the mere presence of some agent with
     /// that uuid (H2). The agent is mounted **suspended** and is not activated (nor
     /// re-bound at AMS, nor recorded in `seen`) until [`Node::commit_migrated`].
     fn process_migrate(&mut self, payload: &[u8]) -> bool {
         let Some(mp) = MigratePayload::decode(payload) else { return false };
+        let now = now_ms();
         let from_addr = mp.from_addr.clone();
         let (snap, ho) = (mp.snapshot, mp.handoff);
         if !snap.verify() || !ho.verify() {
@@ -881,10 +1730,15 @@ impl Node {
             || ho.agent != snap.uuid
             || ho.epoch != snap.epoch
             || ho.from_pub != snap.origin_pub
+            || ho.snapshot_hash != snap.digest()
         {
             crate::flow!("[{}] ⛔ migrate: handoff not for me / inconsistent", self.label);
             return false;
         }
+        if !ho.valid_at(now) {
+            crate::flow!("[{}] ⛔ migrate: handoff for '{}' outside its window", self.label, snap.uuid);
+            return false;
+        }
         // C2 — reject reserved system ids and refuse to overwrite a locally-born
         // (non-migrated) agent, so an unauthenticated peer cannot hijack an identity.
         if adapters::is_reserved_sender(&snap.uuid) {
@@ -895,18 +1749,47 @@ impl Node {
             crate::flow!("[{}] ⛔ migrate: '{}' collides with a local agent", self.label, snap.uuid);
             return false;
         }
-        // C2 — origin authenticity: the snapshot must be signed by the agent's
-        // currently-authorized node key. A known key MUST match; first sighting is
-        // TOFU-accepted only behind the Noise peer allowlist (enforced at accept).
-        // TODO(attestation): replace first-sighting TOFU with an authoritative AMS
-        // key lookup (MOBILITY §7) — staged follow-up behind this same check.
-        if let Some(known) = self.keys.get(&snap.uuid).copied() {
-            if known.as_slice() != snap.origin_pub.as_slice() {
-                self.audit(&snap.uuid, "migrate:bad-origin", "origin key != authorized key");
-                crate::flow!("[{}] ⛔ migrate: origin key ≠ authorized key for '{}'", self.label, snap.uuid);
+        // C2 — origin authenticity. An owned agent (its manifest names an owner)
+        // must arrive with its attestation chain, which this handoff extends to us
+        // (MOBILITY §7); the chain is recorded only at commit, so an aborted prepare
+        // leaves no trace. An unowned agent's snapshot must be signed by its
+        // currently-authorized node key: a known key MUST match, and a first sighting
+        // is TOFU-accepted only behind the Noise peer allowlist (enforced at accept).
+        let owner = Manifest::from_json(&snap.manifest).and_then(|m| m.owner_key());
+        let chain = match (owner, mp.chain) {
+            (Some(owner), Some(mut chain)) => {
+                let admitted = if chain.owner() != owner.as_slice() {
+                    Err(ChainError::WrongOwner)
+                } else if chain.delegation.code_hash != snap.code_hash {
+                    Err(ChainError::WrongCode)
+                } else {
+                    chain
+                        .extend(ho.clone(), now)
+                        .and_then(|()| self.check_chain(&snap.uuid, &chain, &self.key.public_key(), now))
+                };
+                if let Err(e) = admitted {
+                    self.audit(&snap.uuid, "migrate:bad-chain", &e.to_string());
+                    crate::flow!("[{}] ⛔ migrate: attestation chain for '{}' refused: {e}", self.label, snap.uuid);
+                    return false;
+                }
+                Some(chain)
+            }
+            (Some(_), None) => {
+                self.audit(&snap.uuid, "migrate:bad-chain", "owned agent without an attestation chain");
+                crate::flow!("[{}] ⛔ migrate: owned agent '{}' arrived without its chain", self.label, snap.uuid);
                 return false;
             }
-        }
+            (None, _) => {
+                if let Some(known) = self.keys.get(&snap.uuid)
+                    && known.as_slice() != snap.origin_pub.as_slice()
+                {
+                    self.audit(&snap.uuid, "migrate:bad-origin", "origin key != authorized key");
+                    crate::flow!("[{}] ⛔ migrate: origin key ≠ authorized key for '{}'", self.label, snap.uuid);
+                    return false;
+                }
+                None
+            }
+        };
         // Replay guard: reject a non-advancing epoch, consulting both the in-memory
         // and the durable (M4) record so a captured payload cannot re-mount after a
         // restart wiped `seen`.
@@ -949,14 +1832,22 @@ impl Node {
                 }
             }
         };
-        let mut runtime = match self.instantiate_agent(&code, &grant) {
+        let runtime = match self.instantiate_agent(&code, &grant) {
             Ok(rt) => rt,
             Err(_) => {
                 crate::flow!("[{}] ⛔ migrate: code won't instantiate", self.label);
                 return false;
             }
         };
-        runtime.restore(&snap.state);
+        let mut runtime = self.recorded(&snap.uuid, &code, &grant, runtime);
+        self.provision(&snap.uuid, &grant, &mut *runtime);
+        let started = runtime.init().and_then(|()| runtime.restore(&snap.state));
+        self.apply_logs(&snap.uuid, runtime.take_logs());
+        self.apply_usage(&snap.uuid, runtime.take_usage());
+        if started.is_err() {
+            crate::flow!("[{}] ⛔ migrate: agent would not start from its snapshot", self.label);
+            return false;
+        }
         // Pin the origin key for this agent (first sighting) so a later migration
         // under a different key is rejected as impersonation.
         let mut origin = [0u8; 32];
@@ -977,18 +1868,70 @@ impl Node {
                 active: false, // prepared: not live until the source COMMITs (H3/H4)
             },
         );
-        self.provision_state(&snap.uuid);
-        self.provision_crypto(&snap.uuid);
         // Stash the handoff for the AMS re-bind that happens at commit; `seen` and
         // the AMS binding are deferred so an aborted prepare leaves no trace.
-        self.prepared.insert(snap.uuid.clone(), ho);
+        self.prepared.insert(snap.uuid.clone(), (ho, chain, snap.pending));
+        self.note_origin(&snap.uuid, snap.epoch, &snap.origin_pub);
+        self.log_move(StagedMove {
+            role: MoveRole::Destination,
+            phase: MovePhase::Prepared,
+            uuid: snap.uuid.clone(),
+            epoch: snap.epoch,
+            peer_addr: from_addr,
+            payload: payload.to_vec(),
+            since_ms: now_ms(),
+            alias: snap.uuid.clone(),
+            origin: String::new(),
+        });
         crate::flow!("[{}] ⇉ migrated '{}' prepared (epoch {})", self.label, snap.uuid, snap.epoch);
         true
     }
 
+    /// Apply a source's decision on a move staged here. `Some(true)` once the agent
+    /// is live here — now, or by an earlier COMMIT whose COMMITTED was lost (a retry
+    /// is idempotent); `Some(false)` if nothing is staged for that epoch (aborted or
+    /// timed out); `None` if the decision is not signed by the origin of the move
+    /// staged here at that epoch — checked before anything is answered, so a
+    /// stranger learns nothing of our moves.
+    fn settle_staged(&mut self, decision: &MoveDecision) -> Option<bool> {
+        let staged = self
+            .staging
+            .get(&decision.uuid)
+            .filter(|e| e.role == MoveRole::Destination && e.epoch == decision.epoch)
+            .cloned();
+        let Some(entry) = staged else {
+            let signed = self
+                .staged_origin(&decision.uuid)
+                .is_some_and(|(epoch, origin)| epoch == decision.epoch && decision.verify(&origin));
+            if !signed {
+                self.audit(&decision.uuid, "migrate:bad-decision", "no move from its signer staged here");
+                return None;
+            }
+            let last = self.seen.get(&decision.uuid).copied().or_else(|| self.persisted_seen(&decision.uuid));
+            return Some(last.is_some_and(|e| e >= decision.epoch));
+        };
+        let origin = MigratePayload::decode(&entry.payload).map(|mp| mp.snapshot.origin_pub).unwrap_or_default();
+        if !decision.verify(&origin) {
+            self.audit(&decision.uuid, "migrate:bad-decision", "not signed by the move's origin");
+            return None;
+        }
+        if self.agents.get(&decision.uuid).is_none_or(|m| m.active) {
+            self.unlog_move(&decision.uuid); // the staged copy is gone (e.g. lost in a restart)
+            return Some(false);
+        }
+        if decision.commit {
+            self.commit_migrated(&decision.uuid);
+            Some(true)
+        } else {
+            self.abort_prepared(&decision.uuid, &decision.reason);
+            Some(false)
+        }
+    }
+
     /// Finalize a prepared migration: activate the agent, record the epoch in the
-    /// replay guard, and re-bind it at AMS carrying the handoff (H3). Called when the
-    /// source confirms — by COMMIT — that it has tombstoned its copy.
+    /// replay guard, adopt its extended attestation chain, and re-bind it at AMS
+    /// carrying the handoff (H3). Called when the source confirms — by COMMIT — that
+    /// it has tombstoned its copy.
     fn commit_migrated(&mut self, uuid: &str) {
         let epoch = match self.agents.get_mut(uuid) {
             Some(m) if !m.active => {
@@ -999,8 +1942,15 @@ impl Node {
         };
         self.seen.insert(uuid.to_string(), epoch);
         self.persist_seen(uuid, epoch); // M4: survive a restart
+        self.unlog_move(uuid); // after `seen`, so a crash in between still reads as committed
+        self.forwards.remove(uuid); // back here: stop relaying it elsewhere
         crate::flow!("[{}] ⇇ migrated '{}' committed (epoch {})", self.label, uuid, epoch);
-        if let Some(ho) = self.prepared.remove(uuid) {
+        if let Some((ho, chain, pending)) = self.prepared.remove(uuid) {
+            self.rearm(uuid, &pending); // live from here: its deadlines run on this clock
+            if let Some(chain) = chain {
+                self.note_owner(uuid, chain.owner(), chain.epoch());
+                self.install_chain(chain); // before the bind, which carries it
+            }
             if self.routes.contains_key("ams") {
                 let ho_json = serde_json::to_value(&ho).unwrap_or_default();
                 let body = serde_json::json!({
@@ -1013,9 +1963,10 @@ impl Node {
     }
 
     /// Discard a prepared migration that the source never committed (the source
-    /// timed out, errored, or chose to keep its copy) — leaving no trace, so a clean
+    /// sent ABORT, or the staging window closed) — leaving no trace, so a clean
     /// retry can prepare again.
-    fn abort_prepared(&mut self, uuid: &str) {
+    fn abort_prepared(&mut self, uuid: &str, reason: &str) {
+        self.unlog_move(uuid);
         match self.agents.get(uuid) {
             Some(m) if !m.active => {}
             _ => return, // unknown or already committed — never tear down a live agent
@@ -1024,7 +1975,235 @@ impl Node {
             self.aliases.remove(&m.alias);
         }
         self.prepared.remove(uuid);
-        crate::flow!("[{}] ⛔ migrated '{}' aborted (no commit)", self.label, uuid);
+        self.drop_usage(uuid);
+        self.audit(uuid, "migrate:aborted", reason);
+        crate::flow!("[{}] ⛔ migrated '{}' aborted ({reason})", self.label, uuid);
+    }
+
+    /// Close the staging window: discard every agent staged here for longer than
+    /// [`STAGING_TIMEOUT_MS`] without a decision. The source still holds its live
+    /// copy — or, having logged a COMMIT, learns of the abort when it retries.
+    fn expire_staging(&mut self, now: u64) {
+        let expired: Vec<String> = self
+            .staging
+            .values()
+            .filter(|e| e.role == MoveRole::Destination && now >= e.since_ms + STAGING_TIMEOUT_MS)
+            .map(|e| e.uuid.clone())
+            .collect();
+        for uuid in expired {
+            self.abort_prepared(&uuid, "staging timeout");
+        }
+    }
+
+    /// Finish or undo the moves a previous run left in flight, from the durable
+    /// staging log (the MOBILITY §6 crash cases). [`Node::serve`] calls this on
+    /// start; it is safe to call again, and an unreachable peer leaves its entry for
+    /// the next attempt — which, for a decided COMMIT, the serve loop makes every
+    /// [`COMMIT_RETRY_MS`].
+    ///
+    /// - source, before the COMMIT decision → ABORT the destination's staging and
+    ///   keep the agent here, re-mounted from the logged snapshot if need be;
+    /// - source, COMMIT decided → re-send it: COMMITTED tombstones the local copy,
+    ///   ABORT (the staging window closed first) revives it;
+    /// - clone → as a source, but the original was never suspended: the entry is
+    ///   just dropped once the destination settles the copy;
+    /// - destination → re-stage the logged payload for what is left of its staging
+    ///   window, so a source that commits late still lands; otherwise drop it. The
+    ///   handoff names this node's key, so re-staging needs the identity the node
+    ///   had before the restart ([`Node::load_key`]); with an ephemeral key the
+    ///   entry is dropped and the source's COMMIT is answered with ABORT.
+    ///
+    /// Returns how many moves were settled or re-staged. A re-sent COMMIT settles
+    /// later, when its answer comes back ([`Node::settle_resends`]).
+    pub fn recover_migrations(&mut self) -> usize {
+        for entry in self.persisted_moves() {
+            self.staging.entry(entry.uuid.clone()).or_insert(entry);
+        }
+        let now = now_ms();
+        let mut done = 0;
+        for entry in self.staging.values().cloned().collect::<Vec<_>>() {
+            match (entry.role, entry.phase) {
+                (MoveRole::Source | MoveRole::Clone, MovePhase::Committing) => self.resend_commit(&entry),
+                (MoveRole::Clone, _) => {
+                    let abort = MoveDecision::sealed(&entry.uuid, 0, false, "source restarted before commit", &self.key);
+                    self.post_decision(&entry.peer_addr, abort); // best effort, as for a move
+                    self.unlog_move(&entry.uuid);
+                    done += 1;
+                }
+                (MoveRole::Source, _) => {
+                    let abort = MoveDecision::sealed(&entry.uuid, entry.epoch, false, "source restarted before commit", &self.key);
+                    self.post_decision(&entry.peer_addr, abort); // best effort: the staging window covers a dead peer
+                    self.revive(&entry.uuid);
+                    done += 1;
+                }
+                (MoveRole::Destination, _) => {
+                    if self.agents.contains_key(&entry.uuid) {
+                        continue; // still staged in this run; the staging window decides
+                    }
+                    self.unlog_move(&entry.uuid);
+                    if !self.key_persisted {
+                        self.audit(&entry.uuid, "migrate:restage-failed", "node identity not persisted");
+                        crate::flow!("[{}] ⛔ can't re-stage '{}': this node's key did not survive the restart", self.label, entry.uuid);
+                        continue;
+                    }
+                    if now < entry.since_ms + STAGING_TIMEOUT_MS && self.process_migrate(&entry.payload) {
+                        self.log_move(entry); // keep the original window — a restart does not extend it
+                        done += 1;
+                    }
+                }
+            }
+        }
+        done
+    }
+
+    /// Re-send the COMMIT of a source entry whose COMMITTED never came, from a
+    /// worker thread so a slow or dead destination cannot hold up the serve loop.
+    /// The entry's clock restarts now; [`Node::settle_resends`] acts on the answer.
+    /// A move whose re-send is still out is not sent twice.
+    fn resend_commit(&mut self, entry: &StagedMove) {
+        if !self.resending.insert(entry.uuid.clone()) {
+            return;
+        }
+        self.set_move_phase(&entry.uuid, MovePhase::Committing);
+        let commit = MoveDecision::sealed(&entry.uuid, entry.epoch, true, "", &self.key);
+        let (noise, tx, entry) = (self.noise.clone(), self.resend_tx.clone(), entry.clone());
+        std::thread::spawn(move || {
+            let outcome = send_decision(&noise, &entry.peer_addr, &commit);
+            let _ = tx.send((entry, outcome));
+        });
+    }
+
+    /// Act on the answers to re-sent COMMITs: COMMITTED tombstones the local copy,
+    /// ABORT revives it (a clone's entry is just dropped, the copy audited if
+    /// live). While the outcome is still unknown the copy stays suspended, and
+    /// [`Node::retry_commits`] tries again a period on. Returns how many settled.
+    fn settle_resends(&mut self) -> usize {
+        let mut done = 0;
+        while let Ok((entry, outcome)) = self.resend_rx.try_recv() {
+            self.resending.remove(&entry.uuid);
+            if !self.staging.contains_key(&entry.uuid) {
+                continue; // settled some other way meanwhile
+            }
+            match outcome {
+                Ok(live) if entry.role == MoveRole::Clone => {
+                    self.unlog_move(&entry.uuid);
+                    if live {
+                        self.note_cloned(&entry.origin, &entry.uuid, &entry.peer_addr);
+                    }
+                }
+                Ok(true) => {
+                    self.start_forwarding(&entry.uuid, &entry.peer_addr, &entry.payload);
+                    self.tombstone(&entry.uuid);
+                }
+                Ok(false) => self.revive(&entry.uuid),
+                Err(e) => {
+                    crate::flow!("[{}] ⚠ migrate: commit of '{}' still unconfirmed: {e}", self.label, entry.uuid);
+                    self.set_move_phase(&entry.uuid, MovePhase::Committing); // the next try is a period on
+                    continue;
+                }
+            }
+            done += 1;
+        }
+        done
+    }
+
+    /// Re-send every COMMIT decided at least [`COMMIT_RETRY_MS`] ago and still
+    /// unconfirmed (the serve loop's periodic half of [`Node::recover_migrations`]).
+    fn retry_commits(&mut self, now: u64) {
+        let due: Vec<StagedMove> = self
+            .staging
+            .values()
+            .filter(|e| matches!(e.role, MoveRole::Source | MoveRole::Clone) && e.phase == MovePhase::Committing)
+            .filter(|e| now >= e.since_ms + COMMIT_RETRY_MS)
+            .cloned()
+            .collect();
+        for entry in due {
+            self.resend_commit(&entry);
+        }
+    }
+
+    /// Remember who originated the move of `uuid` staged here at `epoch`, durably,
+    /// so a decision arriving after the staging is settled can still be verified.
+    fn note_origin(&mut self, uuid: &str, epoch: u64, origin: &[u8]) {
+        if let Some(store) = &self.store {
+            let mut bytes = epoch.to_be_bytes().to_vec();
+            bytes.extend_from_slice(origin);
+            let _ = store.put("_migrate_origin", uuid, &bytes);
+        }
+        self.origins.insert(uuid.to_string(), (epoch, origin.to_vec()));
+    }
+
+    /// The `(epoch, origin key)` of the last move of `uuid` staged here, if any.
+    fn staged_origin(&self, uuid: &str) -> Option<(u64, Vec<u8>)> {
+        if let Some(o) = self.origins.get(uuid) {
+            return Some(o.clone());
+        }
+        let bytes = self.store.as_ref()?.get("_migrate_origin", uuid).ok().flatten()?;
+        let (epoch, origin) = bytes.split_first_chunk::<8>()?;
+        Some((u64::from_be_bytes(*epoch), origin.to_vec()))
+    }
+
+    /// Record an in-flight move in the staging log, durably when the node has a
+    /// store (M4).
+    fn log_move(&mut self, entry: StagedMove) {
+        if let Some(store) = &self.store {
+            let _ = store.put("_migrate_log", &entry.uuid, &entry.encode());
+        }
+        self.staging.insert(entry.uuid.clone(), entry);
+    }
+
+    /// Advance a logged move to `phase` — durably, before acting on it.
+    fn set_move_phase(&mut self, uuid: &str, phase: MovePhase) {
+        if let Some(mut entry) = self.staging.get(uuid).cloned() {
+            entry.phase = phase;
+            entry.since_ms = now_ms();
+            self.log_move(entry);
+        }
+    }
+
+    /// Drop a settled move from the staging log.
+    fn unlog_move(&mut self, uuid: &str) {
+        self.staging.remove(uuid);
+        if let Some(store) = &self.store {
+            let _ = store.del("_migrate_log", uuid);
+        }
+    }
+
+    /// The durable staging log — what a restarted node finds in flight.
+    fn persisted_moves(&self) -> Vec<StagedMove> {
+        let Some(store) = &self.store else { return Vec::new() };
+        let keys = store.scan("_migrate_log", "").unwrap_or_default();
+        keys.into_iter()
+            .filter_map(|(key, _)| store.get("_migrate_log", &key).ok().flatten())
+            .filter_map(|bytes| StagedMove::decode(&bytes))
+            .collect()
+    }
+
+    /// Adopt `chain` as what this node signs under for its agent, durably, so the
+    /// agent keeps its authority across a restart.
+    fn install_chain(&mut self, chain: Attestation) {
+        if let Some(store) = &self.store {
+            let _ = store.put("_attest", chain.agent(), &chain.encode());
+        }
+        self.chains.insert(chain.agent().to_string(), chain);
+    }
+
+    /// Forget the chain of an agent that no longer runs here.
+    fn drop_chain(&mut self, uuid: &str) {
+        self.chains.remove(uuid);
+        if let Some(store) = &self.store {
+            let _ = store.del("_attest", uuid);
+        }
+    }
+
+    /// The durably-recorded attestation chains of this node's owned agents.
+    fn persisted_chains(&self) -> Vec<Attestation> {
+        let Some(store) = &self.store else { return Vec::new() };
+        let keys = store.scan("_attest", "").unwrap_or_default();
+        keys.into_iter()
+            .filter_map(|(key, _)| store.get("_attest", &key).ok().flatten())
+            .filter_map(|bytes| Attestation::decode(&bytes))
+            .collect()
     }
 
     /// Persist a committed migration epoch so the replay guard survives a restart
@@ -1045,10 +2224,12 @@ impl Node {
         })
     }
 
-    /// Stamp `sender_pub`/`nonce` and sign a message with this node's key (R1).
+    /// Stamp `sender_pub`/`nonce` (and an owned sender's chain) and sign a message
+    /// with this node's key (R1).
     fn seal(&self, m: &mut NodeMsg) {
         m.sender_pub = self.key.public_key().to_vec();
         m.nonce = self.key.nonce().to_vec();
+        m.attest = self.chains.get(&m.from).map(Attestation::encode).unwrap_or_default();
         m.sig = Vec::new();
         m.sig = self.key.sign(&signing_bytes(m)).to_vec();
     }
@@ -1070,17 +2251,20 @@ impl Node {
         const MAX_INFLIGHT_INFER: usize = 32; // bound concurrent llm worker threads (M2)
 
         listener.set_nonblocking(true).ok();
+        self.own_addrs.extend(listener.local_addr().ok());
         // Bounded inbound queue (M1): a flooding peer blocks on send (backpressure)
         // instead of growing node memory without limit.
         let (in_tx, in_rx) = std::sync::mpsc::sync_channel::<NodeMsg>(1024);
         let (rz_tx, rz_rx) = std::sync::mpsc::channel::<(String, Sender<String>)>();
         let (mg_tx, mg_rx) = std::sync::mpsc::channel::<(Vec<u8>, Sender<bool>)>();
-        let (mg_fin_tx, mg_fin_rx) = std::sync::mpsc::channel::<(String, bool)>(); // (uuid, commit?)
+        let (mg_fin_tx, mg_fin_rx) = std::sync::mpsc::channel::<(MoveDecision, Sender<Option<bool>>)>();
         let (llm_tx, llm_rx) = std::sync::mpsc::channel::<(String, u64, String)>();
+        let (dl_tx, dl_rx) = std::sync::mpsc::channel::<(Delegation, Sender<bool>)>();
         let conns = Arc::new(AtomicUsize::new(0));
         let infl = Arc::new(AtomicUsize::new(0)); // in-flight inferences (M2)
         let per_ip = Arc::new(Mutex::new(HashMap::<std::net::IpAddr, usize>::new())); // M8
         let allow = self.noise_allow.clone().map(Arc::new); // C2a: shared per-connection
+        self.recover_migrations(); // settle moves a previous run left in flight
 
         while !shutdown.load(Ordering::Relaxed) {
             // 1. Local kickoff injections (trusted, in-process — never the wire).
@@ -1114,21 +2298,31 @@ impl Node {
                 let _ = resp.send(mounted);
             }
 
-            // 3b'. Finalize a prepared migration once the source decides: commit
-            //      (activate + AMS re-bind) on COMMIT, else abort (drop the prepared
-            //      copy). The agent is live on exactly one node across the handoff.
-            while let Ok((uuid, commit)) = mg_fin_rx.try_recv() {
-                if commit {
-                    self.commit_migrated(&uuid);
-                } else {
-                    self.abort_prepared(&uuid);
-                }
+            // 3b'. Settle a staged migration once the source decides: COMMIT
+            //      activates (+ AMS re-bind), ABORT drops the staged copy; a staging
+            //      left undecided past its window is dropped too. The agent is live on
+            //      exactly one node across the handoff.
+            while let Ok((decision, resp)) = mg_fin_rx.try_recv() {
+                let _ = resp.send(self.settle_staged(&decision));
             }
 
-            // 3c. Fire any due timers (M3 scheduling — agent autonomy).
+            // 3b''. An owner's Delegation published to this node: compact the chain
+            //       held here, or record a revocation (MOBILITY §7.1).
+            while let Ok((delegation, resp)) = dl_rx.try_recv() {
+                let _ = resp.send(self.accept_delegation(delegation).is_ok());
+            }
             let now = now_ms();
+            self.expire_staging(now);
+            self.settle_resends();
+            self.retry_commits(now);
+            self.expire_forwards(now);
+
+            // 3c. Fire any due timers (M3 scheduling — agent autonomy).
             let mut due: Vec<(String, u64)> = Vec::new();
             for (uuid, slots) in &self.timers {
+                if !self.agents.get(uuid).is_some_and(|m| m.active) {
+                    continue; // suspended mid-move: its timers wait (or travel in the snapshot)
+                }
                 for (id, deadline) in slots {
                     if *deadline <= now {
                         due.push((uuid.clone(), *id));
@@ -1160,21 +2354,15 @@ impl Node {
                     });
                 }
             } else {
-                self.pending_infers.clear(); // no backend → drop (no reply)
+                self.pending_infers.clear(); // no backend → drop (each request expires)
             }
 
-            // 3e. Deliver completed inferences back as messages from "llm" (the
-            //     async reply-by-message model — the agent correlates by request_id).
+            // 3e. Deliver completed inferences back as messages from "llm", then
+            //     answer the ones that ran out of time with an error.
             while let Ok((uuid, req_id, text)) = llm_rx.try_recv() {
-                let body = serde_json::json!({ "request_id": req_id, "text": text }).to_string();
-                self.pump(NodeMsg {
-                    to: uuid,
-                    from: "llm".into(),
-                    unl: b"obj(inferred, x)".to_vec(),
-                    body: body.into_bytes(),
-                    ..Default::default()
-                });
+                self.answer_infer(&uuid, req_id, &text);
             }
+            self.expire_infers(now_ms());
 
             // 4. Accept new connections; each is handshaked + read in its own thread
             //    so a slow peer cannot stall the loop (H3/R7). Shed load past the cap.
@@ -1194,19 +2382,20 @@ impl Node {
                         *c += 1;
                     }
                     conns.fetch_add(1, Ordering::Relaxed);
-                    let (noise, in_tx, rz_tx, mg_tx, mg_fin_tx, cs, conns2, al, pip) = (
+                    let (noise, in_tx, rz_tx, mg_tx, mg_fin_tx, dl_tx, cs, conns2, al, pip) = (
                         self.noise.clone(),
                         in_tx.clone(),
                         rz_tx.clone(),
                         mg_tx.clone(),
                         mg_fin_tx.clone(),
+                        dl_tx.clone(),
                         self.code_store.clone(),
                         conns.clone(),
                         allow.clone(),
                         per_ip.clone(),
                     );
                     std::thread::spawn(move || {
-                        handle_conn(s, &noise, &in_tx, &rz_tx, &mg_tx, &mg_fin_tx, &cs, al);
+                        handle_conn(s, &noise, &in_tx, &rz_tx, &mg_tx, &mg_fin_tx, &dl_tx, &cs, al);
                         conns2.fetch_sub(1, Ordering::Relaxed);
                         if let Some(ip) = ip {
                             let mut map = pip.lock().unwrap_or_else(|e| e.into_inner());
@@ -1238,6 +2427,32 @@ impl Node {
             );
             return;
         }
+        // MOBILITY §8 — a relayed message carries its last forwarder's stamp; a
+        // stamp that is not for this envelope, or past the hop bound, is dropped, as
+        // is an envelope addressed to another node that arrives without one.
+        let hops = if msg.relay.is_empty() {
+            if !msg.to_addr.is_empty() && !self.is_own_addr(&msg.to_addr) {
+                self.audit(&msg.from, "forward:unstamped", &msg.to);
+                crate::flow!("[{}] ⛔ dropped msg for '{}' addressed to {} (no relay stamp)", self.label, msg.to, msg.to_addr);
+                return;
+            }
+            0
+        } else {
+            match Relay::decode(&msg.relay) {
+                Some(r) if r.verify(&msg.sig) && r.hops <= MAX_FORWARD_HOPS => r.hops,
+                _ => {
+                    self.audit(&msg.from, "forward:bad-relay", &msg.to);
+                    crate::flow!("[{}] ⛔ dropped relayed msg for '{}' (bad stamp or hop bound)", self.label, msg.to);
+                    return;
+                }
+            }
+        };
+        // A redirect hint is acted on only after the checks below, and only for an
+        // agent we already trust a key for — a hint pins nothing.
+        let hint = msg.unl == MOVED_UNL;
+        if hint && !self.keys.contains_key(&msg.from) {
+            return;
+        }
         if !self.authorize(&msg) {
             crate::flow!(
                 "[{}] ⛔ impersonation of '{}' — sender key ≠ first-seen (TOFU)",
@@ -1263,15 +2478,138 @@ impl Node {
                 }
             }
         }
+        if hint {
+            self.apply_redirect(&msg);
+            return;
+        }
+        if self.forwards.contains_key(&msg.to) && self.local_uuid(&msg.to).is_none() {
+            self.relay(msg, hops);
+            return;
+        }
         self.pump(msg);
     }
 
+    /// Leave a forwarder behind for an agent just handed on to `dest_addr`
+    /// (MOBILITY §8): route local senders there and relay stray wire messages for
+    /// [`FORWARD_TTL_MS`], while the AMS re-bind propagates; both lapse together
+    /// ([`Node::expire_forwards`]). `payload` is the committed move's, whose handoff
+    /// later proves the redirect hints.
+    fn start_forwarding(&mut self, uuid: &str, dest_addr: &str, payload: &[u8]) {
+        let Some(handoff) = MigratePayload::decode(payload).map(|p| p.handoff) else { return };
+        self.routes.insert(uuid.into(), dest_addr.into());
+        let until_ms = now_ms() + FORWARD_TTL_MS;
+        self.forwards.insert(uuid.into(), Forward { addr: dest_addr.into(), handoff, until_ms, hinted: HashSet::new() });
+    }
+
+    /// Drop forwarding entries past their window, and the routes they left — unless
+    /// something has re-pointed the route since — so later sends re-resolve
+    /// through AMS.
+    fn expire_forwards(&mut self, now: u64) {
+        let expired: Vec<(String, String)> = self
+            .forwards
+            .iter()
+            .filter(|(_, f)| f.until_ms <= now)
+            .map(|(uuid, f)| (uuid.clone(), f.addr.clone()))
+            .collect();
+        for (uuid, addr) in expired {
+            self.forwards.remove(&uuid);
+            if self.routes.get(&uuid) == Some(&addr) {
+                self.routes.remove(&uuid);
+            }
+        }
+    }
+
+    /// Relay a wire message for an agent that moved away: re-stamp it with the next
+    /// hop count under this node's key and send it on to the agent's new address,
+    /// then hint the sender's node — once per address — to route there directly. A
+    /// message already relayed [`MAX_FORWARD_HOPS`] times, or one arriving after
+    /// the entry expired, is dropped.
+    fn relay(&mut self, mut m: NodeMsg, hops: u8) {
+        if hops >= MAX_FORWARD_HOPS {
+            self.audit(&m.to, "forward:hop-bound", &m.from);
+            crate::flow!("[{}] ⛔ msg for moved '{}' dropped after {} hops", self.label, m.to, hops);
+            return;
+        }
+        let now = now_ms();
+        let Some(fwd) = self.forwards.get_mut(&m.to).filter(|f| f.until_ms > now) else { return };
+        let addr = fwd.addr.clone();
+        let hint = !m.from_addr.is_empty() && fwd.hinted.insert(m.from_addr.clone());
+        m.relay = Relay::sealed(hops + 1, &m.sig, &self.key).encode();
+        crate::flow!("[{}] ↪ relaying msg for moved '{}' to {}", self.label, m.to, addr);
+        if let Err(e) = self.send_to(&addr, &m) {
+            self.audit(&m.to, "forward:failed", &e.to_string());
+        }
+        if hint && let Some(redirect) = self.redirect_hint(&m.to, &m.from, &m.from_addr) {
+            let _ = self.send_to(&m.from_addr, &redirect);
+        }
+    }
+
+    /// Whether `to_addr`, the address a sender dialed, reaches this node: the
+    /// socket address it advertises or listens on, however either is spelled. A
+    /// node advertising a wildcard address (`0.0.0.0`, `[::]`) is reached on any
+    /// of its interfaces, so there the port decides.
+    fn is_own_addr(&self, to_addr: &str) -> bool {
+        if to_addr == self.addr {
+            return true;
+        }
+        let Ok(to) = to_addr.parse::<SocketAddr>() else { return false };
+        self.own_addrs.iter().any(|own| {
+            own.port() == to.port() && (own.ip().is_unspecified() || own.ip().to_canonical() == to.ip().to_canonical())
+        })
+    }
+
+    /// A redirect hint to `to` at `to_addr`: signed as the moved `agent` by this
+    /// node, naming its new address and carrying the move's handoff as proof.
+    fn redirect_hint(&self, agent: &str, to: &str, to_addr: &str) -> Option<NodeMsg> {
+        let fwd = self.forwards.get(agent)?;
+        let body = serde_json::json!({ "agent": agent, "address": fwd.addr, "handoff": fwd.handoff });
+        let mut m = NodeMsg {
+            to: to.into(),
+            from: agent.into(),
+            from_addr: self.addr.clone(),
+            unl: MOVED_UNL.to_vec(),
+            body: body.to_string().into_bytes(),
+            to_addr: to_addr.into(),
+            ..Default::default()
+        };
+        self.seal(&mut m);
+        Some(m)
+    }
+
+    /// Act on a forwarder's redirect hint, which has passed [`Node::authorize`] and
+    /// the replay check. When its handoff comes from the key this node trusts for
+    /// the agent (TOFU) and the hint is signed by that key, the new address
+    /// replaces the cached route and the handoff's key is trusted from now on.
+    /// Anything else changes nothing: the forwarder keeps relaying until the route
+    /// re-resolves.
+    fn apply_redirect(&mut self, m: &NodeMsg) {
+        let Ok(v) = serde_json::from_slice::<serde_json::Value>(&m.body) else { return };
+        let (Some(addr), Some(ho)) = (
+            v.get("address").and_then(|a| a.as_str()),
+            v.get("handoff").and_then(|h| serde_json::from_value::<Handoff>(h.clone()).ok()),
+        ) else {
+            return;
+        };
+        if ho.agent != m.from || ho.from_pub != m.sender_pub || !ho.verify() || !ho.valid_at(now_ms()) {
+            return;
+        }
+        let trusted = self.keys.get(&m.from).is_some_and(|k| k.as_slice() == ho.from_pub.as_slice());
+        if trusted && let Ok(to_pub) = <[u8; 32]>::try_from(ho.to_pub.as_slice()) {
+            self.keys.insert(m.from.clone(), to_pub);
+            self.routes.insert(m.from.clone(), addr.to_string());
+            crate::flow!("[{}] ↪ '{}' moved: route → {}", self.label, m.from, addr);
+        }
+    }
+
     /// R3: trust-on-first-use from-authorization. The first node key seen signing
     /// for a given `from` uuid owns it; a later message claiming that uuid under a
     /// different key is rejected as impersonation (`THREAT_MODEL.md` C1/C2/C5).
-    /// Authoritative AMS-distributed keys + owner delegation (`MOBILITY.md` §7)
-    /// strengthen this in M5.
+    /// An owned agent is authorized by its attestation chain instead
+    /// ([`Node::authorize_chain`]).
     fn authorize(&mut self, m: &NodeMsg) -> bool {
+        if !m.attest.is_empty() || self.owners.contains_key(&m.from) {
+            return self.authorize_chain(m);
+        }
         let mut pk = [0u8; 32];
         pk.copy_from_slice(&m.sender_pub); // length already checked in wire_admit
         match self.keys.get(&m.from).copied() {
@@ -1295,6 +2633,62 @@ impl Node {
         }
     }
 
+    /// MOBILITY §7: a message from an owned agent must carry a chain, rooted at the
+    /// agent's owner, that ends at the signing node — walked for every message. Once
+    /// an agent is known to be owned, a message without its chain is refused rather
+    /// than falling back to TOFU.
+    fn authorize_chain(&mut self, m: &NodeMsg) -> bool {
+        let now = now_ms();
+        let admitted = Attestation::decode(&m.attest)
+            .ok_or(ChainError::Malformed)
+            .and_then(|chain| self.check_chain(&m.from, &chain, &m.sender_pub, now).map(|()| chain));
+        match admitted {
+            Ok(chain) => {
+                self.note_owner(&m.from, chain.owner(), chain.epoch());
+                true
+            }
+            Err(e) => {
+                self.audit(&m.from, "attestation", &e.to_string());
+                false
+            }
+        }
+    }
+
+    /// Whether `chain` authorizes `signer` to act as `agent` at `now`: it verifies,
+    /// ends at `signer`, is rooted at the owner pinned for the agent, and is not
+    /// below the highest epoch seen for it (so a node the owner delegated past, or
+    /// a host the agent has left, cannot sign as it). An agent already pinned by
+    /// key (TOFU) with no owner known yet only takes a chain whose root delegation
+    /// is to that key — otherwise anyone could claim an unowned agent by minting an
+    /// owner of their own.
+    fn check_chain(&self, agent: &str, chain: &Attestation, signer: &[u8], now: u64) -> Result<(), ChainError> {
+        if chain.agent() != agent {
+            return Err(ChainError::WrongAgent(0));
+        }
+        if chain.verify(now)?.as_slice() != signer {
+            return Err(ChainError::NotHolder);
+        }
+        match self.owners.get(agent) {
+            Some((owner, _)) if owner.as_slice() != chain.owner() => Err(ChainError::WrongOwner),
+            Some(&(_, floor)) if chain.epoch() < floor => Err(ChainError::Superseded(floor)),
+            Some(_) => Ok(()),
+            None => match self.keys.get(agent) {
+                Some(pinned) if pinned.as_slice() != chain.delegation.to_node.as_slice() => Err(ChainError::WrongOwner),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Pin `owner` for `agent` on first sighting and raise the agent's epoch floor.
+    /// Callers have already checked the owner against any pin.
+    fn note_owner(&mut self, agent: &str, owner: &[u8], epoch: u64) {
+        let Ok(owner) = <[u8; 32]>::try_from(owner) else { return };
+        let (pinned, floor) = self.owners.entry(agent.to_string()).or_insert((owner, epoch));
+        if *pinned == owner {
+            *floor = (*floor).max(epoch);
+        }
+    }
+
     /// True if `m.body` carries a handoff signed by `from_key` (the agent's current
     /// authorized key) that authorizes `to_key` (the new sender key) for this agent.
     fn handoff_authorizes(&self, m: &NodeMsg, from_key: &[u8; 32], to_key: &[u8; 32]) -> bool {
@@ -1368,11 +2762,14 @@ impl Node {
             if !self.agents.get(&uuid).map(|m| m.active).unwrap_or(false) {
                 continue;
             }
+            if self.fuel_denied(&uuid, "message") {
+                continue;
+            }
             // Cache the sender's return address so replies have a route.
             if !m.from.is_empty() && !m.from_addr.is_empty() {
                 self.routes.insert(m.from.clone(), m.from_addr.clone());
             }
-            let (result, sends, ops, infers, spawns) = {
+            let (result, sends, ops, infers, spawns, logs, usage) = {
                 let mounted = self.agents.get_mut(&uuid).expect("local uuid is mounted");
                 crate::flow!("[{}] ← {} : {}", mounted.alias, m.from, String::from_utf8_lossy(&m.unl));
                 let result = mounted.runtime.config(&m.from, &m.unl, &m.body);
@@ -1382,8 +2779,12 @@ impl Node {
                     mounted.runtime.take_timer_ops(),
                     mounted.runtime.take_infer_reqs(),
                     mounted.runtime.take_spawn_reqs(),
+                    mounted.runtime.take_logs(),
+                    mounted.runtime.take_usage(),
                 )
             };
+            self.apply_logs(&uuid, logs);
+            self.apply_usage(&uuid, usage);
             self.supervise(&uuid, &result);
             self.apply_timer_ops(&uuid, ops);
             self.apply_infer_reqs(&uuid, infers);
@@ -1419,12 +2820,14 @@ impl Node {
     /// Seal a cross-node message and send it over Noise; if the recipient has no
     /// address (e.g. `result`), surface it to the sink instead.
     fn wire_or_sink(&mut self, mut m: NodeMsg) {
-        self.seal(&mut m);
         match self.address_of(&m.to) {
             Some(addr) => {
+                m.to_addr = canonical_addr(&addr);
+                self.seal(&mut m);
                 let _ = self.send_to(&addr, &m);
             }
             None => {
+                self.seal(&mut m);
                 if let Some(sink) = &self.sink {
                     let _ = sink.send(m);
                 }
@@ -1482,8 +2885,15 @@ impl Node {
     /// Fire a due timer: run the agent's `tick`, then route its sends and apply any
     /// timers it (re-)armed.
     fn fire_tick(&mut self, uuid: &str, timer_id: u64) {
+        if self.fuel_denied(uuid, "tick") {
+            // Timers are one-shot and this one is already off the schedule: defer it
+            // to the next window rather than lose the agent's deadline.
+            let next = self.fuel_window.get(uuid).map_or(0, |w| w.0.saturating_add(FUEL_WINDOW_MS));
+            self.timers.entry(uuid.to_string()).or_default().insert(timer_id, next);
+            return;
+        }
         let now = now_ms();
-        let (result, sends, ops, infers, spawns) = {
+        let (result, sends, ops, infers, spawns, logs, usage) = {
             let Some(m) = self.agents.get_mut(uuid) else { return };
             if !m.active {
                 return; // a prepared-but-uncommitted migrated agent does not tick (H4)
@@ -1495,8 +2905,12 @@ impl Node {
                 m.runtime.take_timer_ops(),
                 m.runtime.take_infer_reqs(),
                 m.runtime.take_spawn_reqs(),
+                m.runtime.take_logs(),
+                m.runtime.take_usage(),
             )
         };
+        self.apply_logs(uuid, logs);
+        self.apply_usage(uuid, usage);
         self.supervise(uuid, &result);
         self.apply_timer_ops(uuid, ops);
         self.apply_infer_reqs(uuid, infers);
@@ -1748,6 +3162,91 @@ mod tests {
         assert!(kinds.iter().any(|k| k == "quarantined")); // and the quarantine
     }
 
+    #[test]
+    fn guest_log_lines_are_audited_with_their_conversation() {
+        struct Rec(std::sync::Mutex<Vec<(String, String, String)>>);
+        impl AuditSink for Rec {
+            fn record(&self, e: &AuditEvent) {
+                self.0.lock().unwrap().push((e.agent.clone(), e.kind.clone(), e.detail.clone()));
+            }
+        }
+        const LOGGER: &str = r#"
+        (module
+          (import "fipa:agent/logging" "log" (func $log (param i32 i32 i32)))
+          (memory (export "memory") 1)
+          (global $bump (mut i32) (i32.const 1024))
+          (data (i32.const 0) "helloboot")
+          (func (export "init")
+            (call $log (i32.const 2) (i32.const 5) (i32.const 4)))
+          (func (export "tick") (param i64 i64)
+            (call $log (i32.const 2) (i32.const 0) (i32.const 5)))
+          (func (export "alloc") (param $n i32) (result i32)
+            (local $p i32)
+            (local.set $p (global.get $bump))
+            (global.set $bump (i32.add (global.get $bump) (local.get $n)))
+            (local.get $p))
+          (func (export "config") (param i32 i32 i32 i32)
+            (call $log (i32.const 2) (i32.const 0) (i32.const 5))))
+        "#;
+        let rec = Arc::new(Rec(std::sync::Mutex::new(Vec::new())));
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        n.set_audit(rec.clone());
+        n.mount_wasm("L", "l", LOGGER.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let body = br#"{"_acl":{"cid":"c1","pid":"fipa-request","perf":"request"}}"#.to_vec();
+        n.pump(NodeMsg { to: "L".into(), from: "seed".into(), unl: b"obj(request, x)".to_vec(), body, ..Default::default() });
+        n.fire_tick("L", 1);
+        n.upgrade("L", LOGGER.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let events = rec.0.lock().unwrap();
+        let logged: Vec<&str> = events.iter().filter(|e| e.1 == "log:info").map(|e| e.2.as_str()).collect();
+        // init at mount, the message, the tick (no conversation), init at upgrade
+        assert_eq!(logged, ["boot", "[c1] hello", "hello", "boot"]);
+    }
+
+    #[test]
+    fn a_recorded_agent_replays_offline() {
+        let dir = std::env::temp_dir().join(format!("record-{}", std::process::id()));
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        n.set_record_dir(&dir);
+        n.mount_wasm("W", "w", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        for _ in 0..2 {
+            n.pump(NodeMsg { to: "W".into(), from: "seed".into(), unl: b"obj(ping)".to_vec(), ..Default::default() });
+        }
+        let name = format!("W.{}.trace", &code_hash(COUNTER_WASM.as_bytes())[..12]);
+        let trace = std::fs::read(dir.join(name)).unwrap();
+        let out = crate::wasm::replay(&crate::wasm::WasmtimeEngine, COUNTER_WASM.as_bytes(), &trace[..]).unwrap();
+        assert_eq!((out.steps, out.divergence), (3, None)); // init + two deliveries
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_call_past_its_wall_clock_deadline_is_audited_as_deadline() {
+        struct Rec(std::sync::Mutex<Vec<String>>);
+        impl AuditSink for Rec {
+            fn record(&self, e: &AuditEvent) {
+                self.0.lock().unwrap().push(e.kind.clone());
+            }
+        }
+        const SPIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "init"))
+          (func (export "alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "config") (param i32 i32 i32 i32)
+            (loop (br 0))))
+        "#;
+        let rec = Arc::new(Rec(std::sync::Mutex::new(Vec::new())));
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        n.set_audit(rec.clone());
+        let mut m = wmanifest(&[]);
+        m.budget.fuel = 1_000_000_000_000; // fuel won't run out first — the deadline will
+        m.budget.wall_ms = 20;
+        n.mount_wasm("SPIN", "spin", SPIN.as_bytes().to_vec(), &m, None).unwrap();
+        n.pump(NodeMsg { to: "SPIN".into(), from: "seed".into(), unl: b"go".to_vec(), ..Default::default() });
+        let kinds = rec.0.lock().unwrap();
+        assert!(kinds.iter().any(|k| k == "deadline"));
+        assert!(!kinds.iter().any(|k| k == "fault"));
+    }
+
     #[test]
     fn spawn_restricts_child_caps_to_parent() {
         use crate::manifest::Capability;
@@ -1829,6 +3328,130 @@ mod tests {
         h.join().ok();
     }
 
+    /// `COUNTER_WASM` with a different step: the same state layout, new behaviour.
+    fn counter_stepping(by: i32) -> Vec<u8> {
+        COUNTER_WASM.replace("(i32.const 1))))", &format!("(i32.const {by}))))")).into_bytes()
+    }
+
+    fn count(n: &mut Node, uuid: &str) -> Vec<u8> {
+        n.agents.get_mut(uuid).unwrap().runtime.snapshot()
+    }
+
+    #[test]
+    fn a_spent_fuel_budget_drops_further_input_for_the_minute() {
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let mut m = wmanifest(&[]);
+        m.budget.fuel_per_min = 1;
+        n.mount_wasm("W", "w", COUNTER_WASM.as_bytes().to_vec(), &m, None).unwrap();
+        n.mount_wasm("U", "u", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        // `init` is billed too: leave room for exactly one more call
+        let spent = n.fuel_window["W"].1;
+        n.agents.get_mut("W").unwrap().grant.budget.fuel_per_min = spent + 1;
+        for to in ["W", "W", "U", "U"] {
+            n.pump(NodeMsg { to: to.into(), from: "seed".into(), unl: b"obj(ping)".to_vec(), ..Default::default() });
+        }
+        assert_eq!(count(&mut n, "W"), vec![1, 0, 0, 0]);
+        assert_eq!(count(&mut n, "U"), vec![2, 0, 0, 0]); // unmetered
+        assert!(n.fuel_window["W"].1 > spent + 1);
+    }
+
+    #[test]
+    fn a_throttled_tick_is_deferred_to_the_next_window_and_audited_once() {
+        struct Rec(std::sync::Mutex<Vec<String>>);
+        impl AuditSink for Rec {
+            fn record(&self, e: &AuditEvent) {
+                self.0.lock().unwrap().push(e.kind.clone());
+            }
+        }
+        let rec = Arc::new(Rec(std::sync::Mutex::new(Vec::new())));
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        n.set_audit(rec.clone());
+        let mut m = wmanifest(&[]);
+        m.budget.fuel_per_min = 1; // spent by `init`
+        n.mount_wasm("W", "w", COUNTER_WASM.as_bytes().to_vec(), &m, None).unwrap();
+
+        n.fire_tick("W", 7); // due, but the budget is spent
+        let next = n.fuel_window["W"].0 + FUEL_WINDOW_MS;
+        assert_eq!(n.timers["W"].get(&7), Some(&next));
+        n.pump(NodeMsg { to: "W".into(), from: "seed".into(), unl: b"obj(ping)".to_vec(), ..Default::default() });
+        n.fire_tick("W", 7);
+        assert_eq!(n.timers["W"].get(&7), Some(&next));
+        let kinds = rec.0.lock().unwrap();
+        assert_eq!(kinds.iter().filter(|k| *k == "denied:fuel-budget").count(), 1);
+    }
+
+    #[test]
+    fn upgrade_swaps_the_code_and_carries_the_state_over() {
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        n.mount_wasm("W", "w", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[Capability::State]), None).unwrap();
+        let ping = || NodeMsg { to: "W".into(), from: "seed".into(), unl: b"obj(ping)".to_vec(), ..Default::default() };
+        n.pump(ping());
+        n.pump(ping());
+        n.upgrade("W", counter_stepping(10), &wmanifest(&[]), None).unwrap(); // narrower grant: fine
+        assert_eq!(count(&mut n, "W"), vec![2, 0, 0, 0]); // the count survived
+        n.pump(ping());
+        assert_eq!(count(&mut n, "W"), vec![12, 0, 0, 0]); // …and the new code runs
+        assert!(!n.granted("W", Capability::State));
+        assert_eq!(n.agents["W"].code.as_deref(), Some(&counter_stepping(10)[..]));
+    }
+
+    #[test]
+    fn upgrade_refuses_escalation_and_rolls_back_a_failed_restore() {
+        struct Rec(std::sync::Mutex<Vec<String>>);
+        impl AuditSink for Rec {
+            fn record(&self, e: &AuditEvent) {
+                self.0.lock().unwrap().push(e.kind.clone());
+            }
+        }
+        let rec = Arc::new(Rec(std::sync::Mutex::new(Vec::new())));
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        n.set_audit(rec.clone());
+        n.mount_wasm("W", "w", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        n.pump(NodeMsg { to: "W".into(), from: "seed".into(), unl: b"obj(ping)".to_vec(), ..Default::default() });
+
+        // a new manifest may not ask for more than the agent already holds
+        assert!(n.upgrade("W", counter_stepping(2), &wmanifest(&[Capability::Spawn]), None).is_err());
+        let mut greedy = wmanifest(&[]);
+        greedy.budget.fuel *= 2;
+        assert!(n.upgrade("W", counter_stepping(2), &greedy, None).is_err());
+
+        // new code that rejects the snapshot never replaces the running agent
+        let broken = COUNTER_WASM.replace("(global.set $n (i32.load (local.get $p)))", "(unreachable)");
+        assert!(n.upgrade("W", broken.into_bytes(), &wmanifest(&[]), None).is_err());
+
+        assert_eq!(count(&mut n, "W"), vec![1, 0, 0, 0]);
+        assert_eq!(n.agents["W"].code.as_deref(), Some(COUNTER_WASM.as_bytes()));
+        let kinds = rec.0.lock().unwrap();
+        assert_eq!(kinds.iter().filter(|k| *k == "upgrade:escalation").count(), 2);
+        assert!(kinds.contains(&"upgrade:failed".to_string()));
+        assert!(!kinds.contains(&"upgraded".to_string()));
+    }
+
+    #[test]
+    fn upgraded_code_has_its_state_store_while_restoring() {
+        let dir = std::env::temp_dir().join(format!("upgrade-state-{}", std::process::id()));
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        n.set_store(crate::adapters::SledStore::open(&dir).unwrap());
+        let m = wmanifest(&[Capability::State]);
+        n.mount_wasm("W", "w", COUNTER_WASM.as_bytes().to_vec(), &m, None).unwrap();
+        // new code that writes its store from `restore`, and traps if it has none
+        let code = COUNTER_WASM
+            .replace(
+                r#"(memory (export "memory") 1)"#,
+                r#"(import "fipa:agent/storage" "store" (func $store (param i32 i32 i32 i32) (result i32)))
+      (memory (export "memory") 1)
+      (data (i32.const 512) "k")"#,
+            )
+            .replace(
+                "(global.set $n (i32.load (local.get $p)))",
+                "(if (call $store (i32.const 512) (i32.const 1) (i32.const 512) (i32.const 1)) (then (unreachable)))
+        (global.set $n (i32.load (local.get $p)))",
+            );
+        n.upgrade("W", code.into_bytes(), &m, None).unwrap();
+        assert_eq!(n.store.as_ref().unwrap().get("W", "k").unwrap().as_deref(), Some(&b"k"[..]));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn net_scope_none_sandboxes_to_local() {
         let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
@@ -1972,6 +3595,9 @@ mod tests {
             brain: Brain::Wasm,
             grants: grants.to_vec(),
             budget: Budget::default(),
+            stateless: false,
+            owner: None,
+            lineage: Vec::new(),
         }
     }
 
@@ -2055,7 +3681,7 @@ mod tests {
         let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
         n.set_profile(NodeProfile::iot());
         let mut m = wmanifest(&[]);
-        m.budget = Budget { mem_kb: 256, fuel: 1_000_000, state_kb: 64, timers: 2, msg_per_s: 50, net: "platform".into() };
+        m.budget = Budget { mem_kb: 256, fuel: 1_000_000, state_kb: 64, timers: 2, msg_per_s: 50, wall_ms: 1_000, fuel_per_min: 0, net: "platform".into() };
         let code = wat::parse_str(COUNTER_WASM).unwrap(); // wasmi needs binary wasm
         n.mount_wasm("CTR", "ctr", code, &m, None).unwrap(); // → wasmi interpreter
         n.pump(NodeMsg { to: "CTR".into(), from: "CTR".into(), unl: b"inc".to_vec(), ..Default::default() });
@@ -2063,6 +3689,29 @@ mod tests {
         assert_eq!(n.agents.get_mut("CTR").unwrap().runtime.snapshot(), vec![1, 0, 0, 0]);
     }
 
+    #[test]
+    fn a_stateless_agent_runtime_is_reset_and_reused_after_unmount() {
+        let mut n = Node::new("seed", "s", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let mut m = wmanifest(&[]);
+        m.stateless = true;
+        n.mount_wasm("B1", "b1", COUNTER_WASM.as_bytes().to_vec(), &m, None).unwrap();
+        n.pump(NodeMsg { to: "B1".into(), from: "seed".into(), unl: b"inc".to_vec(), ..Default::default() });
+        assert!(n.unmount("B1"));
+        assert!(!n.agents.contains_key("B1") && !n.aliases.contains_key("b1"));
+        assert_eq!(n.warm.values().map(Vec::len).sum::<usize>(), 1); // kept warm
+
+        n.mount_wasm("B2", "b2", COUNTER_WASM.as_bytes().to_vec(), &m, None).unwrap();
+        assert_eq!(n.warm.values().map(Vec::len).sum::<usize>(), 0); // taken from the pool
+        // the reused instance starts clean — nothing of B1's state leaks to B2
+        assert_eq!(n.agents.get_mut("B2").unwrap().runtime.snapshot(), vec![0, 0, 0, 0]);
+
+        // a stateful agent is dropped, not pooled
+        n.mount_wasm("C", "c", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        assert!(n.unmount("C"));
+        assert_eq!(n.warm.values().map(Vec::len).sum::<usize>(), 0);
+        assert!(!n.unmount("seed")); // the primary stays
+    }
+
     #[test]
     fn wasm_agent_migrates_with_state_between_nodes() {
         // source A hosts a mobile wasm counter, incremented to 3
@@ -2104,7 +3753,7 @@ mod tests {
         let m2 = sign(&kb, "X", Vec::new());
         assert!(n.wire_admit(&m2) && !n.authorize(&m2));
         // X under key B WITH a valid handoff A→B → accepted, TOFU moves to B
-        let ho = Handoff::sealed("X", kb.public_key().to_vec(), 1, &ka);
+        let ho = Handoff::sealed("X", kb.public_key().to_vec(), 1, "", now_ms(), &ka);
         let body = serde_json::json!({ "handoff": serde_json::to_value(&ho).unwrap() }).to_string();
         let m3 = sign(&kb, "X", body.into_bytes());
         assert!(n.wire_admit(&m3) && n.authorize(&m3));
@@ -2175,9 +3824,9 @@ mod tests {
         let mut dst = Node::new("seed-d", "d", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
         let manifest = wmanifest(&[]).to_json();
         let snap =
-            AgentSnapshot::sealed("ams", 1, COUNTER_WASM.as_bytes().to_vec(), Vec::new(), manifest, &attacker);
-        let ho = Handoff::sealed("ams", dst.node_pub().to_vec(), 1, &attacker);
-        let payload = MigratePayload { snapshot: snap, handoff: ho, from_addr: dst.addr.clone() }.encode();
+            AgentSnapshot::sealed("ams", 1, COUNTER_WASM.as_bytes().to_vec(), Vec::new(), manifest, Pending::default(), &attacker);
+        let ho = Handoff::sealed("ams", dst.node_pub().to_vec(), 1, &snap.digest(), now_ms(), &attacker);
+        let payload = MigratePayload { snapshot: snap, handoff: ho, from_addr: dst.addr.clone(), chain: None }.encode();
         dst.process_migrate(&payload);
         assert!(!dst.agents.contains_key("ams"));
     }
@@ -2197,9 +3846,9 @@ mod tests {
         let attacker = NodeCrypto::generate();
         let manifest = wmanifest(&[]).to_json();
         let snap =
-            AgentSnapshot::sealed("CTR", 99, COUNTER_WASM.as_bytes().to_vec(), Vec::new(), manifest, &attacker);
-        let ho = Handoff::sealed("CTR", b.node_pub().to_vec(), 99, &attacker);
-        let p2 = MigratePayload { snapshot: snap, handoff: ho, from_addr: b.addr.clone() }.encode();
+            AgentSnapshot::sealed("CTR", 99, COUNTER_WASM.as_bytes().to_vec(), Vec::new(), manifest, Pending::default(), &attacker);
+        let ho = Handoff::sealed("CTR", b.node_pub().to_vec(), 99, &snap.digest(), now_ms(), &attacker);
+        let p2 = MigratePayload { snapshot: snap, handoff: ho, from_addr: b.addr.clone(), chain: None }.encode();
         assert!(!b.process_migrate(&p2)); // impostor key → not prepared
         assert_eq!(b.seen.get("CTR"), Some(&1)); // epoch-99 impostor rejected; pin held
     }
@@ -2233,14 +3882,208 @@ mod tests {
         let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
         let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
         assert!(b.process_migrate(&payload));
-        b.abort_prepared("CTR");
+        b.abort_prepared("CTR", "source gave up");
         assert!(!b.agents.contains_key("CTR")); // prepared mount torn down
         assert!(b.seen.get("CTR").is_none()); // no replay-guard trace left behind
+        assert!(b.staging.is_empty()); // nor a staging-log entry
         // A clean retry (epoch bumped) prepares again.
         let payload2 = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
         assert!(b.process_migrate(&payload2));
     }
 
+    #[test]
+    fn a_staged_move_is_settled_only_by_the_origin_and_commit_is_idempotent() {
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        assert!(b.process_migrate(&payload));
+        assert_eq!(b.staging.get("CTR").map(|e| e.phase), Some(MovePhase::Prepared));
+
+        // A decision not signed by the origin is ignored — no answer, still staged.
+        let forged = MoveDecision::sealed("CTR", 1, true, "", &NodeCrypto::generate());
+        assert_eq!(b.settle_staged(&forged), None);
+        assert!(!b.agents.get("CTR").unwrap().active);
+        // Nor is one for an agent never staged here.
+        assert_eq!(b.settle_staged(&MoveDecision::sealed("XYZ", 1, true, "", &a.key)), None);
+
+        // ABORT rolls the staging back; a late COMMIT for it is answered with ABORT.
+        assert_eq!(b.settle_staged(&MoveDecision::sealed("CTR", 1, false, "operator", &a.key)), Some(false));
+        assert!(!b.agents.contains_key("CTR") && b.staging.is_empty());
+        assert_eq!(b.settle_staged(&MoveDecision::sealed("CTR", 1, true, "", &a.key)), Some(false));
+
+        // Re-staged and committed; a retried COMMIT (COMMITTED lost) re-acks.
+        let payload2 = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        assert!(b.process_migrate(&payload2));
+        let commit = MoveDecision::sealed("CTR", 2, true, "", &a.key);
+        assert_eq!(b.settle_staged(&commit), Some(true));
+        assert!(b.agents.get("CTR").unwrap().active && b.staging.is_empty());
+        assert_eq!(b.settle_staged(&commit), Some(true));
+        // Once settled, only the origin still gets an answer.
+        assert_eq!(b.settle_staged(&MoveDecision::sealed("CTR", 2, true, "", &NodeCrypto::generate())), None);
+    }
+
+    #[test]
+    fn an_unconfirmed_commit_is_retried_while_the_node_runs() {
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let payload = a.build_migrate_payload("CTR", &NodeCrypto::generate().public_key()).unwrap();
+        let since = now_ms() - COMMIT_RETRY_MS;
+        a.agents.get_mut("CTR").unwrap().active = false;
+        a.log_move(StagedMove {
+            role: MoveRole::Source,
+            phase: MovePhase::Committing,
+            uuid: "CTR".into(),
+            epoch: 1,
+            peer_addr: "127.0.0.1:1".into(), // the destination is unreachable
+            payload,
+            since_ms: since,
+            alias: "ctr".into(),
+            origin: String::new(),
+        });
+        a.retry_commits(since + COMMIT_RETRY_MS - 1);
+        assert_eq!(a.staging["CTR"].since_ms, since); // not due yet
+        a.retry_commits(now_ms());
+        await_resends(&mut a);
+        // Tried, still unknown: the copy stays suspended and the next try is a period on.
+        assert!(a.staging["CTR"].since_ms > since);
+        assert_eq!(a.staging["CTR"].phase, MovePhase::Committing);
+        assert!(!a.agents["CTR"].active);
+    }
+
+    /// Wait for a node's re-sent COMMITs to be answered, and settle them.
+    fn await_resends(n: &mut Node) {
+        while !n.resending.is_empty() {
+            n.settle_resends();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn a_commit_resend_does_not_hold_up_the_serve_loop() {
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let payload = a.build_migrate_payload("CTR", &NodeCrypto::generate().public_key()).unwrap();
+        // a destination that takes the connection and never answers
+        let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+        let since = now_ms() - COMMIT_RETRY_MS;
+        a.agents.get_mut("CTR").unwrap().active = false;
+        a.log_move(StagedMove {
+            role: MoveRole::Source,
+            phase: MovePhase::Committing,
+            uuid: "CTR".into(),
+            epoch: 1,
+            peer_addr: silent.local_addr().unwrap().to_string(),
+            payload,
+            since_ms: since,
+            alias: "ctr".into(),
+            origin: String::new(),
+        });
+        let t = std::time::Instant::now();
+        a.retry_commits(now_ms());
+        assert!(t.elapsed() < DIAL_TIMEOUT);
+        assert!(a.resending.contains("CTR") && a.staging["CTR"].since_ms > since);
+        // due again while the first is still out: not sent twice
+        a.retry_commits(now_ms() + COMMIT_RETRY_MS);
+        assert_eq!(a.resending.len(), 1);
+        assert_eq!(a.settle_resends(), 0);
+        assert!(!a.agents["CTR"].active);
+    }
+
+    #[test]
+    fn an_undecided_staging_expires() {
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        assert!(b.process_migrate(&payload));
+        let since = b.staging["CTR"].since_ms;
+        b.expire_staging(since + STAGING_TIMEOUT_MS - 1);
+        assert!(b.agents.contains_key("CTR")); // still inside the window
+        b.expire_staging(since + STAGING_TIMEOUT_MS);
+        assert!(!b.agents.contains_key("CTR") && b.staging.is_empty());
+    }
+
+    #[test]
+    fn a_restarted_node_settles_in_flight_moves_from_its_log() {
+        let dirs = ["src", "dst"].map(|n| std::env::temp_dir().join(format!("2pc-{n}-{}", std::process::id())));
+        // a node restarts with its store and its identity: the handoff names its key
+        let node = |uuid: &str, dir: &std::path::Path| {
+            let mut n = Node::new(uuid, uuid, "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+            n.load_key(dir.with_extension("key")).unwrap();
+            n.set_store(crate::adapters::SledStore::open(dir).unwrap());
+            n
+        };
+
+        // Source: CTR (n = 3) is mid-PREPARE when the node dies.
+        let mut a = node("seed-a", &dirs[0]);
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        for _ in 0..3 {
+            a.pump(NodeMsg { to: "CTR".into(), from: "CTR".into(), unl: b"inc".to_vec(), ..Default::default() });
+        }
+        let mut b = node("seed-b", &dirs[1]);
+        let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        a.log_move(StagedMove {
+            role: MoveRole::Source,
+            phase: MovePhase::Preparing,
+            uuid: "CTR".into(),
+            epoch: 1,
+            peer_addr: "127.0.0.1:1".into(), // the destination is unreachable from here
+            payload: payload.clone(),
+            since_ms: now_ms(),
+            alias: "ctr".into(),
+            origin: String::new(),
+        });
+        // Destination: staged it, then dies too.
+        assert!(b.process_migrate(&payload));
+        let origin = a.key.clone();
+        drop((a, b));
+
+        // The restarted source never decided to commit: the agent comes back, with
+        // its state, at the aborted epoch (so a retry advances past it).
+        let mut a = node("seed-a", &dirs[0]);
+        assert_eq!(a.recover_migrations(), 1);
+        assert!(a.agents.get("CTR").unwrap().active);
+        assert_eq!(a.agents.get_mut("CTR").unwrap().runtime.snapshot(), vec![3, 0, 0, 0]);
+        assert_eq!(a.agents["CTR"].epoch, 1);
+        assert_eq!(a.agents["CTR"].alias, "ctr"); // re-mounted under its own alias
+        assert!(a.persisted_moves().is_empty());
+
+        // The restarted destination re-stages for the rest of the window, so a
+        // source that did commit can still land the agent.
+        let mut b = node("seed-b", &dirs[1]);
+        assert_eq!(b.recover_migrations(), 1);
+        assert!(!b.agents.get("CTR").unwrap().active);
+        assert_eq!(b.settle_staged(&MoveDecision::sealed("CTR", 1, true, "", &origin)), Some(true));
+        assert_eq!(b.agents.get_mut("CTR").unwrap().runtime.snapshot(), vec![3, 0, 0, 0]);
+        assert!(b.persisted_moves().is_empty());
+
+        drop((a, b));
+        for dir in &dirs {
+            std::fs::remove_dir_all(dir).ok();
+            std::fs::remove_file(dir.with_extension("key")).ok();
+        }
+    }
+
+    #[test]
+    fn a_destination_without_its_old_identity_does_not_re_stage() {
+        let dir = std::env::temp_dir().join(format!("2pc-ephemeral-{}", std::process::id()));
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        b.set_store(crate::adapters::SledStore::open(&dir).unwrap());
+        assert!(b.process_migrate(&a.build_migrate_payload("CTR", &b.node_pub()).unwrap()));
+        drop(b);
+
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        b.set_store(crate::adapters::SledStore::open(&dir).unwrap());
+        assert_eq!(b.recover_migrations(), 0);
+        assert!(!b.agents.contains_key("CTR"));
+        assert!(b.persisted_moves().is_empty());
+        drop(b);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn handoff_with_mismatched_epoch_is_rejected() {
         // A handoff authorizes a key change only for the bind epoch it rides on; a
@@ -2257,7 +4100,7 @@ mod tests {
         let m1 = sign(&ka, "X", Vec::new());
         assert!(n.wire_admit(&m1) && n.authorize(&m1)); // X owned by A (TOFU)
 
-        let ho = Handoff::sealed("X", kb.public_key().to_vec(), 1, &ka); // handoff at epoch 1
+        let ho = Handoff::sealed("X", kb.public_key().to_vec(), 1, "", now_ms(), &ka); // handoff at epoch 1
         let bad = serde_json::json!({ "epoch": 2, "handoff": serde_json::to_value(&ho).unwrap() }).to_string();
         let m2 = sign(&kb, "X", bad.into_bytes());
         assert!(n.wire_admit(&m2) && !n.authorize(&m2)); // bind epoch 2 ≠ handoff epoch 1 → rejected
@@ -2266,4 +4109,472 @@ mod tests {
         let m3 = sign(&kb, "X", good.into_bytes());
         assert!(n.wire_admit(&m3) && n.authorize(&m3)); // epochs match → accepted
     }
+
+    // ── Attestation chain (MOBILITY §7) ───────────────────────────────────
+
+    /// A node hosting an owned counter "CTR", delegated by `owner`.
+    fn owned_node(owner: &NodeCrypto, alias: &str) -> Node {
+        let mut n = Node::new(&format!("seed-{alias}"), alias, "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let mut manifest = wmanifest(&[]);
+        manifest.owner = Some(hex::encode(owner.public_key()));
+        n.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &manifest, None).unwrap();
+        n
+    }
+
+    fn delegation(owner: &NodeCrypto, to: &Node, epoch: u64) -> Delegation {
+        let now = now_ms();
+        let hash = code_hash(COUNTER_WASM.as_bytes());
+        Delegation::sealed("CTR", to.node_pub().to_vec(), &hash, epoch, now, now + 60_000, owner)
+    }
+
+    /// A message from CTR as node `n` sends it (sealed, carrying n's chain).
+    fn from_ctr(n: &Node) -> NodeMsg {
+        let mut m = NodeMsg { to: "N".into(), from: "CTR".into(), ..Default::default() };
+        n.seal(&mut m);
+        m
+    }
+
+    #[test]
+    fn an_owned_agent_moves_and_speaks_under_its_attestation_chain() {
+        let owner = NodeCrypto::generate();
+        let mut a = owned_node(&owner, "a");
+        assert_eq!(a.delegate(delegation(&NodeCrypto::generate(), &a, 0)), Err(ChainError::WrongOwner));
+        a.delegate(delegation(&owner, &a, 0)).unwrap();
+        let mut c = dummy_node(); // a counterparty
+        assert!(c.authorize(&from_ctr(&a)));
+
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        let mut stripped = MigratePayload::decode(&payload).unwrap();
+        stripped.chain = None;
+        assert!(!b.process_migrate(&stripped.encode())); // an owned agent needs its chain
+        assert!(b.process_migrate(&payload));
+        b.commit_migrated("CTR");
+        assert_eq!(b.chains["CTR"].holder(), b.node_pub().as_slice());
+        assert_eq!(b.chains["CTR"].epoch(), 1);
+
+        assert!(c.authorize(&from_ctr(&b))); // the chain now ends at b
+        assert!(!c.authorize(&from_ctr(&a))); // a handed the agent on: its chain is superseded
+        let mut bare = NodeMsg { to: "N".into(), from: "CTR".into(), ..Default::default() };
+        a.seal(&mut bare);
+        bare.attest.clear();
+        bare.sig = a.key.sign(&signing_bytes(&bare)).to_vec();
+        assert!(!c.authorize(&bare)); // no falling back to TOFU once CTR is known to be owned
+    }
+
+    #[test]
+    fn an_owned_agent_upgrades_only_under_its_owners_delegation_of_the_new_code() {
+        let owner = NodeCrypto::generate();
+        let mut a = owned_node(&owner, "a");
+        a.delegate(delegation(&owner, &a, 0)).unwrap();
+        let (a_pub, now, code) = (a.node_pub(), now_ms(), counter_stepping(2));
+        let of = |key: &NodeCrypto, code: &[u8]| {
+            Delegation::sealed("CTR", a_pub.to_vec(), &code_hash(code), 0, now, now + 60_000, key)
+        };
+        let mut manifest = wmanifest(&[]);
+        manifest.owner = Some(hex::encode(owner.public_key()));
+        let mut usurper = manifest.clone();
+        usurper.owner = Some(hex::encode(NodeCrypto::generate().public_key()));
+
+        assert!(a.upgrade("CTR", code.clone(), &manifest, None).is_err());
+        assert!(a.upgrade("CTR", code.clone(), &manifest, Some(delegation(&owner, &a, 0))).is_err()); // the old code
+        assert!(a.upgrade("CTR", code.clone(), &manifest, Some(of(&NodeCrypto::generate(), &code))).is_err());
+        assert!(a.upgrade("CTR", code.clone(), &usurper, Some(of(&owner, &code))).is_err());
+        assert!(a.upgrade("CTR", code.clone(), &wmanifest(&[]), Some(of(&owner, &code))).is_err()); // disowned
+        assert_eq!(a.agents["CTR"].code.as_deref(), Some(COUNTER_WASM.as_bytes())); // all refused
+
+        a.upgrade("CTR", code.clone(), &manifest, Some(of(&owner, &code))).unwrap();
+        // the chain names the new code, so the agent still moves
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        assert!(b.process_migrate(&a.build_migrate_payload("CTR", &b.node_pub()).unwrap()));
+
+        // and an unowned agent cannot be given an owner by an upgrade
+        let mut n = dummy_node();
+        n.mount_wasm("W", "w", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        assert!(n.upgrade("W", code, &manifest, None).is_err());
+    }
+
+    #[test]
+    fn a_chain_cannot_claim_an_agent_already_pinned_by_key() {
+        let k = NodeCrypto::generate();
+        let mut c = dummy_node();
+        assert!(c.authorize(&signed_by(&k, "CTR"))); // CTR pinned to k, no owner known
+
+        // an attacker mints an owner of their own and delegates CTR to their node
+        let rogue = NodeCrypto::generate();
+        let mut x = owned_node(&rogue, "x");
+        x.delegate(delegation(&rogue, &x, 0)).unwrap();
+        assert!(!c.authorize(&from_ctr(&x)));
+        assert!(!c.owners.contains_key("CTR")); // the rogue owner is not pinned
+        assert!(c.authorize(&signed_by(&k, "CTR"))); // the real agent still speaks
+    }
+
+    #[test]
+    fn the_owner_compacts_and_revokes_a_chain() {
+        let owner = NodeCrypto::generate();
+        let mut a = owned_node(&owner, "a");
+        a.delegate(delegation(&owner, &a, 0)).unwrap();
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        assert!(b.process_migrate(&a.build_migrate_payload("CTR", &b.node_pub()).unwrap()));
+        b.commit_migrated("CTR");
+
+        // compaction: a fresh Delegation to the current holder replaces the chain
+        assert_eq!(b.delegate(delegation(&owner, &a, 1)), Err(ChainError::NotHolder));
+        b.delegate(delegation(&owner, &b, 1)).unwrap();
+        assert!(b.chains["CTR"].handoffs.is_empty());
+        let mut c = dummy_node();
+        assert!(c.authorize(&from_ctr(&b)));
+
+        // revocation: the owner delegates past b, and c refuses b's chain from then on
+        let elsewhere = Node::new("seed-d", "d", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        assert_eq!(c.revoke(&delegation(&NodeCrypto::generate(), &elsewhere, 5)), Err(ChainError::WrongOwner));
+        c.revoke(&delegation(&owner, &elsewhere, 5)).unwrap();
+        assert!(!c.authorize(&from_ctr(&b)));
+    }
+
+    #[test]
+    fn an_owner_compacts_and_revokes_over_the_wire() {
+        let owner = NodeCrypto::generate();
+        let mut a = owned_node(&owner, "a");
+        a.delegate(delegation(&owner, &a, 0)).unwrap();
+        let mut c = dummy_node(); // a counterparty that has heard from CTR at a
+        assert!(c.authorize(&from_ctr(&a)));
+        let compaction = delegation(&owner, &a, 1);
+        let elsewhere = Node::new("seed-d", "d", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let revocation = delegation(&owner, &elsewhere, 5);
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let serving = |mut n: Node| {
+            let l = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = l.local_addr().unwrap().to_string();
+            let sd = shutdown.clone();
+            (addr, thread::spawn(move || {
+                n.serve(l, sd);
+                n
+            }))
+        };
+        let ((aa, ha), (ca, hc)) = (serving(a), serving(c));
+        let tool = dummy_node(); // whatever the owner publishes through
+        assert!(tool.publish_delegation(&aa, &compaction).unwrap());
+        assert!(!tool.publish_delegation(&ca, &delegation(&NodeCrypto::generate(), &elsewhere, 5)).unwrap());
+        assert!(tool.publish_delegation(&ca, &revocation).unwrap());
+        shutdown.store(true, Ordering::Relaxed);
+        let (a, mut c) = (ha.join().unwrap(), hc.join().unwrap());
+
+        assert_eq!(a.chains["CTR"].epoch(), 1); // compacted where it runs
+        assert!(!c.authorize(&from_ctr(&a))); // and refused where the revocation reached
+    }
+
+    // ── Timers and requests across a move (MOBILITY §8) ──────────────────
+
+    #[test]
+    fn timers_and_outstanding_requests_move_with_the_agent() {
+        use crate::manifest::Capability;
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let manifest = wmanifest(&[Capability::Time, Capability::Llm]);
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &manifest, None).unwrap();
+        a.apply_timer_ops("CTR", vec![TimerOp::Set { id: 7, delay_ms: 60_000 }]);
+        a.apply_infer_reqs("CTR", vec![InferReq { req_id: 42, prompt: "hello".into() }]);
+
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        let carried = MigratePayload::decode(&payload).unwrap().snapshot.pending;
+        assert_eq!(carried.requests, vec![(42, "hello".to_string())]);
+        assert!(carried.timers[0].0 == 7 && carried.timers[0].1 <= 60_000); // remaining, not a deadline
+        assert!(b.process_migrate(&payload));
+        assert!(!b.timers.contains_key("CTR")); // nothing runs while staged
+        b.commit_migrated("CTR");
+
+        let deadline = b.timers["CTR"][&7];
+        assert!(deadline > now_ms() + 50_000 && deadline <= now_ms() + 60_000); // re-anchored on b's clock
+        assert_eq!(b.outstanding["CTR"][&42].0, "hello");
+        assert!(b.pending_infers.contains(&("CTR".to_string(), 42, "hello".to_string()))); // issued again
+
+        a.tombstone("CTR"); // a's late reply for CTR now finds nothing to answer
+        assert!(!a.timers.contains_key("CTR") && !a.outstanding.contains_key("CTR"));
+        assert!(a.pending_infers.is_empty());
+    }
+
+    /// Hands every inference reply it gets to the sink.
+    struct LlmEcho;
+    impl Agent for LlmEcho {
+        fn on_message(&mut self, unl: &str, body: &[u8], ctx: &mut Ctx) {
+            if ctx.from() == "llm" {
+                ctx.send("result", unl, body.to_vec());
+            }
+        }
+    }
+
+    fn infer_reply(m: &NodeMsg) -> (u64, String) {
+        let v: serde_json::Value = serde_json::from_slice(&m.body).unwrap();
+        (v["request_id"].as_u64().unwrap(), v["text"].as_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn an_unanswered_inference_expires_with_an_error_reply() {
+        let (tx, rx) = mpsc::channel();
+        let mut n = Node::new("E", "e", "127.0.0.1:0", Box::new(NativeRuntime::new(LlmEcho)));
+        n.set_sink(tx);
+        n.apply_infer_reqs("E", vec![InferReq { req_id: 9, prompt: "hi".into() }]);
+        let until = n.outstanding["E"][&9].1;
+        n.expire_infers(until - 1);
+        assert!(rx.try_recv().is_err()); // still waiting
+        n.expire_infers(until);
+        assert_eq!(infer_reply(&rx.try_recv().unwrap()), (9, "error: timed out".to_string()));
+        assert!(n.outstanding["E"].is_empty() && n.pending_infers.is_empty());
+        n.answer_infer("E", 9, "late"); // the backend's eventual answer is stale
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn a_carried_request_refused_on_rearm_is_answered() {
+        let (tx, rx) = mpsc::channel();
+        let mut n = Node::new("E", "e", "127.0.0.1:0", Box::new(NativeRuntime::new(LlmEcho)));
+        n.set_sink(tx);
+        let requests = (0..17).map(|id| (id, format!("q{id}"))).collect();
+        n.rearm("E", &Pending { timers: Vec::new(), requests });
+        assert_eq!(n.pending_infers.len(), 16); // the burst cap holds on re-issue too
+        assert_eq!(infer_reply(&rx.try_recv().unwrap()), (16, "error: denied".to_string()));
+        assert!(rx.try_recv().is_err());
+    }
+
+    // ── Forwarding after a move (MOBILITY §8) ────────────────────────────
+
+    #[test]
+    fn a_relayed_message_is_delivered_only_within_its_hop_bound() {
+        let (tx, rx) = mpsc::channel();
+        let mut n = dummy_node();
+        n.set_sink(tx);
+        let (k, forwarder) = (NodeCrypto::generate(), NodeCrypto::generate());
+        let mut m = NodeMsg { to: "N".into(), from: "X".into(), unl: b"obj(ping, x)".to_vec(), ..Default::default() };
+        m.sender_pub = k.public_key().to_vec();
+        m.nonce = k.nonce().to_vec();
+        m.sig = k.sign(&signing_bytes(&m)).to_vec();
+
+        let stamped = |hops, sig: &[u8]| {
+            let mut r = m.clone();
+            r.relay = Relay::sealed(hops, sig, &forwarder).encode();
+            r
+        };
+        n.accept_wire(stamped(MAX_FORWARD_HOPS + 1, &m.sig)); // past the hop bound
+        n.accept_wire(stamped(1, &[0; 64])); // a stamp lifted from another message
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+        n.accept_wire(stamped(1, &m.sig));
+        assert!(rx.recv_timeout(Duration::from_secs(1)).is_ok()); // delivered → Ponger's pong
+
+        // dialed elsewhere and signed so: a stripped stamp can't pass it off as direct
+        let mut elsewhere = m.clone();
+        elsewhere.to_addr = "127.0.0.1:7009".into();
+        elsewhere.nonce = k.nonce().to_vec();
+        elsewhere.sig = k.sign(&signing_bytes(&elsewhere)).to_vec();
+        n.accept_wire(elsewhere);
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn a_message_dialed_under_another_spelling_of_this_node_is_direct() {
+        let k = NodeCrypto::generate();
+        // Whether a message `to_addr`-ed as `dialed` reaches node N advertised at `addr`.
+        let delivered = |addr: &str, dialed: &str| {
+            let (tx, rx) = mpsc::channel();
+            let mut n = Node::new("N", "n", addr, Box::new(NativeRuntime::new(Ponger)));
+            n.set_sink(tx);
+            let mut m = NodeMsg { to: "N".into(), from: "X".into(), unl: b"obj(ping, x)".to_vec(), ..Default::default() };
+            m.to_addr = dialed.into();
+            m.sender_pub = k.public_key().to_vec();
+            m.nonce = k.nonce().to_vec();
+            m.sig = k.sign(&signing_bytes(&m)).to_vec();
+            n.accept_wire(m);
+            rx.recv_timeout(Duration::from_millis(500)).is_ok()
+        };
+        assert!(delivered("localhost:7021", &canonical_addr("localhost:7021"))); // hostname vs IP
+        assert!(delivered("[::1]:7022", "[0:0:0:0:0:0:0:1]:7022")); // IPv6, spelled out
+        assert!(delivered("0.0.0.0:7023", "10.1.2.3:7023")); // a wildcard advertised address
+        assert!(!delivered("0.0.0.0:7023", "10.1.2.3:7024"));
+        assert!(!delivered("127.0.0.1:7025", "127.0.0.2:7025"));
+    }
+
+    #[test]
+    fn the_former_host_forwards_and_redirects_a_sender_that_trusts_it() {
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:7001", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let b = Node::new("seed-b", "b", "127.0.0.1:7002", Box::new(NativeRuntime::new(Ponger)));
+        let mut r = dummy_node(); // has heard from CTR at a, and routes to it there
+        assert!(r.authorize(&from_ctr(&a)));
+        r.add_route("CTR", "127.0.0.1:7001");
+        let mut stranger = dummy_node(); // routes to CTR at a, but never heard from it
+        stranger.add_route("CTR", "127.0.0.1:7001");
+
+        let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        a.start_forwarding("CTR", "127.0.0.1:7002", &payload);
+        a.tombstone("CTR");
+        assert_eq!(a.routes["CTR"], "127.0.0.1:7002"); // local senders follow the agent
+        assert!(a.forwards.contains_key("CTR"));
+
+        // a hint self-signed under another key, from a's address, moves nothing
+        let rogue = NodeCrypto::generate();
+        let mut forged = signed_by(&rogue, "CTR");
+        forged.to = "N".into();
+        forged.from_addr = "127.0.0.1:7001".into();
+        forged.unl = MOVED_UNL.to_vec();
+        forged.body = serde_json::json!({ "agent": "CTR", "address": "127.0.0.1:6666", "handoff": "" }).to_string().into_bytes();
+        forged.sig = rogue.sign(&signing_bytes(&forged)).to_vec();
+        r.accept_wire(forged);
+        assert_eq!(r.routes["CTR"], "127.0.0.1:7001");
+
+        let hint = a.redirect_hint("CTR", "N", &r.addr).unwrap();
+        r.accept_wire(hint.clone());
+        assert_eq!(r.routes["CTR"], "127.0.0.1:7002");
+        assert_eq!(r.keys["CTR"], b.node_pub()); // the handoff moves the trusted key too
+        r.add_route("CTR", "127.0.0.1:7001");
+        r.accept_wire(hint.clone()); // a replayed hint is dropped
+        assert_eq!(r.routes["CTR"], "127.0.0.1:7001");
+        stranger.accept_wire(hint);
+        assert_eq!(stranger.routes["CTR"], "127.0.0.1:7001"); // unverifiable: a keeps relaying
+        assert!(!stranger.keys.contains_key("CTR")); // and a hint pins nothing
+    }
+
+    #[test]
+    fn a_forwarded_route_lapses_with_its_forwarder() {
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:7001", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let b = Node::new("seed-b", "b", "127.0.0.1:7002", Box::new(NativeRuntime::new(Ponger)));
+        let payload = a.build_migrate_payload("CTR", &b.node_pub()).unwrap();
+        a.start_forwarding("CTR", "127.0.0.1:7002", &payload);
+        a.tombstone("CTR");
+        a.expire_forwards(now_ms());
+        assert!(a.forwards.contains_key("CTR") && a.routes.contains_key("CTR"));
+        a.expire_forwards(now_ms() + FORWARD_TTL_MS + 1);
+        assert!(!a.forwards.contains_key("CTR"));
+        assert!(!a.routes.contains_key("CTR")); // the next send re-resolves through AMS
+    }
+
+    #[test]
+    fn a_frame_from_an_older_node_decodes_and_verifies() {
+        let k = NodeCrypto::generate();
+        let m = signed_by(&k, "X");
+        let mut frame = Vec::new();
+        for f in [m.to.as_bytes(), m.from.as_bytes(), m.from_addr.as_bytes(), &m.unl[..], &m.body[..], &m.nonce[..], &m.sig[..], &m.sender_pub[..]] {
+            put(&mut frame, f); // the fields ended at `sender_pub`
+        }
+        let d = decode_msg(&frame).unwrap();
+        assert!(d.attest.is_empty() && d.relay.is_empty() && d.to_addr.is_empty());
+        assert!(dummy_node().wire_admit(&d));
+        assert_eq!(decode_msg(&encode_msg(&m)).unwrap().sig, m.sig);
+    }
+
+    // ── Cloning (MOBILITY §3) ─────────────────────────────────────────────
+
+    #[test]
+    fn a_clone_is_a_new_instance_of_the_same_type_with_its_lineage() {
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let mut manifest = wmanifest(&[]);
+        manifest.type_id = uuid::Uuid::new_v4();
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &manifest, None).unwrap();
+        for _ in 0..3 {
+            a.pump(NodeMsg { to: "CTR".into(), from: "CTR".into(), unl: b"inc".to_vec(), ..Default::default() });
+        }
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        let (clone, payload) = a.build_clone_payload("CTR", &b.node_pub()).unwrap();
+        assert_ne!(clone, "CTR");
+        assert!(b.process_migrate(&payload));
+        b.commit_migrated(&clone);
+
+        let copy = b.agents.get_mut(&clone).unwrap();
+        assert!(copy.active);
+        assert_eq!(copy.runtime.snapshot(), vec![3, 0, 0, 0]); // starts from the parent's state
+        let carried = copy.manifest.as_ref().unwrap();
+        assert_eq!(carried.type_id, manifest.type_id);
+        assert_eq!(carried.lineage, vec!["CTR".to_string()]);
+        assert!(!b.agents.contains_key("CTR"));
+
+        // the original is untouched and keeps its identity and epoch
+        assert!(a.agents["CTR"].active);
+        assert_eq!(a.agents["CTR"].epoch, 0);
+        assert!(a.agents["CTR"].manifest.as_ref().unwrap().lineage.is_empty());
+    }
+
+    #[test]
+    fn an_owned_agent_is_not_cloned_by_its_host() {
+        let mut a = owned_node(&NodeCrypto::generate(), "a");
+        let err = a.clone_agent("CTR", "127.0.0.1:1", &[0; 32]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(a.agents["CTR"].active);
+    }
+
+    #[test]
+    fn a_clone_whose_committed_was_lost_is_settled_not_minted_again() {
+        let lb = TcpListener::bind("127.0.0.1:0").unwrap();
+        let bb = lb.local_addr().unwrap().to_string();
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let mut b = Node::new("seed-b", "b", &bb, Box::new(NativeRuntime::new(Ponger)));
+        let b_pub = b.node_pub();
+        // b staged and activated the copy, but its COMMITTED never reached a
+        let (clone, payload) = a.build_clone_payload("CTR", &b_pub).unwrap();
+        assert!(b.process_migrate(&payload));
+        b.commit_migrated(&clone);
+        a.log_move(StagedMove {
+            role: MoveRole::Clone,
+            phase: MovePhase::Committing,
+            uuid: clone.clone(),
+            epoch: 0,
+            peer_addr: bb.clone(),
+            payload,
+            since_ms: now_ms(),
+            alias: clone.clone(),
+            origin: "CTR".into(),
+        });
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let sd = shutdown.clone();
+        let hb = thread::spawn(move || b.serve(lb, sd));
+
+        // Retrying while the outcome is unknown is refused rather than minting a second copy.
+        assert!(a.clone_agent("CTR", &bb, &b_pub).is_err());
+        assert_eq!(a.staging.len(), 1);
+        // The serve loop's retry learns the copy is live and settles the entry.
+        a.retry_commits(now_ms() + COMMIT_RETRY_MS);
+        await_resends(&mut a);
+        assert!(a.staging.is_empty());
+        assert!(a.agents["CTR"].active); // the original never paused
+        // The next clone is a fresh instance.
+        let again = a.clone_agent("CTR", &bb, &b_pub).unwrap();
+        assert_ne!(again, clone);
+        assert!(a.staging.is_empty());
+
+        shutdown.store(true, Ordering::Relaxed);
+        hb.join().ok();
+    }
+
+    #[test]
+    fn ams_binds_a_clone_it_never_saw_on_the_source_nodes_handoff() {
+        let l = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ams_addr = l.local_addr().unwrap().to_string();
+        let mut ams = Node::new("seed-ams", "ams", &ams_addr, Box::new(NativeRuntime::new(ams_agent::Ams::new())));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let sd = shutdown.clone();
+        let h = thread::spawn(move || ams.serve(l, sd));
+
+        let mut a = Node::new("seed-a", "a", "127.0.0.1:0", Box::new(NativeRuntime::new(Ponger)));
+        a.mount_wasm("CTR", "ctr", COUNTER_WASM.as_bytes().to_vec(), &wmanifest(&[]), None).unwrap();
+        let mut b = Node::new("seed-b", "b", "127.0.0.1:7002", Box::new(NativeRuntime::new(Ponger)));
+        b.add_route("ams", &ams_addr);
+        let (clone, payload) = a.build_clone_payload("CTR", &b.node_pub()).unwrap();
+        assert!(b.process_migrate(&payload));
+        // b binds the copy under its own key, carrying the handoff a signed to it
+        b.commit_migrated(&clone);
+
+        let mut c = dummy_node();
+        c.set_ams(&ams_addr);
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        let mut at = c.address_of(&clone);
+        while at.is_none() && std::time::Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+            at = c.address_of(&clone);
+        }
+        assert_eq!(at.as_deref(), Some("127.0.0.1:7002"));
+
+        shutdown.store(true, Ordering::Relaxed);
+        h.join().ok();
+    }
 }
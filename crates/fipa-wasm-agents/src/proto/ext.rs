@@ -0,0 +1,212 @@
+// proto/ext.rs - Hand-written extensions to the generated types
+//
+// Constructors for the generated message types, plus the migration-package
+// integrity check and per-agent load metrics that sit next to them. These
+// replace the old hand-rolled `acl_message`/`agent` model: code that used it
+// builds `proto` types through the shims below instead.
+
+use super::{AclMessage, AgentId, AgentMigration, NodeMetrics, Performative, ProtocolType};
+use crate::adapters::{self, NodeCrypto};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+impl AgentId {
+    /// An agent ID with a name and no transport addresses
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            addresses: Vec::new(),
+            resolvers: Vec::new(),
+        }
+    }
+}
+
+impl AclMessage {
+    /// A fresh message with a random message ID
+    pub fn new(performative: Performative, sender: AgentId, receivers: Vec<AgentId>) -> Self {
+        Self {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            performative: performative as i32,
+            sender: Some(sender),
+            receivers,
+            ..Default::default()
+        }
+    }
+
+    /// Set the content bytes
+    pub fn with_content(mut self, content: impl Into<Vec<u8>>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// Set the interaction protocol
+    pub fn with_protocol(mut self, protocol: ProtocolType) -> Self {
+        self.protocol = Some(protocol as i32);
+        self
+    }
+
+    /// Set the conversation ID
+    pub fn with_conversation_id(mut self, conversation_id: impl Into<String>) -> Self {
+        self.conversation_id = Some(conversation_id.into());
+        self
+    }
+}
+
+/// Integrity data of a sealed [`AgentMigration`] package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageVerification {
+    /// SHA-256 over the signed fields of the package
+    pub hash: [u8; 32],
+
+    /// Ed25519 signature over the signed fields
+    pub signature: Vec<u8>,
+
+    /// Public key of the signing node
+    pub signer_public_key: Vec<u8>,
+
+    /// When the package was sealed (Unix millis)
+    pub timestamp: i64,
+}
+
+impl AgentMigration {
+    /// Sign the package with the origin node key, stamping key and time
+    pub fn seal(&mut self, key: &NodeCrypto) {
+        self.public_key = key.public_key().to_vec();
+        self.timestamp = chrono::Utc::now().timestamp_millis();
+        self.signature = key.sign(&self.signing_bytes()).to_vec();
+    }
+
+    /// The integrity data this package carries
+    pub fn verification(&self) -> PackageVerification {
+        PackageVerification {
+            hash: Sha256::digest(self.signing_bytes()).into(),
+            signature: self.signature.clone(),
+            signer_public_key: self.public_key.clone(),
+            timestamp: self.timestamp,
+        }
+    }
+
+    /// Verify the origin signature, and that any inlined module matches
+    /// `wasm_hash`
+    pub fn verify(&self) -> bool {
+        let hash = self.wasm_hash.as_slice();
+        if self.wasm_module.as_ref().is_some_and(|m| Sha256::digest(m).as_slice() != hash) {
+            return false; // inlined module does not match its hash
+        }
+        let (Ok(pk), Ok(sig)) = (
+            <[u8; 32]>::try_from(self.public_key.as_slice()),
+            <[u8; 64]>::try_from(self.signature.as_slice()),
+        ) else {
+            return false;
+        };
+        adapters::verify(&pk, &self.signing_bytes(), &sig)
+    }
+
+    /// Every field except the signature and the (fetchable) module, each
+    /// length-prefixed under a version tag
+    fn signing_bytes(&self) -> Vec<u8> {
+        fn put(b: &mut Vec<u8>, field: &[u8]) {
+            b.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            b.extend_from_slice(field);
+        }
+
+        let mut b = Vec::new();
+        b.extend_from_slice(b"fipa:package:v1\0");
+        put(&mut b, self.agent_id.as_ref().map(|a| a.name.as_bytes()).unwrap_or_default());
+        put(&mut b, &self.wasm_hash);
+        put(&mut b, &self.state.as_ref().map(prost::Message::encode_to_vec).unwrap_or_default());
+        put(&mut b, &self.capabilities.as_ref().map(prost::Message::encode_to_vec).unwrap_or_default());
+        put(&mut b, self.migration_history.join("\n").as_bytes());
+        b.extend_from_slice(&self.reason.to_be_bytes());
+        put(&mut b, &self.public_key);
+        b.extend_from_slice(&self.timestamp.to_be_bytes());
+        b
+    }
+}
+
+/// Load an agent puts on its node
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadMetrics {
+    /// Open protocol conversations
+    pub active_conversations: usize,
+
+    /// CPU share used by the agent
+    pub cpu_usage_percent: f32,
+
+    /// Linear memory in use
+    pub memory_usage_bytes: usize,
+}
+
+impl LoadMetrics {
+    /// Add this agent's load to a node total
+    pub fn add_to(&self, node: &mut NodeMetrics) {
+        node.active_agents += 1;
+        node.active_conversations += self.active_conversations as u32;
+        node.cpu_usage_percent += self.cpu_usage_percent;
+        node.memory_used_bytes += self.memory_usage_bytes as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package() -> AgentMigration {
+        let module = b"\0asm".to_vec();
+        AgentMigration {
+            agent_id: Some(AgentId::named("mover")),
+            wasm_hash: Sha256::digest(&module).to_vec(),
+            wasm_module: Some(module),
+            migration_history: vec!["node-1".into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_message_shims() {
+        let msg = AclMessage::new(Performative::Request, AgentId::named("a"), vec![AgentId::named("b")])
+            .with_content("do it")
+            .with_protocol(ProtocolType::ProtocolRequest)
+            .with_conversation_id("c1");
+
+        assert_eq!(msg.performative(), Performative::Request);
+        assert_eq!(msg.protocol, Some(ProtocolType::ProtocolRequest as i32));
+        assert_eq!(msg.content, b"do it");
+        assert!(!msg.message_id.is_empty());
+    }
+
+    #[test]
+    fn test_sealed_package_verifies() {
+        let key = NodeCrypto::generate();
+        let mut pkg = package();
+        assert!(!pkg.verify());
+
+        pkg.seal(&key);
+        assert!(pkg.verify());
+        assert_eq!(pkg.verification().signer_public_key, key.public_key().to_vec());
+
+        let mut tampered = pkg.clone();
+        tampered.migration_history.push("node-2".into());
+        assert!(!tampered.verify());
+
+        let mut swapped = pkg.clone();
+        swapped.wasm_module = Some(b"other".to_vec());
+        assert!(!swapped.verify());
+
+        // The module may be left out for the destination to fetch by hash.
+        pkg.wasm_module = None;
+        assert!(pkg.verify());
+    }
+
+    #[test]
+    fn test_load_metrics_sum_into_node() {
+        let mut node = NodeMetrics::default();
+        for conversations in [2, 3] {
+            LoadMetrics { active_conversations: conversations, memory_usage_bytes: 1024, ..Default::default() }
+                .add_to(&mut node);
+        }
+        assert_eq!(node.active_agents, 2);
+        assert_eq!(node.active_conversations, 5);
+        assert_eq!(node.memory_used_bytes, 2048);
+    }
+}
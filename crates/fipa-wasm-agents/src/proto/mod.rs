@@ -11,5 +11,9 @@ pub mod fipa {
 // Re-export common types for convenience
 pub use fipa::v1::*;
 
+// Hand-written constructors and helpers on the generated types
+mod ext;
+pub use ext::{LoadMetrics, PackageVerification};
+
 /// File descriptor set for gRPC reflection
 pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("fipa_descriptor");
@@ -1,8 +1,10 @@
 // protocol/contract_net.rs - FIPA Contract Net Protocol
 
+use super::negotiation::{BiddingStrategy, Decision, EvaluationContext, ProposalEvaluator};
 use super::state_machine::*;
 use crate::proto;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
 /// FIPA Contract Net Protocol States
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -62,6 +64,21 @@ pub struct ContractNetProtocol {
 
     /// Results from accepted contractors
     results: HashMap<String, Vec<u8>>,
+
+    /// Refusals received
+    refusals: usize,
+
+    /// Initiator: picks winners at the deadline
+    evaluator: Option<Arc<dyn ProposalEvaluator>>,
+
+    /// Participant: answers CFPs
+    bidder: Option<Arc<dyn BiddingStrategy>>,
+
+    /// When the CFP went out
+    started_at: i64,
+
+    /// Award messages not yet handed out by `poll`
+    outbox: VecDeque<proto::AclMessage>,
 }
 
 impl ContractNetProtocol {
@@ -74,6 +91,11 @@ impl ContractNetProtocol {
             proposals: Vec::new(),
             accepted: HashMap::new(),
             results: HashMap::new(),
+            refusals: 0,
+            evaluator: None,
+            bidder: None,
+            started_at: 0,
+            outbox: VecDeque::new(),
         }
     }
 
@@ -83,6 +105,25 @@ impl ContractNetProtocol {
         self
     }
 
+    /// Set the deadline for proposals (defaults to the CFP's `reply_by`)
+    pub fn with_deadline(mut self, deadline: i64) -> Self {
+        self.base.deadline = Some(deadline);
+        self
+    }
+
+    /// Award automatically at the deadline, or once every expected
+    /// participant has answered
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn ProposalEvaluator>) -> Self {
+        self.evaluator = Some(evaluator);
+        self
+    }
+
+    /// Answer CFPs automatically with this strategy
+    pub fn with_bidder(mut self, bidder: Arc<dyn BiddingStrategy>) -> Self {
+        self.bidder = Some(bidder);
+        self
+    }
+
     /// Get all proposals
     pub fn proposals(&self) -> &[Proposal] {
         &self.proposals
@@ -100,6 +141,79 @@ impl ContractNetProtocol {
             })
     }
 
+    fn cfp(&self) -> Option<&proto::AclMessage> {
+        self.base.messages.iter().find(|m| m.performative == proto::Performative::Cfp as i32)
+    }
+
+    fn context<'a>(&self, cfp: &'a [u8], now_ms: i64) -> EvaluationContext<'a> {
+        EvaluationContext {
+            cfp,
+            round: 1,
+            max_rounds: 1,
+            started_ms: self.started_at,
+            deadline_ms: self.base.deadline,
+            now_ms,
+        }
+    }
+
+    /// Run the evaluator over the proposals: accept-proposal to each winner,
+    /// one reject-proposal to everyone else
+    fn award(&mut self, now_ms: i64) -> ProcessResult {
+        let (Some(evaluator), Some(cfp)) = (self.evaluator.clone(), self.cfp().cloned()) else {
+            return ProcessResult::Continue;
+        };
+        self.base.deadline.take();
+        if self.proposals.is_empty() {
+            self.state = ContractNetState::Failed;
+            return ProcessResult::Failed("No proposals received".into());
+        }
+
+        let winners = match evaluator.evaluate(&self.proposals, &self.context(&cfp.content, now_ms)) {
+            Decision::Accept(names) => names,
+            Decision::Revise(_) | Decision::RejectAll => Vec::new(),
+        };
+        let mut losers = Vec::new();
+        for proposal in self.proposals.clone() {
+            if winners.contains(&proposal.bidder.name) {
+                let accept = addressed(&cfp, proto::Performative::AcceptProposal, vec![proposal.bidder.clone()], proposal.content.clone());
+                self.accepted.insert(proposal.bidder.name.clone(), proposal);
+                self.outbox.push_back(accept);
+            } else {
+                losers.push(proposal.bidder);
+            }
+        }
+        if !losers.is_empty() {
+            self.outbox.push_back(addressed(&cfp, proto::Performative::RejectProposal, losers, Vec::new()));
+        }
+        for msg in &self.outbox {
+            self.base.record_message(msg.clone());
+        }
+
+        self.state = if self.accepted.is_empty() {
+            ContractNetState::Rejected
+        } else {
+            ContractNetState::InExecution
+        };
+        match self.outbox.pop_front() {
+            Some(msg) => ProcessResult::Respond(msg),
+            None => ProcessResult::Continue,
+        }
+    }
+
+    /// Answer a CFP with the bidding strategy, as if the reply came through `process`
+    fn bid(&mut self, cfp: &proto::AclMessage) -> Result<Option<proto::AclMessage>, ProtocolError> {
+        let Some(bidder) = self.bidder.clone() else { return Ok(None) };
+        let now = chrono::Utc::now().timestamp_millis();
+        let (performative, content) = match bidder.bid(&self.context(&cfp.content, now)) {
+            Some(terms) => (proto::Performative::Propose, terms),
+            None => (proto::Performative::Refuse, Vec::new()),
+        };
+        self.state = self.validate_transition(performative)?;
+        let reply = create_response(cfp, performative, content);
+        self.base.record_message(reply.clone());
+        Ok(Some(reply))
+    }
+
     fn validate_transition(&self, performative: proto::Performative) -> Result<ContractNetState, ProtocolError> {
         use proto::Performative::*;
         use ContractNetState::*;
@@ -149,6 +263,13 @@ impl ProtocolStateMachine for ContractNetProtocol {
         match performative {
             proto::Performative::Cfp => {
                 self.task_description = Some(msg.content.clone());
+                self.started_at = chrono::Utc::now().timestamp_millis();
+                if self.base.deadline.is_none() {
+                    self.base.deadline = msg.reply_by;
+                }
+            }
+            proto::Performative::Refuse => {
+                self.refusals += 1;
             }
             proto::Performative::Propose => {
                 if let Some(sender) = &msg.sender {
@@ -170,6 +291,21 @@ impl ProtocolStateMachine for ContractNetProtocol {
 
         self.state = new_state;
 
+        if performative == proto::Performative::Cfp
+            && self.base.role == Role::Participant
+            && let Some(reply) = self.bid(&msg)?
+        {
+            return Ok(ProcessResult::Respond(reply));
+        }
+        let answered = self.proposals.len() + self.refusals;
+        if self.state == ContractNetState::ProposalsReceived
+            && self.base.role == Role::Initiator
+            && self.expected_participants > 0
+            && answered >= self.expected_participants
+        {
+            return Ok(self.award(chrono::Utc::now().timestamp_millis()));
+        }
+
         match &self.state {
             ContractNetState::Completed => Ok(ProcessResult::Complete(CompletionData {
                 result: self.results.values().next().cloned(),
@@ -182,6 +318,17 @@ impl ProtocolStateMachine for ContractNetProtocol {
         }
     }
 
+    fn poll(&mut self, now_ms: i64) -> ProcessResult {
+        if let Some(msg) = self.outbox.pop_front() {
+            return ProcessResult::Respond(msg);
+        }
+        let collecting = matches!(self.state, ContractNetState::CfpSent | ContractNetState::ProposalsReceived);
+        if self.base.role != Role::Initiator || !collecting || self.base.deadline.is_none_or(|d| now_ms < d) {
+            return ProcessResult::Continue;
+        }
+        self.award(now_ms)
+    }
+
     fn is_complete(&self) -> bool {
         matches!(
             self.state,
@@ -218,3 +365,101 @@ impl ProtocolStateMachine for ContractNetProtocol {
         &self.base.messages
     }
 }
+
+/// A message from the CFP's sender, in its conversation, to `receivers`
+pub(super) fn addressed(
+    cfp: &proto::AclMessage,
+    performative: proto::Performative,
+    receivers: Vec<proto::AgentId>,
+    content: Vec<u8>,
+) -> proto::AclMessage {
+    proto::AclMessage {
+        message_id: uuid::Uuid::new_v4().to_string(),
+        performative: performative as i32,
+        sender: cfp.sender.clone(),
+        receivers,
+        protocol: cfp.protocol,
+        conversation_id: cfp.conversation_id.clone(),
+        in_reply_to: Some(cfp.message_id.clone()),
+        language: cfp.language.clone(),
+        encoding: cfp.encoding.clone(),
+        ontology: cfp.ontology.clone(),
+        content,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::negotiation::{terms_of, FixedBid, LowestPrice};
+    use super::*;
+
+    fn message(performative: proto::Performative, sender: &str, content: &str) -> proto::AclMessage {
+        proto::AclMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            performative: performative as i32,
+            sender: Some(proto::AgentId::named(sender)),
+            receivers: vec![proto::AgentId::named("a"), proto::AgentId::named("b")],
+            conversation_id: Some("cn-1".into()),
+            content: content.as_bytes().to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_evaluator_awards_once_all_replied() {
+        let mut protocol = ContractNetProtocol::new(Role::Initiator)
+            .with_expected_participants(2)
+            .with_evaluator(Arc::new(LowestPrice::new()));
+
+        protocol.process(message(proto::Performative::Cfp, "manager", "{}")).unwrap();
+        let first = protocol.process(message(proto::Performative::Propose, "a", r#"{"price": 100}"#)).unwrap();
+        assert!(matches!(first, ProcessResult::Continue));
+
+        let ProcessResult::Respond(accept) = protocol.process(message(proto::Performative::Propose, "b", r#"{"price": 80}"#)).unwrap() else {
+            panic!("expected an award");
+        };
+        assert_eq!(accept.performative(), proto::Performative::AcceptProposal);
+        assert_eq!(accept.receivers[0].name, "b");
+        assert_eq!(accept.conversation_id.as_deref(), Some("cn-1"));
+
+        let ProcessResult::Respond(reject) = protocol.poll(0) else {
+            panic!("expected the rejection");
+        };
+        assert_eq!(reject.performative(), proto::Performative::RejectProposal);
+        assert_eq!(reject.receivers[0].name, "a");
+        assert!(matches!(protocol.poll(0), ProcessResult::Continue));
+        assert_eq!(protocol.state_name(), "in_execution");
+    }
+
+    #[test]
+    fn test_evaluator_runs_at_deadline() {
+        let mut protocol = ContractNetProtocol::new(Role::Initiator)
+            .with_expected_participants(3)
+            .with_deadline(1_000)
+            .with_evaluator(Arc::new(LowestPrice::new()));
+
+        protocol.process(message(proto::Performative::Cfp, "manager", "{}")).unwrap();
+        protocol.process(message(proto::Performative::Propose, "a", r#"{"price": 100}"#)).unwrap();
+        assert!(matches!(protocol.poll(999), ProcessResult::Continue));
+
+        let ProcessResult::Respond(accept) = protocol.poll(1_000) else {
+            panic!("expected an award at the deadline");
+        };
+        assert_eq!(accept.receivers[0].name, "a");
+        assert!(matches!(protocol.poll(1_001), ProcessResult::Continue));
+    }
+
+    #[test]
+    fn test_bidder_answers_cfp() {
+        let terms = [("price".to_string(), 42.0)].into_iter().collect();
+        let mut protocol = ContractNetProtocol::new(Role::Participant).with_bidder(Arc::new(FixedBid::new(terms)));
+
+        let ProcessResult::Respond(bid) = protocol.process(message(proto::Performative::Cfp, "manager", "{}")).unwrap() else {
+            panic!("expected a bid");
+        };
+        assert_eq!(bid.performative(), proto::Performative::Propose);
+        assert_eq!(terms_of(&bid.content).unwrap()["price"], 42.0);
+        assert_eq!(protocol.state_name(), "proposals_received");
+    }
+}
@@ -0,0 +1,185 @@
+// protocol/conversation.rs - Conversation Handlers and Manager
+
+//! Conversation bookkeeping on top of [`ProtocolStateMachine`].
+//!
+//! A [`GenericConversation`] pairs a protocol state machine with typed,
+//! caller-owned metadata (a deadline, the task under negotiation, ...).
+//! [`ConversationManager`] routes incoming messages to the right
+//! conversation by conversation ID and drops finished ones.
+
+use super::state_machine::{create_state_machine, ProcessResult, ProtocolError, ProtocolStateMachine};
+use crate::proto;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// A conversation that can consume messages and report progress
+pub trait ConversationHandler: Send + Sync {
+    /// Feed a message; returns the reply the protocol wants sent, if any
+    fn handle_message(&mut self, msg: proto::AclMessage) -> Result<Option<proto::AclMessage>, ProtocolError>;
+
+    /// Current protocol state name
+    fn state_name(&self) -> &str;
+
+    /// Whether the conversation reached a terminal state
+    fn is_complete(&self) -> bool;
+}
+
+/// A protocol state machine with attached metadata
+#[derive(Debug)]
+pub struct GenericConversation<M = ()> {
+    machine: Box<dyn ProtocolStateMachine>,
+
+    /// Caller-defined conversation metadata
+    pub metadata: M,
+}
+
+impl<M> GenericConversation<M> {
+    /// Wrap an existing state machine
+    pub fn new(machine: Box<dyn ProtocolStateMachine>, metadata: M) -> Self {
+        Self { machine, metadata }
+    }
+
+    /// Start a conversation for a built-in protocol
+    pub fn for_protocol(protocol: proto::ProtocolType, metadata: M) -> Result<Self, ProtocolError> {
+        Ok(Self::new(create_state_machine(protocol)?, metadata))
+    }
+
+    /// The underlying state machine
+    pub fn machine(&self) -> &dyn ProtocolStateMachine {
+        self.machine.as_ref()
+    }
+
+    /// Mutable access to the underlying state machine
+    pub fn machine_mut(&mut self) -> &mut dyn ProtocolStateMachine {
+        self.machine.as_mut()
+    }
+
+    /// Messages processed so far
+    pub fn messages(&self) -> &[proto::AclMessage] {
+        self.machine.message_history()
+    }
+}
+
+impl<M: Send + Sync> ConversationHandler for GenericConversation<M> {
+    fn handle_message(&mut self, msg: proto::AclMessage) -> Result<Option<proto::AclMessage>, ProtocolError> {
+        match self.machine.process(msg)? {
+            ProcessResult::Respond(reply) => Ok(Some(reply)),
+            _ => Ok(None),
+        }
+    }
+
+    fn state_name(&self) -> &str {
+        self.machine.state_name()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.machine.is_complete()
+    }
+}
+
+/// Routes messages to conversations by conversation ID
+#[derive(Default)]
+pub struct ConversationManager {
+    conversations: HashMap<String, Box<dyn ConversationHandler>>,
+}
+
+impl ConversationManager {
+    /// Create an empty manager
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a conversation under an ID, replacing any previous one
+    pub fn add_conversation(&mut self, conversation_id: impl Into<String>, handler: Box<dyn ConversationHandler>) {
+        self.conversations.insert(conversation_id.into(), handler);
+    }
+
+    /// Look up a conversation
+    pub fn get(&self, conversation_id: &str) -> Option<&dyn ConversationHandler> {
+        self.conversations.get(conversation_id).map(|h| h.as_ref())
+    }
+
+    /// Dispatch a message to its conversation
+    pub fn handle_message(&mut self, msg: proto::AclMessage) -> Result<Option<proto::AclMessage>, ProtocolError> {
+        let conversation_id = msg.conversation_id.clone().ok_or(ProtocolError::MissingConversationId)?;
+
+        match self.conversations.get_mut(&conversation_id) {
+            Some(handler) => handler.handle_message(msg),
+            None => Err(ProtocolError::UnknownConversation(conversation_id)),
+        }
+    }
+
+    /// Drop conversations that reached a terminal state
+    pub fn cleanup_completed(&mut self) {
+        self.conversations.retain(|_, handler| !handler.is_complete());
+    }
+
+    /// Number of tracked conversations
+    pub fn len(&self) -> usize {
+        self.conversations.len()
+    }
+
+    /// Whether no conversations are tracked
+    pub fn is_empty(&self) -> bool {
+        self.conversations.is_empty()
+    }
+}
+
+impl Debug for ConversationManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.conversations.iter().map(|(id, h)| (id, h.state_name())))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(performative: proto::Performative, conversation_id: Option<&str>) -> proto::AclMessage {
+        proto::AclMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            performative: performative as i32,
+            sender: Some(proto::AgentId { name: "a".into(), ..Default::default() }),
+            receivers: vec![proto::AgentId { name: "b".into(), ..Default::default() }],
+            conversation_id: conversation_id.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generic_conversation_keeps_metadata() {
+        let mut conv = GenericConversation::for_protocol(proto::ProtocolType::ProtocolRequest, "deadline").unwrap();
+        conv.handle_message(message(proto::Performative::Request, Some("c1"))).unwrap();
+
+        assert_eq!(conv.state_name(), "requested");
+        assert_eq!(conv.metadata, "deadline");
+        assert_eq!(conv.messages().len(), 1);
+    }
+
+    #[test]
+    fn test_manager_routes_and_cleans_up() {
+        let mut manager = ConversationManager::new();
+        let conv = GenericConversation::for_protocol(proto::ProtocolType::ProtocolRequest, ()).unwrap();
+        manager.add_conversation("c1", Box::new(conv));
+
+        assert!(matches!(
+            manager.handle_message(message(proto::Performative::Request, None)),
+            Err(ProtocolError::MissingConversationId)
+        ));
+        assert!(matches!(
+            manager.handle_message(message(proto::Performative::Request, Some("c2"))),
+            Err(ProtocolError::UnknownConversation(_))
+        ));
+
+        manager.handle_message(message(proto::Performative::Request, Some("c1"))).unwrap();
+        manager.cleanup_completed();
+        assert_eq!(manager.len(), 1);
+
+        manager.handle_message(message(proto::Performative::Refuse, Some("c1"))).unwrap();
+        assert!(manager.get("c1").unwrap().is_complete());
+        manager.cleanup_completed();
+        assert!(manager.is_empty());
+    }
+}
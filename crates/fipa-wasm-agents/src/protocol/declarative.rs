@@ -0,0 +1,629 @@
+// protocol/declarative.rs - Protocols Defined in a Spec File
+//
+//! Declarative protocol definitions.
+//!
+//! A domain protocol is described as data — roles, states, the performatives
+//! allowed on each transition, per-state timeouts and terminal outcomes — and
+//! loaded from TOML or JSON. [`ProtocolSpec::compile`] checks the definition
+//! once, at load time; the resulting [`CompiledProtocol`] hands out
+//! [`DeclarativeProtocol`] instances that run it as a [`ProtocolStateMachine`].
+//!
+//! ```toml
+//! name = "stock-reservation"
+//! protocol_type = "request"          # optional: reported by protocol_type()
+//! roles = ["initiator", "participant"]
+//! initial = "start"
+//!
+//! [[states]]
+//! name = "start"
+//!
+//! [[states]]
+//! name = "asked"
+//! timeout_ms = 5000                  # optional; on_timeout defaults to failing
+//! on_timeout = "expired"
+//!
+//! [[states]]
+//! name = "reserved"
+//! outcome = "completed"
+//!
+//! [[states]]
+//! name = "expired"
+//! outcome = "failed"
+//!
+//! [[transitions]]
+//! from = ["start"]
+//! performatives = ["request"]
+//! to = "asked"
+//! by = "initiator"
+//!
+//! [[transitions]]
+//! from = ["asked"]
+//! performatives = ["agree", "inform-done"]
+//! to = "reserved"
+//! by = "participant"
+//! ```
+//!
+//! `from = ["*"]` matches every non-terminal state. Performatives use their
+//! FIPA names (`inform-done`, `accept-proposal`, …).
+//!
+//! `by` is enforced: each sender is bound to the role of the first transition
+//! it takes, and may only take that role's transitions (or ones with no `by`)
+//! from then on. Only one sender can be the initiator. Two transitions may
+//! leave a state on the same performative if they are sent by different roles.
+
+use super::state_machine::*;
+use crate::proto;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Matches every non-terminal state in a transition's `from`.
+pub const ANY_STATE: &str = "*";
+
+/// Errors loading or compiling a protocol definition.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SpecError {
+    #[error("cannot read protocol spec: {0}")]
+    Io(String),
+
+    #[error("cannot parse protocol spec: {0}")]
+    Parse(String),
+
+    #[error("protocol '{protocol}': {detail}")]
+    Invalid { protocol: String, detail: String },
+}
+
+/// How a terminal state ends the conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StateOutcome {
+    Completed,
+    Failed,
+}
+
+/// One state of a declared protocol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSpec {
+    pub name: String,
+    /// Set on terminal states only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<StateOutcome>,
+    /// How long the conversation may stay in this state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Where a timeout leads; failing the conversation if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_timeout: Option<String>,
+}
+
+/// One transition of a declared protocol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransitionSpec {
+    /// Source states, or [`ANY_STATE`].
+    pub from: Vec<String>,
+    /// Performatives that take it, by FIPA name.
+    pub performatives: Vec<String>,
+    pub to: String,
+    /// The role that sends these performatives.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+}
+
+/// A protocol definition as written in a spec file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolSpec {
+    pub name: String,
+    /// The [`proto::ProtocolType`] reported, by name (`contract-net`); unspecified if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_type: Option<String>,
+    /// Role names: `initiator`, `participant`, `broker`.
+    pub roles: Vec<String>,
+    pub initial: String,
+    pub states: Vec<StateSpec>,
+    pub transitions: Vec<TransitionSpec>,
+}
+
+/// Parse a FIPA performative name (`inform-done`).
+pub fn performative_from_name(name: &str) -> Option<proto::Performative> {
+    let upper = name.trim().to_ascii_uppercase().replace('-', "_");
+    proto::Performative::from_str_name(&format!("PERFORMATIVE_{upper}"))
+        .filter(|p| *p != proto::Performative::Unspecified)
+}
+
+fn role_from_name(name: &str) -> Option<Role> {
+    match name {
+        "initiator" => Some(Role::Initiator),
+        "participant" => Some(Role::Participant),
+        "broker" => Some(Role::Broker),
+        _ => None,
+    }
+}
+
+impl ProtocolSpec {
+    /// Parse a TOML definition.
+    pub fn from_toml(text: &str) -> Result<Self, SpecError> {
+        toml::from_str(text).map_err(|e| SpecError::Parse(e.to_string()))
+    }
+
+    /// Parse a JSON definition.
+    pub fn from_json(text: &str) -> Result<Self, SpecError> {
+        serde_json::from_str(text).map_err(|e| SpecError::Parse(e.to_string()))
+    }
+
+    /// Load a definition from `path`: JSON for `.json`, TOML otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SpecError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| SpecError::Io(format!("{}: {e}", path.display())))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&text),
+            _ => Self::from_toml(&text),
+        }
+    }
+
+    fn invalid(&self, detail: impl Into<String>) -> SpecError {
+        SpecError::Invalid { protocol: self.name.clone(), detail: detail.into() }
+    }
+
+    /// Check the definition and compile it into a transition table.
+    pub fn compile(self) -> Result<CompiledProtocol, SpecError> {
+        let protocol_type = match &self.protocol_type {
+            None => proto::ProtocolType::ProtocolUnspecified,
+            Some(name) => {
+                let upper = name.to_ascii_uppercase().replace('-', "_");
+                proto::ProtocolType::from_str_name(&format!("PROTOCOL_{upper}"))
+                    .ok_or_else(|| self.invalid(format!("unknown protocol type '{name}'")))?
+            }
+        };
+
+        let mut roles = Vec::new();
+        for name in &self.roles {
+            roles.push(role_from_name(name).ok_or_else(|| self.invalid(format!("unknown role '{name}'")))?);
+        }
+        if roles.is_empty() {
+            return Err(self.invalid("no roles declared"));
+        }
+
+        let mut index = HashMap::new();
+        for (i, state) in self.states.iter().enumerate() {
+            if state.name == ANY_STATE || index.insert(state.name.as_str(), i).is_some() {
+                return Err(self.invalid(format!("state '{}' declared twice or reserved", state.name)));
+            }
+        }
+        let state_of = |name: &str, what: &str| {
+            index.get(name).copied().ok_or_else(|| self.invalid(format!("{what} names unknown state '{name}'")))
+        };
+        let initial = state_of(&self.initial, "initial")?;
+        if self.states[initial].outcome.is_some() {
+            return Err(self.invalid(format!("initial state '{}' is terminal", self.initial)));
+        }
+        for state in &self.states {
+            if state.outcome.is_some() && state.timeout_ms.is_some() {
+                return Err(self.invalid(format!("terminal state '{}' has a timeout", state.name)));
+            }
+            if state.timeout_ms.is_none() && state.on_timeout.is_some() {
+                return Err(self.invalid(format!("state '{}' has on_timeout but no timeout_ms", state.name)));
+            }
+            if let Some(target) = &state.on_timeout {
+                state_of(target, &format!("on_timeout of '{}'", state.name))?;
+            }
+        }
+
+        let mut table: HashMap<(usize, proto::Performative), Vec<Arm>> = HashMap::new();
+        for (t, transition) in self.transitions.iter().enumerate() {
+            let to = state_of(&transition.to, &format!("transition {t}"))?;
+            if let Some(by) = transition.by.as_ref().filter(|by| !self.roles.contains(by)) {
+                return Err(self.invalid(format!("transition {t} is sent by undeclared role '{by}'")));
+            }
+            let by = transition.by.as_deref().and_then(role_from_name);
+            if transition.performatives.is_empty() {
+                return Err(self.invalid(format!("transition {t} allows no performatives")));
+            }
+            let mut sources = HashSet::new();
+            for from in &transition.from {
+                if from == ANY_STATE {
+                    sources.extend((0..self.states.len()).filter(|&i| self.states[i].outcome.is_none()));
+                    continue;
+                }
+                let source = state_of(from, &format!("transition {t}"))?;
+                if self.states[source].outcome.is_some() {
+                    return Err(self.invalid(format!("transition {t} leaves terminal state '{from}'")));
+                }
+                sources.insert(source);
+            }
+            for name in &transition.performatives {
+                let performative = performative_from_name(name)
+                    .ok_or_else(|| self.invalid(format!("transition {t} names unknown performative '{name}'")))?;
+                for &source in &sources {
+                    let arms = table.entry((source, performative)).or_default();
+                    // two targets are fine only for senders of different roles
+                    if arms.iter().any(|a| a.to != to && (a.by.is_none() || by.is_none() || a.by == by)) {
+                        return Err(self.invalid(format!(
+                            "'{}' on {:?} leads to two states",
+                            self.states[source].name, performative
+                        )));
+                    }
+                    if !arms.contains(&Arm { by, to }) {
+                        arms.push(Arm { by, to });
+                    }
+                }
+            }
+        }
+
+        let on_timeout = self.states.iter().map(|s| s.on_timeout.as_ref().map(|t| index[t.as_str()])).collect();
+        Ok(CompiledProtocol {
+            spec: Arc::new(self),
+            protocol_type,
+            roles,
+            initial,
+            table: Arc::new(table),
+            on_timeout: Arc::new(on_timeout),
+        })
+    }
+}
+
+/// Where a performative leads from a state when sent by `by` (any role if `None`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Arm {
+    by: Option<Role>,
+    to: usize,
+}
+
+/// A checked protocol definition, ready to instantiate. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct CompiledProtocol {
+    spec: Arc<ProtocolSpec>,
+    protocol_type: proto::ProtocolType,
+    roles: Vec<Role>,
+    initial: usize,
+    table: Arc<HashMap<(usize, proto::Performative), Vec<Arm>>>,
+    /// Per state: where its timeout leads.
+    on_timeout: Arc<Vec<Option<usize>>>,
+}
+
+impl CompiledProtocol {
+    /// Load and compile the definition at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SpecError> {
+        ProtocolSpec::load(path)?.compile()
+    }
+
+    /// The protocol's declared name.
+    pub fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    /// A fresh conversation playing `role`. `None` if the protocol declares no such role.
+    pub fn instantiate(&self, role: Role) -> Option<DeclarativeProtocol> {
+        self.roles.contains(&role).then(|| DeclarativeProtocol {
+            compiled: self.clone(),
+            state: self.initial,
+            base: ConversationBase::new(uuid::Uuid::new_v4().to_string(), role),
+            state_deadline: None,
+            timed_out: false,
+            senders: HashMap::new(),
+        })
+    }
+}
+
+/// A running conversation of a declared protocol.
+#[derive(Debug)]
+pub struct DeclarativeProtocol {
+    compiled: CompiledProtocol,
+    state: usize,
+    base: ConversationBase,
+    /// When the current state times out (Unix ms).
+    state_deadline: Option<i64>,
+    /// A timeout with nowhere to go failed the conversation.
+    timed_out: bool,
+    /// The role each sender took on its first transition.
+    senders: HashMap<String, Role>,
+}
+
+impl DeclarativeProtocol {
+    /// Create with a specific conversation ID
+    pub fn with_conversation_id(mut self, id: String) -> Self {
+        self.base.conversation_id = id;
+        self
+    }
+
+    fn spec_state(&self) -> &StateSpec {
+        &self.compiled.spec.states[self.state]
+    }
+
+    /// The state `msg` leads to, and the role its sender takes by sending it (if
+    /// that is decided).
+    fn validate_transition(&self, msg: &proto::AclMessage) -> Result<(usize, Option<Role>), ProtocolError> {
+        let performative = proto::Performative::try_from(msg.performative)
+            .map_err(|_| ProtocolError::ValidationFailed("Invalid performative".into()))?;
+        if self.timed_out {
+            return Err(ProtocolError::Timeout);
+        }
+        let arms = self.compiled.table.get(&(self.state, performative)).ok_or_else(|| {
+            ProtocolError::InvalidTransition {
+                from: self.spec_state().name.clone(),
+                to: format!("{:?}", performative),
+            }
+        })?;
+        let sender = msg.sender.as_ref().map(|s| s.name.as_str()).filter(|n| !n.is_empty());
+        let bound = sender.and_then(|s| self.senders.get(s)).copied();
+        let initiator_taken = self.senders.iter().any(|(n, r)| *r == Role::Initiator && Some(n.as_str()) != sender);
+        let fits = |by: Option<Role>| match (by, bound) {
+            (None, _) => true,
+            (Some(role), Some(bound)) => role == bound,
+            (Some(role), None) => role != Role::Initiator || !initiator_taken,
+        };
+        let open: Vec<Arm> = arms.iter().copied().filter(|a| fits(a.by)).collect();
+        match open.as_slice() {
+            [] => Err(ProtocolError::ValidationFailed(format!(
+                "{:?} in '{}' is not sent by {}'s role",
+                performative,
+                self.spec_state().name,
+                sender.unwrap_or("this sender")
+            ))),
+            [only] => Ok((only.to, only.by)),
+            [first, rest @ ..] if rest.iter().all(|a| a.to == first.to) => Ok((first.to, None)),
+            _ => Err(ProtocolError::ValidationFailed(format!(
+                "{:?} in '{}': the sender's role is ambiguous",
+                performative,
+                self.spec_state().name
+            ))),
+        }
+    }
+
+    /// Enter `state` at `now_ms`, arming its timeout, and report where that leaves us.
+    fn enter(&mut self, state: usize, now_ms: i64) -> ProcessResult {
+        self.state = state;
+        self.state_deadline = self.spec_state().timeout_ms.map(|ms| now_ms.saturating_add(ms as i64));
+        match self.spec_state().outcome {
+            Some(StateOutcome::Completed) => {
+                let mut data = CompletionData {
+                    result: self.base.messages.last().map(|m| m.content.clone()),
+                    ..Default::default()
+                };
+                data.metadata.insert("state".into(), self.spec_state().name.clone());
+                ProcessResult::Complete(data)
+            }
+            Some(StateOutcome::Failed) => ProcessResult::Failed(format!("{} ended in '{}'", self.compiled.name(), self.spec_state().name)),
+            None => ProcessResult::Continue,
+        }
+    }
+}
+
+impl ProtocolStateMachine for DeclarativeProtocol {
+    fn protocol_type(&self) -> proto::ProtocolType {
+        self.compiled.protocol_type
+    }
+
+    fn state_name(&self) -> &str {
+        &self.spec_state().name
+    }
+
+    fn validate(&self, msg: &proto::AclMessage) -> Result<(), ProtocolError> {
+        self.validate_transition(msg)?;
+        Ok(())
+    }
+
+    fn process(&mut self, msg: proto::AclMessage) -> Result<ProcessResult, ProtocolError> {
+        let (next, role) = self.validate_transition(&msg)?;
+        if let Some(sender) = &msg.sender {
+            if let Some(role) = role.filter(|_| !sender.name.is_empty()) {
+                self.senders.insert(sender.name.clone(), role);
+            }
+            self.base.add_participant(sender.clone());
+        }
+        self.base.record_message(msg);
+        Ok(self.enter(next, chrono::Utc::now().timestamp_millis()))
+    }
+
+    fn poll(&mut self, now_ms: i64) -> ProcessResult {
+        if self.state_deadline.is_none_or(|t| now_ms < t) {
+            return ProcessResult::Continue;
+        }
+        match self.compiled.on_timeout[self.state] {
+            Some(target) => self.enter(target, now_ms),
+            None => {
+                self.state_deadline = None;
+                self.timed_out = true;
+                ProcessResult::Failed(format!("{} timed out in '{}'", self.compiled.name(), self.spec_state().name))
+            }
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.spec_state().outcome.is_some() || self.timed_out
+    }
+
+    fn is_failed(&self) -> bool {
+        self.spec_state().outcome == Some(StateOutcome::Failed) || self.timed_out
+    }
+
+    fn expected_performatives(&self) -> Vec<proto::Performative> {
+        let mut expected: Vec<_> =
+            self.compiled.table.keys().filter(|(s, _)| *s == self.state).map(|(_, p)| *p).collect();
+        expected.sort_by_key(|p| *p as i32);
+        expected
+    }
+
+    fn serialize_state(&self) -> Result<Vec<u8>, ProtocolError> {
+        Ok(self.spec_state().name.as_bytes().to_vec())
+    }
+
+    fn message_history(&self) -> &[proto::AclMessage] {
+        &self.base.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESERVATION: &str = r#"
+        name = "stock-reservation"
+        protocol_type = "request"
+        roles = ["initiator", "participant"]
+        initial = "start"
+
+        [[states]]
+        name = "start"
+
+        [[states]]
+        name = "asked"
+        timeout_ms = 5000
+        on_timeout = "expired"
+
+        [[states]]
+        name = "reserved"
+        outcome = "completed"
+
+        [[states]]
+        name = "expired"
+        outcome = "failed"
+
+        [[transitions]]
+        from = ["start"]
+        performatives = ["request"]
+        to = "asked"
+        by = "initiator"
+
+        [[transitions]]
+        from = ["asked"]
+        performatives = ["agree", "inform-done"]
+        to = "reserved"
+        by = "participant"
+
+        [[transitions]]
+        from = ["*"]
+        performatives = ["cancel"]
+        to = "expired"
+    "#;
+
+    fn message(performative: proto::Performative) -> proto::AclMessage {
+        proto::AclMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            performative: performative as i32,
+            content: b"sku-42".to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn from(performative: proto::Performative, sender: &str) -> proto::AclMessage {
+        proto::AclMessage {
+            sender: Some(proto::AgentId { name: sender.into(), addresses: vec![], resolvers: vec![] }),
+            ..message(performative)
+        }
+    }
+
+    fn compiled() -> CompiledProtocol {
+        ProtocolSpec::from_toml(RESERVATION).unwrap().compile().unwrap()
+    }
+
+    #[test]
+    fn a_declared_protocol_runs_to_its_outcome() {
+        let mut protocol = compiled().instantiate(Role::Participant).unwrap();
+        assert_eq!(protocol.protocol_type(), proto::ProtocolType::ProtocolRequest);
+        assert_eq!(protocol.expected_performatives(), [proto::Performative::Cancel, proto::Performative::Request]);
+        assert!(matches!(protocol.process(message(proto::Performative::Request)).unwrap(), ProcessResult::Continue));
+        assert_eq!(protocol.state_name(), "asked");
+
+        let err = protocol.process(message(proto::Performative::Propose)).unwrap_err();
+        assert!(matches!(err, ProtocolError::InvalidTransition { ref from, ref to } if from == "asked" && to == "Propose"));
+
+        let ProcessResult::Complete(data) = protocol.process(message(proto::Performative::InformDone)).unwrap() else {
+            panic!("reserved is a completed outcome")
+        };
+        assert_eq!(data.result.as_deref(), Some(b"sku-42".as_slice()));
+        assert_eq!(data.metadata["state"], "reserved");
+        assert!(protocol.is_complete() && !protocol.is_failed());
+        assert!(protocol.expected_performatives().is_empty());
+    }
+
+    #[test]
+    fn a_state_timeout_follows_on_timeout() {
+        let mut protocol = compiled().instantiate(Role::Initiator).unwrap();
+        protocol.process(message(proto::Performative::Request)).unwrap();
+        let deadline = protocol.state_deadline.unwrap();
+        assert!(matches!(protocol.poll(deadline - 1), ProcessResult::Continue));
+        assert!(matches!(protocol.poll(deadline), ProcessResult::Failed(_)));
+        assert_eq!(protocol.state_name(), "expired");
+        assert!(protocol.is_failed());
+        assert!(compiled().instantiate(Role::Broker).is_none());
+    }
+
+    #[test]
+    fn a_transition_is_taken_only_by_its_role() {
+        let mut protocol = compiled().instantiate(Role::Initiator).unwrap();
+        protocol.process(from(proto::Performative::Request, "buyer")).unwrap();
+        // the buyer opened as initiator; `agree` is the participant's
+        let err = protocol.process(from(proto::Performative::Agree, "buyer")).unwrap_err();
+        assert!(matches!(err, ProtocolError::ValidationFailed(_)));
+        assert_eq!(protocol.state_name(), "asked");
+        assert!(matches!(protocol.process(from(proto::Performative::Agree, "shop")).unwrap(), ProcessResult::Complete(_)));
+    }
+
+    #[test]
+    fn one_performative_may_lead_to_different_states_by_role() {
+        let text = format!(
+            "{RESERVATION}{}",
+            r#"
+            [[transitions]]
+            from = ["asked"]
+            performatives = ["inform"]
+            to = "reserved"
+            by = "participant"
+
+            [[transitions]]
+            from = ["asked"]
+            performatives = ["inform"]
+            to = "expired"
+            by = "initiator"
+            "#
+        );
+        let compiled = ProtocolSpec::from_toml(&text).unwrap().compile().unwrap();
+        let run = |informer: &str| {
+            let mut protocol = compiled.instantiate(Role::Initiator).unwrap();
+            protocol.process(from(proto::Performative::Request, "buyer")).unwrap();
+            protocol.process(from(proto::Performative::Inform, informer)).unwrap();
+            protocol.state_name().to_string()
+        };
+        assert_eq!(run("shop"), "reserved");
+        assert_eq!(run("buyer"), "expired");
+        assert_eq!(run("mallory"), "reserved"); // the initiator role is taken
+    }
+
+    #[test]
+    fn json_definitions_load_too() {
+        let spec = ProtocolSpec::from_toml(RESERVATION).unwrap();
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(ProtocolSpec::from_json(&json).unwrap(), spec);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reservation.json");
+        std::fs::write(&path, json).unwrap();
+        assert_eq!(CompiledProtocol::load(&path).unwrap().name(), "stock-reservation");
+    }
+
+    #[test]
+    fn compile_errors_name_the_offending_part() {
+        let broken = |from: &str, to: &str| {
+            let text = RESERVATION.replacen(from, to, 1);
+            ProtocolSpec::from_toml(&text).unwrap().compile().unwrap_err().to_string()
+        };
+        assert_eq!(
+            broken(r#"to = "reserved""#, r#"to = "booked""#),
+            "protocol 'stock-reservation': transition 1 names unknown state 'booked'"
+        );
+        assert_eq!(
+            broken(r#""agree", "inform-done""#, r#""agree", "inform-finished""#),
+            "protocol 'stock-reservation': transition 1 names unknown performative 'inform-finished'"
+        );
+        assert_eq!(
+            broken(r#"by = "participant""#, r#"by = "broker""#),
+            "protocol 'stock-reservation': transition 1 is sent by undeclared role 'broker'"
+        );
+        assert_eq!(
+            broken(r#"performatives = ["request"]"#, r#"performatives = ["cancel"]"#),
+            "protocol 'stock-reservation': 'start' on Cancel leads to two states"
+        );
+        assert!(broken(r#"on_timeout = "expired""#, r#"on_timeout = "never""#).contains("unknown state 'never'"));
+    }
+}
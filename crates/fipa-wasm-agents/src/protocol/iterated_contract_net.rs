@@ -30,9 +30,11 @@
 //! ```
 
 use super::state_machine::*;
-use super::contract_net::Proposal;
+use super::contract_net::{addressed, Proposal};
+use super::negotiation::{BiddingStrategy, Decision, EvaluationContext, ProposalEvaluator};
 use crate::proto;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
 /// Round information
 #[derive(Debug, Clone)]
@@ -118,6 +120,21 @@ pub struct IteratedContractNetProtocol {
 
     /// Deadline per round (milliseconds)
     round_deadline: i64,
+
+    /// When the first CFP went out
+    started_at: i64,
+
+    /// When the current round's CFP went out
+    round_started_at: i64,
+
+    /// Initiator: decides each round at its deadline
+    evaluator: Option<Arc<dyn ProposalEvaluator>>,
+
+    /// Participant: answers each round's CFP
+    bidder: Option<Arc<dyn BiddingStrategy>>,
+
+    /// Messages not yet handed out by `poll`
+    outbox: VecDeque<proto::AclMessage>,
 }
 
 impl IteratedContractNetProtocol {
@@ -133,6 +150,11 @@ impl IteratedContractNetProtocol {
             selected_contractor: None,
             result: None,
             round_deadline: 30000, // 30 seconds default
+            started_at: 0,
+            round_started_at: 0,
+            evaluator: None,
+            bidder: None,
+            outbox: VecDeque::new(),
         }
     }
 
@@ -148,6 +170,11 @@ impl IteratedContractNetProtocol {
             selected_contractor: None,
             result: None,
             round_deadline: 30000,
+            started_at: 0,
+            round_started_at: 0,
+            evaluator: None,
+            bidder: None,
+            outbox: VecDeque::new(),
         }
     }
 
@@ -163,6 +190,24 @@ impl IteratedContractNetProtocol {
         self
     }
 
+    /// Set an overall negotiation deadline (absolute, Unix millis)
+    pub fn with_deadline(mut self, deadline: i64) -> Self {
+        self.base.deadline = Some(deadline);
+        self
+    }
+
+    /// Decide each round automatically when its deadline passes
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn ProposalEvaluator>) -> Self {
+        self.evaluator = Some(evaluator);
+        self
+    }
+
+    /// Answer each round's CFP automatically with this strategy
+    pub fn with_bidder(mut self, bidder: Arc<dyn BiddingStrategy>) -> Self {
+        self.bidder = Some(bidder);
+        self
+    }
+
     /// Get current round
     pub fn current_round(&self) -> u32 {
         self.current_round
@@ -222,6 +267,7 @@ impl IteratedContractNetProtocol {
         self.current_round += 1;
         self.current_proposals.clear();
         self.state = IteratedContractNetState::CfpSent;
+        self.round_started_at = chrono::Utc::now().timestamp_millis();
 
         Ok(self.current_round)
     }
@@ -318,6 +364,102 @@ impl IteratedContractNetProtocol {
         Ok(())
     }
 
+    fn context<'a>(&self, cfp: &'a [u8], now_ms: i64) -> EvaluationContext<'a> {
+        EvaluationContext {
+            cfp,
+            round: self.current_round,
+            max_rounds: self.max_rounds,
+            started_ms: self.started_at,
+            deadline_ms: self.base.deadline,
+            now_ms,
+        }
+    }
+
+    /// Close the current round with the evaluator: accept the winner, call
+    /// for revisions, or end without agreement
+    fn close_round(&mut self, now_ms: i64) -> ProcessResult {
+        let cfp = self.base.messages.iter().rev().find(|m| m.performative == proto::Performative::Cfp as i32);
+        let (Some(evaluator), Some(cfp)) = (self.evaluator.clone(), cfp.cloned()) else {
+            return ProcessResult::Continue;
+        };
+        let mut proposals: Vec<Proposal> = self.current_proposals.values().cloned().collect();
+        proposals.sort_by(|a, b| (a.received_at, &a.bidder.name).cmp(&(b.received_at, &b.bidder.name)));
+
+        let decision = match evaluator.evaluate(&proposals, &self.context(&cfp.content, now_ms)) {
+            Decision::Accept(names) => match names.into_iter().find(|n| self.current_proposals.contains_key(n)) {
+                Some(winner) => Ok(winner),
+                None => Err(None),
+            },
+            Decision::Revise(content) if self.can_continue() => Err(Some(content)),
+            Decision::Revise(_) | Decision::RejectAll => Err(None),
+        };
+        let (winner, revision) = match decision {
+            Ok(winner) => (Some(winner), None),
+            Err(revision) => (None, revision),
+        };
+
+        let losers: Vec<proto::AgentId> = proposals
+            .iter()
+            .filter(|p| Some(&p.bidder.name) != winner.as_ref())
+            .map(|p| p.bidder.clone())
+            .collect();
+        if let Some(winner) = &winner {
+            let proposal = &self.current_proposals[winner];
+            let accept = addressed(&cfp, proto::Performative::AcceptProposal, vec![proposal.bidder.clone()], proposal.content.clone());
+            self.outbox.push_back(accept);
+        }
+        if !losers.is_empty() {
+            self.outbox.push_back(addressed(&cfp, proto::Performative::RejectProposal, losers, Vec::new()));
+        }
+
+        match (winner, revision) {
+            (Some(winner), _) => {
+                self.selected_contractor = Some(winner);
+                self.state = IteratedContractNetState::Accepted;
+            }
+            (None, Some(content)) => {
+                let mut next = addressed(&cfp, proto::Performative::Cfp, cfp.receivers.clone(), content);
+                next.in_reply_to = None;
+                self.rounds.push(NegotiationRound {
+                    round: self.current_round,
+                    cfp_content: cfp.content.clone(),
+                    proposals,
+                    timestamp: now_ms,
+                });
+                self.current_round += 1;
+                self.current_proposals.clear();
+                self.round_started_at = now_ms;
+                self.state = IteratedContractNetState::CfpSent;
+                self.outbox.push_back(next);
+            }
+            (None, None) => {
+                self.state = IteratedContractNetState::NoAgreement;
+            }
+        }
+        for msg in &self.outbox {
+            self.base.record_message(msg.clone());
+        }
+
+        match self.outbox.pop_front() {
+            Some(msg) => ProcessResult::Respond(msg),
+            None => ProcessResult::Failed("No agreement reached".into()),
+        }
+    }
+
+    /// Answer a CFP with the bidding strategy, as if the reply came through `process`
+    fn bid(&mut self, cfp: &proto::AclMessage) -> Result<Option<proto::AclMessage>, ProtocolError> {
+        let Some(bidder) = self.bidder.clone() else { return Ok(None) };
+        let now = chrono::Utc::now().timestamp_millis();
+        let (performative, content) = match bidder.bid(&self.context(&cfp.content, now)) {
+            Some(terms) => (proto::Performative::Propose, terms),
+            None => (proto::Performative::Refuse, Vec::new()),
+        };
+        self.state = self.validate_transition(performative)?;
+        let reply = create_response(cfp, performative, content);
+        self.base.record_message(reply.clone());
+        Ok(Some(reply))
+    }
+
     /// Validate state transition
     fn validate_transition(&self, performative: proto::Performative) -> Result<IteratedContractNetState, ProtocolError> {
         use proto::Performative::*;
@@ -383,12 +525,15 @@ impl ProtocolStateMachine for IteratedContractNetProtocol {
 
         match performative {
             proto::Performative::Cfp => {
+                let now = chrono::Utc::now().timestamp_millis();
                 if self.state == IteratedContractNetState::NotStarted {
                     self.current_round = 1;
+                    self.started_at = now;
                 } else {
                     self.current_round += 1;
                     self.current_proposals.clear();
                 }
+                self.round_started_at = now;
             }
             proto::Performative::Propose => {
                 if let Some(sender) = &msg.sender {
@@ -411,6 +556,13 @@ impl ProtocolStateMachine for IteratedContractNetProtocol {
 
         self.state = new_state;
 
+        if performative == proto::Performative::Cfp
+            && self.base.role == Role::Participant
+            && let Some(reply) = self.bid(&msg)?
+        {
+            return Ok(ProcessResult::Respond(reply));
+        }
+
         match &self.state {
             IteratedContractNetState::Completed => Ok(ProcessResult::Complete(CompletionData {
                 result: self.result.clone(),
@@ -427,6 +579,17 @@ impl ProtocolStateMachine for IteratedContractNetProtocol {
         }
     }
 
+    fn poll(&mut self, now_ms: i64) -> ProcessResult {
+        if let Some(msg) = self.outbox.pop_front() {
+            return ProcessResult::Respond(msg);
+        }
+        let round_over = now_ms >= self.round_started_at + self.round_deadline;
+        if self.base.role != Role::Initiator || self.state != IteratedContractNetState::CfpSent || !round_over {
+            return ProcessResult::Continue;
+        }
+        self.close_round(now_ms)
+    }
+
     fn is_complete(&self) -> bool {
         matches!(
             self.state,
@@ -476,6 +639,7 @@ impl ProtocolStateMachine for IteratedContractNetProtocol {
 
 #[cfg(test)]
 mod tests {
+    use super::super::negotiation::{terms_of, ConcedingBidder, ConcessionCurve};
     use super::*;
 
     #[test]
@@ -533,4 +697,70 @@ mod tests {
         assert_eq!(accepted.bidder.name, "agent1");
         assert_eq!(protocol.selected_contractor(), Some("agent1"));
     }
+
+    fn message(performative: proto::Performative, sender: &str, content: &str) -> proto::AclMessage {
+        proto::AclMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            performative: performative as i32,
+            sender: Some(proto::AgentId::named(sender)),
+            receivers: vec![proto::AgentId::named("a"), proto::AgentId::named("b")],
+            conversation_id: Some("icn-1".into()),
+            content: content.as_bytes().to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_concession_curve_revises_then_accepts() {
+        let mut protocol = IteratedContractNetProtocol::new_as_initiator(3)
+            .with_round_deadline(1_000)
+            .with_evaluator(Arc::new(ConcessionCurve::new(60.0, 100.0)));
+
+        protocol.process(message(proto::Performative::Cfp, "buyer", "{}")).unwrap();
+        protocol.process(message(proto::Performative::Propose, "a", r#"{"price": 90}"#)).unwrap();
+        protocol.process(message(proto::Performative::Propose, "b", r#"{"price": 95}"#)).unwrap();
+
+        // Round 1 target is ~73: nobody qualifies, so ask again.
+        let later = chrono::Utc::now().timestamp_millis() + 10_000;
+        let ProcessResult::Respond(reject) = protocol.poll(later) else {
+            panic!("expected the round to close");
+        };
+        assert_eq!(reject.performative(), proto::Performative::RejectProposal);
+        assert_eq!(reject.receivers.len(), 2);
+        let ProcessResult::Respond(cfp) = protocol.poll(later) else {
+            panic!("expected a revised CFP");
+        };
+        assert_eq!(cfp.performative(), proto::Performative::Cfp);
+        assert!((terms_of(&cfp.content).unwrap()["price"] - 73.33).abs() < 0.01);
+        assert_eq!(protocol.current_round(), 2);
+        assert_eq!(protocol.rounds().len(), 1);
+
+        // Round 2 target is ~87.
+        protocol.process(message(proto::Performative::Propose, "b", r#"{"price": 85}"#)).unwrap();
+        assert!(matches!(protocol.poll(later + 999), ProcessResult::Continue));
+        let ProcessResult::Respond(accept) = protocol.poll(later + 1_000) else {
+            panic!("expected an award");
+        };
+        assert_eq!(accept.performative(), proto::Performative::AcceptProposal);
+        assert_eq!(protocol.selected_contractor(), Some("b"));
+        assert_eq!(protocol.state, IteratedContractNetState::Accepted);
+    }
+
+    #[test]
+    fn test_conceding_bidder_follows_rounds() {
+        let mut protocol = IteratedContractNetProtocol::new_as_participant()
+            .with_bidder(Arc::new(ConcedingBidder::new(120.0, 80.0)));
+
+        let ProcessResult::Respond(first) = protocol.process(message(proto::Performative::Cfp, "buyer", "{}")).unwrap() else {
+            panic!("expected a bid");
+        };
+        protocol.process(message(proto::Performative::RejectProposal, "buyer", "")).unwrap();
+        let ProcessResult::Respond(second) = protocol.process(message(proto::Performative::Cfp, "buyer", "{}")).unwrap() else {
+            panic!("expected a revised bid");
+        };
+
+        let price = |m: &proto::AclMessage| terms_of(&m.content).unwrap()["price"];
+        assert_eq!(first.performative(), proto::Performative::Propose);
+        assert!(price(&second) < price(&first));
+    }
 }
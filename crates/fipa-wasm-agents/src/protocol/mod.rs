@@ -5,12 +5,21 @@
 //! This module provides type-safe implementations of FIPA interaction protocols:
 //!
 //! - `RequestProtocol` - Simple request-response pattern
+//! - `RequestWhenProtocol` - Request-when / request-whenever on a UNL condition
 //! - `QueryProtocol` - Information retrieval (query-if, query-ref)
 //! - `ContractNetProtocol` - Task allocation through bidding
 //! - `SubscribeProtocol` - Continuous notifications
+//! - `SealedBidAuctionProtocol` - First-/second-price auctions with commit-reveal
+//! - `DeclarativeProtocol` - Domain protocols loaded from a TOML/JSON spec
 //!
 //! Each protocol is implemented as a state machine that validates
-//! message sequences and manages transitions.
+//! message sequences and manages transitions. `GenericConversation` and
+//! `ConversationManager` attach metadata to a machine and route messages
+//! to it by conversation ID.
+//!
+//! Contract-net, iterated contract-net and propose conversations can decide
+//! on their own: give them a `ProposalEvaluator` (initiator side) or a
+//! `BiddingStrategy` (responder side) from the `negotiation` strategies.
 //!
 //! # Example
 //!
@@ -34,27 +43,49 @@
 
 mod brokering;
 mod contract_net;
+mod conversation;
+mod declarative;
 mod dutch_auction;
 mod english_auction;
 mod iterated_contract_net;
+mod negotiation;
 mod propose;
 mod query;
 mod recruiting;
 mod request;
+mod request_when;
+mod sealed_bid_auction;
 mod state_machine;
 mod subscribe;
 
 pub use brokering::{BrokeringProtocol, BrokeringState, ProviderInfo, ProviderStatus};
 pub use contract_net::{ContractNetProtocol, ContractNetState, Proposal};
+pub use conversation::{ConversationHandler, ConversationManager, GenericConversation};
+pub use declarative::{
+    performative_from_name, CompiledProtocol, DeclarativeProtocol, ProtocolSpec, SpecError, StateOutcome, StateSpec,
+    TransitionSpec, ANY_STATE,
+};
 pub use dutch_auction::{DutchAuctionProtocol, DutchAuctionState, PriceUpdate};
 pub use english_auction::{Bid, EnglishAuctionProtocol, EnglishAuctionState};
 pub use iterated_contract_net::{IteratedContractNetProtocol, IteratedContractNetState, NegotiationRound};
+pub use negotiation::{
+    concession, encode_terms, terms_of, BiddingStrategy, ConcedingBidder, ConcessionCurve, Criterion, DeadlineAware,
+    Decision, EvaluationContext, FixedBid, LowestPrice, ProposalEvaluator, Terms, WeightedUtility,
+};
 pub use propose::{ProposeProtocol, ProposeState};
 pub use query::{QueryProtocol, QueryState, QueryType};
 pub use recruiting::{Candidate, RecruitingProtocol, RecruitingState};
 pub use request::{RequestProtocol, RequestState};
+pub use request_when::{
+    condition_of, set_condition, RequestWhenProtocol, RequestWhenState, WhenMode, WhenResponder,
+    CONDITION_PROPERTY, DEFAULT_CHECK_INTERVAL_MS,
+};
+pub use sealed_bid_auction::{
+    commitment_digest, Commitment, Pricing, Reveal, SealedBidAuctionProtocol, SealedBidOutcome, SealedBidState,
+    AUCTION_BIDDERS_PROPERTY, AUCTION_PRICING_PROPERTY, AUCTION_RESERVE_PROPERTY,
+};
 pub use state_machine::{
-    create_response, create_state_machine, CompletionData, ConversationBase, ProcessResult,
-    ProtocolError, ProtocolStateMachine, Role,
+    create_response, create_state_machine, create_state_machine_with, CompletionData, ConversationBase,
+    ProcessResult, ProtocolConfig, ProtocolError, ProtocolStateMachine, Role,
 };
-pub use subscribe::{SubscribeProtocol, SubscribeState};
+pub use subscribe::{clamp_lease, SubscribeProtocol, SubscribeState, DEFAULT_MAX_LEASE_MS, LEASE_MS_PROPERTY};
@@ -0,0 +1,496 @@
+// protocol/negotiation.rs - Negotiation Strategies
+//
+//! Pluggable bid evaluation and bidding strategies.
+//!
+//! Proposals and CFPs carry their terms as a JSON object of numbers, e.g.
+//! `{"price": 90.0, "days": 3}`. A [`ProposalEvaluator`] looks at the
+//! proposals of a round and decides who wins; a [`BiddingStrategy`] turns a
+//! CFP into terms to propose. `ContractNetProtocol`,
+//! `IteratedContractNetProtocol` and `ProposeProtocol` call them at the
+//! deadline (or once every expected reply is in) when one is configured.
+//!
+//! Built-in evaluators:
+//!
+//! - [`LowestPrice`] - cheapest proposal on one attribute
+//! - [`WeightedUtility`] - weighted sum of normalized attributes
+//! - [`ConcessionCurve`] - aspiration that concedes toward a reservation
+//!   price over the rounds, asking for revisions until it is met
+//! - [`DeadlineAware`] - defers to another evaluator, but settles for any
+//!   proposal within the reservation price once time is nearly out
+//!
+//! Concession follows the polynomial time-dependent tactic
+//! `initial + (reservation - initial) * t^(1/beta)`: `beta < 1` holds out
+//! (boulware), `beta > 1` concedes early (conceder).
+
+use super::contract_net::Proposal;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Numeric terms of a CFP or proposal
+pub type Terms = HashMap<String, f64>;
+
+/// Parse terms from JSON content; non-numeric fields are ignored
+pub fn terms_of(content: &[u8]) -> Option<Terms> {
+    let value: serde_json::Value = serde_json::from_slice(content).ok()?;
+    let object = value.as_object()?;
+    Some(
+        object
+            .iter()
+            .filter_map(|(k, v)| v.as_f64().map(|n| (k.clone(), n)))
+            .collect(),
+    )
+}
+
+/// Encode terms as JSON content
+pub fn encode_terms(terms: &Terms) -> Vec<u8> {
+    serde_json::to_vec(terms).unwrap_or_default()
+}
+
+/// Value on the concession curve at progress `t` (clamped to 0..=1)
+pub fn concession(initial: f64, reservation: f64, beta: f64, t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    initial + (reservation - initial) * t.powf(1.0 / beta.max(f64::EPSILON))
+}
+
+impl Proposal {
+    /// The proposal's terms, if its content is a JSON object
+    pub fn terms(&self) -> Option<Terms> {
+        terms_of(&self.content)
+    }
+
+    fn term(&self, attribute: &str) -> Option<f64> {
+        self.terms().and_then(|t| t.get(attribute).copied())
+    }
+}
+
+/// Where a negotiation stands when a strategy is asked
+#[derive(Debug, Clone, Copy)]
+pub struct EvaluationContext<'a> {
+    /// Content of the CFP (or proposal) under negotiation
+    pub cfp: &'a [u8],
+    /// Current round, starting at 1
+    pub round: u32,
+    /// Rounds allowed (1 for a single-shot contract net)
+    pub max_rounds: u32,
+    /// When the negotiation started (Unix millis)
+    pub started_ms: i64,
+    /// Overall deadline, if any (Unix millis)
+    pub deadline_ms: Option<i64>,
+    /// Current time (Unix millis)
+    pub now_ms: i64,
+}
+
+impl EvaluationContext<'_> {
+    /// How far the negotiation has run, from 0 to 1: the larger of the
+    /// round fraction and the elapsed share of the deadline
+    pub fn progress(&self) -> f64 {
+        let by_round = if self.max_rounds > 0 {
+            f64::from(self.round) / f64::from(self.max_rounds)
+        } else {
+            0.0
+        };
+        let by_time = match self.deadline_ms {
+            Some(deadline) if deadline > self.started_ms => {
+                (self.now_ms - self.started_ms) as f64 / (deadline - self.started_ms) as f64
+            }
+            _ => 0.0,
+        };
+        by_round.max(by_time).clamp(0.0, 1.0)
+    }
+
+    /// Whether another round may follow this one
+    pub fn can_revise(&self) -> bool {
+        self.round < self.max_rounds
+    }
+}
+
+/// What to do with a round of proposals
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// Accept the named bidders; everyone else is rejected
+    Accept(Vec<String>),
+    /// Reject this round and call for revised proposals with this CFP content
+    Revise(Vec<u8>),
+    /// Reject every proposal
+    RejectAll,
+}
+
+/// Decides which proposals win
+pub trait ProposalEvaluator: Send + Sync + Debug {
+    /// Evaluate one round of proposals
+    fn evaluate(&self, proposals: &[Proposal], ctx: &EvaluationContext<'_>) -> Decision;
+}
+
+/// Produces the terms a participant proposes
+pub trait BiddingStrategy: Send + Sync + Debug {
+    /// Content to propose in answer to a CFP, or `None` to refuse
+    fn bid(&self, ctx: &EvaluationContext<'_>) -> Option<Vec<u8>>;
+}
+
+/// The proposal with the lowest value of `attribute`, if any
+fn cheapest<'p>(proposals: &'p [Proposal], attribute: &str) -> Option<(&'p Proposal, f64)> {
+    proposals
+        .iter()
+        .filter_map(|p| p.term(attribute).map(|v| (p, v)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Accept the proposal with the lowest value of one attribute
+#[derive(Debug, Clone)]
+pub struct LowestPrice {
+    attribute: String,
+}
+
+impl LowestPrice {
+    /// Compare on `"price"`
+    pub fn new() -> Self {
+        Self { attribute: "price".into() }
+    }
+
+    /// Compare on another attribute
+    pub fn with_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.attribute = attribute.into();
+        self
+    }
+}
+
+impl Default for LowestPrice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProposalEvaluator for LowestPrice {
+    fn evaluate(&self, proposals: &[Proposal], _ctx: &EvaluationContext<'_>) -> Decision {
+        match cheapest(proposals, &self.attribute) {
+            Some((p, _)) => Decision::Accept(vec![p.bidder.name.clone()]),
+            None => Decision::RejectAll,
+        }
+    }
+}
+
+/// One attribute of a [`WeightedUtility`]
+#[derive(Debug, Clone)]
+pub struct Criterion {
+    pub attribute: String,
+    pub weight: f64,
+    pub lower_is_better: bool,
+}
+
+/// Accept the proposal with the highest weighted multi-attribute utility
+///
+/// Each attribute is normalized to 0..=1 across the proposals of the round
+/// (inverted where lower is better), so `threshold` is relative to the field.
+/// Proposals missing an attribute are not considered.
+#[derive(Debug, Clone, Default)]
+pub struct WeightedUtility {
+    criteria: Vec<Criterion>,
+    threshold: f64,
+}
+
+impl WeightedUtility {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prefer lower values of `attribute`
+    pub fn minimize(mut self, attribute: impl Into<String>, weight: f64) -> Self {
+        self.criteria.push(Criterion { attribute: attribute.into(), weight, lower_is_better: true });
+        self
+    }
+
+    /// Prefer higher values of `attribute`
+    pub fn maximize(mut self, attribute: impl Into<String>, weight: f64) -> Self {
+        self.criteria.push(Criterion { attribute: attribute.into(), weight, lower_is_better: false });
+        self
+    }
+
+    /// Minimum utility (0..=1) a winner must reach
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Utility of each eligible proposal, in input order
+    pub fn score<'p>(&self, proposals: &'p [Proposal]) -> Vec<(&'p Proposal, f64)> {
+        let rows: Vec<(&Proposal, Vec<f64>)> = proposals
+            .iter()
+            .filter_map(|p| {
+                let terms = p.terms()?;
+                let values = self.criteria.iter().map(|c| terms.get(&c.attribute).copied()).collect::<Option<_>>()?;
+                Some((p, values))
+            })
+            .collect();
+        let total: f64 = self.criteria.iter().map(|c| c.weight).sum();
+        if rows.is_empty() || total <= 0.0 {
+            return Vec::new();
+        }
+
+        let bounds: Vec<(f64, f64)> = (0..self.criteria.len())
+            .map(|i| {
+                rows.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, v)| (lo.min(v[i]), hi.max(v[i])))
+            })
+            .collect();
+
+        rows.into_iter()
+            .map(|(p, values)| {
+                let utility: f64 = self
+                    .criteria
+                    .iter()
+                    .zip(values.iter().zip(&bounds))
+                    .map(|(c, (v, (lo, hi)))| {
+                        let norm = if hi > lo { (v - lo) / (hi - lo) } else { 1.0 };
+                        c.weight * if c.lower_is_better { 1.0 - norm } else { norm }
+                    })
+                    .sum();
+                (p, utility / total)
+            })
+            .collect()
+    }
+}
+
+impl ProposalEvaluator for WeightedUtility {
+    fn evaluate(&self, proposals: &[Proposal], _ctx: &EvaluationContext<'_>) -> Decision {
+        let best = self.score(proposals).into_iter().max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((p, utility)) if utility >= self.threshold => Decision::Accept(vec![p.bidder.name.clone()]),
+            _ => Decision::RejectAll,
+        }
+    }
+}
+
+/// Buyer-side reservation price with a concession curve
+///
+/// The acceptable value of `attribute` starts at `initial` and concedes
+/// toward `reservation` as the negotiation progresses. The cheapest
+/// proposal within the current target wins; otherwise, while rounds
+/// remain, the CFP is reissued asking for the target.
+#[derive(Debug, Clone)]
+pub struct ConcessionCurve {
+    attribute: String,
+    initial: f64,
+    reservation: f64,
+    beta: f64,
+}
+
+impl ConcessionCurve {
+    /// Concede linearly from `initial` to `reservation` on `"price"`
+    pub fn new(initial: f64, reservation: f64) -> Self {
+        Self { attribute: "price".into(), initial, reservation, beta: 1.0 }
+    }
+
+    pub fn with_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.attribute = attribute.into();
+        self
+    }
+
+    /// Curve shape: below 1 holds out, above 1 concedes early
+    pub fn with_beta(mut self, beta: f64) -> Self {
+        self.beta = beta;
+        self
+    }
+
+    /// Acceptable value at this point of the negotiation
+    pub fn target(&self, ctx: &EvaluationContext<'_>) -> f64 {
+        concession(self.initial, self.reservation, self.beta, ctx.progress())
+    }
+}
+
+impl ProposalEvaluator for ConcessionCurve {
+    fn evaluate(&self, proposals: &[Proposal], ctx: &EvaluationContext<'_>) -> Decision {
+        let target = self.target(ctx);
+        if let Some((p, value)) = cheapest(proposals, &self.attribute)
+            && value <= target
+        {
+            return Decision::Accept(vec![p.bidder.name.clone()]);
+        }
+        if !ctx.can_revise() {
+            return Decision::RejectAll;
+        }
+        let mut cfp = terms_of(ctx.cfp).unwrap_or_default();
+        cfp.insert(self.attribute.clone(), target);
+        Decision::Revise(encode_terms(&cfp))
+    }
+}
+
+/// Deadline-aware acceptance
+///
+/// Defers to `inner`; once progress reaches `urgency` and `inner` has not
+/// accepted anyone, takes the cheapest proposal within `reservation`
+/// rather than letting the negotiation run out.
+#[derive(Debug)]
+pub struct DeadlineAware {
+    inner: Box<dyn ProposalEvaluator>,
+    attribute: String,
+    reservation: f64,
+    urgency: f64,
+}
+
+impl DeadlineAware {
+    /// Settle on `"price"` within `reservation` from 90% progress on
+    pub fn new(inner: impl ProposalEvaluator + 'static, reservation: f64) -> Self {
+        Self { inner: Box::new(inner), attribute: "price".into(), reservation, urgency: 0.9 }
+    }
+
+    pub fn with_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.attribute = attribute.into();
+        self
+    }
+
+    /// Progress (0..=1) from which to settle
+    pub fn with_urgency(mut self, urgency: f64) -> Self {
+        self.urgency = urgency;
+        self
+    }
+}
+
+impl ProposalEvaluator for DeadlineAware {
+    fn evaluate(&self, proposals: &[Proposal], ctx: &EvaluationContext<'_>) -> Decision {
+        let decision = self.inner.evaluate(proposals, ctx);
+        if matches!(decision, Decision::Accept(_)) || ctx.progress() < self.urgency {
+            return decision;
+        }
+        match cheapest(proposals, &self.attribute) {
+            Some((p, value)) if value <= self.reservation => Decision::Accept(vec![p.bidder.name.clone()]),
+            _ => decision,
+        }
+    }
+}
+
+/// Always propose the same terms
+#[derive(Debug, Clone)]
+pub struct FixedBid {
+    terms: Terms,
+}
+
+impl FixedBid {
+    pub fn new(terms: Terms) -> Self {
+        Self { terms }
+    }
+}
+
+impl BiddingStrategy for FixedBid {
+    fn bid(&self, _ctx: &EvaluationContext<'_>) -> Option<Vec<u8>> {
+        Some(encode_terms(&self.terms))
+    }
+}
+
+/// Seller-side concession: ask `initial`, concede toward the `reservation`
+/// floor over the rounds, and meet a CFP's ask when it is above the curve
+#[derive(Debug, Clone)]
+pub struct ConcedingBidder {
+    attribute: String,
+    initial: f64,
+    reservation: f64,
+    beta: f64,
+}
+
+impl ConcedingBidder {
+    /// Concede linearly from `initial` to `reservation` on `"price"`
+    pub fn new(initial: f64, reservation: f64) -> Self {
+        Self { attribute: "price".into(), initial, reservation, beta: 1.0 }
+    }
+
+    pub fn with_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.attribute = attribute.into();
+        self
+    }
+
+    /// Curve shape: below 1 holds out, above 1 concedes early
+    pub fn with_beta(mut self, beta: f64) -> Self {
+        self.beta = beta;
+        self
+    }
+}
+
+impl BiddingStrategy for ConcedingBidder {
+    fn bid(&self, ctx: &EvaluationContext<'_>) -> Option<Vec<u8>> {
+        let target = concession(self.initial, self.reservation, self.beta, ctx.progress());
+        let ask = terms_of(ctx.cfp).and_then(|t| t.get(&self.attribute).copied());
+        let offer = ask.filter(|a| *a >= target).unwrap_or(target);
+        let mut terms = Terms::new();
+        terms.insert(self.attribute.clone(), offer);
+        Some(encode_terms(&terms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto;
+
+    fn proposal(bidder: &str, content: &str) -> Proposal {
+        Proposal {
+            bidder: proto::AgentId { name: bidder.into(), ..Default::default() },
+            content: content.as_bytes().to_vec(),
+            received_at: 0,
+        }
+    }
+
+    fn ctx(round: u32, max_rounds: u32) -> EvaluationContext<'static> {
+        EvaluationContext { cfp: b"{}", round, max_rounds, started_ms: 0, deadline_ms: None, now_ms: 0 }
+    }
+
+    #[test]
+    fn lowest_price_ignores_unpriced_proposals() {
+        let proposals = [proposal("a", r#"{"price": 12}"#), proposal("b", "cheap!"), proposal("c", r#"{"price": 9.5}"#)];
+        assert_eq!(LowestPrice::new().evaluate(&proposals, &ctx(1, 1)), Decision::Accept(vec!["c".into()]));
+        assert_eq!(LowestPrice::new().evaluate(&proposals[1..2], &ctx(1, 1)), Decision::RejectAll);
+    }
+
+    #[test]
+    fn weighted_utility_trades_off_attributes() {
+        let proposals = [
+            proposal("cheap-slow", r#"{"price": 10, "days": 10}"#),
+            proposal("dear-fast", r#"{"price": 20, "days": 1}"#),
+            proposal("middling", r#"{"price": 14, "days": 3}"#),
+        ];
+        let by_price = WeightedUtility::new().minimize("price", 3.0).minimize("days", 1.0);
+        let by_speed = WeightedUtility::new().minimize("price", 1.0).minimize("days", 3.0);
+        let balanced = WeightedUtility::new().minimize("price", 1.0).minimize("days", 1.0);
+
+        assert_eq!(by_price.evaluate(&proposals, &ctx(1, 1)), Decision::Accept(vec!["cheap-slow".into()]));
+        assert_eq!(by_speed.evaluate(&proposals, &ctx(1, 1)), Decision::Accept(vec!["dear-fast".into()]));
+        assert_eq!(balanced.evaluate(&proposals, &ctx(1, 1)), Decision::Accept(vec!["middling".into()]));
+        assert_eq!(balanced.with_threshold(0.9).evaluate(&proposals, &ctx(1, 1)), Decision::RejectAll);
+    }
+
+    #[test]
+    fn concession_curve_revises_then_accepts() {
+        let curve = ConcessionCurve::new(50.0, 100.0);
+        let proposals = [proposal("a", r#"{"price": 80}"#)];
+
+        // Round 1 of 4: target 62.5, so ask for it in a revised CFP.
+        match curve.evaluate(&proposals, &ctx(1, 4)) {
+            Decision::Revise(cfp) => assert_eq!(terms_of(&cfp).unwrap()["price"], 62.5),
+            other => panic!("expected revise, got {:?}", other),
+        }
+        // Round 3 of 4: target 87.5 admits the offer.
+        assert_eq!(curve.evaluate(&proposals, &ctx(3, 4)), Decision::Accept(vec!["a".into()]));
+        // Above the reservation price nothing is ever accepted.
+        assert_eq!(curve.evaluate(&[proposal("a", r#"{"price": 120}"#)], &ctx(4, 4)), Decision::RejectAll);
+    }
+
+    #[test]
+    fn deadline_aware_settles_near_the_deadline() {
+        let strategy = DeadlineAware::new(ConcessionCurve::new(50.0, 200.0).with_beta(0.01), 100.0);
+        let proposals = [proposal("a", r#"{"price": 95}"#)];
+        let early = EvaluationContext { deadline_ms: Some(1000), now_ms: 100, ..ctx(1, 10) };
+        let late = EvaluationContext { now_ms: 950, ..early };
+
+        assert!(matches!(strategy.evaluate(&proposals, &early), Decision::Revise(_)));
+        assert_eq!(strategy.evaluate(&proposals, &late), Decision::Accept(vec!["a".into()]));
+    }
+
+    #[test]
+    fn conceding_bidder_moves_toward_its_floor() {
+        let bidder = ConcedingBidder::new(100.0, 60.0);
+        let price = |c: &EvaluationContext<'_>| terms_of(&bidder.bid(c).unwrap()).unwrap()["price"];
+
+        assert_eq!(price(&ctx(0, 4)), 100.0);
+        assert_eq!(price(&ctx(2, 4)), 80.0);
+        assert_eq!(price(&ctx(4, 4)), 60.0);
+        // A CFP asking above the curve is met.
+        assert_eq!(price(&EvaluationContext { cfp: br#"{"price": 90}"#, ..ctx(2, 4) }), 90.0);
+    }
+}
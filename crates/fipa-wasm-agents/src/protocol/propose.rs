@@ -18,8 +18,11 @@
 //!     |                              |
 //! ```
 
+use super::contract_net::Proposal;
+use super::negotiation::{Decision, EvaluationContext, ProposalEvaluator};
 use super::state_machine::*;
 use crate::proto;
+use std::sync::Arc;
 
 /// FIPA Propose Protocol States
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -62,6 +65,9 @@ pub struct ProposeProtocol {
 
     /// Response content
     response_content: Option<Vec<u8>>,
+
+    /// Participant: answers the proposal automatically
+    evaluator: Option<Arc<dyn ProposalEvaluator>>,
 }
 
 impl ProposeProtocol {
@@ -72,6 +78,7 @@ impl ProposeProtocol {
             base: ConversationBase::new(uuid::Uuid::new_v4().to_string(), role),
             proposal_content: None,
             response_content: None,
+            evaluator: None,
         }
     }
 
@@ -87,6 +94,37 @@ impl ProposeProtocol {
         self
     }
 
+    /// Accept or reject incoming proposals automatically with this strategy
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn ProposalEvaluator>) -> Self {
+        self.evaluator = Some(evaluator);
+        self
+    }
+
+    /// Answer a proposal with the evaluator, as if the reply came through `process`
+    fn respond(&mut self, propose: &proto::AclMessage) -> Result<Option<proto::AclMessage>, ProtocolError> {
+        let Some(evaluator) = self.evaluator.clone() else { return Ok(None) };
+        let Some(bidder) = propose.sender.clone() else { return Ok(None) };
+        let now = chrono::Utc::now().timestamp_millis();
+        let proposal = Proposal { bidder, content: propose.content.clone(), received_at: now };
+        let context = EvaluationContext {
+            cfp: &[],
+            round: 1,
+            max_rounds: 1,
+            started_ms: now,
+            deadline_ms: self.base.deadline,
+            now_ms: now,
+        };
+
+        let performative = match evaluator.evaluate(std::slice::from_ref(&proposal), &context) {
+            Decision::Accept(names) if names.contains(&proposal.bidder.name) => proto::Performative::AcceptProposal,
+            _ => proto::Performative::RejectProposal,
+        };
+        let reply = create_response(propose, performative, Vec::new());
+        self.state = self.validate_transition(performative)?;
+        self.base.record_message(reply.clone());
+        Ok(Some(reply))
+    }
+
     /// Validate state transition
     fn validate_transition(&self, performative: proto::Performative) -> Result<ProposeState, ProtocolError> {
         use proto::Performative::*;
@@ -155,6 +193,13 @@ impl ProtocolStateMachine for ProposeProtocol {
 
         self.state = new_state;
 
+        if self.state == ProposeState::Proposed
+            && self.base.role == Role::Participant
+            && let Some(reply) = self.respond(&msg)?
+        {
+            return Ok(ProcessResult::Respond(reply));
+        }
+
         match &self.state {
             ProposeState::Proposed => Ok(ProcessResult::Continue),
             ProposeState::Accepted => Ok(ProcessResult::Complete(CompletionData {
@@ -202,6 +247,7 @@ impl ProtocolStateMachine for ProposeProtocol {
 
 #[cfg(test)]
 mod tests {
+    use super::super::negotiation::ConcessionCurve;
     use super::*;
 
     fn create_test_message(performative: proto::Performative) -> proto::AclMessage {
@@ -251,4 +297,19 @@ mod tests {
         assert!(matches!(result, ProcessResult::Failed(_)));
         assert!(protocol.is_failed());
     }
+
+    #[test]
+    fn test_evaluator_answers_proposal() {
+        let mut protocol = ProposeProtocol::new(Role::Participant).with_evaluator(Arc::new(ConcessionCurve::new(50.0, 80.0)));
+        let mut propose = create_test_message(proto::Performative::Propose);
+        propose.content = br#"{"price": 70}"#.to_vec();
+
+        // A single-shot proposal is judged at the reservation price.
+        let ProcessResult::Respond(reply) = protocol.process(propose).unwrap() else {
+            panic!("expected an answer");
+        };
+        assert_eq!(reply.performative(), proto::Performative::AcceptProposal);
+        assert_eq!(reply.receivers[0].name, "sender");
+        assert_eq!(protocol.state, ProposeState::Accepted);
+    }
 }
@@ -0,0 +1,563 @@
+// protocol/request_when.rs - FIPA Request-When / Request-Whenever Protocols
+//
+//! FIPA Request-When Protocol Implementation (and its repeating form)
+//!
+//! The initiator asks the responder to perform an action once a condition
+//! holds. The condition is UNL, carried as text in the
+//! [`CONDITION_PROPERTY`] user property; the action is the message content.
+//!
+//! The responder evaluates the condition through its [`WhenResponder`]: when
+//! its own state changes ([`RequestWhenProtocol::state_changed`]) and on a
+//! timer ([`ProtocolStateMachine::poll`], every `check_interval_ms`). It
+//! fires on the condition *becoming* true, so a `request-whenever` performs
+//! once per rising edge rather than on every check while it stays true.
+//!
+//! The responder answers the request with [`RequestWhenProtocol::agree`] (or
+//! [`RequestWhenProtocol::refuse`]), which runs its own reply through the state
+//! machine so the wait starts. [`create_state_machine`] does not build this
+//! protocol, since a responder without its [`WhenResponder`] never fires.
+//!
+//! # Protocol Flow
+//!
+//! ```text
+//! Initiator                          Responder
+//!     |                                  |
+//!     |--- REQUEST-WHEN(EVER) ---------->|  (action; when-condition property)
+//!     |                                  |
+//!     |<-- AGREE / REFUSE ---------------|
+//!     |                                  |
+//!     |  ... condition becomes true ...  |
+//!     |                                  |
+//!     |<-- INFORM-RESULT / FAILURE ------|  (request-when: done)
+//!     |                                  |
+//!     |  ... and again, whenever ...     |  (request-whenever: until cancelled)
+//!     |                                  |
+//!     |--- CANCEL ---------------------->|
+//! ```
+
+use super::state_machine::*;
+use crate::proto;
+use std::sync::Arc;
+use unl_core::UnlGraph;
+
+/// `user_properties` key carrying the UNL condition (table or list form).
+pub const CONDITION_PROPERTY: &str = "when-condition";
+
+/// How often a responder re-evaluates the condition unless configured otherwise.
+pub const DEFAULT_CHECK_INTERVAL_MS: u64 = 1000;
+
+/// Put `condition` on a `request-when`/`request-whenever`.
+pub fn set_condition(msg: &mut proto::AclMessage, condition: &UnlGraph) {
+    msg.user_properties.insert(CONDITION_PROPERTY.into(), unl_parser::to_table(condition));
+}
+
+/// The UNL condition carried by `msg`.
+pub fn condition_of(msg: &proto::AclMessage) -> Result<UnlGraph, ProtocolError> {
+    let text = msg
+        .user_properties
+        .get(CONDITION_PROPERTY)
+        .ok_or_else(|| ProtocolError::ValidationFailed("request-when carries no condition".into()))?;
+    unl_parser::parse_sentence(text)
+        .map_err(|e| ProtocolError::ValidationFailed(format!("unparseable condition: {e}")))
+}
+
+/// The responder agent's side of the protocol: judging the condition against
+/// its own state, and performing the action once it holds.
+pub trait WhenResponder: Send + Sync {
+    /// Whether `condition` holds now.
+    fn holds(&self, condition: &UnlGraph) -> bool;
+
+    /// Perform `action`; `Ok(result)` is informed, `Err(reason)` is a failure.
+    fn perform(&self, action: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// Whether the action is performed once or on every rising edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhenMode {
+    /// `request-when`: perform once, then complete.
+    Once,
+    /// `request-whenever`: perform each time, until cancelled.
+    Whenever,
+}
+
+impl WhenMode {
+    fn performative(&self) -> proto::Performative {
+        match self {
+            WhenMode::Once => proto::Performative::RequestWhen,
+            WhenMode::Whenever => proto::Performative::RequestWhenever,
+        }
+    }
+}
+
+/// FIPA Request-When Protocol States
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestWhenState {
+    /// Initial state
+    NotStarted,
+    /// Request-when has been sent
+    Requested,
+    /// Agreed; waiting for the condition
+    Waiting,
+    /// Action performed (request-when only)
+    Completed,
+    /// Action failed
+    Failed,
+    /// Request was refused
+    Refused,
+    /// Protocol was cancelled
+    Cancelled,
+}
+
+impl RequestWhenState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestWhenState::NotStarted => "not_started",
+            RequestWhenState::Requested => "requested",
+            RequestWhenState::Waiting => "waiting",
+            RequestWhenState::Completed => "completed",
+            RequestWhenState::Failed => "failed",
+            RequestWhenState::Refused => "refused",
+            RequestWhenState::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// FIPA Request-When / Request-Whenever Protocol Implementation
+pub struct RequestWhenProtocol {
+    state: RequestWhenState,
+    base: ConversationBase,
+    mode: WhenMode,
+
+    /// The request, kept to address the informs
+    request: Option<proto::AclMessage>,
+
+    /// The parsed condition
+    condition: Option<UnlGraph>,
+
+    /// Responder: judges the condition and performs the action
+    responder: Option<Arc<dyn WhenResponder>>,
+
+    /// Whether the condition held at the last evaluation
+    held: bool,
+
+    /// Responder: the timer re-evaluation period
+    check_interval_ms: u64,
+
+    /// Responder: when the condition was last evaluated on the timer (Unix ms)
+    last_check: Option<i64>,
+
+    /// Number of times the action was performed
+    firings: usize,
+
+    /// Last result content
+    last_result: Option<Vec<u8>>,
+}
+
+impl std::fmt::Debug for RequestWhenProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestWhenProtocol")
+            .field("state", &self.state)
+            .field("mode", &self.mode)
+            .field("held", &self.held)
+            .field("firings", &self.firings)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RequestWhenProtocol {
+    /// Create a new protocol instance in `mode`
+    pub fn new(role: Role, mode: WhenMode) -> Self {
+        Self {
+            state: RequestWhenState::NotStarted,
+            base: ConversationBase::new(uuid::Uuid::new_v4().to_string(), role),
+            mode,
+            request: None,
+            condition: None,
+            responder: None,
+            held: false,
+            check_interval_ms: DEFAULT_CHECK_INTERVAL_MS,
+            last_check: None,
+            firings: 0,
+            last_result: None,
+        }
+    }
+
+    /// Create with a specific conversation ID
+    pub fn with_conversation_id(mut self, id: String) -> Self {
+        self.base.conversation_id = id;
+        self
+    }
+
+    /// Responder: judge the condition and perform the action through `responder`.
+    pub fn with_responder(mut self, responder: Arc<dyn WhenResponder>) -> Self {
+        self.responder = Some(responder);
+        self
+    }
+
+    /// Responder: re-evaluate the condition on the timer every `ms`.
+    pub fn with_check_interval(mut self, ms: u64) -> Self {
+        self.check_interval_ms = ms.max(1);
+        self
+    }
+
+    /// Whether the action is performed once or repeatedly.
+    pub fn mode(&self) -> WhenMode {
+        self.mode
+    }
+
+    /// The condition, once a request carried one.
+    pub fn condition(&self) -> Option<&UnlGraph> {
+        self.condition.as_ref()
+    }
+
+    /// Number of times the action was performed.
+    pub fn firings(&self) -> usize {
+        self.firings
+    }
+
+    /// Content of the last inform or failure.
+    pub fn last_result(&self) -> Option<&[u8]> {
+        self.last_result.as_deref()
+    }
+
+    /// Responder: accept the request. Returns the `agree` to send and what it led
+    /// to — the inform (or failure) to send after it if the condition already holds.
+    pub fn agree(&mut self) -> Result<(proto::AclMessage, ProcessResult), ProtocolError> {
+        self.answer(proto::Performative::Agree, Vec::new())
+    }
+
+    /// Responder: decline the request with `reason`. Returns the `refuse` to send.
+    pub fn refuse(&mut self, reason: &str) -> Result<proto::AclMessage, ProtocolError> {
+        self.answer(proto::Performative::Refuse, reason.as_bytes().to_vec()).map(|(reply, _)| reply)
+    }
+
+    /// Our reply to the request, run through the state machine like a received one.
+    fn answer(
+        &mut self,
+        performative: proto::Performative,
+        content: Vec<u8>,
+    ) -> Result<(proto::AclMessage, ProcessResult), ProtocolError> {
+        let request = self.request.as_ref().ok_or_else(|| ProtocolError::InvalidTransition {
+            from: self.state.as_str().to_string(),
+            to: format!("{:?}", performative),
+        })?;
+        let reply = create_response(request, performative, content);
+        let result = self.process(reply.clone())?;
+        Ok((reply, result))
+    }
+
+    /// Responder: the agent's state changed — re-evaluate the condition now.
+    pub fn state_changed(&mut self) -> ProcessResult {
+        self.evaluate()
+    }
+
+    /// Evaluate the condition and, on a rising edge, perform the action and
+    /// return the inform (or failure) to send.
+    fn evaluate(&mut self) -> ProcessResult {
+        if self.state != RequestWhenState::Waiting || self.base.role == Role::Initiator {
+            return ProcessResult::Continue;
+        }
+        let (Some(responder), Some(condition), Some(request)) =
+            (self.responder.clone(), self.condition.as_ref(), self.request.as_ref())
+        else {
+            return ProcessResult::Continue;
+        };
+        let holds = responder.holds(condition);
+        let rising = holds && !self.held;
+        self.held = holds;
+        if !rising {
+            return ProcessResult::Continue;
+        }
+        let reply = match responder.perform(&request.content) {
+            Ok(result) => create_response(request, proto::Performative::InformResult, result),
+            Err(reason) => create_response(request, proto::Performative::Failure, reason.into_bytes()),
+        };
+        // our own reply drives the transition, exactly as a received one would
+        if let Err(e) = self.process(reply.clone()) {
+            return ProcessResult::Failed(e.to_string());
+        }
+        ProcessResult::Respond(reply)
+    }
+
+    fn validate_transition(&self, performative: proto::Performative) -> Result<RequestWhenState, ProtocolError> {
+        use proto::Performative::*;
+        use RequestWhenState::*;
+
+        match (&self.state, performative) {
+            (NotStarted, p) if p == self.mode.performative() => Ok(Requested),
+            (Requested, Agree) => Ok(Waiting),
+            (Requested, Refuse) => Ok(Refused),
+            (Waiting, InformDone | InformResult | Inform) => match self.mode {
+                WhenMode::Once => Ok(Completed),
+                WhenMode::Whenever => Ok(Waiting),
+            },
+            (Waiting, Failure) => Ok(Failed),
+            (_, Cancel) => Ok(Cancelled),
+            (state, perf) => Err(ProtocolError::InvalidTransition {
+                from: state.as_str().to_string(),
+                to: format!("{:?}", perf),
+            }),
+        }
+    }
+}
+
+impl ProtocolStateMachine for RequestWhenProtocol {
+    fn protocol_type(&self) -> proto::ProtocolType {
+        match self.mode {
+            WhenMode::Once => proto::ProtocolType::ProtocolRequestWhen,
+            WhenMode::Whenever => proto::ProtocolType::ProtocolRequestWhenever,
+        }
+    }
+
+    fn state_name(&self) -> &str {
+        self.state.as_str()
+    }
+
+    fn validate(&self, msg: &proto::AclMessage) -> Result<(), ProtocolError> {
+        let performative = proto::Performative::try_from(msg.performative)
+            .map_err(|_| ProtocolError::ValidationFailed("Invalid performative".into()))?;
+        if self.validate_transition(performative)? == RequestWhenState::Requested {
+            condition_of(msg)?;
+        }
+        Ok(())
+    }
+
+    fn process(&mut self, msg: proto::AclMessage) -> Result<ProcessResult, ProtocolError> {
+        let performative = proto::Performative::try_from(msg.performative)
+            .map_err(|_| ProtocolError::ValidationFailed("Invalid performative".into()))?;
+
+        let new_state = self.validate_transition(performative)?;
+
+        match performative {
+            proto::Performative::RequestWhen | proto::Performative::RequestWhenever => {
+                self.condition = Some(condition_of(&msg)?);
+                if let Some(sender) = &msg.sender {
+                    self.base.add_participant(sender.clone());
+                }
+                self.request = Some(msg.clone());
+            }
+            proto::Performative::InformDone
+            | proto::Performative::InformResult
+            | proto::Performative::Inform
+            | proto::Performative::Failure => {
+                self.firings += 1;
+                self.last_result = Some(msg.content.clone());
+            }
+            _ => {}
+        }
+
+        self.base.record_message(msg);
+        self.state = new_state;
+
+        match &self.state {
+            // the condition may already hold when the responder agrees
+            RequestWhenState::Waiting if performative == proto::Performative::Agree => Ok(self.evaluate()),
+            RequestWhenState::Completed => Ok(ProcessResult::Complete(CompletionData {
+                result: self.last_result.clone(),
+                ..Default::default()
+            })),
+            RequestWhenState::Failed => Ok(ProcessResult::Failed("Request-when action failed".into())),
+            RequestWhenState::Refused => Ok(ProcessResult::Failed("Request-when refused".into())),
+            RequestWhenState::Cancelled => Ok(ProcessResult::Failed("Request-when cancelled".into())),
+            _ => Ok(ProcessResult::Continue),
+        }
+    }
+
+    fn poll(&mut self, now_ms: i64) -> ProcessResult {
+        if self.last_check.is_some_and(|t| now_ms < t.saturating_add(self.check_interval_ms as i64)) {
+            return ProcessResult::Continue;
+        }
+        self.last_check = Some(now_ms);
+        self.evaluate()
+    }
+
+    fn is_complete(&self) -> bool {
+        matches!(
+            self.state,
+            RequestWhenState::Completed
+                | RequestWhenState::Failed
+                | RequestWhenState::Refused
+                | RequestWhenState::Cancelled
+        )
+    }
+
+    fn is_failed(&self) -> bool {
+        matches!(
+            self.state,
+            RequestWhenState::Failed | RequestWhenState::Refused | RequestWhenState::Cancelled
+        )
+    }
+
+    fn expected_performatives(&self) -> Vec<proto::Performative> {
+        use proto::Performative::*;
+
+        match &self.state {
+            RequestWhenState::NotStarted => vec![self.mode.performative()],
+            RequestWhenState::Requested => vec![Agree, Refuse, Cancel],
+            RequestWhenState::Waiting => vec![InformResult, InformDone, Inform, Failure, Cancel],
+            _ => vec![],
+        }
+    }
+
+    fn serialize_state(&self) -> Result<Vec<u8>, ProtocolError> {
+        Ok(self.state.as_str().as_bytes().to_vec())
+    }
+
+    fn message_history(&self) -> &[proto::AclMessage] {
+        &self.base.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// "Stock drops below the threshold" — `holds` reads a shared level.
+    struct Restocker {
+        stock: AtomicU64,
+        threshold: u64,
+    }
+
+    impl WhenResponder for Restocker {
+        fn holds(&self, _condition: &UnlGraph) -> bool {
+            self.stock.load(Ordering::SeqCst) < self.threshold
+        }
+
+        fn perform(&self, action: &[u8]) -> Result<Vec<u8>, String> {
+            self.stock.fetch_add(100, Ordering::SeqCst);
+            Ok(action.to_vec())
+        }
+    }
+
+    fn message(performative: proto::Performative) -> proto::AclMessage {
+        let agent = |name: &str| proto::AgentId { name: name.into(), addresses: vec![], resolvers: vec![] };
+        let mut msg = proto::AclMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            performative: performative as i32,
+            sender: Some(agent("monitor")),
+            receivers: vec![agent("warehouse")],
+            conversation_id: Some("conv-1".into()),
+            content: b"reorder".to_vec(),
+            ..Default::default()
+        };
+        set_condition(&mut msg, &unl_parser::parse_sentence("aoj(drop, stock)").unwrap());
+        msg
+    }
+
+    fn responder(mode: WhenMode, stock: u64) -> (RequestWhenProtocol, Arc<Restocker>) {
+        let agent = Arc::new(Restocker { stock: AtomicU64::new(stock), threshold: 10 });
+        let protocol = RequestWhenProtocol::new(Role::Participant, mode)
+            .with_responder(agent.clone())
+            .with_check_interval(1_000);
+        (protocol, agent)
+    }
+
+    #[test]
+    fn a_request_without_a_condition_is_rejected() {
+        let protocol = RequestWhenProtocol::new(Role::Participant, WhenMode::Once);
+        let mut request = message(proto::Performative::RequestWhen);
+        request.user_properties.clear();
+        assert!(matches!(protocol.validate(&request), Err(ProtocolError::ValidationFailed(_))));
+        // and the wrong performative for the mode
+        assert!(protocol.validate(&message(proto::Performative::RequestWhenever)).is_err());
+    }
+
+    #[test]
+    fn request_when_fires_once_on_a_state_change() {
+        let (mut protocol, agent) = responder(WhenMode::Once, 50);
+        protocol.process(message(proto::Performative::RequestWhen)).unwrap();
+        assert!(protocol.condition().is_some());
+        assert!(matches!(protocol.process(message(proto::Performative::Agree)).unwrap(), ProcessResult::Continue));
+        assert!(matches!(protocol.state_changed(), ProcessResult::Continue));
+
+        agent.stock.store(5, Ordering::SeqCst);
+        let ProcessResult::Respond(inform) = protocol.state_changed() else { panic!("fires on the change") };
+        assert_eq!(inform.performative, proto::Performative::InformResult as i32);
+        assert_eq!(inform.content, b"reorder");
+        assert_eq!(protocol.state, RequestWhenState::Completed);
+        assert!(protocol.is_complete() && !protocol.is_failed());
+        assert_eq!(protocol.firings(), 1);
+    }
+
+    #[test]
+    fn a_condition_already_true_fires_at_the_agree() {
+        let (mut protocol, _) = responder(WhenMode::Once, 0);
+        protocol.process(message(proto::Performative::RequestWhen)).unwrap();
+        let result = protocol.process(message(proto::Performative::Agree)).unwrap();
+        assert!(matches!(result, ProcessResult::Respond(_)));
+        assert!(protocol.is_complete());
+    }
+
+    #[test]
+    fn the_responders_own_agree_starts_the_wait() {
+        let (mut protocol, agent) = responder(WhenMode::Once, 50);
+        assert!(protocol.agree().is_err()); // nothing to agree to yet
+        protocol.process(message(proto::Performative::RequestWhen)).unwrap();
+        let (agree, result) = protocol.agree().unwrap();
+        assert_eq!(agree.performative, proto::Performative::Agree as i32);
+        assert_eq!(agree.receivers[0].name, "monitor");
+        assert!(matches!(result, ProcessResult::Continue));
+        assert_eq!(protocol.state, RequestWhenState::Waiting);
+        agent.stock.store(5, Ordering::SeqCst);
+        assert!(matches!(protocol.state_changed(), ProcessResult::Respond(_)));
+
+        // already true: the inform follows the agree at once
+        let (mut protocol, _) = responder(WhenMode::Once, 0);
+        protocol.process(message(proto::Performative::RequestWhen)).unwrap();
+        let (_, result) = protocol.agree().unwrap();
+        assert!(matches!(result, ProcessResult::Respond(_)));
+
+        let (mut protocol, _) = responder(WhenMode::Once, 50);
+        protocol.process(message(proto::Performative::RequestWhen)).unwrap();
+        assert_eq!(protocol.refuse("no stock feed").unwrap().content, b"no stock feed");
+        assert!(protocol.is_failed());
+    }
+
+    #[test]
+    fn request_whenever_fires_on_each_rising_edge_until_cancelled() {
+        let (mut protocol, agent) = responder(WhenMode::Whenever, 50);
+        protocol.process(message(proto::Performative::RequestWhenever)).unwrap();
+        protocol.process(message(proto::Performative::Agree)).unwrap();
+
+        // the timer: nothing while the condition is false
+        assert!(matches!(protocol.poll(0), ProcessResult::Continue));
+        agent.stock.store(5, Ordering::SeqCst);
+        // not yet due
+        assert!(matches!(protocol.poll(999), ProcessResult::Continue));
+        assert!(matches!(protocol.poll(1_000), ProcessResult::Respond(_)));
+        assert_eq!(protocol.state, RequestWhenState::Waiting);
+
+        // restocked by the action, so it must drop again to re-fire
+        assert!(matches!(protocol.poll(2_000), ProcessResult::Continue));
+        agent.stock.store(3, Ordering::SeqCst);
+        assert!(matches!(protocol.state_changed(), ProcessResult::Respond(_)));
+        // still true, but no new edge
+        agent.stock.store(3, Ordering::SeqCst);
+        assert!(matches!(protocol.poll(3_000), ProcessResult::Continue));
+        assert_eq!(protocol.firings(), 2);
+
+        assert!(matches!(protocol.process(message(proto::Performative::Cancel)).unwrap(), ProcessResult::Failed(_)));
+        assert!(protocol.is_complete());
+        // cancelled: a fresh edge no longer fires
+        protocol.held = false;
+        assert!(matches!(protocol.state_changed(), ProcessResult::Continue));
+    }
+
+    #[test]
+    fn the_initiator_tracks_informs_without_evaluating() {
+        let mut protocol = RequestWhenProtocol::new(Role::Initiator, WhenMode::Whenever);
+        protocol.process(message(proto::Performative::RequestWhenever)).unwrap();
+        protocol.process(message(proto::Performative::Agree)).unwrap();
+        for _ in 0..3 {
+            assert!(matches!(
+                protocol.process(message(proto::Performative::InformResult)).unwrap(),
+                ProcessResult::Continue
+            ));
+        }
+        assert_eq!(protocol.firings(), 3);
+        let result = protocol.process(message(proto::Performative::Failure)).unwrap();
+        assert!(matches!(result, ProcessResult::Failed(_)));
+        assert!(protocol.is_failed());
+    }
+}
@@ -0,0 +1,1229 @@
+// protocol/sealed_bid_auction.rs - Sealed-Bid Auction with Commit-Reveal
+//
+//! Sealed-Bid Auction Protocol Implementation (first-price and Vickrey)
+//!
+//! A plain sealed-bid auction trusts the auctioneer to report the bids
+//! honestly (`THREAT_MODEL.md` M2). Here bidders first *commit*: they send
+//! `SHA-256(domain ‖ conversation ‖ bidder ‖ bid ‖ nonce)`, signed with their
+//! agent [`Keyring`]. Once commitments close the auctioneer broadcasts the full
+//! set and asks for the *reveals*; the outcome carries every commitment and
+//! every reveal, so any participant can recompute the winner and the clearing
+//! price ([`SealedBidOutcome::verify`]).
+//!
+//! - The auctioneer cannot invent a bid (it has no bidder's key) or alter one
+//!   (the reveal would not open the commitment). Bidders' keys are pinned by
+//!   the auctioneer from what it already knows of them (the node's pinned keys,
+//!   the AMS) and published on the `cfp`; a commitment counts only from a bidder
+//!   the `cfp` called, signed under the key it lists — so neither a stranger nor
+//!   a bidder the auctioneer makes up after the fact can take part.
+//! - It cannot drop one unseen: each bidder checks its commitment is in the
+//!   broadcast set, that the outcome carries exactly that set, and — having
+//!   revealed — that its reveal is among the outcome's.
+//! - A bidder cannot change its bid after seeing others: the set is fixed
+//!   before anything is revealed. A commitment left unrevealed forfeits.
+//!
+//! # Protocol Flow
+//!
+//! ```text
+//! Auctioneer                        Bidders
+//!     |                                |
+//!     |--------- CFP ----------------->|  (item; pricing + reserve as properties)
+//!     |                                |
+//!     |<-------- PROPOSE --------------|  (signed commitment, no bid)
+//!     |                                |
+//!     |  ... commit deadline ...       |
+//!     |                                |
+//!     |--------- REQUEST ------------->|  (to all: every commitment, reveal now)
+//!     |                                |
+//!     |<-------- INFORM ---------------|  (bid + nonce)
+//!     |                                |
+//!     |  ... all revealed / deadline   |
+//!     |                                |
+//!     |--------- INFORM -------------->|  (to all: outcome, verifiable)
+//! ```
+//!
+//! Bidders check the pricing and reserve announced on the `cfp` against the
+//! outcome, and the bidder keys listed on it against any they pinned
+//! themselves ([`with_bidder_key`](SealedBidAuctionProtocol::with_bidder_key)).
+
+use super::state_machine::*;
+use crate::proto;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use unl_agent::Keyring;
+
+/// `user_properties` key on the `cfp` naming the pricing rule.
+pub const AUCTION_PRICING_PROPERTY: &str = "auction-pricing";
+
+/// `user_properties` key on the `cfp` carrying the reserve price, if any.
+pub const AUCTION_RESERVE_PROPERTY: &str = "auction-reserve";
+
+/// `user_properties` key on the `cfp` carrying each bidder's public key: a JSON
+/// object from bidder name to hex key.
+pub const AUCTION_BIDDERS_PROPERTY: &str = "auction-bidders";
+
+/// Domain separator for commitment digests.
+const COMMIT_DOMAIN: &[u8] = b"fipa:sealed-bid:v1\0";
+
+/// Bytes of nonce drawn for each commitment.
+const NONCE_LEN: usize = 32;
+
+/// What the winner of a sealed-bid auction pays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pricing {
+    /// The winner pays its own bid.
+    FirstPrice,
+    /// Vickrey: the winner pays the second-highest bid, or the reserve price
+    /// when it bid alone (its own bid if there is no reserve).
+    SecondPrice,
+}
+
+impl Pricing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Pricing::FirstPrice => "first-price",
+            Pricing::SecondPrice => "second-price",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "first-price" => Some(Pricing::FirstPrice),
+            "second-price" => Some(Pricing::SecondPrice),
+            _ => None,
+        }
+    }
+}
+
+/// The digest a bidder commits to.
+pub fn commitment_digest(
+    conversation_id: &str,
+    bidder: &str,
+    amount: f64,
+    nonce: &[u8],
+) -> Vec<u8> {
+    let mut h = Sha256::new();
+    h.update(COMMIT_DOMAIN);
+    h.update(conversation_id.as_bytes());
+    h.update([0]);
+    h.update(bidder.as_bytes());
+    h.update([0]);
+    h.update(amount.to_bits().to_be_bytes());
+    h.update(nonce);
+    h.finalize().to_vec()
+}
+
+/// A bidder's sealed bid: the digest, signed with its key. The key it verifies
+/// under is the one the `cfp` lists for the bidder, never one it brings along.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Commitment {
+    /// Bidder agent name
+    pub bidder: String,
+    /// [`commitment_digest`] of the bid
+    pub digest: Vec<u8>,
+    /// [`Keyring::sign`] over the digest
+    pub signature: Vec<u8>,
+}
+
+impl Commitment {
+    /// Seal `amount` for `bidder` in conversation `conversation_id`: the
+    /// commitment to send now and the reveal to keep until asked.
+    pub fn seal(
+        keyring: &dyn Keyring,
+        conversation_id: &str,
+        bidder: &str,
+        amount: f64,
+    ) -> (Commitment, Reveal) {
+        let nonce = keyring.random(NONCE_LEN);
+        let digest = commitment_digest(conversation_id, bidder, amount, &nonce);
+        let commitment = Commitment {
+            bidder: bidder.to_string(),
+            signature: keyring.sign(&digest),
+            digest,
+        };
+        (
+            commitment,
+            Reveal {
+                bidder: bidder.to_string(),
+                amount,
+                nonce,
+            },
+        )
+    }
+
+    /// Whether the signature holds for the digest under `public_key`.
+    pub fn is_signed_by(&self, public_key: &[u8], keyring: &dyn Keyring) -> bool {
+        keyring.verify(public_key, &self.digest, &self.signature)
+    }
+}
+
+/// An opened bid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reveal {
+    /// Bidder agent name
+    pub bidder: String,
+    /// Bid amount
+    pub amount: f64,
+    /// The nonce hashed into the commitment
+    pub nonce: Vec<u8>,
+}
+
+impl Reveal {
+    /// Whether this reveal opens `commitment` in conversation `conversation_id`.
+    pub fn opens(&self, commitment: &Commitment, conversation_id: &str) -> bool {
+        self.bidder == commitment.bidder
+            && self.amount.is_finite()
+            && commitment_digest(conversation_id, &self.bidder, self.amount, &self.nonce)
+                == commitment.digest
+    }
+}
+
+/// The published result: everything needed to recompute it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedBidOutcome {
+    pub pricing: Pricing,
+    pub reserve_price: Option<f64>,
+    /// Every commitment accepted, in receipt order
+    pub commitments: Vec<Commitment>,
+    /// Every reveal that opened its commitment
+    pub reveals: Vec<Reveal>,
+    /// Winning bidder, if any bid met the reserve
+    pub winner: Option<String>,
+    /// What the winner pays
+    pub clearing_price: Option<f64>,
+}
+
+impl SealedBidOutcome {
+    /// Recompute the outcome of conversation `conversation_id` from its
+    /// commitments and reveals, and check it is the one reported. `bidders`
+    /// are the keys of the bidders the `cfp` called; a commitment from anyone
+    /// else, or not signed under its bidder's key, fails the outcome.
+    pub fn verify(
+        &self,
+        conversation_id: &str,
+        bidders: &BTreeMap<String, Vec<u8>>,
+        keyring: &dyn Keyring,
+    ) -> Result<(), ProtocolError> {
+        let invalid = |why: String| Err(ProtocolError::ValidationFailed(why));
+        for (i, c) in self.commitments.iter().enumerate() {
+            let Some(key) = bidders.get(&c.bidder) else {
+                return invalid(format!("{} was not called for bids", c.bidder));
+            };
+            if !c.is_signed_by(key, keyring) {
+                return invalid(format!("commitment of {} is not signed", c.bidder));
+            }
+            if self.commitments[..i].iter().any(|o| o.bidder == c.bidder) {
+                return invalid(format!("{} committed twice", c.bidder));
+            }
+        }
+        for r in &self.reveals {
+            let opened = self.commitments.iter().find(|c| c.bidder == r.bidder);
+            if !opened.is_some_and(|c| r.opens(c, conversation_id)) {
+                return invalid(format!(
+                    "reveal of {} does not open its commitment",
+                    r.bidder
+                ));
+            }
+        }
+        let (winner, price) = settle(
+            self.pricing,
+            self.reserve_price,
+            &self.commitments,
+            &self.reveals,
+        );
+        if winner != self.winner || price != self.clearing_price {
+            return invalid(format!(
+                "reported {:?} at {:?}, bids give {:?} at {:?}",
+                self.winner, self.clearing_price, winner, price
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Winner and price from the reveals. Ties go to the earliest commitment.
+fn settle(
+    pricing: Pricing,
+    reserve: Option<f64>,
+    commitments: &[Commitment],
+    reveals: &[Reveal],
+) -> (Option<String>, Option<f64>) {
+    // Eligible bids in commitment (receipt) order.
+    let bids: Vec<&Reveal> = commitments
+        .iter()
+        .filter_map(|c| reveals.iter().find(|r| r.bidder == c.bidder))
+        .filter(|r| reserve.is_none_or(|min| r.amount >= min))
+        .collect();
+    let Some(best) = bids
+        .iter()
+        .enumerate()
+        .fold(None::<usize>, |best, (i, r)| match best {
+            Some(b) if bids[b].amount >= r.amount => Some(b),
+            _ => Some(i),
+        })
+    else {
+        return (None, None);
+    };
+    let top = bids[best];
+    let price = match pricing {
+        Pricing::FirstPrice => top.amount,
+        Pricing::SecondPrice => bids
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != best)
+            .map(|(_, r)| r.amount)
+            .fold(None, |m: Option<f64>, a| Some(m.map_or(a, |m| m.max(a))))
+            .or(reserve)
+            .unwrap_or(top.amount),
+    };
+    (Some(top.bidder.clone()), Some(price))
+}
+
+/// Sealed-Bid Auction Protocol States
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SealedBidState {
+    /// Initial state
+    NotStarted,
+    /// `cfp` out, commitments being collected
+    Committing,
+    /// Commitments closed, reveals being collected
+    Revealing,
+    /// Outcome published (auctioneer) or received and verified (bidder)
+    Completed,
+    /// Auction failed (or, for a bidder, the outcome did not verify)
+    Failed,
+    /// Auction was cancelled
+    Cancelled,
+}
+
+impl SealedBidState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SealedBidState::NotStarted => "not_started",
+            SealedBidState::Committing => "committing",
+            SealedBidState::Revealing => "revealing",
+            SealedBidState::Completed => "completed",
+            SealedBidState::Failed => "failed",
+            SealedBidState::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Sealed-Bid Auction Protocol Implementation
+///
+/// The auctioneer stamps its `cfp` with [`announce`](Self::announce), collects
+/// commitments, broadcasts them with [`close_commitments`](Self::close_commitments)
+/// and publishes the outcome with [`close_auction`](Self::close_auction) once
+/// every bidder revealed. With deadlines set, [`ProtocolStateMachine::poll`]
+/// closes each phase on time. A bidder answers the `cfp` with
+/// [`bid`](Self::bid) and reveals automatically when asked.
+pub struct SealedBidAuctionProtocol {
+    state: SealedBidState,
+    base: ConversationBase,
+    keyring: Arc<dyn Keyring>,
+
+    pricing: Pricing,
+
+    /// Reserve price (minimum acceptable)
+    reserve_price: Option<f64>,
+
+    /// Item being auctioned
+    item_description: Option<Vec<u8>>,
+
+    /// Auctioneer: how long commitments stay open (ms)
+    commit_ms: Option<i64>,
+
+    /// Auctioneer: how long reveals stay open (ms)
+    reveal_ms: Option<i64>,
+
+    /// Each bidder's public key: pinned by the auctioneer and listed on the
+    /// `cfp`; a bidder takes them from the `cfp`, checked against its own pins
+    bidder_keys: BTreeMap<String, Vec<u8>>,
+
+    /// Commitments accepted (auctioneer) or broadcast (bidder), in receipt order
+    commitments: Vec<Commitment>,
+
+    /// Reveals that opened their commitment
+    reveals: Vec<Reveal>,
+
+    /// Bidder: our own sealed bid, kept until the reveal request
+    sealed: Option<(Commitment, Reveal)>,
+
+    outcome: Option<SealedBidOutcome>,
+}
+
+impl std::fmt::Debug for SealedBidAuctionProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SealedBidAuctionProtocol")
+            .field("state", &self.state)
+            .field("conversation_id", &self.base.conversation_id)
+            .field("pricing", &self.pricing)
+            .field("commitments", &self.commitments.len())
+            .field("reveals", &self.reveals.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SealedBidAuctionProtocol {
+    fn new(role: Role, pricing: Pricing, keyring: Arc<dyn Keyring>) -> Self {
+        Self {
+            state: SealedBidState::NotStarted,
+            base: ConversationBase::new(uuid::Uuid::new_v4().to_string(), role),
+            keyring,
+            pricing,
+            reserve_price: None,
+            item_description: None,
+            commit_ms: None,
+            reveal_ms: None,
+            bidder_keys: BTreeMap::new(),
+            commitments: vec![],
+            reveals: vec![],
+            sealed: None,
+            outcome: None,
+        }
+    }
+
+    /// Create a new sealed-bid auction (as auctioneer). The keyring checks
+    /// the bidders' signatures.
+    pub fn new_as_auctioneer(pricing: Pricing, keyring: Arc<dyn Keyring>) -> Self {
+        Self::new(Role::Initiator, pricing, keyring)
+    }
+
+    /// Create a new sealed-bid auction (as bidder). The keyring signs our
+    /// commitment; pricing and reserve are taken from the `cfp`.
+    pub fn new_as_bidder(keyring: Arc<dyn Keyring>) -> Self {
+        Self::new(Role::Participant, Pricing::FirstPrice, keyring)
+    }
+
+    /// Set reserve price
+    pub fn with_reserve_price(mut self, price: f64) -> Self {
+        self.reserve_price = Some(price);
+        self
+    }
+
+    /// Set item description
+    pub fn with_item_description(mut self, desc: Vec<u8>) -> Self {
+        self.item_description = Some(desc);
+        self
+    }
+
+    /// Set conversation ID
+    pub fn with_conversation_id(mut self, id: String) -> Self {
+        self.base.conversation_id = id;
+        self
+    }
+
+    /// Pin `bidder`'s public key. The auctioneer pins every `cfp` receiver's
+    /// (from the node's pinned keys or the AMS); a bidder may pin the keys it
+    /// already knows, and then refuses a `cfp` listing other ones.
+    pub fn with_bidder_key(mut self, bidder: &str, public_key: Vec<u8>) -> Self {
+        self.bidder_keys.insert(bidder.to_string(), public_key);
+        self
+    }
+
+    /// Auctioneer: close commitments `commit_ms` after the `cfp` and reveals
+    /// `reveal_ms` after that, on [`ProtocolStateMachine::poll`].
+    pub fn with_deadlines(mut self, commit_ms: u64, reveal_ms: u64) -> Self {
+        self.commit_ms = Some(commit_ms as i64);
+        self.reveal_ms = Some(reveal_ms as i64);
+        self
+    }
+
+    pub fn pricing(&self) -> Pricing {
+        self.pricing
+    }
+
+    pub fn commitments(&self) -> &[Commitment] {
+        &self.commitments
+    }
+
+    /// The published (auctioneer) or verified (bidder) outcome.
+    pub fn outcome(&self) -> Option<&SealedBidOutcome> {
+        self.outcome.as_ref()
+    }
+
+    /// Get winner
+    pub fn winner(&self) -> Option<&str> {
+        self.outcome.as_ref()?.winner.as_deref()
+    }
+
+    fn cid(&self) -> &str {
+        &self.base.conversation_id
+    }
+
+    /// Bidder: our own id as the `cfp` addressed us.
+    fn me(&self, bidder: &str) -> proto::AgentId {
+        let cfp = self.base.messages.first();
+        cfp.and_then(|m| m.receivers.iter().find(|r| r.name == bidder).cloned())
+            .unwrap_or_else(|| proto::AgentId {
+                name: bidder.to_string(),
+                ..Default::default()
+            })
+    }
+
+    fn arm(&mut self, after_ms: Option<i64>) {
+        self.base.deadline = after_ms.map(|ms| chrono::Utc::now().timestamp_millis() + ms);
+    }
+
+    fn invalid_transition(&self, to: &str) -> ProtocolError {
+        ProtocolError::InvalidTransition {
+            from: self.state.as_str().to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    /// Auctioneer: stamp the outgoing `cfp` with the conversation, pricing,
+    /// reserve and the receivers' pinned keys, and open commitments. Every
+    /// receiver needs a pinned key; the bidders are exactly the receivers.
+    pub fn announce(&mut self, cfp: &mut proto::AclMessage) -> Result<(), ProtocolError> {
+        if self.base.role != Role::Initiator || self.state != SealedBidState::NotStarted {
+            return Err(self.invalid_transition("announce"));
+        }
+        let mut keys = BTreeMap::new();
+        for receiver in &cfp.receivers {
+            let Some(key) = self.bidder_keys.get(&receiver.name) else {
+                return Err(ProtocolError::ValidationFailed(format!(
+                    "no key pinned for bidder {}",
+                    receiver.name
+                )));
+            };
+            keys.insert(receiver.name.clone(), key.clone());
+        }
+        let listed: BTreeMap<&String, String> = keys
+            .iter()
+            .map(|(name, key)| (name, hex::encode(key)))
+            .collect();
+        let listed = serde_json::to_string(&listed)
+            .map_err(|e| ProtocolError::SerializationError(e.to_string()))?;
+        self.bidder_keys = keys;
+        cfp.performative = proto::Performative::Cfp as i32;
+        cfp.protocol = Some(proto::ProtocolType::ProtocolSealedBidAuction as i32);
+        cfp.conversation_id = Some(self.base.conversation_id.clone());
+        cfp.user_properties.insert(
+            AUCTION_PRICING_PROPERTY.into(),
+            self.pricing.as_str().into(),
+        );
+        if let Some(reserve) = self.reserve_price {
+            cfp.user_properties
+                .insert(AUCTION_RESERVE_PROPERTY.into(), reserve.to_string());
+        }
+        cfp.user_properties
+            .insert(AUCTION_BIDDERS_PROPERTY.into(), listed);
+        if let Some(desc) = &self.item_description {
+            cfp.content = desc.clone();
+        }
+        for receiver in &cfp.receivers {
+            self.base.add_participant(receiver.clone());
+        }
+        self.base.record_message(cfp.clone());
+        self.state = SealedBidState::Committing;
+        self.arm(self.commit_ms);
+        Ok(())
+    }
+
+    /// Bidder: the `propose` committing to `amount`. The bid itself stays
+    /// here until the auctioneer asks for reveals.
+    pub fn bid(&mut self, bidder: &str, amount: f64) -> Result<proto::AclMessage, ProtocolError> {
+        if self.base.role != Role::Participant
+            || self.state != SealedBidState::Committing
+            || self.sealed.is_some()
+        {
+            return Err(self.invalid_transition("bid"));
+        }
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(ProtocolError::ValidationFailed(format!(
+                "bid {amount} is not a price"
+            )));
+        }
+        if self.bidder_keys.get(bidder) != Some(&self.keyring.public_key()) {
+            return Err(ProtocolError::ValidationFailed(format!(
+                "the cfp does not list our key for {bidder}"
+            )));
+        }
+        let cfp = self.base.messages.first().cloned().unwrap_or_default();
+        let (commitment, reveal) =
+            Commitment::seal(self.keyring.as_ref(), self.cid(), bidder, amount);
+        let content = serde_json::to_vec(&commitment)
+            .map_err(|e| ProtocolError::SerializationError(e.to_string()))?;
+        let mut propose = create_response(&cfp, proto::Performative::Propose, content);
+        propose.sender = Some(self.me(bidder));
+        self.sealed = Some((commitment, reveal));
+        self.base.record_message(propose.clone());
+        Ok(propose)
+    }
+
+    /// Auctioneer: close commitments. Returns the `request` for reveals,
+    /// addressed to every bidder and carrying every commitment.
+    pub fn close_commitments(&mut self) -> Result<proto::AclMessage, ProtocolError> {
+        if self.base.role != Role::Initiator || self.state != SealedBidState::Committing {
+            return Err(self.invalid_transition("reveal"));
+        }
+        let cfp = self.base.messages.first().cloned().unwrap_or_default();
+        let content = serde_json::to_vec(&self.commitments)
+            .map_err(|e| ProtocolError::SerializationError(e.to_string()))?;
+        let mut request = create_response(&cfp, proto::Performative::Request, content);
+        request.sender = cfp.sender.clone();
+        request.receivers = self
+            .base
+            .participants
+            .iter()
+            .filter(|p| self.commitments.iter().any(|c| c.bidder == p.name))
+            .cloned()
+            .collect();
+        self.base.record_message(request.clone());
+        self.state = SealedBidState::Revealing;
+        self.arm(self.reveal_ms);
+        Ok(request)
+    }
+
+    /// Auctioneer: settle on the reveals so far (unrevealed commitments
+    /// forfeit). Returns the `inform` publishing the outcome to every bidder.
+    pub fn close_auction(&mut self) -> Result<proto::AclMessage, ProtocolError> {
+        if self.base.role != Role::Initiator || self.state != SealedBidState::Revealing {
+            return Err(self.invalid_transition("close"));
+        }
+        let (winner, clearing_price) = settle(
+            self.pricing,
+            self.reserve_price,
+            &self.commitments,
+            &self.reveals,
+        );
+        let outcome = SealedBidOutcome {
+            pricing: self.pricing,
+            reserve_price: self.reserve_price,
+            commitments: self.commitments.clone(),
+            reveals: self.reveals.clone(),
+            winner,
+            clearing_price,
+        };
+        let cfp = self.base.messages.first().cloned().unwrap_or_default();
+        let content = serde_json::to_vec(&outcome)
+            .map_err(|e| ProtocolError::SerializationError(e.to_string()))?;
+        let mut inform = create_response(&cfp, proto::Performative::Inform, content);
+        inform.sender = cfp.sender.clone();
+        inform.receivers = cfp.receivers.clone();
+        self.base.record_message(inform.clone());
+        self.outcome = Some(outcome);
+        self.state = SealedBidState::Completed;
+        self.base.deadline = None;
+        Ok(inform)
+    }
+
+    /// Auctioneer: take a commitment from `propose`.
+    fn accept_commitment(&mut self, msg: &proto::AclMessage) -> Result<(), ProtocolError> {
+        let commitment: Commitment = serde_json::from_slice(&msg.content)
+            .map_err(|e| ProtocolError::ValidationFailed(format!("malformed commitment: {e}")))?;
+        if msg.sender.as_ref().map(|s| s.name.as_str()) != Some(commitment.bidder.as_str()) {
+            return Err(ProtocolError::ValidationFailed(
+                "commitment not from its bidder".into(),
+            ));
+        }
+        if self
+            .commitments
+            .iter()
+            .any(|c| c.bidder == commitment.bidder)
+        {
+            return Err(ProtocolError::ValidationFailed(format!(
+                "{} already committed",
+                commitment.bidder
+            )));
+        }
+        let Some(key) = self.bidder_keys.get(&commitment.bidder) else {
+            return Err(ProtocolError::ValidationFailed(format!(
+                "{} was not called for bids",
+                commitment.bidder
+            )));
+        };
+        if !commitment.is_signed_by(key, self.keyring.as_ref()) {
+            return Err(ProtocolError::ValidationFailed(format!(
+                "commitment of {} is not signed",
+                commitment.bidder
+            )));
+        }
+        if let Some(sender) = &msg.sender {
+            self.base.add_participant(sender.clone());
+        }
+        self.commitments.push(commitment);
+        Ok(())
+    }
+
+    /// Auctioneer: take a reveal from `inform`.
+    fn accept_reveal(&mut self, msg: &proto::AclMessage) -> Result<(), ProtocolError> {
+        let reveal: Reveal = serde_json::from_slice(&msg.content)
+            .map_err(|e| ProtocolError::ValidationFailed(format!("malformed reveal: {e}")))?;
+        if msg.sender.as_ref().map(|s| s.name.as_str()) != Some(reveal.bidder.as_str()) {
+            return Err(ProtocolError::ValidationFailed(
+                "reveal not from its bidder".into(),
+            ));
+        }
+        let opens = self
+            .commitments
+            .iter()
+            .any(|c| reveal.opens(c, &self.base.conversation_id));
+        if !opens || self.reveals.iter().any(|r| r.bidder == reveal.bidder) {
+            return Err(ProtocolError::ValidationFailed(format!(
+                "reveal of {} does not open its commitment",
+                reveal.bidder
+            )));
+        }
+        self.reveals.push(reveal);
+        Ok(())
+    }
+
+    /// Bidder: the reveal request. Our commitment must be in the set.
+    fn reveal(&mut self, msg: &proto::AclMessage) -> Result<ProcessResult, ProtocolError> {
+        let set: Vec<Commitment> = serde_json::from_slice(&msg.content).map_err(|e| {
+            ProtocolError::ValidationFailed(format!("malformed commitment set: {e}"))
+        })?;
+        let Some((ours, reveal)) = &self.sealed else {
+            self.commitments = set;
+            return Ok(ProcessResult::Continue); // did not bid
+        };
+        if !set.contains(ours) {
+            self.state = SealedBidState::Failed;
+            return Ok(ProcessResult::Failed("Our commitment was left out".into()));
+        }
+        let content = serde_json::to_vec(reveal)
+            .map_err(|e| ProtocolError::SerializationError(e.to_string()))?;
+        let mut inform = create_response(msg, proto::Performative::Inform, content);
+        inform.sender = Some(self.me(&reveal.bidder));
+        self.commitments = set;
+        self.base.record_message(inform.clone());
+        Ok(ProcessResult::Respond(inform))
+    }
+
+    /// Bidder: the published outcome, checked against the announced terms and
+    /// the commitment set we were shown.
+    fn check_outcome(&mut self, msg: &proto::AclMessage) -> Result<ProcessResult, ProtocolError> {
+        let outcome: SealedBidOutcome = serde_json::from_slice(&msg.content)
+            .map_err(|e| ProtocolError::ValidationFailed(format!("malformed outcome: {e}")))?;
+        let verdict =
+            if outcome.pricing != self.pricing || outcome.reserve_price != self.reserve_price {
+                Err(ProtocolError::ValidationFailed(
+                    "outcome terms differ from the cfp".into(),
+                ))
+            } else if self.sealed.is_some() && outcome.commitments != self.commitments {
+                Err(ProtocolError::ValidationFailed(
+                    "outcome commitments differ from the reveal request".into(),
+                ))
+            } else if let Some((_, ours)) = &self.sealed
+                && !outcome.reveals.contains(ours)
+            {
+                // We revealed (the outcome only follows the reveal request), so
+                // "forfeited" would be the auctioneer discarding our bid.
+                Err(ProtocolError::ValidationFailed(
+                    "outcome leaves out our reveal".into(),
+                ))
+            } else {
+                outcome.verify(
+                    &self.base.conversation_id,
+                    &self.bidder_keys,
+                    self.keyring.as_ref(),
+                )
+            };
+        match verdict {
+            Ok(()) => {
+                let result = msg.content.clone();
+                self.outcome = Some(outcome);
+                self.state = SealedBidState::Completed;
+                Ok(ProcessResult::Complete(CompletionData {
+                    result: Some(result),
+                    ..Default::default()
+                }))
+            }
+            Err(e) => {
+                self.state = SealedBidState::Failed;
+                Ok(ProcessResult::Failed(format!(
+                    "Auction outcome does not verify: {e}"
+                )))
+            }
+        }
+    }
+
+    /// Bidder: the bidder keys the `cfp` lists. A key we pinned must match.
+    fn take_bidder_keys(&mut self, listed: Option<&String>) -> Result<(), ProtocolError> {
+        let bad = ProtocolError::ValidationFailed;
+        let listed = listed.ok_or_else(|| bad("cfp lists no bidder keys".into()))?;
+        let listed: BTreeMap<String, String> =
+            serde_json::from_str(listed).map_err(|e| bad(format!("malformed bidder keys: {e}")))?;
+        let mut keys = BTreeMap::new();
+        for (bidder, key) in listed {
+            let key =
+                hex::decode(&key).map_err(|e| bad(format!("malformed key for {bidder}: {e}")))?;
+            if self
+                .bidder_keys
+                .get(&bidder)
+                .is_some_and(|pinned| *pinned != key)
+            {
+                return Err(bad(format!("cfp lists another key for {bidder}")));
+            }
+            keys.insert(bidder, key);
+        }
+        self.bidder_keys = keys;
+        Ok(())
+    }
+
+    /// Validate state transition based on performative
+    fn validate_transition(
+        &self,
+        performative: proto::Performative,
+    ) -> Result<SealedBidState, ProtocolError> {
+        use proto::Performative::*;
+        use SealedBidState::*;
+
+        let bidder = self.base.role == Role::Participant;
+        match (&self.state, performative) {
+            // Auctioneer calls for bids
+            (NotStarted, Cfp) if bidder => Ok(Committing),
+            // Bidder commits (or declines)
+            (Committing, Propose) | (Committing, Refuse) if !bidder => Ok(Committing),
+            // Auctioneer asks for reveals
+            (Committing, Request) if bidder => Ok(Revealing),
+            // Bidder reveals
+            (Revealing, Inform) if !bidder => Ok(Revealing),
+            // Auctioneer publishes the outcome (also to those who did not bid)
+            (Committing, Inform) | (Revealing, Inform) if bidder => Ok(Completed),
+            // Failure
+            (_, Failure) => Ok(Failed),
+            // Cancel
+            (_, Cancel) => Ok(Cancelled),
+            (state, perf) => Err(ProtocolError::InvalidTransition {
+                from: state.as_str().to_string(),
+                to: format!("{:?}", perf),
+            }),
+        }
+    }
+}
+
+impl ProtocolStateMachine for SealedBidAuctionProtocol {
+    fn protocol_type(&self) -> proto::ProtocolType {
+        proto::ProtocolType::ProtocolSealedBidAuction
+    }
+
+    fn state_name(&self) -> &str {
+        self.state.as_str()
+    }
+
+    fn validate(&self, msg: &proto::AclMessage) -> Result<(), ProtocolError> {
+        let performative = proto::Performative::try_from(msg.performative)
+            .map_err(|_| ProtocolError::ValidationFailed("Invalid performative".into()))?;
+
+        self.validate_transition(performative)?;
+        Ok(())
+    }
+
+    fn process(&mut self, msg: proto::AclMessage) -> Result<ProcessResult, ProtocolError> {
+        let performative = proto::Performative::try_from(msg.performative)
+            .map_err(|_| ProtocolError::ValidationFailed("Invalid performative".into()))?;
+
+        let new_state = self.validate_transition(performative)?;
+        let auctioneer = self.base.role == Role::Initiator;
+
+        match performative {
+            proto::Performative::Cfp => {
+                let props = &msg.user_properties;
+                self.pricing = props
+                    .get(AUCTION_PRICING_PROPERTY)
+                    .and_then(|p| Pricing::parse(p))
+                    .ok_or_else(|| {
+                        ProtocolError::ValidationFailed("cfp names no pricing".into())
+                    })?;
+                self.reserve_price = props
+                    .get(AUCTION_RESERVE_PROPERTY)
+                    .and_then(|r| r.parse().ok());
+                self.take_bidder_keys(props.get(AUCTION_BIDDERS_PROPERTY))?;
+                self.item_description = Some(msg.content.clone());
+                if let Some(id) = &msg.conversation_id {
+                    self.base.conversation_id = id.clone();
+                }
+            }
+            proto::Performative::Propose if auctioneer => self.accept_commitment(&msg)?,
+            proto::Performative::Inform if auctioneer => self.accept_reveal(&msg)?,
+            proto::Performative::Request => {
+                self.base.record_message(msg.clone());
+                self.state = new_state;
+                return self.reveal(&msg);
+            }
+            proto::Performative::Inform => {
+                self.base.record_message(msg.clone());
+                return self.check_outcome(&msg);
+            }
+            _ => {}
+        }
+
+        self.base.record_message(msg);
+        self.state = new_state;
+
+        match &self.state {
+            SealedBidState::Revealing
+                if auctioneer && self.reveals.len() == self.commitments.len() =>
+            {
+                let inform = self.close_auction()?;
+                Ok(ProcessResult::Respond(inform))
+            }
+            SealedBidState::Failed => Ok(ProcessResult::Failed("Auction failed".into())),
+            SealedBidState::Cancelled => Ok(ProcessResult::Failed("Auction cancelled".into())),
+            _ => Ok(ProcessResult::Continue),
+        }
+    }
+
+    fn poll(&mut self, now_ms: i64) -> ProcessResult {
+        if self.base.role != Role::Initiator || self.base.deadline.is_none_or(|d| now_ms < d) {
+            return ProcessResult::Continue;
+        }
+        let next = match self.state {
+            SealedBidState::Committing if self.commitments.is_empty() => {
+                self.state = SealedBidState::Failed;
+                self.base.deadline = None;
+                return ProcessResult::Failed("No bids committed".into());
+            }
+            SealedBidState::Committing => self.close_commitments(),
+            SealedBidState::Revealing => self.close_auction(),
+            _ => return ProcessResult::Continue,
+        };
+        match next {
+            Ok(msg) => ProcessResult::Respond(msg),
+            Err(e) => ProcessResult::Failed(e.to_string()),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        matches!(
+            self.state,
+            SealedBidState::Completed | SealedBidState::Failed | SealedBidState::Cancelled
+        )
+    }
+
+    fn is_failed(&self) -> bool {
+        matches!(
+            self.state,
+            SealedBidState::Failed | SealedBidState::Cancelled
+        )
+    }
+
+    fn expected_performatives(&self) -> Vec<proto::Performative> {
+        use proto::Performative::*;
+
+        match &self.state {
+            SealedBidState::NotStarted => vec![Cfp],
+            SealedBidState::Committing => vec![Propose, Refuse, Request, Cancel],
+            SealedBidState::Revealing => vec![Inform, Cancel],
+            _ => vec![],
+        }
+    }
+
+    fn serialize_state(&self) -> Result<Vec<u8>, ProtocolError> {
+        Ok(self.state.as_str().as_bytes().to_vec())
+    }
+
+    fn message_history(&self) -> &[proto::AclMessage] {
+        &self.base.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{verify, NodeCrypto};
+
+    /// An agent keyring over a real Ed25519 key, as a node provisions it.
+    struct Ed25519(NodeCrypto);
+
+    impl Ed25519 {
+        fn new() -> Arc<Self> {
+            Arc::new(Ed25519(NodeCrypto::generate()))
+        }
+    }
+
+    impl Keyring for Ed25519 {
+        fn sign(&self, bytes: &[u8]) -> Vec<u8> {
+            self.0.sign(bytes).to_vec()
+        }
+        fn verify(&self, pubkey: &[u8], bytes: &[u8], sig: &[u8]) -> bool {
+            match (<[u8; 32]>::try_from(pubkey), <[u8; 64]>::try_from(sig)) {
+                (Ok(pk), Ok(sig)) => verify(&pk, bytes, &sig),
+                _ => false,
+            }
+        }
+        fn public_key(&self) -> Vec<u8> {
+            self.0.public_key().to_vec()
+        }
+        fn random(&self, n: usize) -> Vec<u8> {
+            let mut v = vec![0u8; n];
+            rand::RngCore::fill_bytes(&mut rand::rng(), &mut v);
+            v
+        }
+    }
+
+    fn agent(name: &str) -> proto::AgentId {
+        proto::AgentId {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Run a whole auction; returns the auctioneer and the bidders.
+    fn run(
+        pricing: Pricing,
+        bids: &[(&'static str, f64)],
+    ) -> (SealedBidAuctionProtocol, Vec<SealedBidAuctionProtocol>) {
+        let (auctioneer, mut bidders, published) = run_to_outcome(pricing, bids);
+        for bidder in &mut bidders {
+            assert!(matches!(
+                bidder.process(published.clone()).unwrap(),
+                ProcessResult::Complete(_)
+            ));
+        }
+        (auctioneer, bidders)
+    }
+
+    /// An auctioneer with every bidder's key pinned, and its `cfp`.
+    fn announced(
+        pricing: Pricing,
+        keys: &[(&'static str, Arc<Ed25519>)],
+    ) -> (SealedBidAuctionProtocol, proto::AclMessage) {
+        let mut auctioneer = SealedBidAuctionProtocol::new_as_auctioneer(pricing, Ed25519::new())
+            .with_reserve_price(50.0);
+        for (name, key) in keys {
+            auctioneer = auctioneer.with_bidder_key(name, key.public_key());
+        }
+        let mut cfp = proto::AclMessage {
+            sender: Some(agent("auctioneer")),
+            receivers: keys.iter().map(|(b, _)| agent(b)).collect(),
+            ..Default::default()
+        };
+        auctioneer.announce(&mut cfp).unwrap();
+        (auctioneer, cfp)
+    }
+
+    /// A bidder holding `key` that has received `cfp`.
+    fn called(cfp: &proto::AclMessage, name: &str, key: Arc<Ed25519>) -> SealedBidAuctionProtocol {
+        let mut bidder = SealedBidAuctionProtocol::new_as_bidder(key);
+        let mut own = cfp.clone();
+        own.receivers = vec![agent(name)];
+        bidder.process(own).unwrap();
+        bidder
+    }
+
+    /// Run an auction up to its published outcome, not yet delivered to the
+    /// bidders.
+    fn run_to_outcome(
+        pricing: Pricing,
+        bids: &[(&'static str, f64)],
+    ) -> (SealedBidAuctionProtocol, Vec<SealedBidAuctionProtocol>, proto::AclMessage) {
+        let keys: Vec<_> = bids.iter().map(|(b, _)| (*b, Ed25519::new())).collect();
+        let (mut auctioneer, cfp) = announced(pricing, &keys);
+
+        let mut bidders = vec![];
+        for ((name, amount), (_, key)) in bids.iter().zip(keys) {
+            let mut bidder = called(&cfp, name, key);
+            let propose = bidder.bid(name, *amount).unwrap();
+            assert!(!String::from_utf8_lossy(&propose.content).contains("amount"));
+            auctioneer.process(propose).unwrap();
+            bidders.push(bidder);
+        }
+
+        let request = auctioneer.close_commitments().unwrap();
+        let mut published = None;
+        for bidder in &mut bidders {
+            let ProcessResult::Respond(reveal) = bidder.process(request.clone()).unwrap() else {
+                panic!("bidder did not reveal");
+            };
+            if let ProcessResult::Respond(inform) = auctioneer.process(reveal).unwrap() {
+                published = Some(inform);
+            }
+        }
+        let published = published.expect("all revealed, so the auction closes");
+        (auctioneer, bidders, published)
+    }
+
+    #[test]
+    fn first_price_winner_pays_own_bid() {
+        let (auctioneer, bidders) = run(
+            Pricing::FirstPrice,
+            &[("b1", 120.0), ("b2", 150.0), ("b3", 150.0)],
+        );
+        assert_eq!(auctioneer.winner(), Some("b2")); // tie goes to the earlier commitment
+        assert_eq!(auctioneer.outcome().unwrap().clearing_price, Some(150.0));
+        assert!(bidders.iter().all(|b| b.winner() == Some("b2")));
+    }
+
+    #[test]
+    fn second_price_winner_pays_runner_up_or_reserve() {
+        let (auctioneer, _) = run(
+            Pricing::SecondPrice,
+            &[("b1", 120.0), ("b2", 150.0), ("b3", 40.0)],
+        );
+        assert_eq!(auctioneer.winner(), Some("b2"));
+        assert_eq!(auctioneer.outcome().unwrap().clearing_price, Some(120.0));
+
+        // b3 is under the reserve, so b1 bids alone and pays the reserve.
+        let (auctioneer, _) = run(Pricing::SecondPrice, &[("b1", 120.0), ("b3", 40.0)]);
+        assert_eq!(auctioneer.winner(), Some("b1"));
+        assert_eq!(auctioneer.outcome().unwrap().clearing_price, Some(50.0));
+    }
+
+    #[test]
+    fn a_misreported_outcome_does_not_verify() {
+        let (auctioneer, _) = run(Pricing::SecondPrice, &[("b1", 120.0), ("b2", 150.0)]);
+        let cid = auctioneer.base.conversation_id.clone();
+        let keys = &auctioneer.bidder_keys;
+        let keyring = Ed25519::new();
+        let honest = auctioneer.outcome().unwrap().clone();
+        assert!(honest.verify(&cid, keys, keyring.as_ref()).is_ok());
+
+        // Overcharging the winner.
+        let mut lie = honest.clone();
+        lie.clearing_price = Some(150.0);
+        assert!(lie.verify(&cid, keys, keyring.as_ref()).is_err());
+
+        // Swapping in a shill bid the auctioneer signed itself.
+        let mut lie = honest.clone();
+        let (shill, reveal) = Commitment::seal(auctioneer.keyring.as_ref(), &cid, "b1", 149.0);
+        lie.commitments[0] = shill;
+        lie.reveals[0] = reveal;
+        assert!(lie.verify(&cid, keys, keyring.as_ref()).is_err());
+
+        // Claiming a different bid than was committed.
+        let mut lie = honest;
+        lie.reveals[0].amount = 149.0;
+        assert!(lie.verify(&cid, keys, keyring.as_ref()).is_err());
+    }
+
+    #[test]
+    fn a_bidder_the_cfp_did_not_call_cannot_take_part() {
+        let (auctioneer, bidders, mut published) =
+            run_to_outcome(Pricing::FirstPrice, &[("b1", 120.0), ("b2", 150.0)]);
+        let cid = auctioneer.base.conversation_id.clone();
+
+        // A stranger's signed commitment is refused while bids are open.
+        let (mut open, cfp) = announced(Pricing::FirstPrice, &[("b1", Ed25519::new())]);
+        let stranger = Ed25519::new();
+        let (commitment, _) = Commitment::seal(stranger.as_ref(), open.cid(), "mallory", 500.0);
+        let mut propose = create_response(
+            &cfp,
+            proto::Performative::Propose,
+            serde_json::to_vec(&commitment).unwrap(),
+        );
+        propose.sender = Some(agent("mallory"));
+        assert!(open.process(propose).is_err());
+        assert!(open.commitments().is_empty());
+
+        // Nor can a called bidder's name be borrowed under another key.
+        let (forged, _) = Commitment::seal(stranger.as_ref(), open.cid(), "b1", 500.0);
+        let mut propose = create_response(
+            &cfp,
+            proto::Performative::Propose,
+            serde_json::to_vec(&forged).unwrap(),
+        );
+        propose.sender = Some(agent("b1"));
+        assert!(open.process(propose).is_err());
+
+        // A bidder the auctioneer makes up, with a fresh key, wins a
+        // self-consistent outcome — which no one called for bids accepts.
+        let mut lie = auctioneer.outcome().unwrap().clone();
+        let (shill, reveal) = Commitment::seal(stranger.as_ref(), &cid, "mallory", 151.0);
+        lie.commitments.push(shill);
+        lie.reveals.push(reveal);
+        lie.winner = Some("mallory".into());
+        lie.clearing_price = Some(151.0);
+        let mut keys = auctioneer.bidder_keys.clone();
+        keys.insert("mallory".into(), stranger.public_key());
+        assert!(lie.verify(&cid, &keys, stranger.as_ref()).is_ok());
+        assert!(
+            lie.verify(&cid, &auctioneer.bidder_keys, stranger.as_ref())
+                .is_err()
+        );
+        published.content = serde_json::to_vec(&lie).unwrap();
+        // one who was called but did not bid checks it against the cfp's keys
+        let mut observer = called(&bidders[0].base.messages[0], "b3", Ed25519::new());
+        assert!(matches!(
+            observer.process(published).unwrap(),
+            ProcessResult::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn a_bidder_refuses_a_cfp_listing_another_key_for_a_bidder_it_knows() {
+        let (b1, b2) = (Ed25519::new(), Ed25519::new());
+        let (_, cfp) = announced(Pricing::FirstPrice, &[("b1", b1.clone()), ("b2", b2)]);
+        let mut wary = SealedBidAuctionProtocol::new_as_bidder(b1.clone())
+            .with_bidder_key("b2", Ed25519::new().public_key());
+        assert!(wary.process(cfp.clone()).is_err());
+
+        // And its own key has to be the one listed for it.
+        let mut imposter = called(&cfp, "b1", Ed25519::new());
+        assert!(imposter.bid("b1", 100.0).is_err());
+        assert!(called(&cfp, "b1", b1).bid("b1", 100.0).is_ok());
+    }
+
+    #[test]
+    fn an_outcome_that_drops_a_received_reveal_is_rejected_by_its_bidder() {
+        let (auctioneer, mut bidders, mut published) =
+            run_to_outcome(Pricing::SecondPrice, &[("b1", 120.0), ("b2", 150.0)]);
+        // The auctioneer passes b2's reveal off as forfeited: still self-consistent.
+        let mut lie = auctioneer.outcome().unwrap().clone();
+        lie.reveals.retain(|r| r.bidder != "b2");
+        lie.winner = Some("b1".into());
+        lie.clearing_price = Some(50.0);
+        let cid = &auctioneer.base.conversation_id;
+        assert!(
+            lie.verify(cid, &auctioneer.bidder_keys, auctioneer.keyring.as_ref())
+                .is_ok()
+        );
+        published.content = serde_json::to_vec(&lie).unwrap();
+
+        assert!(matches!(
+            bidders[0].process(published.clone()).unwrap(),
+            ProcessResult::Complete(_)
+        )); // b1 can't tell
+        assert!(matches!(
+            bidders[1].process(published).unwrap(),
+            ProcessResult::Failed(_)
+        ));
+        assert!(bidders[1].is_failed());
+    }
+
+    #[test]
+    fn a_bidder_left_out_of_the_commitment_set_fails() {
+        let key = Ed25519::new();
+        let (_, cfp) = announced(Pricing::SecondPrice, &[("b1", key.clone())]);
+        let mut bidder = called(&cfp, "b1", key);
+        bidder.bid("b1", 100.0).unwrap();
+        let mut request = create_response(&cfp, proto::Performative::Request, b"[]".to_vec());
+        request.receivers = vec![agent("b1")];
+        assert!(matches!(
+            bidder.process(request).unwrap(),
+            ProcessResult::Failed(_)
+        ));
+        assert!(bidder.is_failed());
+    }
+
+    #[test]
+    fn commit_and_reveal_deadlines_close_on_poll() {
+        let mut auctioneer =
+            SealedBidAuctionProtocol::new_as_auctioneer(Pricing::FirstPrice, Ed25519::new())
+                .with_bidder_key("b1", Ed25519::new().public_key())
+                .with_deadlines(1_000, 1_000);
+        let mut cfp = proto::AclMessage {
+            sender: Some(agent("a")),
+            receivers: vec![agent("b1")],
+            ..Default::default()
+        };
+        auctioneer.announce(&mut cfp).unwrap();
+        let deadline = auctioneer.base.deadline.unwrap();
+        assert!(matches!(
+            auctioneer.poll(deadline - 1),
+            ProcessResult::Continue
+        ));
+        // Nobody committed in time.
+        assert!(matches!(
+            auctioneer.poll(deadline),
+            ProcessResult::Failed(_)
+        ));
+        assert_eq!(auctioneer.state, SealedBidState::Failed);
+    }
+}
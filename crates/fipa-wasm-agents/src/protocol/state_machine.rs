@@ -2,6 +2,7 @@
 
 use crate::proto;
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// Protocol error types
 #[derive(Debug, Clone, thiserror::Error)]
@@ -83,6 +84,13 @@ pub trait ProtocolStateMachine: Send + Sync + Debug {
     /// Process a message and transition state
     fn process(&mut self, msg: proto::AclMessage) -> Result<ProcessResult, ProtocolError>;
 
+    /// Advance on the clock alone (`now_ms`: Unix ms) — lease expiry, renewals.
+    /// The hosting actor calls it periodically; message-driven protocols keep
+    /// the default.
+    fn poll(&mut self, _now_ms: i64) -> ProcessResult {
+        ProcessResult::Continue
+    }
+
     /// Check if protocol is in a terminal state
     fn is_complete(&self) -> bool;
 
@@ -192,9 +200,34 @@ pub fn create_response(
     }
 }
 
-/// Create a protocol state machine from protocol type
+/// Node settings for the state machines [`create_state_machine_with`] builds
+#[derive(Debug, Clone)]
+pub struct ProtocolConfig {
+    /// Longest subscription lease a subscribe responder grants; longer requests are clamped to it
+    pub max_subscription_lease: Duration,
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        Self { max_subscription_lease: Duration::from_millis(super::subscribe::DEFAULT_MAX_LEASE_MS) }
+    }
+}
+
+/// Create a protocol state machine from protocol type, with default settings.
+///
+/// Request-when and request-whenever are not built here: their responder is
+/// useless without the agent's [`WhenResponder`](super::request_when::WhenResponder),
+/// so callers build a `RequestWhenProtocol` with it.
 pub fn create_state_machine(
     protocol: proto::ProtocolType,
+) -> Result<Box<dyn ProtocolStateMachine>, ProtocolError> {
+    create_state_machine_with(protocol, &ProtocolConfig::default())
+}
+
+/// Create a protocol state machine from protocol type under the node's `config`
+pub fn create_state_machine_with(
+    protocol: proto::ProtocolType,
+    config: &ProtocolConfig,
 ) -> Result<Box<dyn ProtocolStateMachine>, ProtocolError> {
     match protocol {
         proto::ProtocolType::ProtocolRequest => {
@@ -207,7 +240,8 @@ pub fn create_state_machine(
             Ok(Box::new(super::contract_net::ContractNetProtocol::new(Role::Participant)))
         }
         proto::ProtocolType::ProtocolSubscribe => {
-            Ok(Box::new(super::subscribe::SubscribeProtocol::new(Role::Participant)))
+            let max_ms = config.max_subscription_lease.as_millis() as u64;
+            Ok(Box::new(super::subscribe::SubscribeProtocol::new(Role::Participant).with_max_lease(max_ms)))
         }
         _ => Err(ProtocolError::NotSupported(protocol)),
     }
@@ -3,6 +3,30 @@
 use super::state_machine::*;
 use crate::proto;
 
+/// `user_properties` key carrying a subscription lease in ms: requested on
+/// `subscribe` (initial or renewal), granted on the responder's `agree`.
+pub const LEASE_MS_PROPERTY: &str = "lease-ms";
+
+/// The longest lease a responder grants unless configured otherwise.
+pub const DEFAULT_MAX_LEASE_MS: u64 = 5 * 60 * 1000;
+
+/// The lease a responder grants for `requested`: at most `max_ms`, and the full
+/// `max_ms` when the subscriber asked for none (or for 0).
+pub fn clamp_lease(requested: Option<u64>, max_ms: u64) -> u64 {
+    match requested {
+        Some(ms) if ms > 0 => ms.min(max_ms),
+        _ => max_ms,
+    }
+}
+
+fn lease_of(msg: &proto::AclMessage) -> Option<u64> {
+    msg.user_properties.get(LEASE_MS_PROPERTY)?.parse().ok()
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
 /// FIPA Subscribe Protocol States
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SubscribeState {
@@ -14,6 +38,8 @@ pub enum SubscribeState {
     Failed,
     Refused,
     Cancelled,
+    /// The lease ran out without a renewal.
+    Expired,
 }
 
 impl SubscribeState {
@@ -27,11 +53,18 @@ impl SubscribeState {
             SubscribeState::Failed => "failed",
             SubscribeState::Refused => "refused",
             SubscribeState::Cancelled => "cancelled",
+            SubscribeState::Expired => "expired",
         }
     }
 }
 
 /// FIPA Subscribe Protocol Implementation
+///
+/// Subscriptions are leased. The responder clamps the requested lease to its
+/// ceiling, answers a renewal (`subscribe` again on the same conversation) with
+/// a fresh `agree`, and ends the subscription as `Expired` once a lease runs out
+/// unrenewed. The initiator re-sends its `subscribe` at 80% of the granted lease.
+/// Both sides act on the clock through [`ProtocolStateMachine::poll`].
 #[derive(Debug)]
 pub struct SubscribeProtocol {
     state: SubscribeState,
@@ -45,6 +78,21 @@ pub struct SubscribeProtocol {
 
     /// Last notification content
     last_notification: Option<Vec<u8>>,
+
+    /// Responder: the longest lease granted.
+    max_lease_ms: u64,
+
+    /// Initiator: the lease asked for (and asked for again on each renewal).
+    requested_lease_ms: Option<u64>,
+
+    /// The lease in force — granted by the responder, as last agreed.
+    lease_ms: Option<u64>,
+
+    /// When the lease runs out (Unix ms).
+    expires_at: Option<i64>,
+
+    /// Initiator: a renewal is out and not yet agreed.
+    renewing: bool,
 }
 
 impl SubscribeProtocol {
@@ -55,9 +103,63 @@ impl SubscribeProtocol {
             subscription_object: None,
             notification_count: 0,
             last_notification: None,
+            max_lease_ms: DEFAULT_MAX_LEASE_MS,
+            requested_lease_ms: None,
+            lease_ms: None,
+            expires_at: None,
+            renewing: false,
         }
     }
 
+    /// Responder: grant leases of at most `max_ms` (a node-configured ceiling).
+    pub fn with_max_lease(mut self, max_ms: u64) -> Self {
+        self.max_lease_ms = max_ms.max(1);
+        self
+    }
+
+    /// Initiator: ask for a lease of `lease_ms`. Stamp it on the outgoing
+    /// `subscribe` with [`SubscribeProtocol::request_lease`].
+    pub fn with_lease(mut self, lease_ms: u64) -> Self {
+        self.requested_lease_ms = Some(lease_ms);
+        self
+    }
+
+    /// Initiator: put the requested lease on `subscribe`.
+    pub fn request_lease(&self, subscribe: &mut proto::AclMessage) {
+        if let Some(ms) = self.requested_lease_ms {
+            subscribe.user_properties.insert(LEASE_MS_PROPERTY.into(), ms.to_string());
+        }
+    }
+
+    /// Responder: the `agree` to `subscribe`, carrying the lease granted.
+    pub fn agree_to(&self, subscribe: &proto::AclMessage) -> proto::AclMessage {
+        let mut agree = create_response(subscribe, proto::Performative::Agree, Vec::new());
+        let granted = self.lease_ms.unwrap_or_else(|| clamp_lease(lease_of(subscribe), self.max_lease_ms));
+        agree.user_properties.insert(LEASE_MS_PROPERTY.into(), granted.to_string());
+        agree
+    }
+
+    /// The lease in force, once one is.
+    pub fn lease_ms(&self) -> Option<u64> {
+        self.lease_ms
+    }
+
+    /// When the lease in force runs out (Unix ms).
+    pub fn expires_at(&self) -> Option<i64> {
+        self.expires_at
+    }
+
+    fn arm(&mut self, lease_ms: u64, now_ms: i64) {
+        self.lease_ms = Some(lease_ms);
+        self.expires_at = Some(now_ms.saturating_add(lease_ms as i64));
+        self.base.deadline = self.expires_at;
+    }
+
+    /// Initiator: when to send the renewal — 80% into the lease.
+    fn renew_at(&self) -> Option<i64> {
+        Some(self.expires_at? - (self.lease_ms? / 5) as i64)
+    }
+
     /// Get notification count
     pub fn notification_count(&self) -> usize {
         self.notification_count
@@ -69,7 +171,11 @@ impl SubscribeProtocol {
 
         match (&self.state, performative) {
             (NotStarted, Subscribe) => Ok(Subscribed),
+            (Agreed, Subscribe) => Ok(Agreed), // renewal
+            (Active, Subscribe) => Ok(Active),
             (Subscribed, Agree) => Ok(Agreed),
+            (Agreed, Agree) => Ok(Agreed), // renewal granted
+            (Active, Agree) => Ok(Active),
             (Subscribed, Refuse) => Ok(Refused),
             (Agreed, InformResult) => Ok(Active),
             (Active, InformResult) => Ok(Active), // Stay active on notifications
@@ -104,15 +210,37 @@ impl ProtocolStateMachine for SubscribeProtocol {
             .map_err(|_| ProtocolError::ValidationFailed("Invalid performative".into()))?;
 
         let new_state = self.validate_transition(performative)?;
+        let renewal = self.state != SubscribeState::NotStarted && self.state != SubscribeState::Subscribed;
+        let now = now_ms();
 
         self.base.record_message(msg.clone());
 
         match performative {
+            proto::Performative::Subscribe if renewal => match self.base.role {
+                Role::Initiator => self.renewing = true,
+                _ => {
+                    // re-arm the lease and grant it straight away
+                    self.arm(clamp_lease(lease_of(&msg), self.max_lease_ms), now);
+                    self.state = new_state;
+                    return Ok(ProcessResult::Respond(self.agree_to(&msg)));
+                }
+            },
             proto::Performative::Subscribe => {
                 self.subscription_object = Some(msg.content.clone());
                 if let Some(sender) = &msg.sender {
                     self.base.add_participant(sender.clone());
                 }
+                if self.base.role != Role::Initiator {
+                    // the lease runs from receipt, agreed yet or not
+                    self.arm(clamp_lease(lease_of(&msg), self.max_lease_ms), now);
+                }
+            }
+            proto::Performative::Agree if self.base.role == Role::Initiator => {
+                // the granted lease, or ours if the responder did not say
+                if let Some(granted) = lease_of(&msg).or(self.requested_lease_ms) {
+                    self.arm(granted, now);
+                }
+                self.renewing = false;
             }
             proto::Performative::InformResult => {
                 self.notification_count += 1;
@@ -136,6 +264,30 @@ impl ProtocolStateMachine for SubscribeProtocol {
         }
     }
 
+    fn poll(&mut self, now_ms: i64) -> ProcessResult {
+        if self.is_complete() {
+            return ProcessResult::Continue;
+        }
+        if self.expires_at.is_some_and(|t| now_ms >= t) {
+            self.state = SubscribeState::Expired;
+            return ProcessResult::Failed("Subscription lease expired".into());
+        }
+        let live = matches!(self.state, SubscribeState::Agreed | SubscribeState::Active);
+        if self.base.role != Role::Initiator || !live || self.renewing {
+            return ProcessResult::Continue;
+        }
+        if self.renew_at().is_none_or(|t| now_ms < t) {
+            return ProcessResult::Continue;
+        }
+        let subscribe = self.base.messages.iter().find(|m| m.performative == proto::Performative::Subscribe as i32);
+        let Some(mut renew) = subscribe.cloned() else { return ProcessResult::Continue };
+        renew.message_id = uuid::Uuid::new_v4().to_string();
+        self.request_lease(&mut renew);
+        self.base.record_message(renew.clone());
+        self.renewing = true;
+        ProcessResult::Respond(renew)
+    }
+
     fn is_complete(&self) -> bool {
         matches!(
             self.state,
@@ -143,13 +295,14 @@ impl ProtocolStateMachine for SubscribeProtocol {
                 | SubscribeState::Failed
                 | SubscribeState::Refused
                 | SubscribeState::Cancelled
+                | SubscribeState::Expired
         )
     }
 
     fn is_failed(&self) -> bool {
         matches!(
             self.state,
-            SubscribeState::Failed | SubscribeState::Refused | SubscribeState::Cancelled
+            SubscribeState::Failed | SubscribeState::Refused | SubscribeState::Cancelled | SubscribeState::Expired
         )
     }
 
@@ -160,7 +313,7 @@ impl ProtocolStateMachine for SubscribeProtocol {
             SubscribeState::NotStarted => vec![Subscribe],
             SubscribeState::Subscribed => vec![Agree, Refuse, Cancel],
             SubscribeState::Agreed | SubscribeState::Active => {
-                vec![InformResult, Failure, Cancel]
+                vec![InformResult, Subscribe, Agree, Failure, Cancel]
             }
             _ => vec![],
         }
@@ -174,3 +327,93 @@ impl ProtocolStateMachine for SubscribeProtocol {
         &self.base.messages
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(performative: proto::Performative, lease_ms: Option<u64>) -> proto::AclMessage {
+        let agent = |name: &str| proto::AgentId { name: name.into(), addresses: vec![], resolvers: vec![] };
+        let mut msg = proto::AclMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            performative: performative as i32,
+            sender: Some(agent("subscriber")),
+            receivers: vec![agent("df")],
+            conversation_id: Some("conv-1".into()),
+            ..Default::default()
+        };
+        if let Some(ms) = lease_ms {
+            msg.user_properties.insert(LEASE_MS_PROPERTY.into(), ms.to_string());
+        }
+        msg
+    }
+
+    #[test]
+    fn the_responder_clamps_and_expires_the_lease() {
+        assert_eq!(clamp_lease(Some(u64::MAX), 60_000), 60_000);
+        assert_eq!(clamp_lease(None, 60_000), 60_000);
+        assert_eq!(clamp_lease(Some(0), 60_000), 60_000);
+
+        let mut df = SubscribeProtocol::new(Role::Participant).with_max_lease(60_000);
+        let subscribe = message(proto::Performative::Subscribe, Some(u64::MAX));
+        df.process(subscribe.clone()).unwrap();
+        assert_eq!(df.lease_ms(), Some(60_000));
+        let agree = df.agree_to(&subscribe);
+        assert_eq!(agree.user_properties[LEASE_MS_PROPERTY], "60000");
+        df.process(agree).unwrap();
+
+        let expiry = df.expires_at().unwrap();
+        assert!(matches!(df.poll(expiry - 1), ProcessResult::Continue));
+        assert!(matches!(df.poll(expiry), ProcessResult::Failed(_)));
+        assert_eq!(df.state, SubscribeState::Expired);
+        assert!(df.is_complete() && df.is_failed());
+    }
+
+    #[test]
+    fn the_factory_grants_at_most_the_configured_lease() {
+        let config = ProtocolConfig { max_subscription_lease: std::time::Duration::from_secs(60) };
+        let mut df = create_state_machine_with(proto::ProtocolType::ProtocolSubscribe, &config).unwrap();
+        let before = now_ms();
+        df.process(message(proto::Performative::Subscribe, Some(u64::MAX))).unwrap();
+        assert!(matches!(df.poll(before + 59_000), ProcessResult::Continue));
+        assert!(matches!(df.poll(now_ms() + 60_000), ProcessResult::Failed(_))); // not the 5 min default
+    }
+
+    #[test]
+    fn a_renewal_re_arms_the_lease_and_is_agreed() {
+        let mut df = SubscribeProtocol::new(Role::Participant).with_max_lease(60_000);
+        let subscribe = message(proto::Performative::Subscribe, Some(30_000));
+        df.process(subscribe.clone()).unwrap();
+        df.process(df.agree_to(&subscribe)).unwrap();
+        let first = df.expires_at().unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let result = df.process(message(proto::Performative::Subscribe, Some(30_000))).unwrap();
+        let ProcessResult::Respond(agree) = result else { panic!("a renewal is agreed") };
+        assert_eq!(agree.performative, proto::Performative::Agree as i32);
+        assert_eq!(agree.user_properties[LEASE_MS_PROPERTY], "30000");
+        assert!(df.expires_at().unwrap() > first);
+        assert_eq!(df.state, SubscribeState::Agreed);
+    }
+
+    #[test]
+    fn the_initiator_renews_before_expiry() {
+        let mut sub = SubscribeProtocol::new(Role::Initiator).with_lease(10_000);
+        let mut subscribe = message(proto::Performative::Subscribe, None);
+        sub.request_lease(&mut subscribe);
+        sub.process(subscribe).unwrap();
+        // the responder granted less than was asked
+        sub.process(message(proto::Performative::Agree, Some(5_000))).unwrap();
+        assert_eq!(sub.lease_ms(), Some(5_000));
+
+        let expiry = sub.expires_at().unwrap();
+        assert!(matches!(sub.poll(expiry - 1_001), ProcessResult::Continue));
+        let ProcessResult::Respond(renew) = sub.poll(expiry - 1_000) else { panic!("renews at 80%") };
+        assert_eq!(renew.performative, proto::Performative::Subscribe as i32);
+        assert_eq!(renew.user_properties[LEASE_MS_PROPERTY], "10000");
+        // only once while it is outstanding
+        assert!(matches!(sub.poll(expiry - 500), ProcessResult::Continue));
+        // unanswered, the lease still runs out
+        assert!(matches!(sub.poll(expiry), ProcessResult::Failed(_)));
+    }
+}
@@ -0,0 +1,354 @@
+// tools/conformance.rs - Protocol Conformance Checker
+//
+//! Offline protocol conformance checking for recorded message traces
+//!
+//! A [`MessageTrace`] records what agents said; this module checks whether
+//! each conversation in it actually follows the interaction protocol it
+//! names. Entries are grouped by conversation ID and replayed, in trace
+//! order, through a fresh [`ProtocolStateMachine`] for the conversation's
+//! protocol. Every message the machine rejects is reported together with
+//! the state it arrived in and the performatives that state would have
+//! accepted.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use fipa_wasm_agents::tools::{check_trace, MessageTrace};
+//!
+//! let trace = MessageTrace::from_json(&std::fs::read_to_string("trace.json")?)?;
+//! let report = check_trace(&trace);
+//! assert!(report.is_conformant(), "{:#?}", report.violations().collect::<Vec<_>>());
+//! ```
+//!
+//! # Limitations
+//!
+//! The sniffer truncates content to 200 characters, so protocols whose
+//! transitions depend on message content (bids, leases) are replayed
+//! against what was captured. A trace that simply stops mid-conversation
+//! is not a violation; such conversations are reported as incomplete.
+
+use super::sniffer::{MessageTrace, TraceEntry};
+use crate::proto::{AclMessage, AgentId, Performative, ProtocolType};
+use crate::protocol::{create_state_machine, ProtocolError, ProtocolStateMachine};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+
+/// A message a protocol state machine rejected during replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    /// Trace entry ID of the offending message
+    pub entry_id: u64,
+
+    /// Message ID of the offending message
+    pub message_id: String,
+
+    /// Sender of the offending message
+    pub sender: String,
+
+    /// Performative as recorded
+    pub performative: String,
+
+    /// Protocol state the message arrived in
+    pub state: String,
+
+    /// Performatives the state would have accepted
+    pub expected: Vec<String>,
+
+    /// Why the state machine rejected the message
+    pub error: String,
+}
+
+/// Result of replaying one conversation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationCheck {
+    /// Conversation ID
+    pub conversation_id: String,
+
+    /// Protocol the conversation claims (if any message named one)
+    pub protocol: Option<String>,
+
+    /// Number of messages replayed
+    pub messages: usize,
+
+    /// State the machine ended in (None if the conversation was skipped)
+    pub final_state: Option<String>,
+
+    /// Whether the protocol reached a terminal state
+    pub complete: bool,
+
+    /// Rejected messages, in trace order
+    pub violations: Vec<Violation>,
+
+    /// Why the conversation could not be checked
+    pub skipped: Option<String>,
+}
+
+impl ConversationCheck {
+    /// Whether every replayed message was accepted
+    pub fn is_conformant(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Conformance report for a whole trace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    /// Name of the checked trace
+    pub trace: String,
+
+    /// One check per conversation, in order of first appearance
+    pub conversations: Vec<ConversationCheck>,
+
+    /// Entries without a conversation ID (not checkable)
+    pub unscoped: usize,
+}
+
+impl ConformanceReport {
+    /// Whether no conversation has a violation
+    ///
+    /// Skipped and incomplete conversations do not count against the trace.
+    pub fn is_conformant(&self) -> bool {
+        self.conversations.iter().all(ConversationCheck::is_conformant)
+    }
+
+    /// All violations across conversations
+    pub fn violations(&self) -> impl Iterator<Item = (&str, &Violation)> {
+        self.conversations
+            .iter()
+            .flat_map(|c| c.violations.iter().map(move |v| (c.conversation_id.as_str(), v)))
+    }
+
+    /// Conversations that could not be checked
+    pub fn skipped(&self) -> impl Iterator<Item = &ConversationCheck> {
+        self.conversations.iter().filter(|c| c.skipped.is_some())
+    }
+}
+
+/// Check a trace against the built-in protocol state machines
+pub fn check_trace(trace: &MessageTrace) -> ConformanceReport {
+    check_trace_with(trace, create_state_machine)
+}
+
+/// Check a trace, building state machines with `factory`
+///
+/// Use this to check protocols that [`create_state_machine`] does not know,
+/// such as a [`CompiledProtocol`](crate::protocol::CompiledProtocol).
+pub fn check_trace_with<F>(trace: &MessageTrace, factory: F) -> ConformanceReport
+where
+    F: Fn(ProtocolType) -> Result<Box<dyn ProtocolStateMachine>, ProtocolError>,
+{
+    let mut order: Vec<&str> = Vec::new();
+    let mut grouped: HashMap<&str, Vec<&TraceEntry>> = HashMap::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut unscoped = 0;
+
+    for entry in &trace.entries {
+        let Some(cid) = entry.conversation_id.as_deref() else {
+            unscoped += 1;
+            continue;
+        };
+        // The same message is captured once per monitored endpoint.
+        if !entry.message_id.is_empty() && !seen.insert(entry.message_id.as_str()) {
+            continue;
+        }
+        grouped
+            .entry(cid)
+            .or_insert_with(|| {
+                order.push(cid);
+                Vec::new()
+            })
+            .push(entry);
+    }
+
+    let conversations = order
+        .into_iter()
+        .map(|cid| check_conversation(cid, &grouped[cid], &factory))
+        .collect();
+
+    ConformanceReport {
+        trace: trace.name.clone(),
+        conversations,
+        unscoped,
+    }
+}
+
+fn check_conversation<F>(cid: &str, entries: &[&TraceEntry], factory: &F) -> ConversationCheck
+where
+    F: Fn(ProtocolType) -> Result<Box<dyn ProtocolStateMachine>, ProtocolError>,
+{
+    let mut check = ConversationCheck {
+        conversation_id: cid.to_string(),
+        protocol: entries.iter().find_map(|e| e.protocol.clone()),
+        messages: entries.len(),
+        ..Default::default()
+    };
+
+    let Some(name) = check.protocol.clone() else {
+        check.skipped = Some("no message names a protocol".into());
+        return check;
+    };
+    let Some(protocol) = from_debug_name::<ProtocolType>(&name) else {
+        check.skipped = Some(format!("unknown protocol {name}"));
+        return check;
+    };
+    let mut machine = match factory(protocol) {
+        Ok(machine) => machine,
+        Err(e) => {
+            check.skipped = Some(e.to_string());
+            return check;
+        }
+    };
+
+    for entry in entries {
+        let state = machine.state_name().to_string();
+        let expected = machine
+            .expected_performatives()
+            .iter()
+            .map(|p| format!("{:?}", p))
+            .collect();
+        let error = match to_message(entry, protocol) {
+            Ok(msg) => machine.process(msg).err().map(|e| e.to_string()),
+            Err(e) => Some(e),
+        };
+        if let Some(error) = error {
+            check.violations.push(Violation {
+                entry_id: entry.id,
+                message_id: entry.message_id.clone(),
+                sender: entry.sender.clone(),
+                performative: entry.performative.clone(),
+                state,
+                expected,
+                error,
+            });
+        }
+    }
+
+    check.final_state = Some(machine.state_name().to_string());
+    check.complete = machine.is_complete();
+    check
+}
+
+/// Rebuild the ACL message a trace entry was captured from
+fn to_message(entry: &TraceEntry, protocol: ProtocolType) -> Result<AclMessage, String> {
+    let performative = from_debug_name::<Performative>(&entry.performative)
+        .ok_or_else(|| format!("unknown performative {}", entry.performative))?;
+    let agent = |name: &String| AgentId {
+        name: name.clone(),
+        ..Default::default()
+    };
+
+    Ok(AclMessage {
+        message_id: entry.message_id.clone(),
+        performative: performative as i32,
+        sender: Some(agent(&entry.sender)),
+        receivers: entry.receivers.iter().map(agent).collect(),
+        protocol: Some(protocol as i32),
+        conversation_id: entry.conversation_id.clone(),
+        content: entry.content.clone().into_bytes(),
+        user_properties: entry.user_properties.clone(),
+        ..Default::default()
+    })
+}
+
+/// Parse an enum from the `Debug` name the sniffer records
+fn from_debug_name<T: TryFrom<i32> + Debug>(name: &str) -> Option<T> {
+    (0..=i32::from(u8::MAX))
+        .filter_map(|v| T::try_from(v).ok())
+        .find(|t| format!("{:?}", t) == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::sniffer::MessageDirection;
+    use chrono::Utc;
+
+    fn entry(id: u64, cid: &str, sender: &str, receiver: &str, performative: &str) -> TraceEntry {
+        TraceEntry {
+            id,
+            timestamp: Utc::now(),
+            message_id: format!("msg-{}", id),
+            sender: sender.to_string(),
+            receivers: vec![receiver.to_string()],
+            performative: performative.to_string(),
+            protocol: Some("ProtocolRequest".to_string()),
+            conversation_id: Some(cid.to_string()),
+            content: String::new(),
+            content_length: 0,
+            user_properties: HashMap::new(),
+            direction: MessageDirection::Observed,
+        }
+    }
+
+    #[test]
+    fn test_conformant_request() {
+        let mut trace = MessageTrace::new("ok");
+        trace.add_entry(entry(1, "c1", "a", "b", "Request"));
+        trace.add_entry(entry(2, "c1", "b", "a", "Agree"));
+        trace.add_entry(entry(3, "c1", "b", "a", "InformResult"));
+
+        let report = check_trace(&trace);
+        assert!(report.is_conformant());
+        assert_eq!(report.conversations.len(), 1);
+        assert!(report.conversations[0].complete);
+        assert_eq!(report.conversations[0].final_state.as_deref(), Some("completed"));
+    }
+
+    #[test]
+    fn test_violation_reports_state_and_expected() {
+        let mut trace = MessageTrace::new("bad");
+        trace.add_entry(entry(1, "c1", "a", "b", "Request"));
+        trace.add_entry(entry(2, "c1", "b", "a", "InformResult"));
+
+        let report = check_trace(&trace);
+        assert!(!report.is_conformant());
+        let (cid, violation) = report.violations().next().unwrap();
+        assert_eq!(cid, "c1");
+        assert_eq!(violation.entry_id, 2);
+        assert_eq!(violation.performative, "InformResult");
+        assert_eq!(violation.state, "requested");
+        assert!(violation.expected.contains(&"Agree".to_string()));
+        assert!(!report.conversations[0].complete);
+    }
+
+    #[test]
+    fn test_duplicate_captures_and_grouping() {
+        let mut trace = MessageTrace::new("dup");
+        trace.add_entry(entry(1, "c1", "a", "b", "Request"));
+        // Same message seen again at the receiver.
+        let mut again = entry(2, "c1", "a", "b", "Request");
+        again.message_id = "msg-1".to_string();
+        trace.add_entry(again);
+        trace.add_entry(entry(3, "c2", "x", "y", "Request"));
+        trace.add_entry(entry(4, "c1", "b", "a", "Refuse"));
+        let mut loose = entry(5, "c3", "x", "y", "Inform");
+        loose.conversation_id = None;
+        trace.add_entry(loose);
+
+        let report = check_trace(&trace);
+        assert!(report.is_conformant());
+        assert_eq!(report.unscoped, 1);
+        let ids: Vec<_> = report.conversations.iter().map(|c| c.conversation_id.as_str()).collect();
+        assert_eq!(ids, ["c1", "c2"]);
+        assert_eq!(report.conversations[0].messages, 2);
+        assert!(report.conversations[0].complete);
+        assert!(!report.conversations[1].complete);
+    }
+
+    #[test]
+    fn test_unsupported_protocol_is_skipped() {
+        let mut trace = MessageTrace::new("skip");
+        let mut e = entry(1, "c1", "a", "b", "Cfp");
+        e.protocol = Some("ProtocolEnglishAuction".to_string());
+        trace.add_entry(e);
+        let mut e = entry(2, "c2", "a", "b", "Request");
+        e.protocol = None;
+        trace.add_entry(e);
+
+        let report = check_trace(&trace);
+        assert!(report.is_conformant());
+        assert_eq!(report.skipped().count(), 2);
+        assert!(report.conversations[0].final_state.is_none());
+    }
+}
@@ -4,11 +4,14 @@
 //!
 //! This module provides:
 //! - Message sniffer for debugging and monitoring
+//! - Offline protocol conformance checking of recorded traces
 //! - Web dashboard for platform visualization
 //! - Additional monitoring and management tools
 
+pub mod conformance;
 pub mod dashboard;
 pub mod sniffer;
 
+pub use conformance::{check_trace, check_trace_with, ConformanceReport, ConversationCheck, Violation};
 pub use dashboard::{Dashboard, DashboardConfig, DashboardState, SharedState};
 pub use sniffer::{MessageSniffer, SnifferConfig, SnifferFilter, MessageTrace, TraceEntry};
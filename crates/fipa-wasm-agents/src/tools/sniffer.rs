@@ -109,6 +109,11 @@ pub struct TraceEntry {
     /// Content length in bytes
     pub content_length: usize,
 
+    /// User-defined message properties (protocol parameters such as a
+    /// request-when condition travel here)
+    #[serde(default)]
+    pub user_properties: HashMap<String, String>,
+
     /// Direction relative to monitored agent
     pub direction: MessageDirection,
 }
@@ -419,6 +424,7 @@ impl MessageSniffer {
             } else {
                 content
             },
+            user_properties: message.user_properties.clone(),
             direction,
         };
 
@@ -588,6 +594,7 @@ mod tests {
             conversation_id: Some("conv-1".to_string()),
             content: "Hello".to_string(),
             content_length: 5,
+            user_properties: HashMap::new(),
             direction: MessageDirection::Outgoing,
         };
 
@@ -613,6 +620,7 @@ mod tests {
             conversation_id: None,
             content: "Hello".to_string(),
             content_length: 5,
+            user_properties: HashMap::new(),
             direction: MessageDirection::Outgoing,
         };
 
@@ -647,6 +655,7 @@ mod tests {
                 conversation_id: Some("conv-1".to_string()),
                 content: "Hello".to_string(),
                 content_length: 5,
+                user_properties: HashMap::new(),
                 direction: MessageDirection::Outgoing,
             });
         }
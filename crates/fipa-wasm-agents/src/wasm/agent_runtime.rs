@@ -8,7 +8,8 @@
 use anyhow::Result;
 use unl_agent::{Agent, Ctx};
 
-use super::host::OutboundIntent;
+use super::host::{CallUsage, GuestLog, OutboundIntent};
+use super::replay::Tape;
 
 /// What the actor drives. Implemented by the wasm runtime and by the native
 /// in-process runtime.
@@ -39,7 +40,10 @@ pub trait AgentRuntime {
     }
 
     /// Restore migrated state captured by [`AgentRuntime::snapshot`] (default: ignore).
-    fn restore(&mut self, _state: &[u8]) {}
+    /// An `Err` means the agent did not take the state up.
+    fn restore(&mut self, _state: &[u8]) -> Result<()> {
+        Ok(())
+    }
 
     /// Fire a scheduled timer tick into the agent (default: no-op).
     fn tick(&mut self, _timer_id: u64, _now_ms: u64) -> Result<()> {
@@ -68,6 +72,32 @@ pub trait AgentRuntime {
     fn take_spawn_reqs(&mut self) -> Vec<unl_agent::SpawnReq> {
         Vec::new()
     }
+
+    /// Drain the log lines the agent emitted this call (the core `log` capability;
+    /// default: none). The node emits and audits them under the agent's UUID.
+    fn take_logs(&mut self) -> Vec<GuestLog> {
+        Vec::new()
+    }
+
+    /// Drain what the agent's calls since the last drain cost — fuel burned and
+    /// memory high-water per entry point (default: unmetered). The node exports
+    /// them as metrics and charges the fuel against the agent's per-minute budget.
+    fn take_usage(&mut self) -> Vec<CallUsage> {
+        Vec::new()
+    }
+
+    /// Return to a freshly instantiated, not-yet-`init`ed state of the same code so
+    /// the node can reuse it for another stateless agent. `Ok(false)` (the default)
+    /// means this runtime can't be reset and must be dropped instead.
+    fn reset(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// The host-call tape a [`super::Recorder`] or [`super::replay`] arms
+    /// (default: none — the runtime can be neither recorded nor replayed).
+    fn tape(&mut self) -> Option<&mut Tape> {
+        None
+    }
 }
 
 impl AgentRuntime for super::WasmRuntime {
@@ -101,8 +131,49 @@ impl AgentRuntime for super::WasmRuntime {
         self.call_snapshot()
     }
 
-    fn restore(&mut self, state: &[u8]) {
-        self.call_restore(state);
+    fn restore(&mut self, state: &[u8]) -> Result<()> {
+        self.call_restore(state)
+    }
+
+    fn tick(&mut self, timer_id: u64, now_ms: u64) -> Result<()> {
+        self.call_tick(timer_id, now_ms)
+    }
+
+    fn take_timer_ops(&mut self) -> Vec<unl_agent::TimerOp> {
+        std::mem::take(&mut self.host_mut().timer_ops)
+    }
+
+    fn set_state(&mut self, kv: std::sync::Arc<dyn unl_agent::Kv>) {
+        self.set_kv(kv);
+    }
+
+    fn set_keyring(&mut self, kr: std::sync::Arc<dyn unl_agent::Keyring>) {
+        super::WasmRuntime::set_keyring(self, kr);
+    }
+
+    fn take_infer_reqs(&mut self) -> Vec<unl_agent::InferReq> {
+        std::mem::take(&mut self.host_mut().infers)
+    }
+
+    fn take_spawn_reqs(&mut self) -> Vec<unl_agent::SpawnReq> {
+        std::mem::take(&mut self.host_mut().spawns)
+    }
+
+    fn take_logs(&mut self) -> Vec<GuestLog> {
+        self.take_logs()
+    }
+
+    fn take_usage(&mut self) -> Vec<CallUsage> {
+        self.take_usage()
+    }
+
+    fn reset(&mut self) -> Result<bool> {
+        super::WasmRuntime::reset(self)?;
+        Ok(true)
+    }
+
+    fn tape(&mut self) -> Option<&mut Tape> {
+        Some(&mut self.host_mut().tape)
     }
 }
 
@@ -142,6 +213,7 @@ impl<A: Agent> NativeRuntime<A> {
         let kr = self.keyring.clone();
         let agent = &mut self.agent;
         let mut ctx = Ctx::new();
+        ctx.set_now(chrono::Utc::now().timestamp_millis().max(0) as u64);
         if let Some(s) = kv {
             ctx.set_state(s);
         }
@@ -195,8 +267,8 @@ impl<A: Agent> AgentRuntime for NativeRuntime<A> {
         self.agent.snapshot()
     }
 
-    fn restore(&mut self, state: &[u8]) {
-        self.agent.restore(state);
+    fn restore(&mut self, state: &[u8]) -> Result<()> {
+        self.guarded(|a, _ctx| a.restore(state))
     }
 
     fn tick(&mut self, timer_id: u64, now_ms: u64) -> Result<()> {
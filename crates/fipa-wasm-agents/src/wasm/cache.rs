@@ -0,0 +1,267 @@
+//! Ahead-of-time module cache — precompiled wasmtime artifacts on disk.
+//!
+//! Compiling a module dominates cold start, and `mount_wasm`, migration, and
+//! `ManagedAgent::restart` would otherwise each recompile the same bytes. The
+//! cache is content-addressed: an artifact is filed under the module's
+//! [`code_hash`] plus a fingerprint of the shared engine's compilation settings,
+//! so a wasmtime upgrade or a config change simply misses (and [`ModuleCache::open`]
+//! prunes the stale entries).
+//!
+//! Deserializing a precompiled artifact is `unsafe` in wasmtime — it is native
+//! code. The cache only loads what this node wrote itself: every artifact carries
+//! an HMAC-SHA256 trailer keyed by a secret derived from the node key, so a file
+//! planted by anyone without that key fails the check; the directory must be
+//! owner-only (0700, ours, not a symlink) or [`ModuleCache::open`] refuses it; and
+//! wasmtime's own header check rejects an artifact built for another engine.
+//! Anything that fails a check is deleted and recompiled, never trusted.
+
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use wasmtime::component::Component;
+use wasmtime::{Engine, Module, Precompiled};
+
+use crate::process::code_hash;
+
+const EXT: &str = "cwasm";
+
+static GLOBAL: OnceLock<ModuleCache> = OnceLock::new();
+
+/// An on-disk cache of precompiled core modules and components for the node's
+/// shared engine.
+pub struct ModuleCache {
+    dir: PathBuf,
+    fingerprint: String,
+    engine: Engine,
+    /// The HMAC key artifacts are sealed under.
+    key: [u8; 32],
+}
+
+impl ModuleCache {
+    /// Open (creating) the cache at `dir`, sealing artifacts under `key` (a node
+    /// secret, e.g. [`Node::cache_key`](crate::process::node::Node::cache_key)),
+    /// and drop artifacts built by any other engine configuration or wasmtime
+    /// version. Fails if `dir` is not a directory only this user can write.
+    pub fn open(dir: impl Into<PathBuf>, key: [u8; 32]) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
+            let meta = fs::symlink_metadata(&dir)?;
+            // SAFETY: `geteuid` has no preconditions and cannot fail.
+            if !meta.is_dir() || meta.uid() != unsafe { libc::geteuid() } {
+                anyhow::bail!("module cache {} is not a directory we own", dir.display());
+            }
+            fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+            if fs::metadata(&dir)?.mode() & 0o077 != 0 {
+                anyhow::bail!("module cache {} is not owner-only", dir.display());
+            }
+        }
+        let engine = super::runtime::new_engine()?;
+        let cache = ModuleCache { dir, fingerprint: fingerprint(&engine), engine, key };
+        cache.prune();
+        Ok(cache)
+    }
+
+    /// Make this the process-wide cache every `WasmRuntime`/`ComponentRuntime`
+    /// build consults. The first install wins; later calls are ignored.
+    pub fn install(self) {
+        let _ = GLOBAL.set(self);
+    }
+
+    /// The installed process-wide cache, if any.
+    pub(super) fn global() -> Option<&'static ModuleCache> {
+        GLOBAL.get()
+    }
+
+    /// The compiled core module for `code`, from the cache or freshly compiled
+    /// (and then cached).
+    pub fn module(&self, code: &[u8]) -> Result<Module> {
+        let path = self.path(code);
+        if let Some(artifact) = self.load(&path, Precompiled::Module) {
+            // SAFETY: `load` only returns bytes sealed under this node's key, which
+            // only this node's engine wrote; wasmtime re-checks engine compatibility.
+            match unsafe { Module::deserialize(&self.engine, &artifact) } {
+                Ok(module) => return Ok(module),
+                Err(_) => self.evict(&path),
+            }
+        }
+        let artifact = self.engine.precompile_module(code)?;
+        self.save(&path, &artifact);
+        // SAFETY: produced by `precompile_module` on this engine just above.
+        unsafe { Module::deserialize(&self.engine, &artifact) }
+    }
+
+    /// The compiled component for `code`, from the cache or freshly compiled.
+    pub fn component(&self, code: &[u8]) -> Result<Component> {
+        let path = self.path(code);
+        if let Some(artifact) = self.load(&path, Precompiled::Component) {
+            // SAFETY: as in `module` — our own artifact, MAC-verified.
+            match unsafe { Component::deserialize(&self.engine, &artifact) } {
+                Ok(component) => return Ok(component),
+                Err(_) => self.evict(&path),
+            }
+        }
+        let artifact = self.engine.precompile_component(code)?;
+        self.save(&path, &artifact);
+        // SAFETY: produced by `precompile_component` on this engine just above.
+        unsafe { Component::deserialize(&self.engine, &artifact) }
+    }
+
+    fn path(&self, code: &[u8]) -> PathBuf {
+        self.dir.join(format!("{}-{}.{EXT}", code_hash(code), self.fingerprint))
+    }
+
+    /// Read an artifact and verify its HMAC trailer and kind; a corrupt, planted
+    /// or foreign entry is evicted.
+    fn load(&self, path: &Path, kind: Precompiled) -> Option<Vec<u8>> {
+        let mut bytes = fs::read(path).ok()?;
+        let body = bytes.len().checked_sub(32)?;
+        let tag = hmac(&self.key, &bytes[..body]);
+        // Constant-time: a planted file learns nothing from how long we took.
+        let intact = tag.iter().zip(&bytes[body..]).fold(0, |d, (a, b)| d | (a ^ b)) == 0;
+        if !intact || Engine::detect_precompiled(&bytes[..body]) != Some(kind) {
+            self.evict(path);
+            return None;
+        }
+        bytes.truncate(body);
+        Some(bytes)
+    }
+
+    /// Write `artifact` + its HMAC atomically (temp file, then rename). A
+    /// failed write only costs a recompile next time.
+    fn save(&self, path: &Path, artifact: &[u8]) {
+        let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+        let mut bytes = Vec::with_capacity(artifact.len() + 32);
+        bytes.extend_from_slice(artifact);
+        bytes.extend_from_slice(&hmac(&self.key, artifact));
+        if fs::write(&tmp, &bytes).and_then(|()| fs::rename(&tmp, path)).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+
+    fn evict(&self, path: &Path) {
+        let _ = fs::remove_file(path);
+    }
+
+    /// Remove artifacts from other engine fingerprints and leftover temp files.
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let current = format!("-{}.{EXT}", self.fingerprint);
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.contains(".tmp-") || (name.ends_with(EXT) && !name.ends_with(&current)) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// HMAC-SHA256 (RFC 2104) of `data` under a 32-byte `key`.
+fn hmac(key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let (mut ipad, mut opad) = ([0x36u8; 64], [0x5cu8; 64]);
+    for (i, k) in key.iter().enumerate() {
+        ipad[i] ^= k;
+        opad[i] ^= k;
+    }
+    let inner = Sha256::new().chain_update(ipad).chain_update(data).finalize();
+    Sha256::new().chain_update(opad).chain_update(inner).finalize().into()
+}
+
+/// A short, stable digest of everything that makes an artifact engine-specific —
+/// wasmtime's version, target, and compilation settings.
+fn fingerprint(engine: &Engine) -> String {
+    struct Sha(Sha256);
+    impl Hasher for Sha {
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.update(bytes);
+        }
+        fn finish(&self) -> u64 {
+            0 // unused: the digest is read from the inner hasher
+        }
+    }
+    let mut h = Sha(Sha256::new());
+    engine.precompile_compatibility_hash().hash(&mut h);
+    hex::encode(&h.0.finalize()[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUEST: &str = r#"(module (memory (export "memory") 1) (func (export "init")))"#;
+    const KEY: [u8; 32] = [7; 32];
+
+    fn dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fipa-modcache-{tag}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn a_compiled_module_is_served_from_disk_next_time() {
+        let cache = ModuleCache::open(dir("hit"), KEY).unwrap();
+        let path = cache.path(GUEST.as_bytes());
+        assert!(cache.load(&path, Precompiled::Module).is_none()); // cold
+        cache.module(GUEST.as_bytes()).unwrap();
+        assert!(cache.load(&path, Precompiled::Module).is_some()); // warm
+        let module = cache.module(GUEST.as_bytes()).unwrap();
+        assert!(module.get_export("init").is_some());
+    }
+
+    #[test]
+    fn a_corrupt_artifact_is_evicted_and_recompiled() {
+        let cache = ModuleCache::open(dir("corrupt"), KEY).unwrap();
+        cache.module(GUEST.as_bytes()).unwrap();
+        let path = cache.path(GUEST.as_bytes());
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[0] ^= 0xff; // flip a bit in the artifact — the trailer no longer matches
+        fs::write(&path, &bytes).unwrap();
+        assert!(cache.load(&path, Precompiled::Module).is_none());
+        assert!(!path.exists()); // evicted, not trusted
+        cache.module(GUEST.as_bytes()).unwrap();
+        assert!(cache.load(&path, Precompiled::Module).is_some()); // rebuilt
+    }
+
+    #[test]
+    fn an_artifact_sealed_under_another_key_is_not_loaded() {
+        let dir = dir("planted");
+        let theirs = ModuleCache::open(&dir, [9; 32]).unwrap();
+        theirs.module(GUEST.as_bytes()).unwrap(); // a well-formed file, wrong key
+        let ours = ModuleCache::open(&dir, KEY).unwrap();
+        let path = ours.path(GUEST.as_bytes());
+        assert!(ours.load(&path, Precompiled::Module).is_none());
+        assert!(!path.exists()); // evicted, not trusted
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_cache_dir_behind_a_symlink_is_refused() {
+        use std::os::unix::fs::symlink;
+        let target = dir("target");
+        fs::create_dir_all(&target).unwrap();
+        let link = dir("link");
+        symlink(&target, &link).unwrap();
+        assert!(ModuleCache::open(&link, KEY).is_err());
+        fs::remove_file(&link).ok();
+    }
+
+    #[test]
+    fn artifacts_from_another_engine_are_pruned_on_open() {
+        let dir = dir("prune");
+        let cache = ModuleCache::open(&dir, KEY).unwrap();
+        cache.module(GUEST.as_bytes()).unwrap();
+        let stale = dir.join(format!("{}-0000000000000000.{EXT}", code_hash(GUEST.as_bytes())));
+        fs::write(&stale, b"built by an older wasmtime").unwrap();
+        let cache = ModuleCache::open(&dir, KEY).unwrap();
+        assert!(!stale.exists());
+        assert!(cache.path(GUEST.as_bytes()).exists()); // the current one survives
+    }
+}
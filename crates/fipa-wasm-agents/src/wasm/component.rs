@@ -0,0 +1,1040 @@
+//! The Component Model path — agents built against `fipa.wit` with `wit-bindgen`.
+//!
+//! A `.component.wasm` agent targets one of the `agent`, `behavior-agent` or
+//! `minimal-agent` worlds. The host side of every `fipa:agent/*` interface is
+//! generated from the same `fipa.wit` and bound here, so guests get typed records
+//! (`acl-message`, `service-description`, …) and variants (`messaging-error`,
+//! `storage-error`, …) instead of hand-packed `(ptr,len)` pairs. The core-module
+//! ABI in [`super::WasmRuntime`] is unchanged; [`is_component`] picks the path.
+//!
+//! Both paths present the same [`AgentRuntime`] to the node: an inbound message
+//! lands in the guest's `receive-message` queue and `run` drains it; sends,
+//! timers and state go through the same out-gate, timer ops and namespaced store.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use wasmtime::component::{Component, HasSelf, Instance, Linker};
+use wasmtime::{Engine, Store};
+
+use crate::adapters::{HostHooks, MAX_QUEUED_SENDS, MAX_SEND_BYTES};
+use crate::behavior;
+use crate::proto;
+
+use super::agent_runtime::AgentRuntime;
+use super::cache::ModuleCache;
+use super::host::{memory_cap, CallUsage, GuestLog, HostState, LogLevel, OutboundIntent, StorageError};
+use super::imports::MAX_RANDOM_BYTES;
+use super::runtime::{deadline_ticks, deadline_trap, new_engine, DEFAULT_DEADLINE_MS};
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        world: "agent",
+        path: "../../fipa.wit",
+    });
+}
+
+use bindings::fipa::agent::{
+    behaviors, lifecycle, logging, messaging, migration, random, services, storage, timing,
+};
+
+/// Open conversations a component may hold at once; past it the oldest goes.
+const MAX_CONVERSATIONS: usize = 256;
+
+/// How long a conversation stays open for `reply` without `end-conversation`.
+const CONVERSATION_TTL: Duration = Duration::from_secs(600);
+
+/// What a queued inbound message costs against the inbox cap on top of its
+/// content, so a flood of empty messages is bounded too.
+const INBOX_MSG_OVERHEAD: usize = 256;
+
+/// Whether `bytes` is a component binary (layer 1) rather than a core module.
+pub fn is_component(bytes: &[u8]) -> bool {
+    bytes.len() >= 8 && bytes[..4] == *b"\0asm" && bytes[6..8] == [0x01, 0x00]
+}
+
+/// Store data for a component agent: the shared [`HostState`] plus what the typed
+/// interfaces need on top of it.
+pub struct ComponentHost {
+    host: HostState,
+    sends: Arc<Mutex<Vec<OutboundIntent>>>,
+    inbox: VecDeque<messaging::AclMessage>,
+    /// Bytes queued in `inbox`, bounded by `inbox_cap` — the agent's memory
+    /// budget, more than it could take in anyway. Past it, inbound messages drop.
+    inbox_bytes: usize,
+    inbox_cap: usize,
+    /// Open conversations by cid, bounded by [`MAX_CONVERSATIONS`] and
+    /// [`CONVERSATION_TTL`].
+    conversations: HashMap<String, Conversation>,
+    next_msg: u64,
+    services: Vec<services::ServiceDescription>,
+    agent_state: lifecycle::AgentState,
+    /// Repeating timers: id → interval, re-armed as each tick fires.
+    repeating: HashMap<u64, u64>,
+    started: Instant,
+}
+
+/// An open conversation: the peers a `reply` goes to.
+struct Conversation {
+    peers: Vec<String>,
+    opened: Instant,
+    /// Opening order, for evicting the oldest.
+    seq: u64,
+}
+
+impl ComponentHost {
+    fn new(capabilities: proto::AgentCapabilities, sends: Arc<Mutex<Vec<OutboundIntent>>>) -> Self {
+        ComponentHost {
+            inbox_cap: memory_cap(&capabilities),
+            host: HostState::new(capabilities),
+            sends,
+            inbox: VecDeque::new(),
+            inbox_bytes: 0,
+            conversations: HashMap::new(),
+            next_msg: 0,
+            services: Vec::new(),
+            agent_state: lifecycle::AgentState::Starting,
+            repeating: HashMap::new(),
+            started: Instant::now(),
+        }
+    }
+
+    fn mint_id(&mut self) -> String {
+        self.next_msg += 1;
+        format!("m{}", self.next_msg)
+    }
+
+    /// Open `cid` with `peers`, first dropping expired conversations and, at the
+    /// cap, the oldest one — guests can't grow host memory by never ending them.
+    fn open(&mut self, cid: String, peers: Vec<String>) {
+        let now = Instant::now();
+        self.conversations.retain(|_, c| now.duration_since(c.opened) < CONVERSATION_TTL);
+        if self.conversations.len() >= MAX_CONVERSATIONS
+            && let Some(oldest) = self.conversations.iter().min_by_key(|(_, c)| c.seq).map(|(k, _)| k.clone())
+        {
+            self.conversations.remove(&oldest);
+        }
+        self.conversations.insert(cid, Conversation { peers, opened: now, seq: self.next_msg });
+    }
+
+    /// Randomness is the `crypto` capability, as core `crypto-random`: without
+    /// the keyring the guest gets no bytes. Through the tape, like every result
+    /// the guest can't compute itself.
+    fn random(&mut self, n: usize) -> Vec<u8> {
+        let denied = -(StorageError::PermissionDenied.code() as i64);
+        let live = self.host.keyring.clone().map(|kr| kr.random(n)).ok_or(denied);
+        self.host.tape.bytes(live).unwrap_or_default()
+    }
+
+    /// Queue one outbound message through the node out-gate, under the same
+    /// egress caps as `send-unl` (M3).
+    fn emit(&mut self, receiver: &str, performative: messaging::Performative, subject: &str, content: &[u8]) -> Result<(), messaging::MessagingError> {
+        let unl = format!("obj({}, {subject})", unl_verb(performative)).into_bytes();
+        if unl.len() + content.len() > MAX_SEND_BYTES {
+            return Err(messaging::MessagingError::InvalidMessage("message exceeds 1 MiB".into()));
+        }
+        let mut guard = self.sends.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.len() >= MAX_QUEUED_SENDS {
+            return Err(messaging::MessagingError::NetworkError("send queue full".into()));
+        }
+        self.host.messages_sent += 1;
+        guard.push(OutboundIntent { receiver: receiver.to_string(), unl, body: content.to_vec() });
+        Ok(())
+    }
+
+    /// An inbound `(from, unl, body)` as the `acl-message` `receive-message` yields.
+    /// Each starts a conversation keyed by its message id, so `reply` reaches `from`.
+    /// `false` (and nothing queued) once the inbox is at its cap.
+    fn enqueue(&mut self, from: &str, unl: &[u8], body: &[u8]) -> bool {
+        let cost = inbox_cost(from, body);
+        if self.inbox_bytes.saturating_add(cost) > self.inbox_cap {
+            return false;
+        }
+        self.inbox_bytes += cost;
+        let text = String::from_utf8_lossy(unl);
+        self.host.set_conversation(body);
+        let id = self.mint_id();
+        self.open(id.clone(), vec![from.to_string()]);
+        self.host.messages_received += 1;
+        self.inbox.push_back(messaging::AclMessage {
+            message_id: id.clone(),
+            performative: performative_of(&text),
+            sender: messaging::AgentId { name: from.to_string(), addresses: Vec::new() },
+            receivers: vec![self.agent_id()],
+            protocol: None,
+            conversation_id: Some(id),
+            in_reply_to: None,
+            reply_by: None,
+            language: Some("unl".into()),
+            ontology: None,
+            content: body.to_vec(),
+        });
+        true
+    }
+
+    fn dequeue(&mut self) -> Option<messaging::AclMessage> {
+        let msg = self.inbox.pop_front()?;
+        self.inbox_bytes = self.inbox_bytes.saturating_sub(inbox_cost(&msg.sender.name, &msg.content));
+        Some(msg)
+    }
+
+    fn agent_id(&self) -> messaging::AgentId {
+        messaging::AgentId {
+            name: self.host.agent_id.name.clone(),
+            addresses: self.host.agent_id.addresses.clone(),
+        }
+    }
+}
+
+/// What one inbound message holds against the inbox cap.
+fn inbox_cost(from: &str, body: &[u8]) -> usize {
+    INBOX_MSG_OVERHEAD + from.len() + body.len()
+}
+
+/// The UNL verb that mirrors a performative (`INTERACTION_PROTOCOLS.md` §4).
+fn unl_verb(p: messaging::Performative) -> &'static str {
+    use messaging::Performative::*;
+    match p {
+        AcceptProposal => "accept",
+        RejectProposal => "reject",
+        QueryIf | QueryRef => "query",
+        NotUnderstood => "nu",
+        Inform | InformDone | InformIf | InformRef | InformResult => "inform",
+        Agree => "agree",
+        Cancel => "cancel",
+        Cfp => "cfp",
+        Confirm => "confirm",
+        Disconfirm => "disconfirm",
+        Failure => "failure",
+        Propagate => "propagate",
+        Propose => "propose",
+        Proxy => "proxy",
+        Refuse => "refuse",
+        Request => "request",
+        RequestWhen => "request-when",
+        RequestWhenever => "request-whenever",
+        Subscribe => "subscribe",
+    }
+}
+
+/// The performative an inbound UNL carries: the first argument of `f(verb, …)`,
+/// read back through [`unl_verb`]; anything else is an `inform`.
+fn performative_of(unl: &str) -> messaging::Performative {
+    use messaging::Performative::*;
+    let verb = unl
+        .split_once('(')
+        .map(|(_, rest)| rest.split([',', ')']).next().unwrap_or("").trim())
+        .unwrap_or("");
+    match verb {
+        "accept" => AcceptProposal,
+        "reject" => RejectProposal,
+        "query" => QueryRef,
+        "nu" => NotUnderstood,
+        "agree" => Agree,
+        "cancel" => Cancel,
+        "cfp" => Cfp,
+        "confirm" => Confirm,
+        "disconfirm" => Disconfirm,
+        "failure" => Failure,
+        "propagate" => Propagate,
+        "propose" => Propose,
+        "proxy" => Proxy,
+        "refuse" => Refuse,
+        "request" => Request,
+        "request-when" => RequestWhen,
+        "request-whenever" => RequestWhenever,
+        "subscribe" => Subscribe,
+        _ => Inform,
+    }
+}
+
+impl messaging::Host for ComponentHost {
+    fn send_message(&mut self, message: messaging::AclMessage) -> Result<String, messaging::MessagingError> {
+        if message.receivers.is_empty() {
+            return Err(messaging::MessagingError::InvalidMessage("no receivers".into()));
+        }
+        let subject = message.ontology.clone().unwrap_or_else(|| "msg".into());
+        for r in &message.receivers {
+            self.emit(&r.name, message.performative, &subject, &message.content)?;
+        }
+        Ok(if message.message_id.is_empty() { self.mint_id() } else { message.message_id })
+    }
+
+    fn receive_message(&mut self) -> Option<messaging::AclMessage> {
+        self.dequeue()
+    }
+
+    fn has_messages(&mut self) -> bool {
+        !self.inbox.is_empty()
+    }
+
+    fn start_conversation(&mut self, _protocol: messaging::ProtocolType, participants: Vec<messaging::AgentId>) -> Result<String, messaging::MessagingError> {
+        let cid = self.mint_id();
+        self.open(cid.clone(), participants.into_iter().map(|p| p.name).collect());
+        Ok(cid)
+    }
+
+    fn reply(&mut self, conversation_id: String, performative: messaging::Performative, content: Vec<u8>) -> Result<String, messaging::MessagingError> {
+        let peers = self
+            .conversations
+            .get(&conversation_id)
+            .map(|c| c.peers.clone())
+            .ok_or_else(|| messaging::MessagingError::ConversationNotFound(conversation_id.clone()))?;
+        for peer in &peers {
+            self.emit(peer, performative, "msg", &content)?;
+        }
+        Ok(self.mint_id())
+    }
+
+    fn end_conversation(&mut self, conversation_id: String) -> Result<(), messaging::MessagingError> {
+        self.conversations
+            .remove(&conversation_id)
+            .map(|_| ())
+            .ok_or(messaging::MessagingError::ConversationNotFound(conversation_id))
+    }
+
+    // Discovery is a DF conversation (`obj(seek, …)`), not a host call
+    // (`AGENT_HOST_ABI.md` §13), so these point the agent there.
+    fn find_agents_by_service(&mut self, _service_name: String) -> Result<Vec<messaging::AgentId>, messaging::MessagingError> {
+        Err(messaging::MessagingError::NetworkError("discovery goes through the DF".into()))
+    }
+
+    fn find_agents_by_protocol(&mut self, _protocol: messaging::ProtocolType) -> Result<Vec<messaging::AgentId>, messaging::MessagingError> {
+        Err(messaging::MessagingError::NetworkError("discovery goes through the DF".into()))
+    }
+}
+
+impl lifecycle::Host for ComponentHost {
+    fn get_agent_id(&mut self) -> messaging::AgentId {
+        self.agent_id()
+    }
+
+    fn get_state(&mut self) -> lifecycle::AgentState {
+        self.agent_state
+    }
+
+    fn request_shutdown(&mut self) {
+        self.host.shutdown_requested = true;
+        self.agent_state = lifecycle::AgentState::Stopping;
+    }
+
+    fn is_shutdown_requested(&mut self) -> bool {
+        self.host.shutdown_requested
+    }
+
+    fn pause(&mut self) {
+        self.agent_state = lifecycle::AgentState::Paused;
+    }
+
+    fn resume(&mut self) {
+        if self.agent_state == lifecycle::AgentState::Paused {
+            self.agent_state = lifecycle::AgentState::Running;
+        }
+    }
+}
+
+impl services::Host for ComponentHost {
+    fn register_service(&mut self, service: services::ServiceDescription) -> Result<(), messaging::MessagingError> {
+        self.services.retain(|s| s.name != service.name);
+        self.services.push(service);
+        Ok(())
+    }
+
+    fn deregister_service(&mut self, service_name: String) -> Result<(), messaging::MessagingError> {
+        let before = self.services.len();
+        self.services.retain(|s| s.name != service_name);
+        if self.services.len() < before {
+            Ok(())
+        } else {
+            Err(messaging::MessagingError::AgentNotFound(service_name))
+        }
+    }
+
+    fn list_my_services(&mut self) -> Vec<services::ServiceDescription> {
+        self.services.clone()
+    }
+
+    fn update_service(&mut self, service: services::ServiceDescription) -> Result<(), messaging::MessagingError> {
+        self.register_service(service)
+    }
+}
+
+impl migration::Host for ComponentHost {
+    fn get_current_node(&mut self) -> String {
+        self.host.node_id.clone()
+    }
+
+    fn list_nodes(&mut self) -> Vec<migration::NodeInfo> {
+        Vec::new()
+    }
+
+    fn get_node_info(&mut self, _node_id: String) -> Option<migration::NodeInfo> {
+        None
+    }
+
+    // Migration is platform-initiated today (`MOBILITY.md` §11); the agent-side
+    // trigger is not wired, so the request is refused rather than silently dropped.
+    fn migrate_to(&mut self, _node_id: String, _reason: migration::MigrationReason) -> Result<(), messaging::MessagingError> {
+        Err(messaging::MessagingError::Unsupported("agent-initiated migration".into()))
+    }
+
+    fn clone_to(&mut self, _node_id: String) -> Result<messaging::AgentId, messaging::MessagingError> {
+        Err(messaging::MessagingError::Unsupported("agent-initiated cloning".into()))
+    }
+
+    fn is_migrating(&mut self) -> bool {
+        self.host.is_migrating
+    }
+
+    fn get_migration_history(&mut self) -> Vec<String> {
+        self.host.migration_history.clone()
+    }
+}
+
+impl From<StorageError> for storage::StorageError {
+    fn from(e: StorageError) -> Self {
+        match e {
+            StorageError::NotFound(k) => storage::StorageError::NotFound(k),
+            StorageError::QuotaExceeded => storage::StorageError::QuotaExceeded,
+            StorageError::IoError(m) => storage::StorageError::IoError(m),
+            StorageError::Serialization(m) => storage::StorageError::SerializationError(m),
+            StorageError::PermissionDenied => storage::StorageError::PermissionDenied,
+        }
+    }
+}
+
+impl storage::Host for ComponentHost {
+    fn store(&mut self, key: String, value: Vec<u8>) -> Result<(), storage::StorageError> {
+        Ok(self.host.state_store(&key, &value)?)
+    }
+
+    fn load(&mut self, key: String) -> Result<Vec<u8>, storage::StorageError> {
+        Ok(self.host.state_load(&key)?)
+    }
+
+    fn delete(&mut self, key: String) -> Result<(), storage::StorageError> {
+        Ok(self.host.state_delete(&key)?)
+    }
+
+    fn exists(&mut self, key: String) -> bool {
+        self.host.state_exists(&key)
+    }
+
+    fn list_keys(&mut self) -> Vec<String> {
+        self.host.state_keys("")
+    }
+
+    fn list_keys_with_prefix(&mut self, prefix: String) -> Vec<String> {
+        self.host.state_keys(&prefix)
+    }
+
+    fn get_usage(&mut self) -> u64 {
+        self.host.state_usage()
+    }
+
+    fn get_quota(&mut self) -> u64 {
+        self.host.state_quota()
+    }
+}
+
+impl From<logging::LogLevel> for LogLevel {
+    fn from(l: logging::LogLevel) -> Self {
+        match l {
+            logging::LogLevel::Trace => LogLevel::Trace,
+            logging::LogLevel::Debug => LogLevel::Debug,
+            logging::LogLevel::Info => LogLevel::Info,
+            logging::LogLevel::Warn => LogLevel::Warn,
+            logging::LogLevel::Error => LogLevel::Error,
+        }
+    }
+}
+
+impl logging::Host for ComponentHost {
+    fn log(&mut self, level: logging::LogLevel, message: String) {
+        self.host.guest_log(level.into(), message, Vec::new());
+    }
+
+    fn log_structured(&mut self, level: logging::LogLevel, message: String, fields: Vec<(String, String)>) {
+        self.host.guest_log(level.into(), message, fields);
+    }
+
+    fn is_enabled(&mut self, level: logging::LogLevel) -> bool {
+        self.host.log_enabled(level.into())
+    }
+}
+
+impl timing::Host for ComponentHost {
+    fn now(&mut self) -> u64 {
+        chrono::Utc::now().timestamp_millis() as u64
+    }
+
+    fn monotonic_now(&mut self) -> u64 {
+        self.started.elapsed().as_nanos() as u64
+    }
+
+    // Timers ride the node's scheduler (M3): each `schedule` becomes a
+    // `TimerOp::Set` the node arms under the agent's slot budget, and fires back
+    // as [`AgentRuntime::tick`].
+    fn schedule(&mut self, delay_ms: u64) -> u64 {
+        let id = self.host.schedule_timer(delay_ms);
+        self.host.timer_ops.push(unl_agent::TimerOp::Set { id, delay_ms });
+        id
+    }
+
+    fn schedule_repeating(&mut self, interval_ms: u64) -> u64 {
+        let id = self.schedule(interval_ms);
+        self.repeating.insert(id, interval_ms);
+        id
+    }
+
+    fn cancel_timer(&mut self, timer_id: u64) -> bool {
+        self.repeating.remove(&timer_id);
+        self.host.timer_ops.push(unl_agent::TimerOp::Cancel { id: timer_id });
+        self.host.cancel_timer(timer_id)
+    }
+
+    fn timer_fired(&mut self, timer_id: u64) -> bool {
+        self.host.fired_timers.contains(&timer_id)
+    }
+
+    fn get_fired_timers(&mut self) -> Vec<u64> {
+        self.host.take_fired_timers()
+    }
+}
+
+impl random::Host for ComponentHost {
+    fn get_random_bytes(&mut self, len: u32) -> Vec<u8> {
+        self.random(len.min(MAX_RANDOM_BYTES) as usize)
+    }
+
+    // Denied, the scalars are 0.
+    fn get_random_u64(&mut self) -> u64 {
+        self.random(8).try_into().map_or(0, u64::from_le_bytes)
+    }
+
+    fn get_random_f64(&mut self) -> f64 {
+        (random::Host::get_random_u64(self) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl From<behaviors::BehaviorType> for behavior::BehaviorType {
+    fn from(t: behaviors::BehaviorType) -> Self {
+        match t {
+            behaviors::BehaviorType::OneShot => behavior::BehaviorType::OneShot,
+            behaviors::BehaviorType::Cyclic => behavior::BehaviorType::Cyclic,
+            behaviors::BehaviorType::Ticker => behavior::BehaviorType::Ticker,
+            behaviors::BehaviorType::Waker => behavior::BehaviorType::Waker,
+            behaviors::BehaviorType::Sequential => behavior::BehaviorType::Sequential,
+            behaviors::BehaviorType::Parallel => behavior::BehaviorType::Parallel,
+            behaviors::BehaviorType::Fsm => behavior::BehaviorType::FSM,
+        }
+    }
+}
+
+impl From<behavior::BehaviorStatus> for behaviors::BehaviorStatus {
+    fn from(s: behavior::BehaviorStatus) -> Self {
+        match s {
+            behavior::BehaviorStatus::Ready => behaviors::BehaviorStatus::Ready,
+            behavior::BehaviorStatus::Running => behaviors::BehaviorStatus::Running,
+            behavior::BehaviorStatus::Blocked => behaviors::BehaviorStatus::Blocked,
+            behavior::BehaviorStatus::Done => behaviors::BehaviorStatus::Done,
+        }
+    }
+}
+
+impl From<behavior::BehaviorError> for behaviors::BehaviorError {
+    fn from(e: behavior::BehaviorError) -> Self {
+        match e {
+            behavior::BehaviorError::NotFound(id) => behaviors::BehaviorError::NotFound(id),
+            behavior::BehaviorError::InvalidConfig(m) => behaviors::BehaviorError::InvalidConfig(m),
+            behavior::BehaviorError::AlreadyRunning => behaviors::BehaviorError::AlreadyRunning,
+            behavior::BehaviorError::SubBehaviorError(m) => behaviors::BehaviorError::SubBehaviorError(m),
+            behavior::BehaviorError::FSMError(m) => behaviors::BehaviorError::InvalidConfig(m),
+        }
+    }
+}
+
+impl behaviors::Host for ComponentHost {
+    fn add_behavior(&mut self, name: String, config: behaviors::BehaviorConfig) -> Result<u64, behaviors::BehaviorError> {
+        let parallel_completion = config.parallel_completion.map(|c| match c {
+            behaviors::ParallelCompletion::WhenAll => behavior::ParallelCompletion::WhenAll,
+            behaviors::ParallelCompletion::WhenAny => behavior::ParallelCompletion::WhenAny,
+            behaviors::ParallelCompletion::WhenN => behavior::ParallelCompletion::WhenN(config.parallel_n.unwrap_or(1)),
+        });
+        let config = behavior::BehaviorConfig {
+            behavior_type: Some(config.behavior_type.into()),
+            tick_interval_ms: config.tick_interval_ms,
+            wake_after_ms: config.wake_after_ms,
+            sub_behaviors: config.sub_behaviors,
+            parallel_completion,
+            fsm_initial_state: config.fsm_initial_state,
+            fsm_transitions: config
+                .fsm_transitions
+                .into_iter()
+                .map(|t| behavior::FSMTransition { from_state: t.from_state, to_state: t.to_state, event: t.event })
+                .collect(),
+        };
+        Ok(self.host.behavior_scheduler.add_behavior(name, config)?)
+    }
+
+    fn remove_behavior(&mut self, behavior_id: u64) -> Result<(), behaviors::BehaviorError> {
+        Ok(self.host.behavior_scheduler.remove_behavior(behavior_id)?)
+    }
+
+    fn block_behavior(&mut self, behavior_id: u64) -> Result<(), behaviors::BehaviorError> {
+        Ok(self.host.behavior_scheduler.block_behavior(behavior_id)?)
+    }
+
+    fn restart_behavior(&mut self, behavior_id: u64) -> Result<(), behaviors::BehaviorError> {
+        Ok(self.host.behavior_scheduler.restart_behavior(behavior_id)?)
+    }
+
+    fn get_behavior_status(&mut self, behavior_id: u64) -> Result<behaviors::BehaviorStatus, behaviors::BehaviorError> {
+        Ok(self.host.behavior_scheduler.get_status(behavior_id)?.into())
+    }
+
+    fn behavior_done(&mut self, behavior_id: u64) {
+        self.host.behavior_scheduler.behavior_done(behavior_id);
+    }
+
+    fn fsm_event(&mut self, behavior_id: u64, event: String) -> Result<String, behaviors::BehaviorError> {
+        Ok(self.host.behavior_scheduler.fsm_event(behavior_id, &event)?)
+    }
+
+    fn fsm_current_state(&mut self, behavior_id: u64) -> Result<String, behaviors::BehaviorError> {
+        Ok(self.host.behavior_scheduler.fsm_current_state(behavior_id)?)
+    }
+
+    fn reset_behavior(&mut self, behavior_id: u64) -> Result<(), behaviors::BehaviorError> {
+        Ok(self.host.behavior_scheduler.reset_behavior(behavior_id)?)
+    }
+
+    fn list_behaviors(&mut self) -> Vec<(u64, String, behaviors::BehaviorStatus)> {
+        self.host
+            .behavior_scheduler
+            .list_behaviors()
+            .into_iter()
+            .map(|(id, name, status)| (id, name, status.into()))
+            .collect()
+    }
+}
+
+/// A component agent instance: the store, the instantiated component, and the
+/// fuel budget each entry point gets.
+pub struct ComponentRuntime {
+    store: Store<ComponentHost>,
+    instance: Instance,
+    component: Component,
+    component_bytes: Vec<u8>,
+    capabilities: proto::AgentCapabilities,
+    hooks: HostHooks,
+    deadline_ms: u64,
+    /// Fuel the store was last armed with; 0 once settled into `spent`.
+    armed: u64,
+    /// Fuel burned by the export calls of the current entry point so far.
+    spent: u64,
+}
+
+impl ComponentRuntime {
+    /// Compile and instantiate a component agent under `capabilities`.
+    pub fn new(bytes: &[u8], capabilities: &proto::AgentCapabilities) -> Result<Self> {
+        Self::build(bytes, capabilities.clone(), HostHooks::default())
+    }
+
+    fn build(bytes: &[u8], capabilities: proto::AgentCapabilities, hooks: HostHooks) -> Result<Self> {
+        let engine: Engine = new_engine()?;
+        let component = match ModuleCache::global() {
+            Some(cache) => cache.component(bytes)?,
+            None => Component::new(&engine, bytes)?,
+        };
+        Self::instantiate(&engine, component, bytes.to_vec(), capabilities, hooks)
+    }
+
+    fn instantiate(
+        engine: &Engine,
+        component: Component,
+        component_bytes: Vec<u8>,
+        capabilities: proto::AgentCapabilities,
+        hooks: HostHooks,
+    ) -> Result<Self> {
+        let mut store = Store::new(engine, ComponentHost::new(capabilities.clone(), hooks.sends.clone()));
+        store.limiter(|state| &mut state.host.limits);
+        store.set_epoch_deadline(deadline_ticks(DEFAULT_DEADLINE_MS));
+        let mut linker = Linker::new(engine);
+        // `agent` imports every interface; the smaller worlds import a subset, so
+        // one linker serves all three.
+        bindings::Agent::add_to_linker::<_, HasSelf<ComponentHost>>(&mut linker, |s| s)?;
+        store.set_fuel(call_fuel(&capabilities))?;
+        let instance = linker.instantiate(&mut store, &component)?;
+        Ok(Self {
+            store,
+            instance,
+            component,
+            component_bytes,
+            capabilities,
+            hooks,
+            deadline_ms: DEFAULT_DEADLINE_MS,
+            armed: 0,
+            spent: 0,
+        })
+    }
+
+    /// Re-instantiate the compiled component in a fresh store, as
+    /// [`super::WasmRuntime::reset`] does for core modules. `init` is left to the caller.
+    pub fn reset(&mut self) -> Result<()> {
+        self.hooks.sends.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        let engine = self.store.engine().clone();
+        let fresh = Self::instantiate(
+            &engine,
+            self.component.clone(),
+            std::mem::take(&mut self.component_bytes),
+            self.capabilities.clone(),
+            self.hooks.clone(),
+        )?;
+        let deadline_ms = self.deadline_ms;
+        *self = fresh;
+        self.deadline_ms = deadline_ms;
+        Ok(())
+    }
+
+    /// The host-side state (agent id, node id, counters) shared with the core path.
+    pub fn host_mut(&mut self) -> &mut HostState {
+        &mut self.store.data_mut().host
+    }
+
+    /// Set the per-call wall-clock deadline (the agent's `Budget::wall_ms`, L3).
+    pub fn set_deadline(&mut self, ms: u64) {
+        self.deadline_ms = ms;
+    }
+
+    /// Get component bytes
+    pub fn get_component_bytes(&self) -> &[u8] {
+        &self.component_bytes
+    }
+
+    /// Arm the per-call budgets: fresh fuel and a fresh wall-clock deadline.
+    fn refuel(&mut self) {
+        self.settle();
+        self.armed = call_fuel(&self.capabilities);
+        let _ = self.store.set_fuel(self.armed);
+        self.store.set_epoch_deadline(deadline_ticks(self.deadline_ms));
+    }
+
+    /// Fold what the last armed export call burned into `spent`.
+    fn settle(&mut self) {
+        if self.armed > 0 {
+            self.spent += self.armed.saturating_sub(self.store.get_fuel().unwrap_or(0));
+            self.armed = 0;
+        }
+    }
+
+    /// Run one entry point and note its cost. An entry point may make several
+    /// export calls (run, then each runnable behaviour), each freshly fuelled, so
+    /// their burn is summed.
+    fn metered<T>(&mut self, entry: &'static str, call: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let result = call(self);
+        self.settle();
+        let fuel = std::mem::take(&mut self.spent);
+        let host = &mut self.store.data_mut().host;
+        let mem = host.limits.peak();
+        host.record_usage(entry, fuel, mem);
+        result
+    }
+
+    /// `run` followed by a scheduler pass, unmetered; the entry points meter it.
+    fn step(&mut self) -> Result<bool> {
+        if self.store.data().agent_state == lifecycle::AgentState::Paused {
+            return Ok(true);
+        }
+        let more = self.call_run()?.unwrap_or(true);
+        self.step_behaviors()?;
+        Ok(more && !self.store.data().host.shutdown_requested)
+    }
+
+    /// Call a `func()` export; `Ok(false)` if the world doesn't export it.
+    fn call_unit(&mut self, name: &str) -> Result<bool> {
+        let Ok(f) = self.instance.get_typed_func::<(), ()>(&mut self.store, name) else {
+            return Ok(false);
+        };
+        self.refuel();
+        f.call(&mut self.store, ()).map_err(|e| deadline_trap(e, self.deadline_ms))?;
+        f.post_return(&mut self.store)?;
+        Ok(true)
+    }
+
+    /// Call `run: func() -> bool`; `None` if the world doesn't export it.
+    fn call_run(&mut self) -> Result<Option<bool>> {
+        let Ok(f) = self.instance.get_typed_func::<(), (bool,)>(&mut self.store, "run") else {
+            return Ok(None);
+        };
+        self.refuel();
+        let (more,) = f.call(&mut self.store, ()).map_err(|e| deadline_trap(e, self.deadline_ms))?;
+        f.post_return(&mut self.store)?;
+        Ok(Some(more))
+    }
+
+    /// One scheduler pass over the agent's JADE-style behaviours (`agent` and
+    /// `behavior-agent` worlds): `on-behavior-start` once, `execute-behavior`,
+    /// then `on-behavior-end` when it reports done.
+    fn step_behaviors(&mut self) -> Result<()> {
+        let Ok(exec) = self
+            .instance
+            .get_typed_func::<(u64, &str), (bool,)>(&mut self.store, "execute-behavior")
+        else {
+            return Ok(());
+        };
+        let hook = |rt: &mut Self, name: &str, id: u64, bname: &str| -> Result<()> {
+            if let Ok(f) = rt.instance.get_typed_func::<(u64, &str), ()>(&mut rt.store, name) {
+                rt.refuel();
+                f.call(&mut rt.store, (id, bname)).map_err(|e| deadline_trap(e, rt.deadline_ms))?;
+                f.post_return(&mut rt.store)?;
+            }
+            Ok(())
+        };
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        let runnable: Vec<(u64, String)> = self
+            .store
+            .data()
+            .host
+            .behavior_scheduler
+            .get_runnable(now)
+            .into_iter()
+            .map(|b| (b.id, b.name.clone()))
+            .collect();
+        for (id, name) in runnable {
+            let sched = &mut self.store.data_mut().host.behavior_scheduler;
+            if sched.needs_start(id) {
+                sched.mark_started(id);
+                hook(self, "on-behavior-start", id, &name)?;
+            }
+            self.store.data_mut().host.behavior_scheduler.mark_running(id);
+            self.refuel();
+            let (done,) = exec
+                .call(&mut self.store, (id, &name))
+                .map_err(|e| deadline_trap(e, self.deadline_ms))?;
+            exec.post_return(&mut self.store)?;
+            self.store.data_mut().host.behavior_scheduler.handle_completion(id, now, done);
+            if done {
+                hook(self, "on-behavior-end", id, &name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-call CPU budget, the same derivation as the core-module path (H3/R7).
+fn call_fuel(capabilities: &proto::AgentCapabilities) -> u64 {
+    capabilities.max_execution_time_ms.max(1).saturating_mul(1_000_000)
+}
+
+impl AgentRuntime for ComponentRuntime {
+    fn init(&mut self) -> Result<()> {
+        if !self.metered("init", |rt| rt.call_unit("init"))? {
+            return Err(anyhow!("component exports no `init`"));
+        }
+        self.store.data_mut().agent_state = lifecycle::AgentState::Running;
+        Ok(())
+    }
+
+    fn config(&mut self, from: &str, unl: &[u8], body: &[u8]) -> Result<()> {
+        // The WIT worlds have no seed entry point; a component keeps its
+        // configuration in `storage`.
+        if unl_agent::is_seed(unl) {
+            return Ok(());
+        }
+        if !self.store.data_mut().enqueue(from, unl, body) {
+            crate::flow!("wasm: ⛔ component inbox full — dropping a message from '{}'", from);
+            return Ok(());
+        }
+        self.metered("deliver", Self::step).map(|_| ())
+    }
+
+    fn take_sends(&mut self) -> Vec<OutboundIntent> {
+        std::mem::take(&mut *self.hooks.sends.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn run(&mut self) -> Result<bool> {
+        self.metered("run", Self::step)
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        self.store.data_mut().agent_state = lifecycle::AgentState::Stopping;
+        self.metered("shutdown", |rt| rt.call_unit("shutdown"))?;
+        self.store.data_mut().agent_state = lifecycle::AgentState::Stopped;
+        Ok(())
+    }
+
+    fn tick(&mut self, timer_id: u64, _now_ms: u64) -> Result<()> {
+        let host = self.store.data_mut();
+        host.host.conversation = None; // a tick answers no message
+        host.host.timers.remove(&timer_id);
+        host.host.fired_timers.push(timer_id);
+        if let Some(&delay_ms) = host.repeating.get(&timer_id) {
+            host.host.timer_ops.push(unl_agent::TimerOp::Set { id: timer_id, delay_ms });
+        }
+        self.metered("tick", Self::step).map(|_| ())
+    }
+
+    fn take_timer_ops(&mut self) -> Vec<unl_agent::TimerOp> {
+        std::mem::take(&mut self.store.data_mut().host.timer_ops)
+    }
+
+    fn set_state(&mut self, kv: Arc<dyn unl_agent::Kv>) {
+        self.store.data_mut().host.state = Some(kv);
+    }
+
+    fn set_keyring(&mut self, kr: Arc<dyn unl_agent::Keyring>) {
+        self.store.data_mut().host.keyring = Some(kr);
+    }
+
+    fn take_logs(&mut self) -> Vec<GuestLog> {
+        self.store.data_mut().host.take_logs()
+    }
+
+    fn take_usage(&mut self) -> Vec<CallUsage> {
+        std::mem::take(&mut self.store.data_mut().host.usage)
+    }
+
+    fn reset(&mut self) -> Result<bool> {
+        ComponentRuntime::reset(self)?;
+        Ok(true)
+    }
+}
+
+impl std::fmt::Debug for ComponentRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComponentRuntime")
+            .field("component_size", &self.component_bytes.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A `minimal-agent`-shaped component: `init` schedules a 250 ms timer, `run`
+    // reports whether a message is waiting (it never drains the queue).
+    const TIMER_COMPONENT: &str = r#"
+    (component
+      (import "fipa:agent/timing@0.2.0" (instance $timing
+        (export "schedule" (func (param "delay-ms" u64) (result u64)))))
+      (import "fipa:agent/messaging@0.2.0" (instance $messaging
+        (export "has-messages" (func (result bool)))))
+      (core func $schedule (canon lower (func $timing "schedule")))
+      (core func $has (canon lower (func $messaging "has-messages")))
+      (core module $m
+        (import "host" "schedule" (func $schedule (param i64) (result i64)))
+        (import "host" "has-messages" (func $has (result i32)))
+        (func (export "init") (drop (call $schedule (i64.const 250))))
+        (func (export "run") (result i32) (call $has))
+        (func (export "shutdown")))
+      (core instance $i (instantiate $m
+        (with "host" (instance
+          (export "schedule" (func $schedule))
+          (export "has-messages" (func $has))))))
+      (func (export "init") (canon lift (core func $i "init")))
+      (func (export "run") (result bool) (canon lift (core func $i "run")))
+      (func (export "shutdown") (canon lift (core func $i "shutdown"))))
+    "#;
+
+    fn caps() -> proto::AgentCapabilities {
+        proto::AgentCapabilities { max_execution_time_ms: 1000, ..Default::default() }
+    }
+
+    #[test]
+    fn component_and_core_binaries_are_told_apart() {
+        let component = wat::parse_str(TIMER_COMPONENT).unwrap();
+        let core = wat::parse_str("(module)").unwrap();
+        assert!(is_component(&component));
+        assert!(!is_component(&core));
+    }
+
+    #[test]
+    fn component_agent_binds_the_wit_interfaces() {
+        let mut rt = ComponentRuntime::new(TIMER_COMPONENT.as_bytes(), &caps()).unwrap();
+        rt.init().unwrap();
+        // `timing.schedule` rides the node's timer ops
+        assert_eq!(rt.take_timer_ops(), vec![unl_agent::TimerOp::Set { id: 1, delay_ms: 250 }]);
+
+        // nothing queued yet, then a delivered message is visible to the guest
+        assert!(!rt.run().unwrap());
+        rt.config("alice", b"obj(request, LtG)", b"hi").unwrap();
+        assert!(rt.run().unwrap());
+        let msg = rt.store.data_mut().inbox.pop_front().unwrap();
+        assert_eq!(msg.sender.name, "alice");
+        assert_eq!(msg.performative, messaging::Performative::Request);
+        assert_eq!(msg.content, b"hi");
+    }
+
+    #[test]
+    fn send_message_goes_through_the_out_gate() {
+        let mut host = ComponentHost::new(caps(), Default::default());
+        let msg = messaging::AclMessage {
+            message_id: String::new(),
+            performative: messaging::Performative::AcceptProposal,
+            sender: messaging::AgentId { name: "me".into(), addresses: vec![] },
+            receivers: vec![messaging::AgentId { name: "s1".into(), addresses: vec![] }],
+            protocol: Some(messaging::ProtocolType::ContractNet),
+            conversation_id: None,
+            in_reply_to: None,
+            reply_by: None,
+            language: None,
+            ontology: Some("LtG".into()),
+            content: b"{}".to_vec(),
+        };
+        messaging::Host::send_message(&mut host, msg).unwrap();
+        let sends = host.sends.lock().unwrap();
+        assert_eq!(sends[0].receiver, "s1");
+        assert_eq!(sends[0].unl, b"obj(accept, LtG)");
+    }
+
+    #[test]
+    fn randomness_needs_the_crypto_keyring() {
+        let mut host = ComponentHost::new(caps(), Default::default());
+        assert!(random::Host::get_random_bytes(&mut host, 16).is_empty());
+        assert_eq!(random::Host::get_random_u64(&mut host), 0);
+
+        host.host.keyring = Some(Arc::new(crate::wasm::imports::testing::MirrorKeyring));
+        assert_eq!(random::Host::get_random_bytes(&mut host, 16), vec![0x5a; 16]);
+        assert_eq!(random::Host::get_random_u64(&mut host), u64::from_le_bytes([0x5a; 8]));
+    }
+
+    #[test]
+    fn inbound_messages_do_not_grow_the_conversation_table_without_bound() {
+        let mut host = ComponentHost::new(caps(), Default::default());
+        for _ in 0..MAX_CONVERSATIONS + 10 {
+            host.enqueue("alice", b"obj(request, x)", b"");
+        }
+        assert_eq!(host.conversations.len(), MAX_CONVERSATIONS);
+        assert!(!host.conversations.contains_key("m1")); // the oldest went first
+        assert!(host.conversations.contains_key(&format!("m{}", MAX_CONVERSATIONS + 10)));
+    }
+
+    #[test]
+    fn the_inbox_is_capped_by_the_memory_budget() {
+        let caps = proto::AgentCapabilities { max_memory_bytes: 4 * 1024, ..caps() };
+        let mut host = ComponentHost::new(caps, Default::default());
+        let body = [0u8; 1000];
+        let queued = (0..10).take_while(|_| host.enqueue("alice", b"obj(inform, x)", &body)).count();
+        assert_eq!(queued, 4 * 1024 / inbox_cost("alice", &body));
+        assert_eq!(host.inbox.len(), queued); // the one past the cap was dropped
+
+        // draining makes room again
+        assert!(messaging::Host::receive_message(&mut host).is_some());
+        assert!(host.enqueue("alice", b"obj(inform, x)", &body));
+    }
+
+    #[test]
+    fn agent_initiated_moves_are_unsupported_not_unknown() {
+        let mut host = ComponentHost::new(caps(), Default::default());
+        let reason = migration::MigrationReason::LoadBalancing;
+        assert!(matches!(
+            migration::Host::migrate_to(&mut host, "n2".into(), reason),
+            Err(messaging::MessagingError::Unsupported(_))
+        ));
+        assert!(matches!(migration::Host::clone_to(&mut host, "n2".into()), Err(messaging::MessagingError::Unsupported(_))));
+    }
+}
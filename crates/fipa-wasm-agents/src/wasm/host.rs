@@ -1,6 +1,7 @@
 // wasm/host.rs - Host State for WASM Runtime
 
 use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 use crate::behavior::BehaviorScheduler;
 use crate::proto;
 
@@ -31,6 +32,24 @@ pub struct HostState {
     /// Storage usage in bytes
     pub storage_usage: u64,
 
+    /// The agent's namespaced durable store, provisioned by the node only when the
+    /// `state` capability is granted; `None` denies every `fipa:agent/storage` call.
+    pub state: Option<Arc<dyn unl_agent::Kv>>,
+
+    /// The node-held signing oracle, provisioned only when the `crypto` capability
+    /// is granted; `None` denies every `fipa:agent/crypto` call.
+    pub keyring: Option<Arc<dyn unl_agent::Keyring>>,
+
+    /// Timer, inference and spawn requests made since the last drain. The node
+    /// gates each batch on the matching grant (`time`/`llm`/`spawn`).
+    pub timer_ops: Vec<unl_agent::TimerOp>,
+    pub infers: Vec<unl_agent::InferReq>,
+    pub spawns: Vec<unl_agent::SpawnReq>,
+
+    /// Host-call results the guest observes, recorded or replayed (`replay.rs`);
+    /// live (a pass-through) unless a recorder or replayer armed it.
+    pub tape: super::replay::Tape,
+
     /// Registered services
     pub services: Vec<proto::ServiceDescription>,
 
@@ -60,26 +79,48 @@ pub struct HostState {
     pub messages_received: u64,
     pub log_count: u64,
 
-    /// wasm store resource limits (linear-memory cap) — H3/R7.
-    pub limits: wasmtime::StoreLimits,
+    /// Admitted guest log lines awaiting the node's drain.
+    pub logs: Vec<GuestLog>,
+
+    /// The conversation (`_acl.cid`) of the message being handled, tagged onto
+    /// log lines.
+    pub conversation: Option<String>,
+
+    /// Log rate window: (window start ms, lines admitted in it).
+    log_window: (u64, u32),
+
+    /// Lines dropped by the rate limit in the current window.
+    logs_suppressed: u64,
+
+    /// wasm store resource limits (linear-memory cap) — H3/R7 — metering the
+    /// memory they admit.
+    pub limits: MeteredLimits,
+
+    /// Fuel and memory of the entry-point calls made since the last drain.
+    pub usage: Vec<CallUsage>,
+}
+
+/// The linear-memory cap `capabilities` grant; a sane default when unset (0).
+pub(super) fn memory_cap(capabilities: &proto::AgentCapabilities) -> usize {
+    if capabilities.max_memory_bytes == 0 {
+        64 * 1024 * 1024
+    } else {
+        capabilities.max_memory_bytes as usize
+    }
 }
 
 impl HostState {
     /// Create new host state
     pub fn new(capabilities: proto::AgentCapabilities) -> Self {
-        // Cap linear memory; fall back to a sane default when unset (0).
-        let mem_cap = if capabilities.max_memory_bytes == 0 {
-            64 * 1024 * 1024
-        } else {
-            capabilities.max_memory_bytes as usize
-        };
+        let mem_cap = memory_cap(&capabilities);
         Self {
             agent_id: proto::AgentId {
                 name: String::new(),
                 addresses: vec![],
                 resolvers: vec![],
             },
-            limits: wasmtime::StoreLimitsBuilder::new().memory_size(mem_cap).build(),
+            limits: MeteredLimits::new(wasmtime::StoreLimitsBuilder::new().memory_size(mem_cap).build()),
+            usage: Vec::new(),
             capabilities,
             node_id: String::new(),
             mailbox: VecDeque::new(),
@@ -87,6 +128,12 @@ impl HostState {
             unl_sends: Vec::new(),
             storage: HashMap::new(),
             storage_usage: 0,
+            state: None,
+            keyring: None,
+            timer_ops: Vec::new(),
+            infers: Vec::new(),
+            spawns: Vec::new(),
+            tape: Default::default(),
             services: vec![],
             timers: HashMap::new(),
             next_timer_id: 1,
@@ -98,6 +145,10 @@ impl HostState {
             messages_sent: 0,
             messages_received: 0,
             log_count: 0,
+            logs: Vec::new(),
+            conversation: None,
+            log_window: (0, 0),
+            logs_suppressed: 0,
         }
     }
 
@@ -151,6 +202,113 @@ impl HostState {
         }
     }
 
+    /// The provisioned state handle, or the uniform denial.
+    fn kv(&self) -> Result<&Arc<dyn unl_agent::Kv>, StorageError> {
+        self.state.as_ref().ok_or(StorageError::PermissionDenied)
+    }
+
+    /// Write `key` to the namespaced store, refusing a write that would take the
+    /// namespace past its `Budget::state_kb` quota (M4).
+    pub fn state_store(&self, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        let kv = self.kv()?;
+        let old = kv.get(key).map(|v| v.len() as u64).unwrap_or(0);
+        if kv.usage().saturating_sub(old).saturating_add(value.len() as u64) > kv.quota() {
+            return Err(StorageError::QuotaExceeded);
+        }
+        kv.put(key, value);
+        Ok(())
+    }
+
+    /// Read `key` from the namespaced store.
+    pub fn state_load(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.kv()?.get(key).ok_or_else(|| StorageError::NotFound(key.to_string()))
+    }
+
+    /// Delete `key` from the namespaced store.
+    pub fn state_delete(&self, key: &str) -> Result<(), StorageError> {
+        let kv = self.kv()?;
+        if kv.get(key).is_none() {
+            return Err(StorageError::NotFound(key.to_string()));
+        }
+        kv.del(key);
+        Ok(())
+    }
+
+    /// Whether `key` exists (false without the `state` capability).
+    pub fn state_exists(&self, key: &str) -> bool {
+        self.kv().map(|kv| kv.get(key).is_some()).unwrap_or(false)
+    }
+
+    /// Keys beginning with `prefix` (empty without the `state` capability).
+    pub fn state_keys(&self, prefix: &str) -> Vec<String> {
+        self.kv().map(|kv| kv.keys(prefix)).unwrap_or_default()
+    }
+
+    /// Bytes currently held in the namespace.
+    pub fn state_usage(&self) -> u64 {
+        self.kv().map(|kv| kv.usage()).unwrap_or(0)
+    }
+
+    /// The namespace's byte quota.
+    pub fn state_quota(&self) -> u64 {
+        self.kv().map(|kv| kv.quota()).unwrap_or(0)
+    }
+
+    /// Note the conversation of an inbound body (its `_acl.cid`, if any).
+    pub fn set_conversation(&mut self, body: &[u8]) {
+        self.conversation = acl_cid(body);
+    }
+
+    /// Admit one guest log line, at most [`MAX_LOG_LINES_PER_SEC`] per agent per
+    /// second; the overflow is counted and reported as a single warning when the
+    /// window rolls over. A `cid`/`conversation` field overrides the message's own.
+    pub fn guest_log(&mut self, level: LogLevel, message: String, fields: Vec<(String, String)>) {
+        self.log_count += 1;
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        if now.saturating_sub(self.log_window.0) >= 1000 {
+            if self.logs_suppressed > 0 {
+                self.logs.push(GuestLog {
+                    level: LogLevel::Warn,
+                    message: format!("{} log lines suppressed (rate limit)", self.logs_suppressed),
+                    conversation: None,
+                    fields: Vec::new(),
+                });
+                self.logs_suppressed = 0;
+            }
+            self.log_window = (now, 0);
+        }
+        if self.log_window.1 >= MAX_LOG_LINES_PER_SEC {
+            self.logs_suppressed += 1;
+            return;
+        }
+        self.log_window.1 += 1;
+        let fields = clip_fields(fields);
+        let conversation = fields
+            .iter()
+            .find(|(k, _)| k == "cid" || k == "conversation")
+            .map(|(_, v)| v.clone())
+            .or_else(|| self.conversation.clone());
+        self.logs.push(GuestLog { level, message: clip(message), conversation, fields });
+    }
+
+    /// Whether a line at `level` would be recorded right now.
+    pub fn log_enabled(&self, level: LogLevel) -> bool {
+        level.enabled() && self.log_window.1 < MAX_LOG_LINES_PER_SEC
+    }
+
+    /// Drain the admitted log lines.
+    pub fn take_logs(&mut self) -> Vec<GuestLog> {
+        std::mem::take(&mut self.logs)
+    }
+
+    /// Note an entry-point call that burned `fuel` with `mem_bytes` of linear memory
+    /// at its end. A call that burned nothing did not run (the export is absent).
+    pub fn record_usage(&mut self, entry: &'static str, fuel: u64, mem_bytes: u64) {
+        if fuel > 0 {
+            self.usage.push(CallUsage { entry, fuel, mem_bytes });
+        }
+    }
+
     /// Schedule a timer
     pub fn schedule_timer(&mut self, delay_ms: u64) -> u64 {
         let timer_id = self.next_timer_id;
@@ -206,13 +364,206 @@ impl HostState {
 /// A raw UNL send the agent emitted via the `send-unl` host import: the
 /// receiver, the semantic (UNL) bytes, and the data payload. The node validates
 /// it against the receiver's vocabulary, then packages and transmits it.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct OutboundIntent {
     pub receiver: String,
     pub unl: Vec<u8>,
     pub body: Vec<u8>,
 }
 
+/// Guest log lines admitted per agent per second (the rest are counted, not kept).
+pub const MAX_LOG_LINES_PER_SEC: u32 = 50;
+
+/// Longest guest log message kept, and the most its fields may hold together;
+/// longer ones are cut at a char boundary.
+pub const MAX_LOG_LINE_BYTES: usize = 4096;
+
+/// The `tracing` target guest log lines are emitted under.
+pub const GUEST_LOG_TARGET: &str = "fipa::guest";
+
+/// Guest log severity — the `log-level` enum of `fipa.wit`, in its order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// The level for a core-ABI level word (`0` = trace … `4` = error).
+    pub fn from_i32(n: i32) -> Option<Self> {
+        Some(match n {
+            0 => LogLevel::Trace,
+            1 => LogLevel::Debug,
+            2 => LogLevel::Info,
+            3 => LogLevel::Warn,
+            4 => LogLevel::Error,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+
+    /// Whether the installed `tracing` subscriber records guest lines at this level.
+    pub fn enabled(self) -> bool {
+        use tracing::Level;
+        match self {
+            LogLevel::Trace => tracing::enabled!(target: GUEST_LOG_TARGET, Level::TRACE),
+            LogLevel::Debug => tracing::enabled!(target: GUEST_LOG_TARGET, Level::DEBUG),
+            LogLevel::Info => tracing::enabled!(target: GUEST_LOG_TARGET, Level::INFO),
+            LogLevel::Warn => tracing::enabled!(target: GUEST_LOG_TARGET, Level::WARN),
+            LogLevel::Error => tracing::enabled!(target: GUEST_LOG_TARGET, Level::ERROR),
+        }
+    }
+}
+
+/// What one entry-point call cost: the fuel it burned and the agent's linear-memory
+/// high-water mark when it returned. The node drains these
+/// (`AgentRuntime::take_usage`) into per-agent metrics and the fuel-rate budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallUsage {
+    pub entry: &'static str,
+    pub fuel: u64,
+    pub mem_bytes: u64,
+}
+
+/// A wasmtime store limiter that also keeps the high-water mark of the linear
+/// memory it has admitted. Linear memory never shrinks, so the running total of
+/// admitted growth is the peak.
+pub struct MeteredLimits {
+    limits: wasmtime::StoreLimits,
+    peak: usize,
+}
+
+impl MeteredLimits {
+    pub fn new(limits: wasmtime::StoreLimits) -> Self {
+        MeteredLimits { limits, peak: 0 }
+    }
+
+    /// Bytes of linear memory admitted so far, across every memory of the store.
+    pub fn peak(&self) -> u64 {
+        self.peak as u64
+    }
+}
+
+impl wasmtime::ResourceLimiter for MeteredLimits {
+    fn memory_growing(&mut self, current: usize, desired: usize, maximum: Option<usize>) -> anyhow::Result<bool> {
+        let ok = self.limits.memory_growing(current, desired, maximum)?;
+        if ok {
+            self.peak += desired.saturating_sub(current);
+        }
+        Ok(ok)
+    }
+
+    fn memory_grow_failed(&mut self, error: anyhow::Error) -> anyhow::Result<()> {
+        self.limits.memory_grow_failed(error)
+    }
+
+    fn table_growing(&mut self, current: usize, desired: usize, maximum: Option<usize>) -> anyhow::Result<bool> {
+        self.limits.table_growing(current, desired, maximum)
+    }
+
+    fn table_grow_failed(&mut self, error: anyhow::Error) -> anyhow::Result<()> {
+        self.limits.table_grow_failed(error)
+    }
+
+    fn instances(&self) -> usize {
+        self.limits.instances()
+    }
+
+    fn tables(&self) -> usize {
+        self.limits.tables()
+    }
+
+    fn memories(&self) -> usize {
+        self.limits.memories()
+    }
+}
+
+/// One admitted guest log line. The runtime only collects it; the node drains it
+/// (`AgentRuntime::take_logs`) and stamps it with the agent's UUID, so an agent can
+/// neither forge another agent's lines nor suppress its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuestLog {
+    pub level: LogLevel,
+    pub message: String,
+    pub conversation: Option<String>,
+    pub fields: Vec<(String, String)>,
+}
+
+impl GuestLog {
+    /// `key=value` pairs, space-separated.
+    pub fn fields_text(&self) -> String {
+        self.fields.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Emit as a `tracing` event under [`GUEST_LOG_TARGET`], tagged with the agent,
+    /// level and conversation.
+    pub fn emit(&self, agent: &str) {
+        let conversation = self.conversation.as_deref().unwrap_or("");
+        let fields = self.fields_text();
+        let msg = &self.message;
+        match self.level {
+            LogLevel::Trace => tracing::trace!(target: GUEST_LOG_TARGET, agent, conversation, fields, "{msg}"),
+            LogLevel::Debug => tracing::debug!(target: GUEST_LOG_TARGET, agent, conversation, fields, "{msg}"),
+            LogLevel::Info => tracing::info!(target: GUEST_LOG_TARGET, agent, conversation, fields, "{msg}"),
+            LogLevel::Warn => tracing::warn!(target: GUEST_LOG_TARGET, agent, conversation, fields, "{msg}"),
+            LogLevel::Error => tracing::error!(target: GUEST_LOG_TARGET, agent, conversation, fields, "{msg}"),
+        }
+    }
+}
+
+/// Cut `s` to [`MAX_LOG_LINE_BYTES`] on a char boundary.
+fn clip(s: String) -> String {
+    clip_to(s, MAX_LOG_LINE_BYTES)
+}
+
+/// Cut `s` to at most `max` bytes on a char boundary.
+fn clip_to(mut s: String, max: usize) -> String {
+    if s.len() > max {
+        let mut n = max;
+        while !s.is_char_boundary(n) {
+            n -= 1;
+        }
+        s.truncate(n);
+    }
+    s
+}
+
+/// Hold a line's fields to [`MAX_LOG_LINE_BYTES`] in all, as its message is: keys
+/// and values are clipped in order, and fields past the cap are dropped.
+fn clip_fields(fields: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut left = MAX_LOG_LINE_BYTES;
+    let mut kept = Vec::new();
+    for (k, v) in fields {
+        if left == 0 {
+            break;
+        }
+        let k = clip_to(k, left);
+        left -= k.len();
+        let v = clip_to(v, left);
+        left -= v.len();
+        kept.push((k, v));
+    }
+    kept
+}
+
+/// The conversation id of a JSON body's `_acl` header (`INTERACTION_PROTOCOLS.md` §3).
+pub fn acl_cid(body: &[u8]) -> Option<String> {
+    let v: serde_json::Value = serde_json::from_slice(body).ok()?;
+    Some(v.get("_acl")?.get("cid")?.as_str()?.to_string())
+}
+
 /// Storage error types
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum StorageError {
@@ -224,4 +575,24 @@ pub enum StorageError {
 
     #[error("I/O error: {0}")]
     IoError(String),
+
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+
+    #[error("Permission denied")]
+    PermissionDenied,
+}
+
+impl StorageError {
+    /// The status word a `fipa:agent/storage` import returns for this error: one
+    /// plus the `storage-error` variant index in `fipa.wit` (`0` is success).
+    pub const fn code(&self) -> i32 {
+        match self {
+            StorageError::NotFound(_) => 1,
+            StorageError::QuotaExceeded => 2,
+            StorageError::IoError(_) => 3,
+            StorageError::Serialization(_) => 4,
+            StorageError::PermissionDenied => 5,
+        }
+    }
 }
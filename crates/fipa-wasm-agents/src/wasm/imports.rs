@@ -0,0 +1,472 @@
+//! The core-module host imports — one definition, every engine.
+//!
+//! Each `fipa:agent/*` import a core-module agent can call is implemented once
+//! here, against [`Guest`] (the calling instance's memory, its `alloc` export and
+//! its [`HostState`]). [`link_host_imports!`] registers the whole table on a
+//! linker; wasmtime ([`super::WasmRuntime`]) and wasmi ([`super::WasmiModule`])
+//! both expand it, so the same agent binary sees the same surface, the same
+//! gating and the same results on either engine (E2).
+//!
+//! Conventions (`AGENT_HOST_ABI.md` §12): strings and bytes cross as `(ptr,len)`;
+//! a status word is `0` on success, else a [`StorageError::code`]; byte results
+//! come back packed as `(ptr << 32) | len` in guest memory, or the negated code.
+//! Capabilities are enforced where they are provisioned: `state` and `crypto`
+//! calls are denied unless the node handed over a handle, while timer, inference
+//! and spawn requests are queued here and gated by the node when it drains them.
+//! Every result the guest could not compute itself — clock, entropy, state reads,
+//! crypto — passes through [`HostState::tape`], so a session can be recorded and
+//! replayed offline (`replay.rs`).
+
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use unl_agent::{InferReq, SpawnReq, TimerOp};
+
+use super::host::{HostState, LogLevel, OutboundIntent, StorageError};
+use crate::adapters::{MAX_QUEUED_SENDS, MAX_SEND_BYTES};
+
+/// Timer ops and inference requests queued between drains (host heap, as M3).
+const MAX_QUEUED_REQS: usize = 256;
+
+/// Spawn requests queued between drains — each holds a copy of the child's code.
+const MAX_QUEUED_SPAWNS: usize = 4;
+
+/// The most bytes one `random` call may ask for: the buffer is host heap, outside
+/// the guest's memory cap (the M3 reasoning for `send-unl`).
+pub(super) const MAX_RANDOM_BYTES: u32 = 64 * 1024;
+
+/// The calling instance, as an import sees it. Implemented for each engine's
+/// `Caller`.
+pub(super) trait Guest {
+    /// The agent's host state.
+    fn host(&mut self) -> &mut HostState;
+
+    /// Copy `len` bytes at `ptr` out of linear memory; `None` when the range is out
+    /// of bounds or there is no memory — never a panic, audit M10.
+    fn try_read(&mut self, ptr: i32, len: i32) -> Option<Vec<u8>>;
+
+    /// [`Guest::try_read`], empty when the range is out of bounds.
+    fn read(&mut self, ptr: i32, len: i32) -> Vec<u8> {
+        self.try_read(ptr, len).unwrap_or_default()
+    }
+
+    /// Hand `bytes` back: `alloc` a buffer, copy into it, and return the packed
+    /// `(ptr << 32) | len`. A guest without `alloc`/`memory`, or whose `alloc`
+    /// returns an out-of-range pointer, gets the negated I/O-error code.
+    fn give(&mut self, bytes: &[u8]) -> i64;
+}
+
+/// Register every core-module host import on `$linker`, whose closures take
+/// `$caller` (an engine `Caller` implementing [`Guest`]); `send-unl` feeds
+/// `$hooks.sends`. Expands to `?`-propagating `anyhow` errors.
+macro_rules! link_host_imports {
+    ($linker:ident, $caller:ty, $hooks:expr) => {{
+        link_host_imports!(@wrap $linker, $caller;
+            "fipa:agent/messaging" "has-messages" => has_messages() -> i32,
+            "fipa:agent/lifecycle" "request-shutdown" => request_shutdown(),
+            "fipa:agent/lifecycle" "is-shutdown-requested" => is_shutdown_requested() -> i32,
+            "fipa:agent/logging" "log" => log(level: i32, mp: i32, ml: i32),
+            "fipa:agent/logging" "log-structured" => log_structured(level: i32, mp: i32, ml: i32, fp: i32, fl: i32),
+            "fipa:agent/logging" "is-enabled" => log_is_enabled(level: i32) -> i32,
+            "fipa:agent/storage" "store" => storage_store(kp: i32, kl: i32, vp: i32, vl: i32) -> i32,
+            "fipa:agent/storage" "load" => storage_load(kp: i32, kl: i32) -> i64,
+            "fipa:agent/storage" "delete" => storage_delete(kp: i32, kl: i32) -> i32,
+            "fipa:agent/storage" "exists" => storage_exists(kp: i32, kl: i32) -> i32,
+            "fipa:agent/storage" "list-keys" => storage_list_keys() -> i64,
+            "fipa:agent/storage" "list-keys-with-prefix" => storage_list_prefix(pp: i32, pl: i32) -> i64,
+            "fipa:agent/storage" "get-usage" => storage_usage() -> i64,
+            "fipa:agent/storage" "get-quota" => storage_quota() -> i64,
+            "fipa:agent/timing" "now" => now() -> i64,
+            "fipa:agent/timing" "monotonic-now" => monotonic_now() -> i64,
+            "fipa:agent/timing" "set-timer" => set_timer(id: i64, delay_ms: i64),
+            "fipa:agent/timing" "cancel-timer" => cancel_timer(id: i64),
+            "fipa:agent/crypto" "sign" => crypto_sign(bp: i32, bl: i32) -> i64,
+            "fipa:agent/crypto" "verify" => crypto_verify(kp: i32, kl: i32, bp: i32, bl: i32, sp: i32, sl: i32) -> i32,
+            "fipa:agent/crypto" "public-key" => crypto_public_key() -> i64,
+            "fipa:agent/crypto" "random" => crypto_random(n: i32) -> i64,
+            "fipa:agent/llm" "infer" => infer(req_id: i64, pp: i32, pl: i32) -> i32,
+            "fipa:agent/spawn" "spawn" => spawn(up: i32, ul: i32, ap: i32, al: i32, cp: i32, cl: i32, mp: i32, ml: i32) -> i32,
+            "fipa:agent/migration" "is-migrating" => is_migrating() -> i32,
+        );
+        let sends = $hooks.sends.clone();
+        $linker
+            .func_wrap(
+                "fipa:agent/messaging",
+                "send-unl",
+                move |mut caller: $caller, rp: i32, rl: i32, up: i32, ul: i32, bp: i32, bl: i32| {
+                    $crate::wasm::imports::send_unl(&mut caller, &sends, (rp, rl), (up, ul), (bp, bl))
+                },
+            )
+            .map_err(|e| anyhow::anyhow!("link fipa:agent/messaging/send-unl: {e}"))?;
+    }};
+    (@wrap $linker:ident, $caller:ty;
+        $($module:literal $name:literal => $f:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?,)*) => {
+        $(
+            $linker
+                .func_wrap($module, $name, |mut caller: $caller, $($arg: $ty),*| $(-> $ret)? {
+                    $crate::wasm::imports::$f(&mut caller, $($arg),*)
+                })
+                .map_err(|e| anyhow::anyhow!("link {}/{}: {e}", $module, $name))?;
+        )*
+    };
+}
+pub(super) use link_host_imports;
+
+/// The negated "permission denied" status — a gated call made without the grant.
+const DENIED: i64 = -(StorageError::PermissionDenied.code() as i64);
+
+// ── messaging ───────────────────────────────────────────────────────────────
+// Inbound messages arrive through the `deliver` export (§6), not a pull import.
+
+pub(super) fn has_messages(g: &mut impl Guest) -> i32 {
+    !g.host().mailbox.is_empty() as i32
+}
+
+/// The agent emits a message: `send-unl(receiver, unl, body)` as `(ptr,len)`
+/// triples. The node validates, packages and transmits it.
+pub(super) fn send_unl(
+    g: &mut impl Guest,
+    sends: &Mutex<Vec<OutboundIntent>>,
+    (rp, rl): (i32, i32),
+    (up, ul): (i32, i32),
+    (bp, bl): (i32, i32),
+) {
+    let receiver = String::from_utf8_lossy(&g.read(rp, rl)).into_owned();
+    let unl = g.read(up, ul);
+    let body = g.read(bp, bl);
+    // M3 — bound a guest's egress: a single message can't exceed 1 MiB and a
+    // single call can't queue more than MAX_QUEUED_SENDS intents, so a guest
+    // cannot amplify host-heap use beyond its own (capped) memory.
+    if unl.len() + body.len() > MAX_SEND_BYTES {
+        return;
+    }
+    let mut guard = sends.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.len() >= MAX_QUEUED_SENDS {
+        return;
+    }
+    crate::flow!(
+        "wasm: ← agent emitted send-unl → '{}' (unl={} bytes, body={} bytes)",
+        receiver,
+        unl.len(),
+        body.len()
+    );
+    guard.push(OutboundIntent { receiver, unl, body });
+}
+
+// ── lifecycle ───────────────────────────────────────────────────────────────
+
+pub(super) fn request_shutdown(g: &mut impl Guest) {
+    g.host().shutdown_requested = true;
+}
+
+pub(super) fn is_shutdown_requested(g: &mut impl Guest) -> i32 {
+    g.host().shutdown_requested as i32
+}
+
+// ── logging ─────────────────────────────────────────────────────────────────
+// Decoded from guest memory, rate-limited per agent, and queued for the node to
+// emit + audit (`take_logs`). Levels follow the WIT `log-level` order; fields use
+// the `list-keys` encoding, alternating key and value.
+
+pub(super) fn log(g: &mut impl Guest, level: i32, mp: i32, ml: i32) {
+    let message = String::from_utf8_lossy(&g.read(mp, ml)).into_owned();
+    let level = LogLevel::from_i32(level).unwrap_or(LogLevel::Info);
+    g.host().guest_log(level, message, Vec::new());
+}
+
+pub(super) fn log_structured(g: &mut impl Guest, level: i32, mp: i32, ml: i32, fp: i32, fl: i32) {
+    let message = String::from_utf8_lossy(&g.read(mp, ml)).into_owned();
+    let flat = decode_strings(&g.read(fp, fl));
+    let fields = flat.chunks_exact(2).map(|kv| (kv[0].clone(), kv[1].clone())).collect();
+    let level = LogLevel::from_i32(level).unwrap_or(LogLevel::Info);
+    g.host().guest_log(level, message, fields);
+}
+
+pub(super) fn log_is_enabled(g: &mut impl Guest, level: i32) -> i32 {
+    let live = LogLevel::from_i32(level).is_some_and(|l| g.host().log_enabled(l));
+    g.host().tape.word(live as i64) as i32
+}
+
+// ── storage ─────────────────────────────────────────────────────────────────
+// The agent's namespaced durable store (`state` capability).
+
+/// A storage key or value from guest memory; a range outside it is an I/O error,
+/// never an empty key or value.
+fn operand(g: &mut impl Guest, ptr: i32, len: i32) -> Result<Vec<u8>, StorageError> {
+    g.try_read(ptr, len).ok_or_else(|| StorageError::IoError("out of bounds".into()))
+}
+
+/// A storage key from guest memory; keys are UTF-8 in `fipa.wit`.
+fn key(g: &mut impl Guest, ptr: i32, len: i32) -> Result<String, StorageError> {
+    String::from_utf8(operand(g, ptr, len)?).map_err(|e| StorageError::Serialization(e.to_string()))
+}
+
+pub(super) fn storage_store(g: &mut impl Guest, kp: i32, kl: i32, vp: i32, vl: i32) -> i32 {
+    let result = key(g, kp, kl).and_then(|key| {
+        let value = operand(g, vp, vl)?;
+        g.host().state_store(&key, &value)
+    });
+    status(g, result)
+}
+
+pub(super) fn storage_load(g: &mut impl Guest, kp: i32, kl: i32) -> i64 {
+    let value = key(g, kp, kl).and_then(|key| g.host().state_load(&key));
+    hand_back(g, value.map_err(|e| -(e.code() as i64)))
+}
+
+pub(super) fn storage_delete(g: &mut impl Guest, kp: i32, kl: i32) -> i32 {
+    let result = key(g, kp, kl).and_then(|key| g.host().state_delete(&key));
+    status(g, result)
+}
+
+pub(super) fn storage_exists(g: &mut impl Guest, kp: i32, kl: i32) -> i32 {
+    let live = key(g, kp, kl).is_ok_and(|key| g.host().state_exists(&key));
+    g.host().tape.word(live as i64) as i32
+}
+
+pub(super) fn storage_list_keys(g: &mut impl Guest) -> i64 {
+    let keys = g.host().state_keys("");
+    hand_back(g, Ok(encode_strings(&keys)))
+}
+
+pub(super) fn storage_list_prefix(g: &mut impl Guest, pp: i32, pl: i32) -> i64 {
+    let keys = key(g, pp, pl).map(|prefix| encode_strings(&g.host().state_keys(&prefix)));
+    hand_back(g, keys.map_err(|e| -(e.code() as i64)))
+}
+
+pub(super) fn storage_usage(g: &mut impl Guest) -> i64 {
+    let live = g.host().state_usage() as i64;
+    g.host().tape.word(live)
+}
+
+pub(super) fn storage_quota(g: &mut impl Guest) -> i64 {
+    let live = g.host().state_quota() as i64;
+    g.host().tape.word(live)
+}
+
+// ── timing ──────────────────────────────────────────────────────────────────
+// Timers ride the node's scheduler (M3): the agent picks the id, the node arms it
+// under the `time` grant and slot budget, and it fires back as the `tick` export.
+
+pub(super) fn now(g: &mut impl Guest) -> i64 {
+    g.host().tape.word(chrono::Utc::now().timestamp_millis())
+}
+
+/// Nanoseconds on a process-wide monotonic clock (only differences are meaningful).
+pub(super) fn monotonic_now(g: &mut impl Guest) -> i64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    g.host().tape.word(EPOCH.get_or_init(Instant::now).elapsed().as_nanos() as i64)
+}
+
+pub(super) fn set_timer(g: &mut impl Guest, id: i64, delay_ms: i64) {
+    queue(&mut g.host().timer_ops, TimerOp::Set { id: id as u64, delay_ms: delay_ms.max(0) as u64 }, MAX_QUEUED_REQS);
+}
+
+pub(super) fn cancel_timer(g: &mut impl Guest, id: i64) {
+    queue(&mut g.host().timer_ops, TimerOp::Cancel { id: id as u64 }, MAX_QUEUED_REQS);
+}
+
+// ── crypto ──────────────────────────────────────────────────────────────────
+// The node-held signing oracle (`crypto` capability, §7.2); `random` is OS
+// entropy, which a guest cannot get any other way.
+
+pub(super) fn crypto_sign(g: &mut impl Guest, bp: i32, bl: i32) -> i64 {
+    let bytes = g.read(bp, bl);
+    let sig = g.host().keyring.clone().map(|kr| kr.sign(&bytes)).ok_or(DENIED);
+    hand_back(g, sig)
+}
+
+pub(super) fn crypto_verify(g: &mut impl Guest, kp: i32, kl: i32, bp: i32, bl: i32, sp: i32, sl: i32) -> i32 {
+    let (key, bytes, sig) = (g.read(kp, kl), g.read(bp, bl), g.read(sp, sl));
+    let live = g.host().keyring.clone().is_some_and(|kr| kr.verify(&key, &bytes, &sig));
+    g.host().tape.word(live as i64) as i32
+}
+
+pub(super) fn crypto_public_key(g: &mut impl Guest) -> i64 {
+    let key = g.host().keyring.clone().map(|kr| kr.public_key()).ok_or(DENIED);
+    hand_back(g, key)
+}
+
+pub(super) fn crypto_random(g: &mut impl Guest, n: i32) -> i64 {
+    let n = (n.max(0) as u32).min(MAX_RANDOM_BYTES) as usize;
+    let bytes = g.host().keyring.clone().map(|kr| kr.random(n)).ok_or(DENIED);
+    hand_back(g, bytes)
+}
+
+// ── llm / spawn ─────────────────────────────────────────────────────────────
+// Asynchronous: queued here, gated (`llm`/`spawn`) and run by the node, answered
+// by message (§8). The status word only reports whether the request was queued.
+
+pub(super) fn infer(g: &mut impl Guest, req_id: i64, pp: i32, pl: i32) -> i32 {
+    let prompt = g.read(pp, pl);
+    if prompt.len() > MAX_SEND_BYTES {
+        return StorageError::QuotaExceeded.code();
+    }
+    let req = InferReq { req_id: req_id as u64, prompt: String::from_utf8_lossy(&prompt).into_owned() };
+    queue(&mut g.host().infers, req, MAX_QUEUED_REQS)
+}
+
+#[allow(clippy::too_many_arguments)] // four (ptr,len) pairs, as the import takes them
+pub(super) fn spawn(g: &mut impl Guest, up: i32, ul: i32, ap: i32, al: i32, cp: i32, cl: i32, mp: i32, ml: i32) -> i32 {
+    let req = SpawnReq {
+        uuid: String::from_utf8_lossy(&g.read(up, ul)).into_owned(),
+        alias: String::from_utf8_lossy(&g.read(ap, al)).into_owned(),
+        code: g.read(cp, cl),
+        manifest_json: g.read(mp, ml),
+    };
+    queue(&mut g.host().spawns, req, MAX_QUEUED_SPAWNS)
+}
+
+// ── migration ───────────────────────────────────────────────────────────────
+
+pub(super) fn is_migrating(g: &mut impl Guest) -> i32 {
+    g.host().is_migrating as i32
+}
+
+/// A status word through the tape: `0`, or the error's code.
+fn status(g: &mut impl Guest, result: Result<(), StorageError>) -> i32 {
+    let live = result.err().map_or(0, |e| e.code());
+    g.host().tape.word(live as i64) as i32
+}
+
+/// Bytes (or the negated status standing in for them) through the tape, then back
+/// to the guest.
+fn hand_back(g: &mut impl Guest, live: Result<Vec<u8>, i64>) -> i64 {
+    match g.host().tape.bytes(live) {
+        Ok(bytes) => g.give(&bytes),
+        Err(code) => code,
+    }
+}
+
+/// Push onto a bounded queue: `0`, or the quota-exceeded status when full.
+fn queue<T>(q: &mut Vec<T>, item: T, cap: usize) -> i32 {
+    if q.len() >= cap {
+        return StorageError::QuotaExceeded.code();
+    }
+    q.push(item);
+    0
+}
+
+/// `list<string>` on the core ABI: each string as a big-endian u32 length + UTF-8.
+pub(super) fn encode_strings(strings: &[String]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for s in strings {
+        out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+        out.extend_from_slice(s.as_bytes());
+    }
+    out
+}
+
+/// The inverse of [`encode_strings`]; a truncated tail is dropped. At most
+/// `MAX_LOG_FIELDS * 2` strings are read.
+fn decode_strings(bytes: &[u8]) -> Vec<String> {
+    const MAX_LOG_FIELDS: usize = 32;
+    let mut out = Vec::new();
+    let mut p = 0usize;
+    while out.len() < MAX_LOG_FIELDS * 2 && p + 4 <= bytes.len() {
+        let n = u32::from_be_bytes([bytes[p], bytes[p + 1], bytes[p + 2], bytes[p + 3]]) as usize;
+        p += 4;
+        let Some(s) = p.checked_add(n).and_then(|end| bytes.get(p..end)) else {
+            break;
+        };
+        out.push(String::from_utf8_lossy(s).into_owned());
+        p += n;
+    }
+    out
+}
+
+/// A guest that drives the whole gated surface, shared by the per-engine tests so
+/// both run the very same binary.
+#[cfg(test)]
+pub(super) mod testing {
+    /// `run` arms timer 7, cancels 8, asks inference 42 on "hello", spawns "kid",
+    /// and logs "hi"; `tick(id, now)` re-arms `id` at 100 ms; `sig`/`pk`/`rand`
+    /// return packed crypto results and `ok` verifies "olleh" over "hello".
+    pub const SURFACE_GUEST: &str = r#"
+    (module
+      (import "fipa:agent/timing" "set-timer" (func $set (param i64 i64)))
+      (import "fipa:agent/timing" "cancel-timer" (func $cancel (param i64)))
+      (import "fipa:agent/llm" "infer" (func $infer (param i64 i32 i32) (result i32)))
+      (import "fipa:agent/spawn" "spawn"
+        (func $spawn (param i32 i32 i32 i32 i32 i32 i32 i32) (result i32)))
+      (import "fipa:agent/logging" "log" (func $log (param i32 i32 i32)))
+      (import "fipa:agent/crypto" "sign" (func $sign (param i32 i32) (result i64)))
+      (import "fipa:agent/crypto" "verify" (func $verify (param i32 i32 i32 i32 i32 i32) (result i32)))
+      (import "fipa:agent/crypto" "public-key" (func $pk (result i64)))
+      (import "fipa:agent/crypto" "random" (func $rand (param i32) (result i64)))
+      (memory (export "memory") 1)
+      (global $bump (mut i32) (i32.const 1024))
+      (data (i32.const 0) "hello")
+      (data (i32.const 8) "olleh")
+      (data (i32.const 16) "kid")
+      (data (i32.const 24) "k")
+      (data (i32.const 32) "\00asm")
+      (data (i32.const 40) "{}")
+      (data (i32.const 48) "hi")
+      (func (export "init"))
+      (func (export "alloc") (param $n i32) (result i32)
+        (local $p i32)
+        (local.set $p (global.get $bump))
+        (global.set $bump (i32.add (global.get $bump) (local.get $n)))
+        (local.get $p))
+      (func (export "run") (result i32)
+        (call $set (i64.const 7) (i64.const 250))
+        (call $cancel (i64.const 8))
+        (drop (call $infer (i64.const 42) (i32.const 0) (i32.const 5)))
+        (drop (call $spawn (i32.const 16) (i32.const 3) (i32.const 24) (i32.const 1)
+                           (i32.const 32) (i32.const 4) (i32.const 40) (i32.const 2)))
+        (call $log (i32.const 2) (i32.const 48) (i32.const 2))
+        (i32.const 1))
+      (func (export "tick") (param $id i64) (param $now i64)
+        (call $set (local.get $id) (i64.const 100)))
+      (func (export "sig") (result i64) (call $sign (i32.const 0) (i32.const 5)))
+      (func (export "pk") (result i64) (call $pk))
+      (func (export "rand") (result i64) (call $rand (i32.const 16)))
+      (func (export "ok") (result i32)
+        (call $verify (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 5) (i32.const 8) (i32.const 5))))
+    "#;
+
+    /// On each `deliver`, reads the clock and 8 random bytes and sends both to
+    /// "log" — output the module cannot reproduce without the host (replay tests).
+    pub const CLOCK_GUEST: &str = r#"
+    (module
+      (import "fipa:agent/timing" "now" (func $now (result i64)))
+      (import "fipa:agent/crypto" "random" (func $rand (param i32) (result i64)))
+      (import "fipa:agent/messaging" "send-unl" (func $send (param i32 i32 i32 i32 i32 i32)))
+      (memory (export "memory") 1)
+      (global $bump (mut i32) (i32.const 1024))
+      (data (i32.const 0) "log")
+      (func (export "init"))
+      (func (export "alloc") (param $n i32) (result i32)
+        (local $p i32)
+        (local.set $p (global.get $bump))
+        (global.set $bump (i32.add (global.get $bump) (local.get $n)))
+        (local.get $p))
+      (func (export "deliver") (param i32 i32 i32 i32 i32 i32)
+        (local $r i64)
+        (i64.store (i32.const 16) (call $now))
+        (local.set $r (call $rand (i32.const 8)))
+        (call $send
+          (i32.const 0) (i32.const 3)
+          (i32.const 16) (i32.const 8)
+          (i32.wrap_i64 (i64.shr_u (local.get $r) (i64.const 32))) (i32.const 8))))
+    "#;
+
+    /// A stand-in signing oracle: a signature is the reversed message.
+    pub struct MirrorKeyring;
+
+    impl unl_agent::Keyring for MirrorKeyring {
+        fn sign(&self, bytes: &[u8]) -> Vec<u8> {
+            bytes.iter().rev().copied().collect()
+        }
+        fn verify(&self, _pubkey: &[u8], bytes: &[u8], sig: &[u8]) -> bool {
+            self.sign(bytes) == sig
+        }
+        fn public_key(&self) -> Vec<u8> {
+            b"mirror".to_vec()
+        }
+        fn random(&self, n: usize) -> Vec<u8> {
+            vec![0x5a; n]
+        }
+    }
+}
@@ -3,14 +3,24 @@
 //! WASM Runtime for FIPA agents.
 //!
 //! This module provides the wasmtime-based runtime for executing
-//! WASM agents with the FIPA component model interface.
+//! WASM agents: core modules over the `(ptr,len)` host ABI, and components
+//! bound to the `fipa.wit` worlds.
 
 mod agent_runtime;
+mod cache;
+mod component;
 mod host;
+mod imports;
+mod replay;
 mod runtime;
 mod wasmi_engine;
 
 pub use agent_runtime::{AgentRuntime, NativeRuntime};
-pub use host::{HostState, OutboundIntent};
-pub use runtime::{WasmRuntime, WasmtimeEngine};
+pub use cache::ModuleCache;
+pub use component::{is_component, ComponentRuntime};
+pub use host::{
+    CallUsage, GuestLog, HostState, LogLevel, MeteredLimits, OutboundIntent, StorageError, MAX_LOG_LINES_PER_SEC,
+};
+pub use replay::{replay, Divergence, HostCall, Input, Recorder, Replay, Step, Tape, TraceHeader, TRACE_VERSION};
+pub use runtime::{enable_pooling, DeadlineExceeded, Pooling, WasmRuntime, WasmtimeEngine};
 pub use wasmi_engine::{WasmiEngine, WasmiModule};
@@ -0,0 +1,458 @@
+//! Deterministic record/replay of wasm agents.
+//!
+//! A [`Recorder`] wraps a mounted agent's [`AgentRuntime`] and writes one [`Step`]
+//! per entry-point call: the input (`init`, `config`/`deliver`, `tick`, …), every
+//! host-call result the guest observed — clock, randomness, state reads, crypto,
+//! anything the module cannot reproduce on its own — and the intents it emitted.
+//! [`replay`] feeds the same inputs to a fresh instance of the same module on any
+//! [`Engine`], answering host calls from the trace instead of the host, and stops
+//! at the first step whose behaviour differs. Inference replies need no special
+//! case: they arrive as ordinary `config` calls from `"llm"`.
+//!
+//! The trace is JSON Lines — a [`TraceHeader`], then one [`Step`] per line —
+//! written as the agent runs, so it survives the node going down mid-incident.
+//! Wall-clock deadlines are not replayed: a step cut off at its deadline in the
+//! field replays to completion offline.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::agent_runtime::AgentRuntime;
+use super::host::{CallUsage, GuestLog, OutboundIntent};
+use crate::adapters::{Engine, HostHooks, Limits};
+use crate::process::code_hash;
+
+/// Trace format version; a replayer refuses any other.
+pub const TRACE_VERSION: u32 = 1;
+
+/// One host-call result as the guest saw it: a scalar, or bytes handed back (the
+/// `Err` side is the negated status the guest got instead).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostCall {
+    Word(i64),
+    Bytes(Result<Vec<u8>, i64>),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum TapeMode {
+    #[default]
+    Live,
+    Recording,
+    Replaying,
+}
+
+/// Where a runtime's nondeterministic host calls go: straight through (live),
+/// through and onto the tape (recording), or answered from the tape (replaying).
+/// A tape leaves live mode only for the span of one step — [`Tape::record`] or
+/// [`Tape::replay`] before the call, [`Tape::stop`] after it — so host calls made
+/// between steps (a snapshot, say) neither land on the tape nor consume it.
+#[derive(Debug, Default)]
+pub struct Tape {
+    mode: TapeMode,
+    calls: VecDeque<HostCall>,
+    diverged: bool,
+}
+
+impl Tape {
+    /// Start recording a step afresh.
+    pub fn record(&mut self) {
+        self.mode = TapeMode::Recording;
+        self.calls.clear();
+    }
+
+    /// Answer the next step's host calls from `calls`.
+    pub fn replay(&mut self, calls: Vec<HostCall>) {
+        self.mode = TapeMode::Replaying;
+        self.calls = calls.into();
+        self.diverged = false;
+    }
+
+    /// End the step: back to live, handing over what was recorded.
+    pub fn stop(&mut self) -> Vec<HostCall> {
+        self.mode = TapeMode::Live;
+        self.diverged = false;
+        self.calls.drain(..).collect()
+    }
+
+    /// While replaying: whether the guest made a host call the trace does not
+    /// hold, or left recorded calls unmade.
+    pub fn diverged(&self) -> bool {
+        self.mode == TapeMode::Replaying && (self.diverged || !self.calls.is_empty())
+    }
+
+    /// Pass a scalar result through the tape.
+    pub fn word(&mut self, live: i64) -> i64 {
+        match self.mode {
+            TapeMode::Live => live,
+            TapeMode::Recording => {
+                self.calls.push_back(HostCall::Word(live));
+                live
+            }
+            TapeMode::Replaying => match self.calls.pop_front() {
+                Some(HostCall::Word(w)) => w,
+                _ => {
+                    self.diverged = true;
+                    live
+                }
+            },
+        }
+    }
+
+    /// Pass a byte result (or the status that replaced it) through the tape.
+    pub fn bytes(&mut self, live: Result<Vec<u8>, i64>) -> Result<Vec<u8>, i64> {
+        match self.mode {
+            TapeMode::Live => live,
+            TapeMode::Recording => {
+                self.calls.push_back(HostCall::Bytes(live.clone()));
+                live
+            }
+            TapeMode::Replaying => match self.calls.pop_front() {
+                Some(HostCall::Bytes(b)) => b,
+                _ => {
+                    self.diverged = true;
+                    live
+                }
+            },
+        }
+    }
+}
+
+/// The first line of a trace: what it was recorded against.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    pub version: u32,
+    /// [`code_hash`] of the module.
+    pub code_hash: String,
+    /// The per-call fuel and memory cap the agent ran under.
+    pub fuel: u64,
+    pub mem_bytes: u64,
+}
+
+/// An entry-point call into the agent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Input {
+    Init,
+    Config { from: String, unl: Vec<u8>, body: Vec<u8> },
+    Run,
+    Tick { timer_id: u64, now_ms: u64 },
+    Restore { state: Vec<u8> },
+    Shutdown,
+}
+
+/// One recorded call: its input, the host-call results the guest observed, what
+/// it emitted, and whether it completed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub input: Input,
+    pub host_calls: Vec<HostCall>,
+    pub sends: Vec<OutboundIntent>,
+    pub ok: bool,
+}
+
+/// An [`AgentRuntime`] that records every call into the runtime it wraps. A
+/// trace write that fails stops the recording, never the agent.
+pub struct Recorder {
+    inner: Box<dyn AgentRuntime + Send>,
+    out: Option<Box<dyn Write + Send>>,
+    sends: Vec<OutboundIntent>,
+}
+
+impl Recorder {
+    /// Wrap a not-yet-`init`ed runtime of `code`, running under `limits`, and
+    /// write the trace header to `out`.
+    pub fn new(inner: Box<dyn AgentRuntime + Send>, code: &[u8], limits: Limits, out: Box<dyn Write + Send>) -> Self {
+        let header = TraceHeader {
+            version: TRACE_VERSION,
+            code_hash: code_hash(code),
+            fuel: limits.fuel,
+            mem_bytes: limits.mem_bytes as u64,
+        };
+        let mut rec = Recorder { inner, out: Some(out), sends: Vec::new() };
+        rec.write(&header);
+        rec
+    }
+
+    /// Whether the trace is still being written (no write has failed).
+    pub fn recording(&self) -> bool {
+        self.out.is_some()
+    }
+
+    /// Append one JSON line, giving up on the trace at the first failure.
+    fn write(&mut self, line: &impl Serialize) {
+        let Some(out) = &mut self.out else { return };
+        let written = serde_json::to_writer(&mut *out, line)
+            .map_err(io::Error::from)
+            .and_then(|()| out.write_all(b"\n"))
+            .and_then(|()| out.flush());
+        if written.is_err() {
+            self.out = None;
+        }
+    }
+
+    fn step<T>(&mut self, input: Input, call: impl FnOnce(&mut dyn AgentRuntime) -> Result<T>) -> Result<T> {
+        if let Some(tape) = self.inner.tape() {
+            tape.record();
+        }
+        let result = call(&mut *self.inner);
+        let host_calls = self.inner.tape().map(Tape::stop).unwrap_or_default();
+        let sends = self.inner.take_sends();
+        let step = Step { input, host_calls, sends, ok: result.is_ok() };
+        self.write(&step);
+        self.sends.extend(step.sends);
+        result
+    }
+}
+
+impl AgentRuntime for Recorder {
+    fn init(&mut self) -> Result<()> {
+        self.step(Input::Init, |rt| rt.init())
+    }
+
+    fn config(&mut self, from: &str, unl: &[u8], body: &[u8]) -> Result<()> {
+        let input = Input::Config { from: from.into(), unl: unl.to_vec(), body: body.to_vec() };
+        self.step(input, |rt| rt.config(from, unl, body))
+    }
+
+    fn take_sends(&mut self) -> Vec<OutboundIntent> {
+        std::mem::take(&mut self.sends)
+    }
+
+    fn run(&mut self) -> Result<bool> {
+        self.step(Input::Run, |rt| rt.run())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        self.step(Input::Shutdown, |rt| rt.shutdown())
+    }
+
+    fn snapshot(&mut self) -> Vec<u8> {
+        self.inner.snapshot()
+    }
+
+    fn restore(&mut self, state: &[u8]) -> Result<()> {
+        self.step(Input::Restore { state: state.to_vec() }, |rt| rt.restore(state))
+    }
+
+    fn tick(&mut self, timer_id: u64, now_ms: u64) -> Result<()> {
+        self.step(Input::Tick { timer_id, now_ms }, |rt| rt.tick(timer_id, now_ms))
+    }
+
+    fn take_timer_ops(&mut self) -> Vec<unl_agent::TimerOp> {
+        self.inner.take_timer_ops()
+    }
+
+    fn set_state(&mut self, kv: std::sync::Arc<dyn unl_agent::Kv>) {
+        self.inner.set_state(kv);
+    }
+
+    fn set_keyring(&mut self, kr: std::sync::Arc<dyn unl_agent::Keyring>) {
+        self.inner.set_keyring(kr);
+    }
+
+    fn take_infer_reqs(&mut self) -> Vec<unl_agent::InferReq> {
+        self.inner.take_infer_reqs()
+    }
+
+    fn take_spawn_reqs(&mut self) -> Vec<unl_agent::SpawnReq> {
+        self.inner.take_spawn_reqs()
+    }
+
+    fn take_logs(&mut self) -> Vec<GuestLog> {
+        self.inner.take_logs()
+    }
+
+    fn take_usage(&mut self) -> Vec<CallUsage> {
+        self.inner.take_usage()
+    }
+}
+
+/// Where a replay stopped matching its trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Index of the step (0 = the first after the header).
+    pub step: usize,
+    pub input: Input,
+    pub reason: String,
+}
+
+/// The outcome of [`replay`]: how many steps reproduced, and the divergence that
+/// ended it early, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    pub steps: usize,
+    pub divergence: Option<Divergence>,
+}
+
+/// Re-run a trace against `code` on `engine`, offline: each recorded input is fed
+/// to a fresh instance under the recorded limits, its host calls are answered from
+/// the trace, and its sends and outcome are checked against the recording.
+pub fn replay<E>(engine: &E, code: &[u8], trace: impl BufRead) -> Result<Replay>
+where
+    E: Engine,
+    E::Module: AgentRuntime,
+{
+    let mut lines = trace.lines();
+    let header: TraceHeader = serde_json::from_str(&lines.next().ok_or_else(|| anyhow!("empty trace"))??)?;
+    if header.version != TRACE_VERSION {
+        bail!("trace format v{} (this build replays v{TRACE_VERSION})", header.version);
+    }
+    if header.code_hash != code_hash(code) {
+        bail!("trace was recorded against module {}, not this one", header.code_hash);
+    }
+    let limits = Limits { fuel: header.fuel, mem_bytes: header.mem_bytes as usize };
+    let mut m = engine.instantiate(code, limits, HostHooks::default())?;
+    let mut steps = 0;
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let step: Step = serde_json::from_str(&line)?;
+        m.tape().ok_or_else(|| anyhow!("this runtime cannot replay host calls"))?.replay(step.host_calls);
+        let ok = match &step.input {
+            Input::Init => m.init().is_ok(),
+            Input::Config { from, unl, body } => m.config(from, unl, body).is_ok(),
+            Input::Run => m.run().is_ok(),
+            Input::Tick { timer_id, now_ms } => m.tick(*timer_id, *now_ms).is_ok(),
+            Input::Restore { state } => m.restore(state).is_ok(),
+            Input::Shutdown => m.shutdown().is_ok(),
+        };
+        let sends = m.take_sends();
+        let diverged = m.tape().is_some_and(|t| t.diverged());
+        if let Some(tape) = m.tape() {
+            tape.stop();
+        }
+        let reason = if diverged {
+            Some("the guest made different host calls".to_string())
+        } else if ok != step.ok {
+            Some(format!("the call {} but was recorded as {}", verdict(ok), verdict(step.ok)))
+        } else if sends != step.sends {
+            Some(format!("emitted {} intents, recorded {} (or different ones)", sends.len(), step.sends.len()))
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Ok(Replay { steps, divergence: Some(Divergence { step: steps, input: step.input, reason }) });
+        }
+        steps += 1;
+    }
+    Ok(Replay { steps, divergence: None })
+}
+
+fn verdict(ok: bool) -> &'static str {
+    if ok {
+        "succeeded"
+    } else {
+        "failed"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::wasm::imports::testing;
+    use crate::wasm::{WasmtimeEngine, WasmRuntime};
+
+    /// A trace sink the test can read back.
+    #[derive(Clone, Default)]
+    struct Buf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buf {
+        fn write(&mut self, b: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(b);
+            Ok(b.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn limits() -> Limits {
+        Limits { fuel: 100_000_000, mem_bytes: 1 << 20 }
+    }
+
+    fn record(code: &[u8], buf: &Buf) -> Vec<OutboundIntent> {
+        let inner = WasmtimeEngine.instantiate(code, limits(), HostHooks::default()).unwrap();
+        let mut rec = Recorder::new(Box::new(inner), code, limits(), Box::new(buf.clone()));
+        assert!(rec.recording());
+        rec.set_keyring(Arc::new(testing::MirrorKeyring));
+        rec.init().unwrap();
+        rec.config("alice", b"agt(ping)", b"").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5)); // the clock moves on
+        rec.config("alice", b"agt(ping)", b"").unwrap();
+        rec.take_sends()
+    }
+
+    #[test]
+    fn a_recorded_session_replays_bit_for_bit() {
+        let code = wat::parse_str(testing::CLOCK_GUEST).unwrap();
+        let buf = Buf::default();
+        let live = record(&code, &buf);
+        assert_eq!(live.len(), 2);
+        assert_ne!(live[0].unl, live[1].unl); // two different clock readings
+
+        let trace = buf.0.lock().unwrap().clone();
+        let out = replay(&WasmtimeEngine, &code, &trace[..]).unwrap();
+        assert_eq!(out, Replay { steps: 3, divergence: None });
+    }
+
+    #[test]
+    fn a_different_module_or_tampered_output_is_reported() {
+        let code = wat::parse_str(testing::CLOCK_GUEST).unwrap();
+        let buf = Buf::default();
+        record(&code, &buf);
+        let trace = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+
+        let other = wat::parse_str(r#"(module (memory (export "memory") 1) (func (export "init")))"#).unwrap();
+        assert!(replay(&WasmtimeEngine, &other, trace.as_bytes()).is_err());
+
+        // rewrite the second delivery's emitted receiver: replay must notice
+        let mut lines: Vec<String> = trace.lines().map(String::from).collect();
+        let mut step: Step = serde_json::from_str(&lines[3]).unwrap();
+        step.sends[0].receiver = "mallory".into();
+        lines[3] = serde_json::to_string(&step).unwrap();
+        let out = replay(&WasmtimeEngine, &code, lines.join("\n").as_bytes()).unwrap();
+        let divergence = out.divergence.unwrap();
+        assert_eq!((out.steps, divergence.step), (2, 2));
+        assert!(matches!(divergence.input, Input::Config { .. }));
+    }
+
+    #[test]
+    fn the_tape_answers_host_calls_while_replaying() {
+        let code = wat::parse_str(testing::CLOCK_GUEST).unwrap();
+        let mut rt = WasmRuntime::new(&code, &Default::default()).unwrap();
+        let tape = rt.tape().unwrap();
+        tape.replay(vec![HostCall::Word(42), HostCall::Bytes(Ok(vec![7; 8]))]);
+        rt.config("x", b"", b"").unwrap();
+        let sends = rt.take_sends();
+        assert_eq!(sends[0].unl, 42i64.to_le_bytes());
+        assert_eq!(sends[0].body, [7; 8]);
+        assert!(!rt.tape().unwrap().diverged());
+    }
+
+    #[test]
+    fn the_tape_only_records_within_a_step() {
+        let mut tape = Tape::default();
+        tape.record();
+        assert_eq!(tape.word(1), 1);
+        assert_eq!(tape.stop(), [HostCall::Word(1)]);
+        // between steps: passed through, not recorded
+        assert_eq!(tape.word(2), 2);
+        assert_eq!(tape.bytes(Ok(vec![3])), Ok(vec![3]));
+        tape.record();
+        assert_eq!(tape.stop(), []);
+
+        // nor is a stopped replay drawn on or reported as diverged
+        tape.replay(vec![HostCall::Word(4), HostCall::Word(5)]);
+        assert_eq!(tape.word(0), 4);
+        assert!(tape.diverged());
+        tape.stop();
+        assert_eq!(tape.word(6), 6);
+        assert!(!tape.diverged());
+    }
+}
@@ -1,20 +1,23 @@
 // wasm/runtime.rs - Wasmtime Component Model Runtime
 
+use std::sync::OnceLock;
+
 use anyhow::{anyhow, Result};
 use wasmtime::*;
 
 use crate::adapters::{EngineModule, HostHooks, Limits};
+use crate::manifest::NodeProfile;
 use crate::proto;
-use super::host::{HostState, OutboundIntent};
+use super::cache::ModuleCache;
+use super::host::{CallUsage, GuestLog, HostState, OutboundIntent, StorageError};
+use super::imports::{link_host_imports, Guest};
 
 /// WASM Runtime for executing agent modules
 pub struct WasmRuntime {
     /// Wasmtime engine
-    #[allow(dead_code)]
     engine: Engine,
 
-    /// Compiled module
-    #[allow(dead_code)]
+    /// Compiled module (kept so [`WasmRuntime::reset`] can re-instantiate it)
     module: Module,
 
     /// Module bytecode (for migration)
@@ -31,6 +34,9 @@ pub struct WasmRuntime {
 
     /// The host import table (the `send-unl` sink the node drains) — Engine seam.
     hooks: HostHooks,
+
+    /// Wall-clock deadline armed before each entry point, in ms (audit L3).
+    deadline_ms: u64,
 }
 
 impl WasmRuntime {
@@ -41,147 +47,65 @@ impl WasmRuntime {
 
     /// Instantiate with an explicit host import table — the Engine seam's path.
     fn build(code: &[u8], capabilities: proto::AgentCapabilities, hooks: HostHooks) -> Result<Self> {
-        let mut config = Config::new();
-        config.wasm_component_model(true);
-        config.async_support(false);
-        config.consume_fuel(true);
-        // CPU is bounded by fuel: every guest instruction costs fuel and a runaway
-        // loop traps when the per-call budget is exhausted. A wall-clock epoch
-        // interrupt (audit L3) would only add value once a *blocking* host import
-        // exists (none do today — all host calls return immediately), and it needs a
-        // shared-engine watchdog; deferred until that architecture lands.
-        let engine = Engine::new(&config)?;
-        let module = Module::new(&engine, code)?;
+        let engine = new_engine()?;
+        let module = match ModuleCache::global() {
+            Some(cache) => cache.module(code)?,
+            None => Module::new(&engine, code)?,
+        };
+        Self::instantiate(engine, module, code.to_vec(), capabilities, hooks)
+    }
+
+    /// A fresh store + instance of an already-compiled module.
+    fn instantiate(
+        engine: Engine,
+        module: Module,
+        module_bytes: Vec<u8>,
+        capabilities: proto::AgentCapabilities,
+        hooks: HostHooks,
+    ) -> Result<Self> {
         let host_state = HostState::new(capabilities.clone());
         let mut store = Store::new(&engine, host_state);
         store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(deadline_ticks(DEFAULT_DEADLINE_MS));
         store.set_fuel(capabilities.max_execution_time_ms.max(1) as u64 * 1_000_000)?;
         let mut linker = Linker::new(&engine);
         Self::define_host_functions(&mut linker, &hooks)?;
         let instance = linker.instantiate(&mut store, &module)?;
-        Ok(Self { engine, module, module_bytes: code.to_vec(), store, instance, capabilities, hooks })
+        Ok(Self {
+            engine,
+            module,
+            module_bytes,
+            store,
+            instance,
+            capabilities,
+            hooks,
+            deadline_ms: DEFAULT_DEADLINE_MS,
+        })
     }
 
-    /// Define host functions in the linker
-    fn define_host_functions(linker: &mut Linker<HostState>, hooks: &HostHooks) -> Result<()> {
-        // Messaging functions
-        linker.func_wrap("fipa:agent/messaging", "send-message", |mut caller: Caller<'_, HostState>, _msg_ptr: i32, _msg_len: i32| -> i32 {
-            // Implementation would extract message from WASM memory and send
-            let state = caller.data_mut();
-            state.messages_sent += 1;
-            0 // Success
-        })?;
-
-        linker.func_wrap("fipa:agent/messaging", "receive-message", |mut caller: Caller<'_, HostState>| -> i64 {
-            let state = caller.data_mut();
-            if let Some(_msg) = state.mailbox.pop_front() {
-                // Return pointer to message in WASM memory
-                1 // Has message
-            } else {
-                0 // No message
-            }
-        })?;
-
-        linker.func_wrap("fipa:agent/messaging", "has-messages", |caller: Caller<'_, HostState>| -> i32 {
-            let state = caller.data();
-            if state.mailbox.is_empty() { 0 } else { 1 }
-        })?;
-
-        // The agent emits a message: send-unl(receiver, unl, body) as (ptr,len)
-        // triples into WASM memory. The node validates + packages + transmits it.
-        let sends = hooks.sends.clone();
-        linker.func_wrap(
-            "fipa:agent/messaging",
-            "send-unl",
-            move |mut caller: Caller<'_, HostState>,
-             rp: i32, rl: i32, up: i32, ul: i32, bp: i32, bl: i32| {
-                let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory())
-                else {
-                    return;
-                };
-                let read = |caller: &Caller<'_, HostState>, ptr: i32, len: i32| -> Vec<u8> {
-                    let data = memory.data(caller);
-                    let start = ptr as usize;
-                    // saturating add: a negative/huge ptr or len can never overflow
-                    // into a panic or an out-of-range slice (audit M10).
-                    data.get(start..start.saturating_add(len as usize))
-                        .map(<[u8]>::to_vec)
-                        .unwrap_or_default()
-                };
-                let receiver = String::from_utf8_lossy(&read(&caller, rp, rl)).into_owned();
-                let unl = read(&caller, up, ul);
-                let body = read(&caller, bp, bl);
-                // M3 — bound a guest's egress: a single message can't exceed 1 MiB and a
-                // single call can't queue more than MAX_QUEUED_SENDS intents, so a guest
-                // cannot amplify host-heap use beyond its own (capped) memory.
-                if unl.len() + body.len() > crate::adapters::MAX_SEND_BYTES {
-                    return;
-                }
-                let mut guard = sends.lock().unwrap_or_else(|e| e.into_inner());
-                if guard.len() >= crate::adapters::MAX_QUEUED_SENDS {
-                    return;
-                }
-                crate::flow!(
-                    "wasm: ← agent emitted send-unl → '{}' (unl={} bytes, body={} bytes)",
-                    receiver,
-                    unl.len(),
-                    body.len()
-                );
-                guard.push(OutboundIntent { receiver, unl, body });
-            },
+    /// Discard the instance and its host state and re-instantiate the compiled
+    /// module — the reuse path for stateless agents: no recompile, no relink of a
+    /// new runtime, and nothing of the previous tenant survives (fresh memory,
+    /// globals, mailbox, and state/keyring handles). Under the pooling allocator
+    /// the old instance's slot is recycled. `init` is left to the caller.
+    pub fn reset(&mut self) -> Result<()> {
+        self.hooks.sends.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        let fresh = Self::instantiate(
+            self.engine.clone(),
+            self.module.clone(),
+            std::mem::take(&mut self.module_bytes),
+            self.capabilities.clone(),
+            self.hooks.clone(),
         )?;
+        let deadline_ms = self.deadline_ms;
+        *self = fresh;
+        self.deadline_ms = deadline_ms;
+        Ok(())
+    }
 
-        // Lifecycle functions
-        linker.func_wrap("fipa:agent/lifecycle", "get-agent-id", |_caller: Caller<'_, HostState>| -> i64 {
-            // Return pointer to agent ID string
-            0
-        })?;
-
-        linker.func_wrap("fipa:agent/lifecycle", "request-shutdown", |mut caller: Caller<'_, HostState>| {
-            caller.data_mut().shutdown_requested = true;
-        })?;
-
-        linker.func_wrap("fipa:agent/lifecycle", "is-shutdown-requested", |caller: Caller<'_, HostState>| -> i32 {
-            if caller.data().shutdown_requested { 1 } else { 0 }
-        })?;
-
-        // Logging functions
-        linker.func_wrap("fipa:agent/logging", "log", |mut caller: Caller<'_, HostState>, _level: i32, _msg_ptr: i32, _msg_len: i32| {
-            // Read message from WASM memory and log
-            let state = caller.data_mut();
-            state.log_count += 1;
-        })?;
-
-        // Storage functions
-        linker.func_wrap("fipa:agent/storage", "store", |_caller: Caller<'_, HostState>, _key_ptr: i32, _key_len: i32, _val_ptr: i32, _val_len: i32| -> i32 {
-            // Store data
-            0 // Success
-        })?;
-
-        linker.func_wrap("fipa:agent/storage", "load", |_caller: Caller<'_, HostState>, _key_ptr: i32, _key_len: i32| -> i64 {
-            // Load data, return pointer
-            0
-        })?;
-
-        // Timing functions
-        linker.func_wrap("fipa:agent/timing", "now", |_caller: Caller<'_, HostState>| -> i64 {
-            chrono::Utc::now().timestamp_millis()
-        })?;
-
-        linker.func_wrap("fipa:agent/timing", "monotonic-now", |_caller: Caller<'_, HostState>| -> i64 {
-            std::time::Instant::now().elapsed().as_nanos() as i64
-        })?;
-
-        // Migration functions
-        linker.func_wrap("fipa:agent/migration", "get-current-node", |_caller: Caller<'_, HostState>| -> i64 {
-            // Return pointer to node ID
-            0
-        })?;
-
-        linker.func_wrap("fipa:agent/migration", "is-migrating", |caller: Caller<'_, HostState>| -> i32 {
-            if caller.data().is_migrating { 1 } else { 0 }
-        })?;
-
+    /// Define host functions in the linker — the shared core-module import table.
+    fn define_host_functions(linker: &mut Linker<HostState>, hooks: &HostHooks) -> Result<()> {
+        link_host_imports!(linker, Caller<'_, HostState>, hooks);
         Ok(())
     }
 
@@ -192,25 +116,32 @@ impl WasmRuntime {
         (self.capabilities.max_execution_time_ms.max(1) as u64).saturating_mul(1_000_000)
     }
 
-    /// Call the agent's init function (via the Engine seam).
-    pub fn call_init(&mut self) -> Result<()> {
+    /// Run one entry point under a fresh fuel budget and note what it cost —
+    /// fuel burned (trapped calls included) and the memory high-water mark.
+    fn metered<T>(&mut self, entry: &'static str, call: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
         let fuel = self.call_fuel();
         self.refuel(fuel);
-        self.call_void("init")
+        let result = call(self);
+        let spent = fuel.saturating_sub(self.store.get_fuel().unwrap_or(0));
+        let host = self.store.data_mut();
+        let peak = host.limits.peak();
+        host.record_usage(entry, spent, peak);
+        result
+    }
+
+    /// Call the agent's init function (via the Engine seam).
+    pub fn call_init(&mut self) -> Result<()> {
+        self.metered("init", |rt| rt.call_void("init"))
     }
 
     /// Call the agent's run function.
     pub fn call_run(&mut self) -> Result<bool> {
-        let fuel = self.call_fuel();
-        self.refuel(fuel);
-        Ok(self.call_i32("run")? != 0)
+        self.metered("run", |rt| Ok(rt.call_i32("run")? != 0))
     }
 
     /// Call the agent's shutdown function.
     pub fn call_shutdown(&mut self) -> Result<()> {
-        let fuel = self.call_fuel();
-        self.refuel(fuel);
-        self.call_void("shutdown")
+        self.metered("shutdown", |rt| rt.call_void("shutdown"))
     }
 
     /// Handle an incoming message
@@ -223,7 +154,9 @@ impl WasmRuntime {
             .get_typed_func::<(i32, i32), i32>(&mut self.store, "handle-message")
         {
             // Would pass message pointer and length
-            let result = handle_msg.call(&mut self.store, (0, 0))?;
+            let result = self.metered("handle-message", |rt| {
+                handle_msg.call(&mut rt.store, (0, 0)).map_err(|e| deadline_trap(e, rt.deadline_ms))
+            })?;
             Ok(result != 0)
         } else {
             // No handle-message export, will be processed in run()
@@ -238,10 +171,9 @@ impl WasmRuntime {
     /// startup to seed state and again per inbound message. A guest without a
     /// `config` export is a graceful no-op.
     pub fn call_config(&mut self, unl: &[u8], body: &[u8]) -> Result<()> {
-        let fuel = self.call_fuel();
-        self.refuel(fuel);
         crate::flow!("wasm: → config(unl={} bytes, body={} bytes)", unl.len(), body.len());
-        self.call_io("config", &[unl, body])?; // Ok(false) if absent → graceful no-op
+        self.store.data_mut().set_conversation(body);
+        self.metered("config", |rt| rt.call_io("config", &[unl, body]))?; // Ok(false) if absent → graceful no-op
         Ok(())
     }
 
@@ -249,10 +181,26 @@ impl WasmRuntime {
     /// (from-aware). Returns `Ok(false)` if the guest has no `deliver` export, so
     /// the caller can fall back to `call_config`.
     pub fn call_deliver(&mut self, from: &[u8], unl: &[u8], body: &[u8]) -> Result<bool> {
-        let fuel = self.call_fuel();
-        self.refuel(fuel);
         crate::flow!("wasm: → deliver(from={} bytes, unl={} bytes)", from.len(), unl.len());
-        self.call_io("deliver", &[from, unl, body])
+        self.store.data_mut().set_conversation(body);
+        self.metered("deliver", |rt| rt.call_io("deliver", &[from, unl, body]))
+    }
+
+    /// Set the per-call wall-clock deadline — the node derives it from the agent's
+    /// `Budget::wall_ms`. A call that overruns traps with [`DeadlineExceeded`].
+    pub fn set_deadline(&mut self, ms: u64) {
+        self.deadline_ms = ms;
+    }
+
+    /// Provision the agent's namespaced durable store — the backing of the
+    /// `fipa:agent/storage` imports (`state` capability).
+    pub fn set_kv(&mut self, kv: std::sync::Arc<dyn unl_agent::Kv>) {
+        self.store.data_mut().state = Some(kv);
+    }
+
+    /// The agent's host state (its queued requests and provisioned handles).
+    pub(super) fn host_mut(&mut self) -> &mut HostState {
+        self.store.data_mut()
     }
 
     /// Drain the UNL send intents the agent emitted via `send-unl`. The node
@@ -262,19 +210,43 @@ impl WasmRuntime {
         std::mem::take(&mut *self.hooks.sends.lock().unwrap_or_else(|e| e.into_inner()))
     }
 
+    /// Drain the log lines the agent emitted (after the per-agent rate limit).
+    pub fn take_logs(&mut self) -> Vec<GuestLog> {
+        self.store.data_mut().take_logs()
+    }
+
+    /// Drain the cost of the entry-point calls made since the last drain.
+    pub fn take_usage(&mut self) -> Vec<CallUsage> {
+        std::mem::take(&mut self.store.data_mut().usage)
+    }
+
+    /// Provision the node-held keyring behind the `fipa:agent/crypto` imports
+    /// (`crypto` capability).
+    pub fn set_keyring(&mut self, kr: std::sync::Arc<dyn unl_agent::Keyring>) {
+        self.store.data_mut().keyring = Some(kr);
+    }
+
+    /// Fire timer `timer_id` into the agent's `tick(timer_id, now_ms)` export; a
+    /// guest without one ignores its timers.
+    pub fn call_tick(&mut self, timer_id: u64, now_ms: u64) -> Result<()> {
+        let Ok(tick) = self.instance.get_typed_func::<(i64, i64), ()>(&mut self.store, "tick") else {
+            return Ok(());
+        };
+        self.store.data_mut().conversation = None; // a tick answers no message
+        self.metered("tick", |rt| {
+            tick.call(&mut rt.store, (timer_id as i64, now_ms as i64)).map_err(|e| deadline_trap(e, rt.deadline_ms))
+        })
+    }
+
     /// Capture the agent's state via its `snapshot` export (state-based migration).
     /// Empty if the guest exports no `snapshot` (a stateless agent).
     pub fn call_snapshot(&mut self) -> Vec<u8> {
-        let fuel = self.call_fuel();
-        self.refuel(fuel);
-        self.call_packed("snapshot").unwrap_or_default()
+        self.metered("snapshot", |rt| rt.call_packed("snapshot")).unwrap_or_default()
     }
 
     /// Restore state captured by [`Self::call_snapshot`] via the `restore` export.
-    pub fn call_restore(&mut self, state: &[u8]) {
-        let fuel = self.call_fuel();
-        self.refuel(fuel);
-        let _ = self.call_io("restore", &[state]);
+    pub fn call_restore(&mut self, state: &[u8]) -> Result<()> {
+        self.metered("restore", |rt| rt.call_io("restore", &[state])).map(|_| ())
     }
 
     /// Allocate `n` bytes in WASM memory via the guest's `alloc` export.
@@ -359,11 +331,170 @@ impl WasmRuntime {
     }
 }
 
+/// Watchdog period: the shared engine's epoch advances every `EPOCH_TICK_MS`, so a
+/// wall-clock deadline is enforced to within one tick.
+pub const EPOCH_TICK_MS: u64 = 10;
+
+/// The deadline a call gets when the node hasn't set one from the agent's budget.
+pub const DEFAULT_DEADLINE_MS: u64 = 1_000;
+
+/// A call was interrupted at its wall-clock deadline (audit L3) — distinct from a
+/// fuel exhaustion or guest trap, so the supervisor can report it as an SLO breach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("call exceeded its {ms} ms wall-clock deadline")]
+pub struct DeadlineExceeded {
+    pub ms: u64,
+}
+
+/// Sizing for the shared engine's optional pooling instance allocator. Pooled
+/// slots are preallocated and recycled, so instantiation skips `mmap`/`munmap`
+/// and memory is re-initialized copy-on-write — what makes thousands of
+/// short-lived agents cheap. A slot is sized for a component agent, which runs
+/// several core instances (its main module plus adapters and shims), each with
+/// its own memories and tables; a core-module agent takes one of each and leaves
+/// the rest of its slot's share to other core-module agents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pooling {
+    /// Concurrent component agents the pool holds; instantiation past this fails.
+    pub instances: u32,
+    /// Largest linear memory a pooled slot can hold, in bytes.
+    pub max_memory_bytes: u64,
+}
+
+impl Pooling {
+    /// Slots sized to `profile`'s memory ceiling (at most the 4 GiB wasm32 space).
+    pub fn for_profile(profile: &NodeProfile, instances: u32) -> Self {
+        Pooling { instances, max_memory_bytes: profile.ceiling.mem_kb.saturating_mul(1024).min(1 << 32) }
+    }
+}
+
+/// Core instances, memories and tables one pooled component slot may use.
+const SLOT_CORE_INSTANCES: u32 = 8;
+const SLOT_MEMORIES: u32 = 2;
+const SLOT_TABLES: u32 = 4;
+
+static ENGINE: OnceLock<Engine> = OnceLock::new();
+static POOLING: OnceLock<Pooling> = OnceLock::new();
+
+/// Switch the shared engine to the pooling allocator. Must run before the first
+/// agent is instantiated (the engine's allocator is fixed when it is built).
+pub fn enable_pooling(pooling: Pooling) -> Result<()> {
+    if ENGINE.get().is_some() {
+        return Err(anyhow!("the shared engine is already built; enable pooling before mounting agents"));
+    }
+    POOLING.set(pooling).map_err(|_| anyhow!("pooling is already configured"))
+}
+
+/// The shared engine's configuration, with the pooling allocator if `pooling`.
+fn engine_config(pooling: Option<Pooling>) -> Config {
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+    config.async_support(false);
+    config.consume_fuel(true);
+    config.epoch_interruption(true);
+    if let Some(p) = pooling {
+        let mut pool = PoolingAllocationConfig::default();
+        pool.total_component_instances(p.instances)
+            .total_core_instances(p.instances.saturating_mul(SLOT_CORE_INSTANCES))
+            .total_memories(p.instances.saturating_mul(SLOT_MEMORIES))
+            .total_tables(p.instances.saturating_mul(SLOT_TABLES))
+            .max_core_instances_per_component(SLOT_CORE_INSTANCES)
+            .max_memories_per_component(SLOT_MEMORIES)
+            .max_tables_per_component(SLOT_TABLES)
+            .max_memory_size(p.max_memory_bytes as usize);
+        config.allocation_strategy(InstanceAllocationStrategy::Pooling(pool));
+    }
+    config
+}
+
+/// The node-wide engine, shared by every core-module and component agent. It is
+/// fuel-metered (CPU, H3/R7) and epoch-interrupted (wall clock, L3): one watchdog
+/// thread bumps the epoch every [`EPOCH_TICK_MS`], and each call arms its store's
+/// deadline via [`deadline_ticks`]. The epoch bounds wall-clock time that fuel
+/// does not, but it only interrupts guest code: a host import (or a memory grow)
+/// in progress runs to completion, and the deadline trips once control is back in
+/// the guest. Host imports therefore bound their own time: each is local and
+/// quota-bounded, and none waits on the network or another agent (`infer` and
+/// sends are queued, answered later).
+pub(super) fn new_engine() -> Result<Engine> {
+    if let Some(engine) = ENGINE.get() {
+        return Ok(engine.clone());
+    }
+    let fresh = Engine::new(&engine_config(POOLING.get().copied()))?;
+    let mut won = false;
+    let engine = ENGINE.get_or_init(|| {
+        won = true;
+        fresh
+    });
+    if won {
+        let watched = engine.clone();
+        std::thread::Builder::new().name("wasm-epoch".into()).spawn(move || loop {
+            std::thread::sleep(std::time::Duration::from_millis(EPOCH_TICK_MS));
+            watched.increment_epoch();
+        })?;
+    }
+    Ok(engine.clone())
+}
+
+/// Epoch ticks for a `ms` deadline. One extra tick covers the partial tick already
+/// elapsed when the deadline is armed, so a call always gets at least `ms`.
+pub(super) fn deadline_ticks(ms: u64) -> u64 {
+    ms.div_ceil(EPOCH_TICK_MS).max(1) + 1
+}
+
+/// Map a wasmtime epoch interrupt to [`DeadlineExceeded`]; any other error passes
+/// through unchanged.
+pub(super) fn deadline_trap(e: anyhow::Error, ms: u64) -> anyhow::Error {
+    if e.downcast_ref::<Trap>() == Some(&Trap::Interrupt) {
+        DeadlineExceeded { ms }.into()
+    } else {
+        e
+    }
+}
+
+impl Guest for Caller<'_, HostState> {
+    fn host(&mut self) -> &mut HostState {
+        self.data_mut()
+    }
+
+    fn try_read(&mut self, ptr: i32, len: i32) -> Option<Vec<u8>> {
+        let memory = self.get_export("memory").and_then(|e| e.into_memory())?;
+        let start = ptr as u32 as usize;
+        memory.data(&*self).get(start..start.saturating_add(len as u32 as usize)).map(<[u8]>::to_vec)
+    }
+
+    fn give(&mut self, bytes: &[u8]) -> i64 {
+        let io_error = -(StorageError::IoError(String::new()).code() as i64);
+        let Some(alloc) = self
+            .get_export("alloc")
+            .and_then(|e| e.into_func())
+            .and_then(|f| f.typed::<i32, i32>(&*self).ok())
+        else {
+            return io_error;
+        };
+        let Ok(ptr) = alloc.call(&mut *self, bytes.len() as i32) else {
+            return io_error;
+        };
+        let Some(memory) = self.get_export("memory").and_then(|e| e.into_memory()) else {
+            return io_error;
+        };
+        let start = ptr as u32 as usize;
+        match memory.data_mut(&mut *self).get_mut(start..start.saturating_add(bytes.len())) {
+            Some(dst) => {
+                dst.copy_from_slice(bytes);
+                ((ptr as u32 as i64) << 32) | bytes.len() as i64
+            }
+            None => io_error,
+        }
+    }
+}
+
 // The wasmtime backend's implementation of the Engine seam: the five mechanical
 // ops every wasm engine (wasmtime today; wasmi/browser next) must provide.
 impl EngineModule for WasmRuntime {
     fn refuel(&mut self, fuel: u64) {
         let _ = self.store.set_fuel(fuel);
+        self.store.set_epoch_deadline(deadline_ticks(self.deadline_ms));
     }
 
     fn call_void(&mut self, func: &str) -> Result<()> {
@@ -371,7 +502,7 @@ impl EngineModule for WasmRuntime {
             .instance
             .get_typed_func::<(), ()>(&mut self.store, func)
             .map_err(|_| anyhow!("{func} not found"))?;
-        f.call(&mut self.store, ())?;
+        f.call(&mut self.store, ()).map_err(|e| deadline_trap(e, self.deadline_ms))?;
         Ok(())
     }
 
@@ -380,7 +511,7 @@ impl EngineModule for WasmRuntime {
             .instance
             .get_typed_func::<(), i32>(&mut self.store, func)
             .map_err(|_| anyhow!("{func} not found"))?;
-        Ok(f.call(&mut self.store, ())?)
+        f.call(&mut self.store, ()).map_err(|e| deadline_trap(e, self.deadline_ms))
     }
 
     fn call_io(&mut self, func: &str, args: &[&[u8]]) -> Result<bool> {
@@ -394,7 +525,7 @@ impl EngineModule for WasmRuntime {
             params.push(Val::I32(ptr));
             params.push(Val::I32(a.len() as i32));
         }
-        f.call(&mut self.store, &params, &mut [])?;
+        f.call(&mut self.store, &params, &mut []).map_err(|e| deadline_trap(e, self.deadline_ms))?;
         Ok(true)
     }
 
@@ -403,7 +534,7 @@ impl EngineModule for WasmRuntime {
             Ok(f) => f,
             Err(_) => return Ok(Vec::new()),
         };
-        let packed = f.call(&mut self.store, ())?;
+        let packed = f.call(&mut self.store, ()).map_err(|e| deadline_trap(e, self.deadline_ms))?;
         let ptr = (packed >> 32) as usize;
         let len = (packed & 0xffff_ffff) as usize;
         let Some(memory) = self.instance.get_memory(&mut self.store, "memory") else {
@@ -442,6 +573,7 @@ impl std::fmt::Debug for WasmRuntime {
 #[cfg(test)]
 mod config_abi_tests {
     use super::*;
+    use crate::wasm::LogLevel;
 
     // A guest that exports memory + a bump `alloc` + `init` + `config`. Its
     // `config` copies the received UNL bytes to offset 0 and the body to offset
@@ -499,6 +631,21 @@ mod config_abi_tests {
         assert!(rt.call_config(b"x", b"y").is_err());
     }
 
+    #[test]
+    fn looping_agent_is_capped_by_its_wall_clock_deadline() {
+        // fuel far beyond the deadline, so only the epoch watchdog can stop it (L3)
+        let caps = proto::AgentCapabilities { max_execution_time_ms: u32::MAX as u64, ..Default::default() };
+        let mut rt = WasmRuntime::new(LOOP_GUEST.as_bytes(), &caps).unwrap();
+        rt.call_init().unwrap();
+        rt.set_deadline(50);
+        let started = std::time::Instant::now();
+        let err = rt.call_config(b"x", b"y").unwrap_err();
+        assert_eq!(err.downcast_ref::<DeadlineExceeded>(), Some(&DeadlineExceeded { ms: 50 }));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        // the next call gets a fresh deadline rather than tripping on the stale one
+        assert!(rt.call_init().is_ok());
+    }
+
     // A counter: each `deliver` increments n; `snapshot` returns n (4 bytes LE);
     // `restore` sets n. Exercises state-based migration of a wasm agent.
     const COUNTER_GUEST: &str = r#"
@@ -536,10 +683,80 @@ mod config_abi_tests {
         // destination: a fresh instance restores the captured state
         let mut b = WasmRuntime::new(COUNTER_GUEST.as_bytes(), &caps()).unwrap();
         b.call_init().unwrap();
-        b.restore(&snap);
+        b.restore(&snap).unwrap();
         assert_eq!(b.snapshot(), vec![3, 0, 0, 0]); // state migrated
     }
 
+    #[test]
+    fn each_entry_point_reports_its_fuel_and_memory_peak() {
+        use crate::wasm::AgentRuntime;
+        let mut rt = WasmRuntime::new(COUNTER_GUEST.as_bytes(), &caps()).unwrap();
+        rt.config("x", b"inc", b"").unwrap();
+        rt.snapshot();
+        let usage = rt.take_usage();
+        // `deliver` took the message, so `config` never ran and is not billed
+        assert_eq!(usage.iter().map(|u| u.entry).collect::<Vec<_>>(), ["deliver", "snapshot"]);
+        assert!(usage.iter().all(|u| u.fuel > 0 && u.mem_bytes == 65_536)); // one page
+        assert!(rt.take_usage().is_empty());
+    }
+
+    #[test]
+    fn reset_reuses_the_module_with_nothing_left_of_the_previous_tenant() {
+        use crate::wasm::AgentRuntime;
+        let mut rt = WasmRuntime::new(COUNTER_GUEST.as_bytes(), &caps()).unwrap();
+        rt.call_init().unwrap();
+        rt.set_deadline(250);
+        for _ in 0..2 {
+            rt.config("x", b"inc", b"").unwrap();
+        }
+        assert_eq!(rt.snapshot(), vec![2, 0, 0, 0]);
+        rt.reset().unwrap();
+        rt.call_init().unwrap();
+        assert_eq!(rt.snapshot(), vec![0, 0, 0, 0]); // fresh memory and globals
+        assert_eq!(rt.deadline_ms, 250); // the node-set budget carries over
+    }
+
+    #[test]
+    fn the_pooling_allocator_recycles_a_bounded_set_of_slots() {
+        let pooling = Pooling { instances: 2, max_memory_bytes: 1 << 20 };
+        let engine = Engine::new(&engine_config(Some(pooling))).unwrap();
+        let module = Module::new(&engine, r#"(module (memory (export "memory") 1))"#).unwrap();
+        let spawn = || {
+            let mut store = Store::new(&engine, ());
+            Instance::new(&mut store, &module, &[]).map(|_| store)
+        };
+        // two component slots hold as many single-memory core modules as they have memories
+        let a = spawn().unwrap();
+        let _rest: Vec<_> = (1..2 * SLOT_MEMORIES).map(|_| spawn().unwrap()).collect();
+        assert!(spawn().is_err()); // the pool is full
+        drop(a);
+        assert!(spawn().is_ok()); // a's slot is reused
+    }
+
+    #[test]
+    fn a_pooled_slot_fits_a_component_with_several_memories() {
+        let pooling = Pooling { instances: 2, max_memory_bytes: 1 << 20 };
+        let engine = Engine::new(&engine_config(Some(pooling))).unwrap();
+        // a main module and an adapter, each with its own memory and table
+        let component = component::Component::new(
+            &engine,
+            r#"(component
+                 (core module $main (memory (export "memory") 1) (table 1 funcref))
+                 (core module $adapter (memory (export "memory") 1) (table 1 funcref))
+                 (core instance (instantiate $main))
+                 (core instance (instantiate $adapter)))"#,
+        )
+        .unwrap();
+        let linker = component::Linker::<()>::new(&engine);
+        let spawn = || {
+            let mut store = Store::new(&engine, ());
+            linker.instantiate(&mut store, &component).map(|_| store)
+        };
+        let _a = spawn().unwrap();
+        let _b = spawn().unwrap();
+        assert!(spawn().is_err()); // one slot per component agent
+    }
+
     #[test]
     fn oversized_memory_is_refused() {
         // a guest demanding 100 pages (6.4 MiB) against a 1 MiB cap won't instantiate
@@ -593,6 +810,201 @@ mod config_abi_tests {
         assert!(rt.take_unl_sends().is_empty());
     }
 
+    // A guest exercising `fipa:agent/storage`: keys "k1" at 0 and "k2" at 8, the
+    // value "hello" at 16, a bump `alloc` for values the host hands back.
+    const STORAGE_GUEST: &str = r#"
+    (module
+      (import "fipa:agent/storage" "store" (func $store (param i32 i32 i32 i32) (result i32)))
+      (import "fipa:agent/storage" "load" (func $load (param i32 i32) (result i64)))
+      (import "fipa:agent/storage" "delete" (func $delete (param i32 i32) (result i32)))
+      (import "fipa:agent/storage" "exists" (func $exists (param i32 i32) (result i32)))
+      (import "fipa:agent/storage" "list-keys-with-prefix" (func $list (param i32 i32) (result i64)))
+      (import "fipa:agent/storage" "get-usage" (func $usage (result i64)))
+      (memory (export "memory") 1)
+      (global $bump (mut i32) (i32.const 1024))
+      (data (i32.const 0) "k1")
+      (data (i32.const 8) "k2")
+      (data (i32.const 16) "hello")
+      (func (export "init"))
+      (func (export "alloc") (param $n i32) (result i32)
+        (local $p i32)
+        (local.set $p (global.get $bump))
+        (global.set $bump (i32.add (global.get $bump) (local.get $n)))
+        (local.get $p))
+      (func (export "put1") (result i32)
+        (call $store (i32.const 0) (i32.const 2) (i32.const 16) (i32.const 5)))
+      (func (export "put2") (result i32)
+        (call $store (i32.const 8) (i32.const 2) (i32.const 16) (i32.const 5)))
+      (func (export "put_oob_key") (result i32)
+        (call $store (i32.const 65535) (i32.const 2) (i32.const 16) (i32.const 5)))
+      (func (export "put_oob_value") (result i32)
+        (call $store (i32.const 8) (i32.const 2) (i32.const 65534) (i32.const 5)))
+      (func (export "get1") (result i64) (call $load (i32.const 0) (i32.const 2)))
+      (func (export "del1") (result i32) (call $delete (i32.const 0) (i32.const 2)))
+      (func (export "has1") (result i32) (call $exists (i32.const 0) (i32.const 2)))
+      (func (export "keys") (result i64) (call $list (i32.const 0) (i32.const 1)))
+      (func (export "usage") (result i64) (call $usage)))
+    "#;
+
+    /// An in-memory namespaced store with a byte quota (the node's `ScopedKv` shape).
+    struct MemKv {
+        map: std::sync::Mutex<std::collections::BTreeMap<String, Vec<u8>>>,
+        quota: u64,
+    }
+    impl unl_agent::Kv for MemKv {
+        fn get(&self, key: &str) -> Option<Vec<u8>> {
+            self.map.lock().unwrap().get(key).cloned()
+        }
+        fn put(&self, key: &str, val: &[u8]) {
+            self.map.lock().unwrap().insert(key.into(), val.to_vec());
+        }
+        fn del(&self, key: &str) {
+            self.map.lock().unwrap().remove(key);
+        }
+        fn keys(&self, prefix: &str) -> Vec<String> {
+            self.map.lock().unwrap().keys().filter(|k| k.starts_with(prefix)).cloned().collect()
+        }
+        fn usage(&self) -> u64 {
+            self.map.lock().unwrap().values().map(|v| v.len() as u64).sum()
+        }
+        fn quota(&self) -> u64 {
+            self.quota
+        }
+    }
+
+    fn call_i64(rt: &mut WasmRuntime, func: &str) -> i64 {
+        let f = rt.instance.get_typed_func::<(), i64>(&mut rt.store, func).unwrap();
+        let fuel = rt.call_fuel();
+        rt.refuel(fuel);
+        f.call(&mut rt.store, ()).unwrap()
+    }
+
+    #[test]
+    fn storage_imports_round_trip_through_the_namespaced_store() {
+        let kv = std::sync::Arc::new(MemKv { map: Default::default(), quota: 8 });
+        let mut rt = WasmRuntime::new(STORAGE_GUEST.as_bytes(), &caps()).unwrap();
+        rt.set_kv(kv.clone());
+        rt.call_init().unwrap();
+
+        assert_eq!(rt.call_i32("put1").unwrap(), 0);
+        assert_eq!(unl_agent::Kv::get(&*kv, "k1"), Some(b"hello".to_vec())); // durable
+        assert_eq!(rt.call_packed("get1").unwrap(), b"hello");
+        assert_eq!(rt.call_i32("has1").unwrap(), 1);
+        assert_eq!(call_i64(&mut rt, "usage"), 5);
+        assert_eq!(rt.call_packed("keys").unwrap(), [&[0, 0, 0, 2][..], b"k1"].concat());
+
+        // 5 + 5 bytes against an 8-byte state_kb quota → quota-exceeded, no write
+        assert_eq!(rt.call_i32("put2").unwrap(), StorageError::QuotaExceeded.code());
+        assert_eq!(unl_agent::Kv::get(&*kv, "k2"), None);
+
+        assert_eq!(rt.call_i32("del1").unwrap(), 0);
+        assert_eq!(rt.call_i32("del1").unwrap(), 1); // not-found
+        assert_eq!(call_i64(&mut rt, "get1"), -1);
+        assert_eq!(rt.call_i32("has1").unwrap(), 0);
+    }
+
+    #[test]
+    fn an_out_of_bounds_key_or_value_is_an_error_not_an_empty_write() {
+        let kv = std::sync::Arc::new(MemKv { map: Default::default(), quota: 64 });
+        let mut rt = WasmRuntime::new(STORAGE_GUEST.as_bytes(), &caps()).unwrap();
+        rt.set_kv(kv.clone());
+        rt.call_init().unwrap();
+        let io = StorageError::IoError(String::new()).code();
+        assert_eq!(rt.call_i32("put_oob_key").unwrap(), io);
+        assert_eq!(rt.call_i32("put_oob_value").unwrap(), io);
+        assert_eq!(unl_agent::Kv::usage(&*kv), 0); // neither "" nor "k2" was written
+    }
+
+    #[test]
+    fn storage_imports_deny_without_the_state_capability() {
+        let mut rt = WasmRuntime::new(STORAGE_GUEST.as_bytes(), &caps()).unwrap();
+        rt.call_init().unwrap();
+        let denied = StorageError::PermissionDenied.code();
+        assert_eq!(rt.call_i32("put1").unwrap(), denied);
+        assert_eq!(call_i64(&mut rt, "get1"), -(denied as i64));
+        assert_eq!(rt.call_i32("has1").unwrap(), 0);
+        assert_eq!(call_i64(&mut rt, "usage"), 0);
+    }
+
+    // A guest that logs: "hi" at info, then "order" at warn with fields
+    // {cid: c7, sku: LtG} (length-prefixed, alternating key/value).
+    const LOG_GUEST: &str = r#"
+    (module
+      (import "fipa:agent/logging" "log" (func $log (param i32 i32 i32)))
+      (import "fipa:agent/logging" "log-structured" (func $logs (param i32 i32 i32 i32 i32)))
+      (memory (export "memory") 1)
+      (data (i32.const 0) "hi")
+      (data (i32.const 8) "order")
+      (data (i32.const 16) "\00\00\00\03cid\00\00\00\02c7\00\00\00\03sku\00\00\00\03LtG")
+      (func (export "init"))
+      (func (export "run") (result i32)
+        (call $log (i32.const 2) (i32.const 0) (i32.const 2))
+        (call $logs (i32.const 3) (i32.const 8) (i32.const 5) (i32.const 16) (i32.const 27))
+        (i32.const 0)))
+    "#;
+
+    #[test]
+    fn guest_logs_are_decoded_and_tagged() {
+        let mut rt = WasmRuntime::new(LOG_GUEST.as_bytes(), &caps()).unwrap();
+        rt.call_init().unwrap();
+        rt.call_run().unwrap();
+        let logs = rt.take_logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!((logs[0].level, logs[0].message.as_str()), (LogLevel::Info, "hi"));
+        assert_eq!(logs[1].level, LogLevel::Warn);
+        assert_eq!(logs[1].conversation.as_deref(), Some("c7")); // from the cid field
+        assert_eq!(logs[1].fields_text(), "cid=c7 sku=LtG");
+        assert!(rt.take_logs().is_empty()); // drained
+    }
+
+    #[test]
+    fn guest_logs_are_rate_limited_per_agent() {
+        let mut rt = WasmRuntime::new(LOG_GUEST.as_bytes(), &caps()).unwrap();
+        rt.call_init().unwrap();
+        for _ in 0..40 {
+            rt.call_run().unwrap(); // 80 lines in well under a second
+        }
+        assert_eq!(rt.take_logs().len(), crate::wasm::MAX_LOG_LINES_PER_SEC as usize);
+    }
+
+    #[test]
+    fn guest_log_fields_are_held_to_the_line_cap() {
+        let mut host = HostState::new(caps());
+        let big = "v".repeat(crate::wasm::host::MAX_LOG_LINE_BYTES);
+        let fields = vec![("k".into(), big.clone()), ("dropped".into(), big)];
+        host.guest_log(LogLevel::Info, "m".into(), fields);
+        let logs = host.take_logs();
+        let bytes: usize = logs[0].fields.iter().map(|(k, v)| k.len() + v.len()).sum();
+        assert_eq!(bytes, crate::wasm::host::MAX_LOG_LINE_BYTES);
+        assert_eq!(logs[0].fields.len(), 1);
+    }
+
+    #[test]
+    fn the_gated_host_surface_queues_requests_and_uses_the_keyring() {
+        use crate::wasm::imports::testing::{MirrorKeyring, SURFACE_GUEST};
+        use crate::wasm::AgentRuntime;
+        use unl_agent::{InferReq, TimerOp};
+        let mut rt = WasmRuntime::new(SURFACE_GUEST.as_bytes(), &caps()).unwrap();
+        rt.call_init().unwrap();
+        assert!(rt.call_packed("sig").unwrap().is_empty()); // no crypto grant → denied
+        assert_eq!(rt.call_i32("ok").unwrap(), 0);
+
+        rt.run().unwrap();
+        assert_eq!(rt.take_timer_ops(), vec![TimerOp::Set { id: 7, delay_ms: 250 }, TimerOp::Cancel { id: 8 }]);
+        assert_eq!(rt.take_infer_reqs(), vec![InferReq { req_id: 42, prompt: "hello".into() }]);
+        let spawns = rt.take_spawn_reqs();
+        assert_eq!((spawns[0].uuid.as_str(), spawns[0].code.as_slice()), ("kid", &b"\0asm"[..]));
+        assert_eq!(rt.take_logs().len(), 1);
+        rt.tick(9, 0).unwrap();
+        assert_eq!(rt.take_timer_ops(), vec![TimerOp::Set { id: 9, delay_ms: 100 }]);
+
+        rt.set_keyring(std::sync::Arc::new(MirrorKeyring));
+        assert_eq!(rt.call_packed("sig").unwrap(), b"olleh");
+        assert_eq!(rt.call_packed("pk").unwrap(), b"mirror");
+        assert_eq!(rt.call_packed("rand").unwrap(), [0x5a; 16]);
+        assert_eq!(rt.call_i32("ok").unwrap(), 1);
+    }
+
     // End-to-end: a Rust agent compiled to wasm32 via unl_agent::export_agent!.
     // Skips if the sample agent hasn't been built for wasm32.
     #[test]
@@ -6,17 +6,32 @@
 //! by profile, the agent code is unchanged.
 //!
 //! NB: wasmi consumes **binary** wasm (it does not parse WAT). CPU is bounded by
-//! fuel; a hard memory cap is a follow-up (wasmi modules are small on IoT).
+//! fuel and linear memory by a store limiter. The host imports are the shared
+//! [`super::imports`] table, so state, time, crypto, log, spawn and infer behave
+//! exactly as under wasmtime; only the per-call wall-clock deadline is
+//! wasmtime-only (the interpreter has no epoch interruption).
 
 use anyhow::{anyhow, Result};
-use wasmi::{Caller, Config, Engine as WasmiCore, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Val};
+use wasmi::{Caller, Config, Engine as WasmiCore, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Val};
 
 use crate::adapters::{EngineModule, HostHooks, Limits};
+use crate::proto;
 use crate::wasm::{AgentRuntime, OutboundIntent};
 
+use super::host::{CallUsage, GuestLog, HostState, StorageError};
+use super::imports::{link_host_imports, Guest};
+use super::replay::Tape;
+
+/// Store data for a wasmi agent: the [`HostState`] behind the host imports, plus
+/// wasmi's own memory limiter.
+struct WasmiHost {
+    host: HostState,
+    limits: StoreLimits,
+}
+
 /// An instantiated agent module on the wasmi interpreter.
 pub struct WasmiModule {
-    store: Store<StoreLimits>,
+    store: Store<WasmiHost>,
     instance: wasmi::Instance,
     hooks: HostHooks,
     fuel: u64, // per-call CPU budget
@@ -33,43 +48,19 @@ impl crate::adapters::Engine for WasmiEngine {
         config.consume_fuel(true);
         let engine = WasmiCore::new(&config);
         let module = Module::new(&engine, code).map_err(|e| anyhow!("wasmi compile: {e}"))?;
-        // Bound linear-memory growth (audit H6): wasmi otherwise honours only fuel,
-        // so a guest could `memory.grow` until the host is OOM-killed.
-        let limiter = StoreLimitsBuilder::new().memory_size(limits.mem_bytes).build();
-        let mut store = Store::new(&engine, limiter);
+        let caps = proto::AgentCapabilities { max_memory_bytes: limits.mem_bytes as u64, ..Default::default() };
+        let data = WasmiHost {
+            host: HostState::new(caps),
+            // Bound linear-memory growth (audit H6): wasmi otherwise honours only
+            // fuel, so a guest could `memory.grow` until the host is OOM-killed.
+            limits: StoreLimitsBuilder::new().memory_size(limits.mem_bytes).build(),
+        };
+        let mut store = Store::new(&engine, data);
         store.set_fuel(limits.fuel).ok();
-        store.limiter(|lim| lim);
-
-        let mut linker = <Linker<StoreLimits>>::new(&engine);
-        let sends = hooks.sends.clone();
-        linker
-            .func_wrap(
-                "fipa:agent/messaging",
-                "send-unl",
-                move |caller: Caller<StoreLimits>, rp: i32, rl: i32, up: i32, ul: i32, bp: i32, bl: i32| {
-                    let Some(Extern::Memory(mem)) = caller.get_export("memory") else { return };
-                    let data = mem.data(&caller);
-                    let slice = |ptr: i32, len: i32| -> Vec<u8> {
-                        let s = ptr as usize;
-                        // saturating add: a negative/huge ptr or len can never overflow
-                        // into a panic or an out-of-range slice (audit M10).
-                        data.get(s..s.saturating_add(len as usize)).map(<[u8]>::to_vec).unwrap_or_default()
-                    };
-                    let receiver = String::from_utf8_lossy(&slice(rp, rl)).into_owned();
-                    let unl = slice(up, ul);
-                    let body = slice(bp, bl);
-                    // M3 — bound guest egress (see crate::adapters egress caps).
-                    if unl.len() + body.len() > crate::adapters::MAX_SEND_BYTES {
-                        return;
-                    }
-                    let mut guard = sends.lock().unwrap_or_else(|e| e.into_inner());
-                    if guard.len() >= crate::adapters::MAX_QUEUED_SENDS {
-                        return;
-                    }
-                    guard.push(OutboundIntent { receiver, unl, body });
-                },
-            )
-            .map_err(|e| anyhow!("wasmi link: {e}"))?;
+        store.limiter(|data| &mut data.limits);
+
+        let mut linker = <Linker<WasmiHost>>::new(&engine);
+        link_host_imports!(linker, Caller<'_, WasmiHost>, hooks);
 
         let instance = linker
             .instantiate(&mut store, &module)
@@ -80,23 +71,56 @@ impl crate::adapters::Engine for WasmiEngine {
     }
 }
 
+impl Guest for Caller<'_, WasmiHost> {
+    fn host(&mut self) -> &mut HostState {
+        &mut self.data_mut().host
+    }
+
+    fn try_read(&mut self, ptr: i32, len: i32) -> Option<Vec<u8>> {
+        let memory = self.get_export("memory").and_then(|e| e.into_memory())?;
+        let start = ptr as u32 as usize;
+        memory.data(&*self).get(start..start.saturating_add(len as u32 as usize)).map(<[u8]>::to_vec)
+    }
+
+    fn give(&mut self, bytes: &[u8]) -> i64 {
+        let io_error = -(StorageError::IoError(String::new()).code() as i64);
+        let Some(alloc) = self
+            .get_export("alloc")
+            .and_then(|e| e.into_func())
+            .and_then(|f| f.typed::<i32, i32>(&*self).ok())
+        else {
+            return io_error;
+        };
+        let Ok(ptr) = alloc.call(&mut *self, bytes.len() as i32) else {
+            return io_error;
+        };
+        let Some(memory) = self.get_export("memory").and_then(|e| e.into_memory()) else {
+            return io_error;
+        };
+        let start = ptr as u32 as usize;
+        match memory.data_mut(&mut *self).get_mut(start..start.saturating_add(bytes.len())) {
+            Some(dst) => {
+                dst.copy_from_slice(bytes);
+                ((ptr as u32 as i64) << 32) | bytes.len() as i64
+            }
+            None => io_error,
+        }
+    }
+}
+
 // Drive a wasmi agent through the node's `AgentRuntime` seam — so a WasmiModule
 // mounts in a Node exactly like the wasmtime WasmRuntime (E2 integration).
 impl AgentRuntime for WasmiModule {
     fn init(&mut self) -> Result<()> {
-        let fuel = self.fuel;
-        self.refuel(fuel);
-        self.call_void("init")
+        self.metered("init", |m| m.call_void("init"))
     }
 
     fn config(&mut self, from: &str, unl: &[u8], body: &[u8]) -> Result<()> {
-        let fuel = self.fuel;
-        self.refuel(fuel);
-        if self.call_io("deliver", &[from.as_bytes(), unl, body])? {
+        self.store.data_mut().host.set_conversation(body);
+        if self.metered("deliver", |m| m.call_io("deliver", &[from.as_bytes(), unl, body]))? {
             Ok(())
         } else {
-            self.refuel(fuel);
-            self.call_io("config", &[unl, body]).map(|_| ())
+            self.metered("config", |m| m.call_io("config", &[unl, body])).map(|_| ())
         }
     }
 
@@ -105,31 +129,77 @@ impl AgentRuntime for WasmiModule {
     }
 
     fn run(&mut self) -> Result<bool> {
-        let fuel = self.fuel;
-        self.refuel(fuel);
-        Ok(self.call_i32("run")? != 0)
+        self.metered("run", |m| Ok(m.call_i32("run")? != 0))
     }
 
     fn shutdown(&mut self) -> Result<()> {
-        let fuel = self.fuel;
-        self.refuel(fuel);
-        self.call_void("shutdown")
+        self.metered("shutdown", |m| m.call_void("shutdown"))
     }
 
     fn snapshot(&mut self) -> Vec<u8> {
-        let fuel = self.fuel;
-        self.refuel(fuel);
-        self.call_packed("snapshot").unwrap_or_default()
+        self.metered("snapshot", |m| m.call_packed("snapshot")).unwrap_or_default()
     }
 
-    fn restore(&mut self, state: &[u8]) {
-        let fuel = self.fuel;
-        self.refuel(fuel);
-        let _ = self.call_io("restore", &[state]);
+    fn restore(&mut self, state: &[u8]) -> Result<()> {
+        self.metered("restore", |m| m.call_io("restore", &[state])).map(|_| ())
+    }
+
+    fn tick(&mut self, timer_id: u64, now_ms: u64) -> Result<()> {
+        let Ok(tick) = self.instance.get_typed_func::<(i64, i64), ()>(&self.store, "tick") else {
+            return Ok(()); // a guest without `tick` ignores its timers
+        };
+        self.store.data_mut().host.conversation = None; // a tick answers no message
+        self.metered("tick", |m| {
+            tick.call(&mut m.store, (timer_id as i64, now_ms as i64)).map_err(|e| anyhow!("wasmi call tick: {e}"))
+        })
+    }
+
+    fn take_timer_ops(&mut self) -> Vec<unl_agent::TimerOp> {
+        std::mem::take(&mut self.store.data_mut().host.timer_ops)
+    }
+
+    fn set_state(&mut self, kv: std::sync::Arc<dyn unl_agent::Kv>) {
+        self.store.data_mut().host.state = Some(kv);
+    }
+
+    fn set_keyring(&mut self, kr: std::sync::Arc<dyn unl_agent::Keyring>) {
+        self.store.data_mut().host.keyring = Some(kr);
+    }
+
+    fn take_infer_reqs(&mut self) -> Vec<unl_agent::InferReq> {
+        std::mem::take(&mut self.store.data_mut().host.infers)
+    }
+
+    fn take_spawn_reqs(&mut self) -> Vec<unl_agent::SpawnReq> {
+        std::mem::take(&mut self.store.data_mut().host.spawns)
+    }
+
+    fn take_logs(&mut self) -> Vec<GuestLog> {
+        self.store.data_mut().host.take_logs()
+    }
+
+    fn take_usage(&mut self) -> Vec<CallUsage> {
+        std::mem::take(&mut self.store.data_mut().host.usage)
+    }
+
+    fn tape(&mut self) -> Option<&mut Tape> {
+        Some(&mut self.store.data_mut().host.tape)
     }
 }
 
 impl WasmiModule {
+    /// Run one entry point under a fresh fuel budget and note its cost, as the
+    /// wasmtime runtime does; memory is the exported linear memory's size.
+    fn metered<T>(&mut self, entry: &'static str, call: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let fuel = self.fuel;
+        self.refuel(fuel);
+        let result = call(self);
+        let spent = fuel.saturating_sub(self.store.get_fuel().unwrap_or(0));
+        let mem = self.instance.get_memory(&self.store, "memory").map_or(0, |m| m.data(&self.store).len());
+        self.store.data_mut().host.record_usage(entry, spent, mem as u64);
+        result
+    }
+
     fn guest_alloc(&mut self, n: usize) -> Result<i32> {
         let f = self
             .instance
@@ -253,6 +323,57 @@ mod tests {
         assert_eq!(b.call_packed("snapshot").unwrap(), vec![3, 0, 0, 0]);
     }
 
+    /// Everything observable of one pass over the gated host surface.
+    fn exercise<M: EngineModule + AgentRuntime>(m: &mut M) -> (Vec<u8>, Vec<u8>, i32, String) {
+        m.init().unwrap();
+        let denied = m.call_packed("sig").unwrap();
+        m.set_keyring(std::sync::Arc::new(crate::wasm::imports::testing::MirrorKeyring));
+        m.run().unwrap();
+        m.tick(9, 0).unwrap();
+        let signed = m.call_packed("sig").unwrap();
+        let verified = m.call_i32("ok").unwrap();
+        let queued = format!(
+            "{:?} {:?} {:?} {}",
+            m.take_timer_ops(),
+            m.take_infer_reqs(),
+            m.take_spawn_reqs(),
+            m.take_logs().len()
+        );
+        (denied, signed, verified, queued)
+    }
+
+    #[test]
+    fn one_binary_sees_the_same_host_surface_on_wasmi_and_wasmtime() {
+        use crate::wasm::WasmtimeEngine;
+        let wasm = wat::parse_str(crate::wasm::imports::testing::SURFACE_GUEST).unwrap();
+        let mut on_wasmi = WasmiEngine.instantiate(&wasm, limits(), HostHooks::default()).unwrap();
+        let mut on_wasmtime = WasmtimeEngine.instantiate(&wasm, limits(), HostHooks::default()).unwrap();
+        let seen = exercise(&mut on_wasmi);
+        assert_eq!(seen, exercise(&mut on_wasmtime));
+        assert_eq!((seen.0, seen.1, seen.2), (Vec::new(), b"olleh".to_vec(), 1)); // gated by the keyring
+        assert!(seen.3.contains("Set { id: 7, delay_ms: 250 }, Cancel { id: 8 }, Set { id: 9, delay_ms: 100 }"));
+        assert!(seen.3.contains("req_id: 42") && seen.3.contains("uuid: \"kid\""));
+    }
+
+    #[test]
+    fn a_trace_recorded_on_wasmtime_replays_on_wasmi() {
+        use crate::wasm::{replay, Recorder, WasmtimeEngine};
+        let wasm = wat::parse_str(crate::wasm::imports::testing::CLOCK_GUEST).unwrap();
+        let path = std::env::temp_dir().join(format!("wasmi-replay-{}.trace", std::process::id()));
+        let live = WasmtimeEngine.instantiate(&wasm, limits(), HostHooks::default()).unwrap();
+        let file = std::fs::File::create(&path).unwrap();
+        let mut rec = Recorder::new(Box::new(live), &wasm, limits(), Box::new(file));
+        rec.set_keyring(std::sync::Arc::new(crate::wasm::imports::testing::MirrorKeyring));
+        rec.init().unwrap();
+        rec.config("alice", b"agt(ping)", b"").unwrap();
+        drop(rec);
+
+        let trace = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+        let out = replay(&WasmiEngine, &wasm, trace).unwrap();
+        assert_eq!((out.steps, out.divergence), (2, None)); // same clock + entropy, no keyring
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn wasmi_caps_linear_memory() {
         // A module declaring 4 pages (256 KiB) cannot instantiate under a 64 KiB cap
@@ -5,7 +5,8 @@
 //!   the well-defined, stationary infrastructure agents (DF, AMS, PA);
 //! - **wasm32** (`cdylib`): sandboxed and mobile — used for BA (and optionally
 //!   BS). [`export_agent!`] wires the agent to the host ABI
-//!   (`init`/`run`/`alloc`/`config` exports + the `send-unl` import).
+//!   (`init`/`run`/`alloc`/`config`/`deliver`/`tick` exports + the gated
+//!   `fipa:agent/*` imports behind [`Ctx`]).
 //!
 //! The agent never touches the ABI: it reacts to messages and emits replies
 //! through [`Ctx`]. The host driver (native `NativeRuntime` or the wasm glue)
@@ -53,6 +54,18 @@ pub trait Kv: Send + Sync {
     fn get(&self, key: &str) -> Option<Vec<u8>>;
     fn put(&self, key: &str, val: &[u8]);
     fn del(&self, key: &str);
+    /// The agent's keys beginning with `prefix` (`""` lists all; default: none).
+    fn keys(&self, _prefix: &str) -> Vec<String> {
+        Vec::new()
+    }
+    /// Bytes of value data currently held in the namespace (default: 0).
+    fn usage(&self) -> u64 {
+        0
+    }
+    /// The namespace's byte quota — `Budget::state_kb` (default: 0).
+    fn quota(&self) -> u64 {
+        0
+    }
 }
 
 /// The node-held signing oracle granted to an agent with the `crypto` capability
@@ -106,6 +119,7 @@ pub struct SpawnReq {
 #[derive(Default)]
 pub struct Ctx {
     from: String,
+    now_ms: u64,
     sends: Vec<Outgoing>,
     timers: Vec<TimerOp>,
     state: Option<std::sync::Arc<dyn Kv>>,
@@ -131,6 +145,17 @@ impl Ctx {
         self.from.push_str(from);
     }
 
+    /// Wall-clock milliseconds at the start of this call, as the host reads it
+    /// (`0` if the driver supplied no clock, e.g. a bare `Ctx::new()` in a test).
+    pub fn now_ms(&self) -> u64 {
+        self.now_ms
+    }
+
+    /// Set the call's clock reading — called by the runtime before delivering.
+    pub fn set_now(&mut self, now_ms: u64) {
+        self.now_ms = now_ms;
+    }
+
     /// Send a message to another agent (by id).
     pub fn send(&mut self, to: impl Into<String>, unl: impl Into<String>, body: impl Into<Vec<u8>>) {
         self.sends.push(Outgoing { to: to.into(), unl: unl.into(), body: body.into() });
@@ -274,7 +299,8 @@ pub fn is_seed(unl: &[u8]) -> bool {
 #[cfg(target_arch = "wasm32")]
 #[doc(hidden)]
 pub mod wasm_glue {
-    use super::Outgoing;
+    use super::{Ctx, InferReq, Keyring, Kv, Outgoing, SpawnReq, TimerOp};
+    use std::sync::{Arc, OnceLock};
 
     #[link(wasm_import_module = "fipa:agent/messaging")]
     unsafe extern "C" {
@@ -289,6 +315,65 @@ pub mod wasm_glue {
         );
     }
 
+    #[link(wasm_import_module = "fipa:agent/timing")]
+    unsafe extern "C" {
+        #[link_name = "now"]
+        unsafe fn host_now() -> i64;
+        #[link_name = "set-timer"]
+        unsafe fn host_set_timer(id: u64, delay_ms: u64);
+        #[link_name = "cancel-timer"]
+        unsafe fn host_cancel_timer(id: u64);
+    }
+
+    #[link(wasm_import_module = "fipa:agent/storage")]
+    unsafe extern "C" {
+        #[link_name = "store"]
+        unsafe fn host_store(kp: *const u8, kl: usize, vp: *const u8, vl: usize) -> i32;
+        #[link_name = "load"]
+        unsafe fn host_load(kp: *const u8, kl: usize) -> i64;
+        #[link_name = "delete"]
+        unsafe fn host_delete(kp: *const u8, kl: usize) -> i32;
+        #[link_name = "list-keys-with-prefix"]
+        unsafe fn host_keys(pp: *const u8, pl: usize) -> i64;
+        #[link_name = "get-usage"]
+        unsafe fn host_usage() -> i64;
+        #[link_name = "get-quota"]
+        unsafe fn host_quota() -> i64;
+    }
+
+    #[link(wasm_import_module = "fipa:agent/crypto")]
+    unsafe extern "C" {
+        #[link_name = "sign"]
+        unsafe fn host_sign(bp: *const u8, bl: usize) -> i64;
+        #[link_name = "verify"]
+        unsafe fn host_verify(kp: *const u8, kl: usize, bp: *const u8, bl: usize, sp: *const u8, sl: usize) -> i32;
+        #[link_name = "public-key"]
+        unsafe fn host_public_key() -> i64;
+        #[link_name = "random"]
+        unsafe fn host_random(n: usize) -> i64;
+    }
+
+    #[link(wasm_import_module = "fipa:agent/llm")]
+    unsafe extern "C" {
+        #[link_name = "infer"]
+        unsafe fn host_infer(req_id: u64, pp: *const u8, pl: usize) -> i32;
+    }
+
+    #[link(wasm_import_module = "fipa:agent/spawn")]
+    unsafe extern "C" {
+        #[link_name = "spawn"]
+        unsafe fn host_spawn(
+            up: *const u8,
+            ul: usize,
+            ap: *const u8,
+            al: usize,
+            cp: *const u8,
+            cl: usize,
+            mp: *const u8,
+            ml: usize,
+        ) -> i32;
+    }
+
     /// Emit one outgoing message to the host.
     pub fn emit(out: &Outgoing) {
         unsafe {
@@ -303,8 +388,134 @@ pub mod wasm_glue {
         }
     }
 
+    /// A [`Ctx`] backed by the host: the clock, durable state through the storage imports,
+    /// and the keyring only when the host answers for `crypto`.
+    pub fn ctx() -> Ctx {
+        let mut ctx = init_ctx();
+        if public_key().is_some() {
+            ctx.set_keyring(Arc::new(HostKeyring));
+        }
+        ctx
+    }
+
+    /// [`ctx`] without the keyring, for `init` and `restore`: the host provisions
+    /// it afterwards, so asking there would cache a denial.
+    pub fn init_ctx() -> Ctx {
+        let mut ctx = Ctx::new();
+        ctx.set_now(unsafe { host_now() }.max(0) as u64);
+        ctx.set_state(Arc::new(HostKv));
+        ctx
+    }
+
+    /// The node key, asked of the host once and kept: every ask costs a guest
+    /// buffer and a host call (and a tape entry under record/replay).
+    fn public_key() -> Option<&'static [u8]> {
+        static KEY: OnceLock<Option<Vec<u8>>> = OnceLock::new();
+        KEY.get_or_init(|| unpack(unsafe { host_public_key() })).as_deref()
+    }
+
+    /// Hand everything the agent queued on `ctx` to the host; the node gates each
+    /// kind on its capability.
+    pub fn flush(ctx: &mut Ctx) {
+        for out in ctx.take() {
+            emit(&out);
+        }
+        for op in ctx.take_timers() {
+            match op {
+                TimerOp::Set { id, delay_ms } => unsafe { host_set_timer(id, delay_ms) },
+                TimerOp::Cancel { id } => unsafe { host_cancel_timer(id) },
+            }
+        }
+        for InferReq { req_id, prompt } in ctx.take_infers() {
+            unsafe { host_infer(req_id, prompt.as_ptr(), prompt.len()) };
+        }
+        for r in ctx.take_spawns() {
+            let SpawnReq { uuid, alias, code, manifest_json } = &r;
+            unsafe {
+                host_spawn(
+                    uuid.as_ptr(),
+                    uuid.len(),
+                    alias.as_ptr(),
+                    alias.len(),
+                    code.as_ptr(),
+                    code.len(),
+                    manifest_json.as_ptr(),
+                    manifest_json.len(),
+                )
+            };
+        }
+    }
+
+    /// Take ownership of a buffer the host filled via [`alloc`] and returned packed
+    /// as `(ptr << 32) | len`; `None` for a (negative) status.
+    fn unpack(packed: i64) -> Option<Vec<u8>> {
+        if packed < 0 {
+            return None;
+        }
+        let ptr = (packed >> 32) as usize as *mut u8;
+        let len = (packed & 0xffff_ffff) as usize;
+        // SAFETY: the host got `ptr` from our `alloc(len)` — a leaked
+        // `Vec::with_capacity(len.max(1))`, whose capacity is exactly that — and
+        // wrote `len` bytes into it.
+        Some(unsafe { Vec::from_raw_parts(ptr, len, len.max(1)) })
+    }
+
+    /// Durable state through `fipa:agent/storage`; without the `state` grant the
+    /// host denies every call, so reads are `None` and writes no-ops.
+    struct HostKv;
+
+    impl Kv for HostKv {
+        fn get(&self, key: &str) -> Option<Vec<u8>> {
+            unpack(unsafe { host_load(key.as_ptr(), key.len()) })
+        }
+        fn put(&self, key: &str, val: &[u8]) {
+            unsafe { host_store(key.as_ptr(), key.len(), val.as_ptr(), val.len()) };
+        }
+        fn del(&self, key: &str) {
+            unsafe { host_delete(key.as_ptr(), key.len()) };
+        }
+        fn keys(&self, prefix: &str) -> Vec<String> {
+            let bytes = unpack(unsafe { host_keys(prefix.as_ptr(), prefix.len()) }).unwrap_or_default();
+            let mut keys = Vec::new();
+            let mut rest = &bytes[..];
+            while let Some((n, tail)) = rest.split_first_chunk::<4>() {
+                let n = u32::from_be_bytes(*n) as usize;
+                let Some(key) = tail.get(..n) else { break };
+                keys.push(String::from_utf8_lossy(key).into_owned());
+                rest = &tail[n..];
+            }
+            keys
+        }
+        fn usage(&self) -> u64 {
+            unsafe { host_usage() }.max(0) as u64
+        }
+        fn quota(&self) -> u64 {
+            unsafe { host_quota() }.max(0) as u64
+        }
+    }
+
+    /// The node-held keyring through `fipa:agent/crypto`.
+    struct HostKeyring;
+
+    impl Keyring for HostKeyring {
+        fn sign(&self, bytes: &[u8]) -> Vec<u8> {
+            unpack(unsafe { host_sign(bytes.as_ptr(), bytes.len()) }).unwrap_or_default()
+        }
+        fn verify(&self, pubkey: &[u8], bytes: &[u8], sig: &[u8]) -> bool {
+            unsafe {
+                host_verify(pubkey.as_ptr(), pubkey.len(), bytes.as_ptr(), bytes.len(), sig.as_ptr(), sig.len()) == 1
+            }
+        }
+        fn public_key(&self) -> Vec<u8> {
+            public_key().map(<[u8]>::to_vec).unwrap_or_default()
+        }
+        fn random(&self, n: usize) -> Vec<u8> {
+            unpack(unsafe { host_random(n) }).unwrap_or_default()
+        }
+    }
+
     /// The host calls this to reserve `len` bytes before writing an inbound
-    /// `(unl, body)` and calling `config`.
+    /// `(unl, body)` and calling `config`, or a result it hands back.
     #[unsafe(no_mangle)]
     pub extern "C" fn alloc(len: usize) -> *mut u8 {
         let mut v = Vec::<u8>::with_capacity(len.max(1));
@@ -315,9 +526,10 @@ pub mod wasm_glue {
 }
 
 /// Wire an [`Agent`] to the host ABI (wasm32 only). Defines the `init`, `run`,
-/// `config`, and `alloc` exports that drive a single agent instance, decoding
-/// inbound `(unl, body)` and forwarding the agent's replies to the host. The
-/// vocabulary seed (UNL beginning with `{`) is skipped.
+/// `config`, `deliver`, `tick`, and `alloc` exports that drive a single agent
+/// instance, decoding inbound `(unl, body)` and forwarding everything the agent
+/// queued on its [`Ctx`] to the host. The vocabulary seed (UNL beginning with `{`)
+/// is skipped.
 #[macro_export]
 macro_rules! export_agent {
     ($init:expr) => {
@@ -326,6 +538,10 @@ macro_rules! export_agent {
             static mut AGENT: ::core::option::Option<::std::boxed::Box<dyn $crate::Agent>> = None;
 
             fn drive<F: FnOnce(&mut dyn $crate::Agent, &mut $crate::Ctx)>(f: F) {
+                drive_with($crate::wasm_glue::ctx(), f);
+            }
+
+            fn drive_with<F: FnOnce(&mut dyn $crate::Agent, &mut $crate::Ctx)>(mut ctx: $crate::Ctx, f: F) {
                 // wasm32 is single-threaded: exclusive access is sound.
                 let agent = unsafe {
                     let slot = ::core::ptr::addr_of_mut!(AGENT);
@@ -334,16 +550,13 @@ macro_rules! export_agent {
                     }
                     (*slot).as_mut().unwrap().as_mut()
                 };
-                let mut ctx = $crate::Ctx::new();
                 f(agent, &mut ctx);
-                for out in ctx.take() {
-                    $crate::wasm_glue::emit(&out);
-                }
+                $crate::wasm_glue::flush(&mut ctx);
             }
 
             #[unsafe(no_mangle)]
             pub extern "C" fn init() {
-                drive(|a, ctx| a.on_init(ctx));
+                drive_with($crate::wasm_glue::init_ctx(), |a, ctx| a.on_init(ctx));
             }
 
             #[unsafe(no_mangle)]
@@ -412,7 +625,13 @@ macro_rules! export_agent {
             #[unsafe(no_mangle)]
             pub extern "C" fn restore(p: *const u8, l: usize) {
                 let state = unsafe { ::core::slice::from_raw_parts(p, l) };
-                drive(|a, _ctx| a.restore(state));
+                drive_with($crate::wasm_glue::init_ctx(), |a, _ctx| a.restore(state));
+            }
+
+            // A timer the agent armed has fired.
+            #[unsafe(no_mangle)]
+            pub extern "C" fn tick(timer_id: u64, now_ms: u64) {
+                drive(|a, ctx| a.on_tick(timer_id, now_ms, ctx));
             }
 
             // re-export the host allocator so the linker keeps it
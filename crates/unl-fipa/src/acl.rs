@@ -0,0 +1,136 @@
+//! The `_acl` header (`docs/INTERACTION_PROTOCOLS.md` §3): the conversation
+//! envelope carried as a reserved object at the top of a JSON message `body`.
+//! Keys beginning with `_` are envelope; every sibling key is domain content.
+//! Nothing on the wire or the host ABI changes — to the node it is just JSON.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The reserved body key holding the header.
+pub const ACL_KEY: &str = "_acl";
+
+/// A conversation header. `cid`, `pid`, and `perf` are required; the rest are
+/// set only by the protocols that use them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Acl {
+    /// Conversation id, minted by the initiator.
+    pub cid: String,
+    /// Protocol id, e.g. `fipa-request`.
+    pub pid: String,
+    /// The performative — the canonical intent (§4); the UNL verb only mirrors it.
+    pub perf: String,
+    /// Reply-with: an id the sender wants echoed in `irt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rw: Option<String>,
+    /// In-reply-to: the `rw` being answered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub irt: Option<String>,
+    /// Reply-by, relative ms from receipt, so node clock skew never moves a deadline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rb_ms: Option<u64>,
+    /// Iterated contract-net round.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round: Option<u32>,
+    /// Subscription lease.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease_ms: Option<u64>,
+    /// The conversation this one was started from (nesting, §11).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_cid: Option<String>,
+    /// Ontology of the domain fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ont: Option<String>,
+}
+
+impl Acl {
+    /// A header for conversation `cid` of protocol `pid` carrying `perf`.
+    pub fn new(cid: impl Into<String>, pid: impl Into<String>, perf: impl Into<String>) -> Self {
+        Acl { cid: cid.into(), pid: pid.into(), perf: perf.into(), ..Default::default() }
+    }
+
+    /// The header answering this one with `perf`: same conversation and protocol,
+    /// `irt` echoing our `rw`, and the nesting link carried over.
+    pub fn reply(&self, perf: impl Into<String>) -> Self {
+        Acl {
+            irt: self.rw.clone(),
+            parent_cid: self.parent_cid.clone(),
+            round: self.round,
+            ..Acl::new(self.cid.clone(), self.pid.clone(), perf)
+        }
+    }
+
+    /// Split a body into its header and the domain content (the sibling keys).
+    /// `None` if the body is not a JSON object or carries no well-formed `_acl`.
+    pub fn split(body: &[u8]) -> Option<(Acl, Value)> {
+        let Value::Object(mut map) = serde_json::from_slice(body).ok()? else {
+            return None;
+        };
+        let acl = serde_json::from_value(map.remove(ACL_KEY)?).ok()?;
+        Some((acl, Value::Object(map)))
+    }
+
+    /// Join this header with `content` into a body. Content that is not an
+    /// object is carried under `value`.
+    pub fn join(&self, content: Value) -> Vec<u8> {
+        let mut map = match content {
+            Value::Object(map) => map,
+            Value::Null => Map::new(),
+            other => Map::from_iter([("value".to_string(), other)]),
+        };
+        map.insert(ACL_KEY.into(), serde_json::to_value(self).unwrap_or_default());
+        serde_json::to_vec(&Value::Object(map)).unwrap_or_default()
+    }
+}
+
+/// The UNL verb mirroring a performative (§4), e.g. `accept-proposal` → `accept`.
+/// Anything unlisted mirrors as itself.
+pub fn unl_verb(perf: &str) -> &str {
+    match perf {
+        "query-if" | "query-ref" => "query",
+        "accept-proposal" => "accept",
+        "reject-proposal" => "reject",
+        "not-understood" => "nu",
+        other => other,
+    }
+}
+
+/// The `obj(<verb>, <subj>)` sentence for a message carrying `perf` about `subject`.
+pub fn unl_for(perf: &str, subject: &str) -> String {
+    format!("obj({}, {})", unl_verb(perf), subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn header_and_content_split_back_apart() {
+        let mut acl = Acl::new("c1", "fipa-contract-net", "cfp");
+        acl.rb_ms = Some(4000);
+        let body = acl.join(json!({ "task": { "title": "LtG" } }));
+        let text = String::from_utf8(body.clone()).unwrap();
+        assert!(text.contains(r#""rb_ms":4000"#) && !text.contains("lease_ms"));
+        let (back, content) = Acl::split(&body).unwrap();
+        assert_eq!(back, acl);
+        assert_eq!(content, json!({ "task": { "title": "LtG" } }));
+    }
+
+    #[test]
+    fn bodies_without_a_header_are_not_conversations() {
+        assert!(Acl::split(br#"{"reason":"denied"}"#).is_none());
+        assert!(Acl::split(b"[\"s1\"]").is_none());
+        assert!(Acl::split(br#"{"_acl":{"cid":"c"}}"#).is_none()); // pid/perf missing
+    }
+
+    #[test]
+    fn a_reply_threads_the_conversation() {
+        let mut req = Acl::new("c1", "fipa-request", "request");
+        req.rw = Some("m1".into());
+        req.parent_cid = Some("c0".into());
+        let agree = req.reply("agree");
+        assert_eq!((agree.cid.as_str(), agree.irt.as_deref()), ("c1", Some("m1")));
+        assert_eq!(agree.parent_cid.as_deref(), Some("c0"));
+        assert_eq!(unl_for("accept-proposal", "LtG"), "obj(accept, LtG)");
+    }
+}
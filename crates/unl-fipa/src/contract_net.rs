@@ -0,0 +1,577 @@
+//! `fipa-contract-net` and `fipa-iterated-contract-net`
+//! (`docs/INTERACTION_PROTOCOLS.md` §8, §9).
+//!
+//! [`ContractNet`] calls for proposals from a set of participants, collects
+//! `propose`/`refuse` until all have answered or the reply-by passes, and lets
+//! the agent's evaluator pick: award one, reject all, or — iterated — call
+//! another round with a revised task. [`Participant`] is the other side, bidding
+//! and performing through the agent's [`Contractor`].
+
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+
+use crate::acl::Acl;
+use crate::conversation::{Fsm, Msg, Step};
+use crate::request::{reason_of, Refusal};
+
+pub const FIPA_CONTRACT_NET: &str = "fipa-contract-net";
+pub const FIPA_ITERATED_CONTRACT_NET: &str = "fipa-iterated-contract-net";
+
+/// Rounds an iterated contract-net runs at most unless told otherwise.
+pub const DEFAULT_MAX_ROUNDS: u32 = 3;
+
+/// How long a participant holds its bid when the cfp names no reply-by.
+pub const DEFAULT_HOLD_MS: u64 = 30_000;
+
+/// One bid, in the order it arrived.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+    pub bidder: String,
+    pub bid: Value,
+    pub round: u32,
+}
+
+/// The evaluator's verdict on one round's proposals.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision {
+    /// Accept the proposal at this index; every other bidder is rejected.
+    Award(usize),
+    /// Reject every proposal; the conversation fails with `"rejected"`.
+    RejectAll,
+    /// Call another round for `task` from `bidders` (iterated only); the other
+    /// bidders are rejected. Past the last round this rejects all instead.
+    Revise { task: Value, bidders: Vec<String> },
+}
+
+type Evaluator = Box<dyn FnMut(&[Proposal]) -> Decision + Send>;
+
+/// The evaluator that awards the lowest `bid.price` (ties to the earliest).
+pub fn lowest_price(proposals: &[Proposal]) -> Decision {
+    let price = |p: &Proposal| p.bid.get("price").and_then(Value::as_u64).unwrap_or(u64::MAX);
+    match proposals.iter().enumerate().min_by_key(|(_, p)| price(p)) {
+        Some((i, _)) => Decision::Award(i),
+        None => Decision::RejectAll,
+    }
+}
+
+enum Stage {
+    Collecting,
+    /// Awarded to this proposal; waiting for its `inform`.
+    Awarded(Proposal),
+}
+
+/// Initiator of a (possibly iterated) contract-net.
+///
+/// Finishes with `{ "winner", "bid" }` — plus `"result"`, the winner's `inform`,
+/// unless built [`ContractNet::award_only`] — or fails with `"no-proposals"`,
+/// `"rejected"`, the winner's `failure` reason, `"timeout"` or `"cancelled"`.
+/// Proposals arriving after their round closed are rejected.
+pub struct ContractNet {
+    pid: &'static str,
+    subject: String,
+    task: Value,
+    rb_ms: u64,
+    max_rounds: u32,
+    await_result: bool,
+    evaluate: Evaluator,
+    cid: String,
+    round: u32,
+    /// Who this round's cfp went to, and who of them has answered.
+    asked: Vec<String>,
+    answered: HashSet<String>,
+    proposals: Vec<Proposal>,
+    stage: Stage,
+    deadline: Option<u64>,
+}
+
+impl ContractNet {
+    /// Call `participants` (each once, however often listed) for proposals on
+    /// `task` about `subject`; each round waits at most `rb_ms` for the bids, and
+    /// the award at most `rb_ms` for the winner's result.
+    pub fn new(
+        participants: Vec<String>,
+        subject: impl Into<String>,
+        task: Value,
+        rb_ms: u64,
+        evaluate: impl FnMut(&[Proposal]) -> Decision + Send + 'static,
+    ) -> Self {
+        ContractNet {
+            pid: FIPA_CONTRACT_NET,
+            subject: subject.into(),
+            task,
+            rb_ms,
+            max_rounds: 1,
+            await_result: true,
+            evaluate: Box::new(evaluate),
+            cid: String::new(),
+            round: 1,
+            asked: distinct(participants),
+            answered: HashSet::new(),
+            proposals: Vec::new(),
+            stage: Stage::Collecting,
+            deadline: None,
+        }
+    }
+
+    /// Make it an iterated contract-net of at most `max_rounds` rounds.
+    pub fn iterated(mut self, max_rounds: u32) -> Self {
+        self.pid = FIPA_ITERATED_CONTRACT_NET;
+        self.max_rounds = max_rounds.max(1);
+        self
+    }
+
+    /// Finish as soon as the award is sent, for tasks whose execution is not a
+    /// reply from the winner (e.g. a purchase settled through escrow).
+    pub fn award_only(mut self) -> Self {
+        self.await_result = false;
+        self
+    }
+
+    /// The protocol this initiator speaks, for [`Conversations::start`](crate::Conversations::start).
+    pub fn pid(&self) -> &'static str {
+        self.pid
+    }
+
+    fn acl(&self, perf: &str) -> Acl {
+        let mut acl = Acl::new(self.cid.clone(), self.pid, perf);
+        if self.pid == FIPA_ITERATED_CONTRACT_NET {
+            acl.round = Some(self.round);
+        }
+        acl
+    }
+
+    fn send(&self, to: &str, perf: &str, content: Value, out: &mut Vec<Msg>) {
+        out.push(Msg::new(to, self.acl(perf), self.subject.clone(), content));
+    }
+
+    fn call(&mut self, now_ms: u64, out: &mut Vec<Msg>) {
+        for to in &self.asked {
+            let mut acl = self.acl("cfp");
+            acl.rb_ms = Some(self.rb_ms);
+            out.push(Msg::new(to.clone(), acl, self.subject.clone(), json!({ "task": self.task })));
+        }
+        self.deadline = Some(now_ms + self.rb_ms);
+    }
+
+    /// Close the round and act on the evaluator's decision.
+    fn evaluate(&mut self, now_ms: u64, out: &mut Vec<Msg>) -> Step {
+        if self.proposals.is_empty() {
+            return Step::Failed("no-proposals".into());
+        }
+        let proposals = std::mem::take(&mut self.proposals);
+        let decision = match (self.evaluate)(&proposals) {
+            Decision::Award(i) if i >= proposals.len() => Decision::RejectAll,
+            Decision::Revise { .. } if self.round >= self.max_rounds => Decision::RejectAll,
+            decision => decision,
+        };
+        match decision {
+            Decision::Award(i) => {
+                for (j, p) in proposals.iter().enumerate() {
+                    let perf = if i == j { "accept-proposal" } else { "reject-proposal" };
+                    self.send(&p.bidder, perf, json!({ "bid": p.bid }), out);
+                }
+                let won = proposals[i].clone();
+                if !self.await_result {
+                    return Step::Done(json!({ "winner": won.bidder, "bid": won.bid }));
+                }
+                self.stage = Stage::Awarded(won);
+                self.deadline = Some(now_ms + self.rb_ms);
+                Step::Continue
+            }
+            Decision::RejectAll => {
+                for p in &proposals {
+                    self.send(&p.bidder, "reject-proposal", json!({ "bid": p.bid }), out);
+                }
+                Step::Failed("rejected".into())
+            }
+            Decision::Revise { task, bidders } => {
+                for p in proposals.iter().filter(|p| !bidders.contains(&p.bidder)) {
+                    self.send(&p.bidder, "reject-proposal", json!({ "bid": p.bid }), out);
+                }
+                self.asked = distinct(bidders.into_iter().filter(|b| proposals.iter().any(|p| &p.bidder == b)));
+                if self.asked.is_empty() {
+                    return Step::Failed("rejected".into());
+                }
+                self.round += 1;
+                self.task = task;
+                self.answered.clear();
+                self.call(now_ms, out);
+                Step::Continue
+            }
+        }
+    }
+}
+
+/// `names` in order, each kept once.
+fn distinct(names: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    names.into_iter().filter(|n| seen.insert(n.clone())).collect()
+}
+
+impl<A> Fsm<A> for ContractNet {
+    fn on_start(&mut self, cid: &str, now_ms: u64, out: &mut Vec<Msg>) -> Step {
+        if self.asked.is_empty() {
+            return Step::Failed("no-proposals".into());
+        }
+        self.cid = cid.to_string();
+        self.call(now_ms, out);
+        Step::Continue
+    }
+
+    fn on_message(&mut self, msg: &Msg, now_ms: u64, _agent: &mut A, out: &mut Vec<Msg>) -> Step {
+        let perf = msg.acl.perf.as_str();
+        let current = msg.acl.round.unwrap_or(1) == self.round;
+        match &self.stage {
+            Stage::Collecting if current && self.asked.contains(&msg.peer) && !self.answered.contains(&msg.peer) => {
+                match perf {
+                    "propose" => {
+                        let bid = msg.content.get("bid").cloned().unwrap_or(Value::Null);
+                        self.proposals.push(Proposal { bidder: msg.peer.clone(), bid, round: self.round });
+                    }
+                    "refuse" | "not-understood" => {}
+                    _ => return Step::Continue,
+                }
+                self.answered.insert(msg.peer.clone());
+                if self.answered.len() == self.asked.len() {
+                    return self.evaluate(now_ms, out);
+                }
+                Step::Continue
+            }
+            Stage::Awarded(won) if msg.peer == won.bidder && perf != "propose" => match perf {
+                "inform" => {
+                    let result = msg.content.get("result").cloned().unwrap_or(Value::Null);
+                    Step::Done(json!({ "winner": won.bidder, "bid": won.bid, "result": result }))
+                }
+                "failure" | "not-understood" => Step::Failed(reason_of(msg)),
+                _ => Step::Continue,
+            },
+            _ if perf == "propose" => {
+                // too late for its round (or unasked): turn it down
+                out.push(msg.reply("reject-proposal", json!({ "reason": "late" })));
+                Step::Continue
+            }
+            _ => Step::Continue,
+        }
+    }
+
+    fn on_timeout(&mut self, now_ms: u64, agent: &mut A, out: &mut Vec<Msg>) -> Step {
+        match self.stage {
+            Stage::Collecting => self.evaluate(now_ms, out),
+            Stage::Awarded(_) => match self.on_cancel(now_ms, agent, out) {
+                Step::Failed(_) => Step::Failed("timeout".into()),
+                step => step,
+            },
+        }
+    }
+
+    fn on_cancel(&mut self, _now_ms: u64, _agent: &mut A, out: &mut Vec<Msg>) -> Step {
+        let involved: Vec<String> = match &self.stage {
+            Stage::Collecting => self.asked.clone(),
+            Stage::Awarded(won) => vec![won.bidder.clone()],
+        };
+        for to in involved {
+            self.send(&to, "cancel", json!({}), out);
+        }
+        Step::Failed("cancelled".into())
+    }
+
+    fn deadline(&self) -> Option<u64> {
+        self.deadline
+    }
+}
+
+/// What an agent taking part in contract-nets implements.
+pub trait Contractor {
+    /// Bid on a `cfp` (its `task` in `cfp.content`, its round in `cfp.acl`), or
+    /// decline it. Called again for each later round the bidder is kept in.
+    fn propose(&mut self, cfp: &Msg) -> Result<Value, Refusal>;
+
+    /// The bid was accepted: carry the task out, yielding the result to `inform`.
+    fn perform(&mut self, accepted: &Msg, bid: &Value) -> Result<Value, String>;
+
+    /// The bid lost, was cancelled, or was never answered: release whatever it held.
+    fn withdraw(&mut self, _bid: &Value) {}
+}
+
+/// Participant side of a (possibly iterated) contract-net, deciding through the
+/// agent's [`Contractor`]. Holds a bid for twice the cfp's reply-by (or
+/// [`DEFAULT_HOLD_MS`] if it names none) waiting for the verdict before
+/// withdrawing it. Finishes with the performed result, or
+/// fails with `"rejected"`, a refusal or failure reason, `"timeout"` or
+/// `"cancelled"`.
+#[derive(Default)]
+pub struct Participant {
+    bid: Option<Value>,
+    deadline: Option<u64>,
+}
+
+impl Participant {
+    /// A factory for [`Conversations::respond`](crate::Conversations::respond).
+    pub fn factory<A: Contractor>() -> impl Fn(&Msg) -> Box<dyn Fsm<A>> + Send + 'static {
+        |_| Box::new(Participant::default())
+    }
+}
+
+impl<A: Contractor> Fsm<A> for Participant {
+    fn on_message(&mut self, msg: &Msg, now_ms: u64, agent: &mut A, out: &mut Vec<Msg>) -> Step {
+        match msg.acl.perf.as_str() {
+            "cfp" => match agent.propose(msg) {
+                Ok(bid) => {
+                    out.push(msg.reply("propose", json!({ "bid": bid })));
+                    self.bid = Some(bid);
+                    let hold = msg.acl.rb_ms.map_or(DEFAULT_HOLD_MS, |rb| rb.saturating_mul(2));
+                    self.deadline = Some(now_ms.saturating_add(hold));
+                    Step::Continue
+                }
+                Err(refusal) => {
+                    if let Some(held) = self.bid.take() {
+                        agent.withdraw(&held);
+                    }
+                    out.push(refusal.reply_to(msg));
+                    Step::Failed(refusal.reason().to_string())
+                }
+            },
+            "accept-proposal" => {
+                let bid = self.bid.take().unwrap_or(Value::Null);
+                match agent.perform(msg, &bid) {
+                    Ok(result) => {
+                        out.push(msg.reply("inform", json!({ "result": result.clone() })));
+                        Step::Done(result)
+                    }
+                    Err(reason) => {
+                        out.push(msg.reply("failure", json!({ "reason": reason.clone() })));
+                        Step::Failed(reason)
+                    }
+                }
+            }
+            "reject-proposal" | "cancel" => {
+                if let Some(held) = self.bid.take() {
+                    agent.withdraw(&held);
+                }
+                if msg.acl.perf == "cancel" {
+                    out.push(msg.reply("inform", json!({})));
+                    return Step::Failed("cancelled".into());
+                }
+                Step::Failed("rejected".into())
+            }
+            other => {
+                out.push(msg.reply("not-understood", json!({ "reason": format!("unexpected {other}") })));
+                Step::Continue
+            }
+        }
+    }
+
+    fn on_timeout(&mut self, _now_ms: u64, agent: &mut A, _out: &mut Vec<Msg>) -> Step {
+        if let Some(held) = self.bid.take() {
+            agent.withdraw(&held);
+        }
+        Step::Failed("timeout".into())
+    }
+
+    fn deadline(&self) -> Option<u64> {
+        self.deadline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::{Conversations, Outcome};
+    use unl_agent::{Ctx, Outgoing};
+
+    const SLOT: u64 = 99;
+
+    /// A seller quoting a fixed price, or declining with none; `undercut` lowers
+    /// the quote each round.
+    struct Shop {
+        price: Option<u64>,
+        undercut: u64,
+        withdrawn: u32,
+    }
+
+    impl Shop {
+        fn new(price: Option<u64>) -> Self {
+            Shop { price, undercut: 0, withdrawn: 0 }
+        }
+    }
+
+    impl Contractor for Shop {
+        fn propose(&mut self, cfp: &Msg) -> Result<Value, Refusal> {
+            let round = cfp.acl.round.unwrap_or(1) as u64;
+            let price = self.price.ok_or_else(|| Refusal::Refuse("book-not-found".into()))?;
+            Ok(json!({ "price": price - self.undercut * (round - 1) }))
+        }
+        fn perform(&mut self, _accepted: &Msg, bid: &Value) -> Result<Value, String> {
+            Ok(json!({ "sold_at": bid["price"] }))
+        }
+        fn withdraw(&mut self, _bid: &Value) {
+            self.withdrawn += 1;
+        }
+    }
+
+    struct Market {
+        buyer: Conversations,
+        sellers: Vec<(String, Conversations<Shop>, Shop)>,
+    }
+
+    impl Market {
+        fn new(shops: Vec<(&str, Shop)>) -> Self {
+            let sellers = shops
+                .into_iter()
+                .map(|(name, shop)| {
+                    let mut convs = Conversations::new(SLOT);
+                    convs.respond(FIPA_CONTRACT_NET, Participant::factory());
+                    convs.respond(FIPA_ITERATED_CONTRACT_NET, Participant::factory());
+                    (name.to_string(), convs, shop)
+                })
+                .collect();
+            Market { buyer: Conversations::new(SLOT), sellers }
+        }
+
+        /// Deliver `sent` (from the buyer) to the sellers and their answers back,
+        /// until quiet; the buyer's outcomes.
+        fn run(&mut self, mut sent: Vec<Outgoing>, now_ms: u64) -> Vec<Outcome> {
+            let mut done = Vec::new();
+            while !sent.is_empty() {
+                let mut replies = Vec::new();
+                for m in sent.drain(..) {
+                    let (_, convs, shop) = self.sellers.iter_mut().find(|(n, ..)| *n == m.to).unwrap();
+                    let mut ctx = Ctx::new();
+                    ctx.set_from("ba");
+                    ctx.set_now(now_ms);
+                    convs.on_message(&m.unl, &m.body, shop, &mut ctx).unwrap();
+                    replies.extend(ctx.take().into_iter().map(|r| (m.to.clone(), r)));
+                }
+                for (from, r) in replies {
+                    let mut ctx = Ctx::new();
+                    ctx.set_from(&from);
+                    ctx.set_now(now_ms);
+                    done.extend(self.buyer.on_message(&r.unl, &r.body, &mut (), &mut ctx).unwrap());
+                    sent.extend(ctx.take());
+                }
+            }
+            done
+        }
+    }
+
+    fn names(n: &[&str]) -> Vec<String> {
+        n.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn the_lowest_bid_wins_once_everyone_has_answered() {
+        let mut market = Market::new(vec![
+            ("s1", Shop::new(Some(12_000))),
+            ("s2", Shop::new(Some(10_000))),
+            ("s3", Shop::new(None)),
+        ]);
+        let cn = ContractNet::new(names(&["s1", "s2", "s3"]), "LtG", json!({ "title": "LtG" }), 4_000, lowest_price);
+        let mut ctx = Ctx::new();
+        let (cid, _) = market.buyer.start(cn.pid(), Box::new(cn), &mut ctx);
+        let done = market.run(ctx.take(), 100);
+        let want = json!({ "winner": "s2", "bid": { "price": 10_000 }, "result": { "sold_at": 10_000 } });
+        assert_eq!(done, [Outcome { cid, pid: FIPA_CONTRACT_NET.into(), result: Ok(want) }]);
+        assert_eq!(market.sellers[0].2.withdrawn, 1); // s1 was rejected
+        assert!(market.buyer.is_empty() && market.sellers.iter().all(|(_, c, _)| c.is_empty()));
+    }
+
+    #[test]
+    fn a_participant_listed_twice_is_asked_once() {
+        let mut market = Market::new(vec![("s1", Shop::new(Some(12_000))), ("s2", Shop::new(Some(10_000)))]);
+        let cn = ContractNet::new(names(&["s1", "s2", "s1"]), "LtG", json!({}), 4_000, lowest_price);
+        let mut ctx = Ctx::new();
+        market.buyer.start(cn.pid(), Box::new(cn), &mut ctx);
+        let cfps = ctx.take();
+        assert_eq!(cfps.len(), 2);
+        // both answers close the round; no wait for the deadline
+        let done = market.run(cfps, 100);
+        assert_eq!(done[0].result.as_ref().unwrap()["winner"], "s2");
+    }
+
+    #[test]
+    fn a_bid_on_a_cfp_without_reply_by_is_held() {
+        let cfp = Msg::new("ba", Acl::new("c1", FIPA_CONTRACT_NET, "cfp"), "LtG", json!({})); // no rb_ms
+        let mut shop = Shop::new(Some(10_000));
+        let mut p = Participant::default();
+        let step = p.on_message(&cfp, 1_000, &mut shop, &mut Vec::new());
+        assert_eq!(step, Step::Continue);
+        assert_eq!(Fsm::<Shop>::deadline(&p), Some(1_000 + DEFAULT_HOLD_MS));
+        assert_eq!(shop.withdrawn, 0);
+    }
+
+    #[test]
+    fn the_deadline_closes_the_round_and_late_bids_are_rejected() {
+        let mut market = Market::new(vec![("s1", Shop::new(Some(12_000))), ("s2", Shop::new(Some(10_000)))]);
+        let cn = ContractNet::new(names(&["s1", "s2"]), "LtG", json!({}), 4_000, lowest_price);
+        let mut ctx = Ctx::new();
+        market.buyer.start(cn.pid(), Box::new(cn), &mut ctx);
+        let cfps = ctx.take();
+        // only s1 answers in time
+        let mut s1 = Ctx::new();
+        s1.set_from("ba");
+        let (_, convs, shop) = &mut market.sellers[0];
+        convs.on_message(&cfps[0].unl, &cfps[0].body, shop, &mut s1);
+        let mut tick = Ctx::new();
+        tick.set_from("s1");
+        let bid = s1.take();
+        market.buyer.on_message(&bid[0].unl, &bid[0].body, &mut (), &mut tick);
+        assert_eq!(market.buyer.on_tick(SLOT, 4_000, &mut (), &mut tick), Some(vec![]));
+        let accept = tick.take();
+        assert_eq!((accept[0].to.as_str(), accept[0].unl.as_str()), ("s1", "obj(accept, LtG)"));
+        // s2's bid arrives after the round closed: turned down
+        let mut ctx = Ctx::new();
+        ctx.set_from("ba");
+        let (_, s2, shop) = &mut market.sellers[1];
+        s2.on_message(&cfps[1].unl, &cfps[1].body, shop, &mut ctx);
+        let late = ctx.take();
+        let mut back = Ctx::new();
+        back.set_from("s2");
+        assert_eq!(market.buyer.on_message(&late[0].unl, &late[0].body, &mut (), &mut back), Some(vec![]));
+        assert_eq!(back.take()[0].unl, "obj(reject, LtG)");
+        // the winner delivers
+        let done = market.run(accept, 4_100);
+        assert_eq!(done[0].result.as_ref().unwrap()["winner"], "s1");
+    }
+
+    #[test]
+    fn iterated_rounds_narrow_the_field() {
+        let mut cheap = Shop::new(Some(10_000));
+        cheap.undercut = 500;
+        let mut market = Market::new(vec![("s1", cheap), ("s2", Shop::new(Some(11_000))), ("s3", Shop::new(Some(15_000)))]);
+        // keep the two best for another round until someone goes under 9,500
+        let evaluate = |ps: &[Proposal]| {
+            let best = lowest_price(ps);
+            match best {
+                Decision::Award(i) if ps[i].bid["price"].as_u64() < Some(9_500) => best,
+                _ => {
+                    let mut by_price = ps.to_vec();
+                    by_price.sort_by_key(|p| p.bid["price"].as_u64());
+                    let bidders = by_price.iter().take(2).map(|p| p.bidder.clone()).collect();
+                    Decision::Revise { task: json!({ "title": "LtG", "below": 9_500 }), bidders }
+                }
+            }
+        };
+        let cn = ContractNet::new(names(&["s1", "s2", "s3"]), "LtG", json!({ "title": "LtG" }), 4_000, evaluate).iterated(DEFAULT_MAX_ROUNDS);
+        let mut ctx = Ctx::new();
+        market.buyer.start(cn.pid(), Box::new(cn), &mut ctx);
+        let done = market.run(ctx.take(), 100);
+        // round 1: 10,000 · 11,000 · 15,000 → s3 out; round 2: 9,500 → again; round 3: 9,000
+        assert_eq!(done[0].result.as_ref().unwrap()["bid"], json!({ "price": 9_000 }));
+        assert_eq!(market.sellers[2].2.withdrawn, 1);
+    }
+
+    #[test]
+    fn cancel_reaches_everyone_still_bidding() {
+        let mut convs = Conversations::new(SLOT);
+        let mut ctx = Ctx::new();
+        let cn = ContractNet::new(names(&["s1", "s2"]), "LtG", json!({}), 4_000, lowest_price);
+        let (cid, _) = convs.start(cn.pid(), Box::new(cn), &mut ctx);
+        ctx.take();
+        let outcome = convs.cancel(&cid, &mut (), &mut ctx).unwrap();
+        assert_eq!(outcome.result, Err("cancelled".into()));
+        let sent: Vec<_> = ctx.take().into_iter().map(|m| (m.to, m.unl)).collect();
+        assert_eq!(sent, [("s1".into(), "obj(cancel, LtG)".into()), ("s2".into(), "obj(cancel, LtG)".into())]);
+        assert!(convs.is_empty());
+    }
+}
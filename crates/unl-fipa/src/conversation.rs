@@ -0,0 +1,824 @@
+//! The per-agent conversation runtime (`docs/INTERACTION_PROTOCOLS.md` §6).
+//!
+//! [`Conversations`] routes each inbound `_acl` message to the protocol FSM that
+//! owns its `cid`, emits what the FSM sends, and surfaces finished conversations
+//! to the agent. Every FSM's reply-by deadline goes on one min-heap, and the
+//! whole table runs off **one** timer slot armed for the soonest of them — so N
+//! concurrent conversations cost the agent a single `budget.timers` slot.
+//!
+//! Conversations nest (§11). A child started with
+//! [`Conversations::start_child`] — or as a follow-up with [`Conversations::then`]
+//! — carries its parent's cid as `_acl.parent_cid`. A parent that fails, times
+//! out or is cancelled cancels its live children; a parent that finishes waits
+//! for them, and surfaces one outcome with theirs folded in
+//! ([`Outcome`] for the composed shape).
+//!
+//! FSMs are generic over `A`, the part of the agent they consult (a seller's
+//! catalog, say): the agent keeps it beside the table and lends it per call.
+//!
+//! ```ignore
+//! fn on_message(&mut self, unl: &str, body: &[u8], ctx: &mut Ctx) {
+//!     let Some(done) = self.convs.on_message(unl, body, &mut self.shop, ctx) else {
+//!         return self.plain(unl, body, ctx); // no `_acl`: not a conversation
+//!     };
+//!     for outcome in done { /* … */ }
+//! }
+//! fn on_tick(&mut self, id: u64, now_ms: u64, ctx: &mut Ctx) {
+//!     if let Some(done) = self.convs.on_tick(id, now_ms, &mut self.shop, ctx) { /* … */ }
+//! }
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use serde_json::{json, Value};
+use unl_agent::Ctx;
+use unl_core::{NodeRef, Uci};
+
+use crate::acl::{unl_for, Acl};
+use crate::contract_net::{FIPA_CONTRACT_NET, FIPA_ITERATED_CONTRACT_NET};
+use crate::request::{FIPA_QUERY, FIPA_REQUEST};
+
+/// One protocol message as an FSM sees it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Msg {
+    /// The counterpart: the sender of an inbound message, the receiver of an
+    /// outbound one.
+    pub peer: String,
+    pub acl: Acl,
+    /// The concept the conversation is about — the `<subj>` of `obj(<verb>, <subj>)`.
+    pub subject: String,
+    /// The domain content (the body's non-`_` keys).
+    pub content: Value,
+}
+
+impl Msg {
+    pub fn new(peer: impl Into<String>, acl: Acl, subject: impl Into<String>, content: Value) -> Self {
+        Msg { peer: peer.into(), acl, subject: subject.into(), content }
+    }
+
+    /// The message answering this one with `perf`, back to its sender.
+    pub fn reply(&self, perf: &str, content: Value) -> Msg {
+        Msg::new(self.peer.clone(), self.acl.reply(perf), self.subject.clone(), content)
+    }
+}
+
+/// Where a conversation stands after an FSM step.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    Continue,
+    Done(Value),
+    Failed(String),
+}
+
+/// One side of one protocol. The runtime feeds it messages and timeouts, with
+/// the agent's `A` to consult; it answers through `out` and reports when it is
+/// finished.
+pub trait Fsm<A = ()>: Send {
+    /// Open the conversation `cid` (initiators): emit the first messages.
+    fn on_start(&mut self, _cid: &str, _now_ms: u64, _out: &mut Vec<Msg>) -> Step {
+        Step::Continue
+    }
+
+    /// Advance on an inbound message of this conversation.
+    fn on_message(&mut self, msg: &Msg, now_ms: u64, agent: &mut A, out: &mut Vec<Msg>) -> Step;
+
+    /// The deadline from [`Fsm::deadline`] has passed.
+    fn on_timeout(&mut self, now_ms: u64, agent: &mut A, out: &mut Vec<Msg>) -> Step;
+
+    /// The agent abandons the conversation ([`Conversations::cancel`]): tell the
+    /// peers still involved.
+    fn on_cancel(&mut self, _now_ms: u64, _agent: &mut A, _out: &mut Vec<Msg>) -> Step {
+        Step::Failed("cancelled".into())
+    }
+
+    /// The next wall-clock ms at which this FSM wants [`Fsm::on_timeout`], if any.
+    fn deadline(&self) -> Option<u64>;
+}
+
+/// A finished conversation, surfaced to the agent.
+///
+/// A conversation that had children surfaces once all of them finished, as
+/// `{"result": <its own>, "children": [{"cid", "pid", "result"}, …]}` — or as
+/// the first child's failure. Children never surface on their own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub cid: String,
+    pub pid: String,
+    pub result: Result<Value, String>,
+}
+
+/// Builds the responder FSM for a conversation a peer opens.
+type Responder<A> = Box<dyn Fn(&Msg) -> Box<dyn Fsm<A>> + Send>;
+
+/// Builds a conversation's follow-up from its result ([`Conversations::then`]).
+type Then<A> = (String, Box<dyn FnOnce(&Value) -> Option<Box<dyn Fsm<A>>> + Send>);
+
+/// A live conversation's key: `("", cid)` for one this agent initiated (its
+/// replies may come from many peers), `(peer, cid)` for one a peer opened (two
+/// initiators may mint the same cid).
+type Key = (String, String);
+
+struct Live<A> {
+    pid: String,
+    fsm: Box<dyn Fsm<A>>,
+    /// The deadline last pushed on the heap; heap entries that disagree are stale.
+    deadline: Option<u64>,
+    /// The conversation this one was started from.
+    parent: Option<Key>,
+    /// For one initiated here: every peer its FSM has sent to, the only senders
+    /// whose replies it takes.
+    peers: HashSet<String>,
+}
+
+/// A parent's children: those still live, those finished, and — once the
+/// parent's own FSM is done — its result, held until the last child finishes.
+#[derive(Default)]
+struct Family {
+    open: Vec<Key>,
+    finished: Vec<Outcome>,
+    held: Option<Held>,
+}
+
+struct Held {
+    pid: String,
+    result: Value,
+    parent: Option<Key>,
+}
+
+/// The conversation table of one agent: FSMs keyed by conversation id, a
+/// deadline heap, and the one timer slot they share.
+pub struct Conversations<A = ()> {
+    table: HashMap<Key, Live<A>>,
+    heap: BinaryHeap<Reverse<(u64, Key)>>,
+    slot: u64,
+    /// The deadline the slot is currently armed for.
+    armed: Option<u64>,
+    responders: HashMap<String, Responder<A>>,
+    families: HashMap<Key, Family>,
+    then: HashMap<Key, Then<A>>,
+    minted: u64,
+}
+
+/// Performatives that open a conversation; one for a protocol nobody here
+/// answers is met with `not-understood`.
+const OPENING: [&str; 5] = ["request", "query-if", "query-ref", "cfp", "subscribe"];
+
+/// Live conversations a table holds at most; past it, peers opening another are
+/// refused until some finish.
+const MAX_CONVERSATIONS: usize = 256;
+
+/// Whether `perf` opens a conversation of protocol `pid`: the protocol's own
+/// opening performative for the standard ones, any of [`OPENING`] otherwise.
+fn opens(pid: &str, perf: &str) -> bool {
+    match pid {
+        FIPA_REQUEST => perf == "request",
+        FIPA_QUERY => perf == "query-if" || perf == "query-ref",
+        FIPA_CONTRACT_NET | FIPA_ITERATED_CONTRACT_NET => perf == "cfp",
+        _ => OPENING.contains(&perf),
+    }
+}
+
+impl<A> Conversations<A> {
+    /// An empty table whose protocol clock is timer `slot` — an id the agent
+    /// does not use for its own timers.
+    pub fn new(slot: u64) -> Self {
+        Conversations {
+            table: HashMap::new(),
+            heap: BinaryHeap::new(),
+            slot,
+            armed: None,
+            responders: HashMap::new(),
+            families: HashMap::new(),
+            then: HashMap::new(),
+            minted: 0,
+        }
+    }
+
+    /// Answer conversations of protocol `pid` that peers open: `make` builds a
+    /// responder FSM from the opening message.
+    pub fn respond(&mut self, pid: impl Into<String>, make: impl Fn(&Msg) -> Box<dyn Fsm<A>> + Send + 'static) {
+        self.responders.insert(pid.into(), Box::new(make));
+    }
+
+    /// Start a conversation of protocol `pid` driven by the initiator `fsm`. Returns
+    /// the minted cid, and the outcome if the FSM finished on the spot.
+    pub fn start(&mut self, pid: &str, fsm: Box<dyn Fsm<A>>, ctx: &mut Ctx) -> (String, Option<Outcome>) {
+        let now = ctx.now_ms();
+        let started = self.spawn(None, pid, fsm, ctx);
+        self.rearm(now, ctx);
+        started
+    }
+
+    /// Start a conversation of protocol `pid` as a child of the live conversation
+    /// `parent_cid`. `None` if there is no such conversation. Otherwise the
+    /// minted cid, and the parent's composed outcome if this child finished on
+    /// the spot and the parent was only waiting for it.
+    pub fn start_child(
+        &mut self,
+        parent_cid: &str,
+        pid: &str,
+        fsm: Box<dyn Fsm<A>>,
+        ctx: &mut Ctx,
+    ) -> Option<(String, Option<Outcome>)> {
+        let parent = self.key_of(parent_cid)?;
+        let now = ctx.now_ms();
+        let started = self.spawn(Some(parent), pid, fsm, ctx);
+        self.rearm(now, ctx);
+        Some(started)
+    }
+
+    /// When the live conversation `cid` finishes successfully, start a child of
+    /// protocol `pid` built by `make` from its result (`None`: nothing follows).
+    /// The parent's outcome then waits for the child's — e.g. a contract-net
+    /// award continuing into the escrow purchase surfaces as one result. Returns
+    /// whether `cid` is live.
+    pub fn then(
+        &mut self,
+        cid: &str,
+        pid: impl Into<String>,
+        make: impl FnOnce(&Value) -> Option<Box<dyn Fsm<A>>> + Send + 'static,
+    ) -> bool {
+        let Some(key) = self.key_of(cid).filter(|k| self.table.contains_key(k)) else {
+            return false;
+        };
+        self.then.insert(key, (pid.into(), Box::new(make)));
+        true
+    }
+
+    /// Route an inbound message. `None` if its body carries no `_acl` header —
+    /// it is not part of any conversation, and the agent handles it itself.
+    /// Otherwise the conversations that finished as a result (usually none or one).
+    pub fn on_message(&mut self, unl: &str, body: &[u8], agent: &mut A, ctx: &mut Ctx) -> Option<Vec<Outcome>> {
+        let (acl, content) = Acl::split(body)?;
+        let msg = Msg::new(ctx.from(), acl, subject_of(unl), content);
+        let now = ctx.now_ms();
+        let mut done = Vec::new();
+        match self.route(&msg) {
+            Some(key) => {
+                let mut out = Vec::new();
+                let live = self.table.get_mut(&key).expect("routed to a live conversation");
+                let step = live.fsm.on_message(&msg, now, agent, &mut out);
+                done.extend(self.settle(key, step, out, Some(agent), ctx));
+                self.rearm(now, ctx);
+            }
+            // a protocol answered here, opened while the table is full
+            None if self.responders.contains_key(&msg.acl.pid) && opens(&msg.acl.pid, &msg.acl.perf) => {
+                let busy = msg.reply("refuse", json!({ "reason": "busy" }));
+                ctx.send(busy.peer, unl_for(&busy.acl.perf, &busy.subject), busy.acl.join(busy.content));
+            }
+            None if OPENING.contains(&msg.acl.perf.as_str()) => {
+                let nu = msg.reply("not-understood", json!({ "reason": "unsupported-protocol" }));
+                ctx.send(nu.peer, unl_for(&nu.acl.perf, &nu.subject), nu.acl.join(nu.content));
+            }
+            None => {}
+        }
+        Some(done)
+    }
+
+    /// Handle a fired timer. `None` if it is not the protocol clock (an agent
+    /// timer); otherwise the conversations that timed out to completion.
+    pub fn on_tick(&mut self, timer_id: u64, now_ms: u64, agent: &mut A, ctx: &mut Ctx) -> Option<Vec<Outcome>> {
+        if timer_id != self.slot {
+            return None;
+        }
+        self.armed = None; // it just fired
+        let mut done = Vec::new();
+        while let Some(Reverse((deadline, _))) = self.heap.peek() {
+            if *deadline > now_ms {
+                break;
+            }
+            let Some(Reverse((deadline, key))) = self.heap.pop() else { break };
+            let Some(live) = self.table.get_mut(&key).filter(|l| l.deadline == Some(deadline)) else {
+                continue; // stale: finished, or its deadline moved
+            };
+            live.deadline = None;
+            let mut out = Vec::new();
+            let step = live.fsm.on_timeout(now_ms, agent, &mut out);
+            done.extend(self.settle(key, step, out, Some(agent), ctx));
+        }
+        self.rearm(now_ms, ctx);
+        Some(done)
+    }
+
+    /// Abandon conversation `cid`, one this agent initiated: its FSM tells the
+    /// peers still involved, and its children are cancelled with it. `None` if
+    /// no such conversation is live.
+    pub fn cancel(&mut self, cid: &str, agent: &mut A, ctx: &mut Ctx) -> Option<Outcome> {
+        let key = (String::new(), cid.to_string());
+        let now = ctx.now_ms();
+        let outcome = match self.table.get_mut(&key) {
+            Some(live) => {
+                let mut out = Vec::new();
+                let step = match live.fsm.on_cancel(now, agent, &mut out) {
+                    Step::Continue => Step::Failed("cancelled".into()), // cancel always ends it
+                    finished => finished,
+                };
+                self.settle(key, step, out, Some(agent), ctx)
+            }
+            // done itself, but still waiting on its children
+            None => {
+                let held = self.families.get_mut(&key)?.held.take()?;
+                self.finish(key, held.pid, held.parent, Err("cancelled".into()), Some(agent), ctx)
+            }
+        };
+        self.rearm(now, ctx);
+        outcome
+    }
+
+    /// Whether conversation `cid` (initiated here, or opened by any peer) is
+    /// live, or finished and waiting on its children.
+    pub fn contains(&self, cid: &str) -> bool {
+        self.key_of(cid).is_some()
+    }
+
+    /// The cids of the live children of conversation `cid`.
+    pub fn children(&self, cid: &str) -> Vec<String> {
+        let family = self.key_of(cid).and_then(|key| self.families.get(&key));
+        family.map(|f| f.open.iter().map(|(_, c)| c.clone()).collect()).unwrap_or_default()
+    }
+
+    /// Number of live conversations.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// The live conversation `msg` belongs to — one initiated here only if `msg`
+    /// comes from a peer it sent to — opening a responder for it if it
+    /// is the opening performative of a protocol this agent answers and the table
+    /// has room. Messages for no live conversation (e.g. a late reply to one
+    /// already finished) are dropped.
+    fn route(&mut self, msg: &Msg) -> Option<Key> {
+        let theirs = (msg.peer.clone(), msg.acl.cid.clone());
+        if self.table.contains_key(&theirs) {
+            return Some(theirs);
+        }
+        let ours = (String::new(), msg.acl.cid.clone());
+        if self.table.get(&ours).is_some_and(|l| l.peers.contains(&msg.peer)) {
+            return Some(ours);
+        }
+        if !opens(&msg.acl.pid, &msg.acl.perf) || self.table.len() >= MAX_CONVERSATIONS {
+            return None;
+        }
+        let fsm = self.responders.get(&msg.acl.pid)?(msg);
+        // opened as a child of a conversation live here: the same peer's, or ours
+        let parent = msg.acl.parent_cid.as_ref().and_then(|p| {
+            [(msg.peer.clone(), p.clone()), (String::new(), p.clone())]
+                .into_iter()
+                .find(|k| self.table.contains_key(k))
+        });
+        if let Some(p) = &parent {
+            self.families.entry(p.clone()).or_default().open.push(theirs.clone());
+        }
+        let live = Live { pid: msg.acl.pid.clone(), fsm, deadline: None, parent, peers: HashSet::new() };
+        self.table.insert(theirs.clone(), live);
+        Some(theirs)
+    }
+
+    /// The key of conversation `cid`, live or held, preferring one initiated here.
+    fn key_of(&self, cid: &str) -> Option<Key> {
+        let held = self.families.iter().filter(|(_, f)| f.held.is_some()).map(|(k, _)| k);
+        self.table.keys().chain(held).filter(|(_, c)| c == cid).min_by_key(|(peer, _)| !peer.is_empty()).cloned()
+    }
+
+    /// Open a conversation this agent initiates, under `parent` if it is a child.
+    fn spawn(&mut self, parent: Option<Key>, pid: &str, mut fsm: Box<dyn Fsm<A>>, ctx: &mut Ctx) -> (String, Option<Outcome>) {
+        let cid = self.mint(ctx);
+        let mut out = Vec::new();
+        let step = fsm.on_start(&cid, ctx.now_ms(), &mut out);
+        let key = (String::new(), cid.clone());
+        if let Some(p) = &parent {
+            self.families.entry(p.clone()).or_default().open.push(key.clone());
+        }
+        self.table.insert(key.clone(), Live { pid: pid.into(), fsm, deadline: None, parent, peers: HashSet::new() });
+        // a fresh FSM has no children to cancel, so it needs no agent
+        let outcome = self.settle(key, step, out, None, ctx);
+        (cid, outcome)
+    }
+
+    /// Emit an FSM's sends and apply its step: a finished conversation leaves the
+    /// table; a continuing one has its new deadline queued. The peers sent to are
+    /// recorded for one initiated here.
+    fn settle(&mut self, key: Key, step: Step, out: Vec<Msg>, agent: Option<&mut A>, ctx: &mut Ctx) -> Option<Outcome> {
+        if key.0.is_empty()
+            && let Some(live) = self.table.get_mut(&key)
+        {
+            live.peers.extend(out.iter().map(|m| m.peer.clone()));
+        }
+        let parent = self.table.get(&key).and_then(|l| l.parent.clone());
+        emit(out, parent.as_ref(), ctx);
+        let result = match step {
+            Step::Continue => {
+                let live = self.table.get_mut(&key)?;
+                let deadline = live.fsm.deadline();
+                if deadline != live.deadline {
+                    live.deadline = deadline;
+                    if let Some(d) = deadline {
+                        self.heap.push(Reverse((d, key)));
+                    }
+                }
+                return None;
+            }
+            Step::Done(v) => Ok(v),
+            Step::Failed(why) => Err(why),
+        };
+        let live = self.table.remove(&key)?;
+        self.finish(key, live.pid, live.parent, result, agent, ctx)
+    }
+
+    /// A conversation's own FSM finished with `result`. A failure cancels its
+    /// live children; a success starts its follow-up, if any, and waits for its
+    /// children. What is left surfaces — or, for a child, goes to its parent.
+    fn finish(
+        &mut self,
+        key: Key,
+        pid: String,
+        parent: Option<Key>,
+        result: Result<Value, String>,
+        mut agent: Option<&mut A>,
+        ctx: &mut Ctx,
+    ) -> Option<Outcome> {
+        let follow = self.then.remove(&key);
+        let mut family = self.families.remove(&key).unwrap_or_default();
+        let result = match result {
+            Err(why) => {
+                for child in family.open.drain(..) {
+                    self.abandon(child, agent.as_deref_mut(), ctx);
+                }
+                Err(why)
+            }
+            Ok(value) => {
+                if let Some((then_pid, make)) = follow
+                    && let Some(fsm) = make(&value)
+                {
+                    self.families.insert(key.clone(), family);
+                    self.spawn(Some(key.clone()), &then_pid, fsm, ctx);
+                    family = self.families.remove(&key).unwrap_or_default();
+                }
+                if !family.open.is_empty() {
+                    family.held = Some(Held { pid, result: value, parent });
+                    self.families.insert(key, family);
+                    return None;
+                }
+                compose(value, family.finished)
+            }
+        };
+        let outcome = Outcome { cid: key.1.clone(), pid, result };
+        match parent {
+            Some(parent) => self.absorb(parent, &key, outcome, agent, ctx),
+            None => Some(outcome),
+        }
+    }
+
+    /// Child `child` finished: record its outcome with the parent, and finish a
+    /// parent that was only waiting for it.
+    fn absorb(&mut self, parent: Key, child: &Key, outcome: Outcome, agent: Option<&mut A>, ctx: &mut Ctx) -> Option<Outcome> {
+        let family = self.families.get_mut(&parent)?;
+        family.open.retain(|k| k != child);
+        family.finished.push(outcome);
+        if !family.open.is_empty() {
+            return None;
+        }
+        let held = family.held.take()?;
+        self.finish(parent, held.pid, held.parent, Ok(held.result), agent, ctx)
+    }
+
+    /// Cancel `key` because its parent ended: its FSM tells the peers still
+    /// involved, and its own children follow. Nothing surfaces.
+    fn abandon(&mut self, key: Key, mut agent: Option<&mut A>, ctx: &mut Ctx) {
+        self.then.remove(&key);
+        if let Some(mut live) = self.table.remove(&key) {
+            let mut out = Vec::new();
+            if let Some(agent) = agent.as_deref_mut() {
+                live.fsm.on_cancel(ctx.now_ms(), agent, &mut out);
+            }
+            emit(out, live.parent.as_ref(), ctx);
+        }
+        for child in self.families.remove(&key).map(|f| f.open).unwrap_or_default() {
+            self.abandon(child, agent.as_deref_mut(), ctx);
+        }
+    }
+
+    /// Point the slot at the soonest live deadline (or cancel it if none),
+    /// touching the timer only when that changed.
+    fn rearm(&mut self, now_ms: u64, ctx: &mut Ctx) {
+        while let Some(Reverse((d, key))) = self.heap.peek() {
+            if self.table.get(key).is_some_and(|l| l.deadline == Some(*d)) {
+                break;
+            }
+            self.heap.pop();
+        }
+        let next = self.heap.peek().map(|Reverse((d, _))| *d);
+        if next == self.armed {
+            return;
+        }
+        match next {
+            Some(d) => ctx.set_timer(self.slot, d.saturating_sub(now_ms)),
+            None => ctx.cancel_timer(self.slot),
+        }
+        self.armed = next;
+    }
+
+    /// A fresh conversation id: a UUIDv4 from the keyring's randomness, or —
+    /// without the `crypto` grant — the clock and a counter, unique per initiator.
+    fn mint(&mut self, ctx: &Ctx) -> String {
+        self.minted += 1;
+        match ctx.random(16).filter(|b| b.len() == 16) {
+            Some(mut b) => {
+                b[6] = (b[6] & 0x0f) | 0x40;
+                b[8] = (b[8] & 0x3f) | 0x80;
+                let hex: String = b.iter().map(|x| format!("{x:02x}")).collect();
+                format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+            }
+            None => format!("{:x}-{:x}", ctx.now_ms(), self.minted),
+        }
+    }
+}
+
+/// Send an FSM's messages, a child's stamped with its parent's cid.
+fn emit(out: Vec<Msg>, parent: Option<&Key>, ctx: &mut Ctx) {
+    for mut m in out {
+        if m.acl.parent_cid.is_none() {
+            m.acl.parent_cid = parent.map(|(_, cid)| cid.clone());
+        }
+        ctx.send(m.peer, unl_for(&m.acl.perf, &m.subject), m.acl.join(m.content));
+    }
+}
+
+/// A finished parent's result with its children's folded in: unchanged if it
+/// had none, the first child's failure if one failed.
+fn compose(result: Value, children: Vec<Outcome>) -> Result<Value, String> {
+    if children.is_empty() {
+        return Ok(result);
+    }
+    let mut folded = Vec::with_capacity(children.len());
+    for child in children {
+        match child.result {
+            Ok(v) => folded.push(json!({ "cid": child.cid, "pid": child.pid, "result": v })),
+            Err(why) => return Err(format!("{} {}: {why}", child.pid, child.cid)),
+        }
+    }
+    Ok(json!({ "result": result, "children": folded }))
+}
+
+/// The subject word of `obj(<verb>, <subj>)`; `""` if the sentence has none.
+fn subject_of(unl: &str) -> String {
+    let Ok(g) = unl_parser::parse_sentence(unl) else { return String::new() };
+    match g.relations.first().map(|r| &r.target) {
+        Some(NodeRef::Inline(uw)) => match &uw.uci {
+            Uci::Ucn { root, .. } => root.to_string(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unl_agent::{Outgoing, TimerOp};
+
+    const SLOT: u64 = 99;
+
+    /// Initiator: one request with a reply-by, done on `inform`.
+    struct Ask {
+        to: &'static str,
+        rb_ms: u64,
+        deadline: Option<u64>,
+    }
+
+    impl Fsm for Ask {
+        fn on_start(&mut self, cid: &str, now_ms: u64, out: &mut Vec<Msg>) -> Step {
+            let mut acl = Acl::new(cid, "fipa-request", "request");
+            acl.rb_ms = Some(self.rb_ms);
+            out.push(Msg::new(self.to, acl, "LtG", json!({ "action": "price" })));
+            self.deadline = Some(now_ms + self.rb_ms);
+            Step::Continue
+        }
+        fn on_message(&mut self, msg: &Msg, _now_ms: u64, _: &mut (), _out: &mut Vec<Msg>) -> Step {
+            match msg.acl.perf.as_str() {
+                "inform" => Step::Done(msg.content["result"].clone()),
+                _ => Step::Continue,
+            }
+        }
+        fn on_timeout(&mut self, _now_ms: u64, _: &mut (), _out: &mut Vec<Msg>) -> Step {
+            Step::Failed("timeout".into())
+        }
+        fn deadline(&self) -> Option<u64> {
+            self.deadline
+        }
+    }
+
+    /// Responder: answers a request with its result at once.
+    struct Answer;
+
+    impl Fsm for Answer {
+        fn on_message(&mut self, msg: &Msg, _now_ms: u64, _: &mut (), out: &mut Vec<Msg>) -> Step {
+            out.push(msg.reply("inform", json!({ "result": 999 })));
+            Step::Done(Value::Null)
+        }
+        fn on_timeout(&mut self, _now_ms: u64, _: &mut (), _out: &mut Vec<Msg>) -> Step {
+            Step::Continue
+        }
+        fn deadline(&self) -> Option<u64> {
+            None
+        }
+    }
+
+    fn ask(to: &'static str, rb_ms: u64) -> Box<dyn Fsm> {
+        Box::new(Ask { to, rb_ms, deadline: None })
+    }
+
+    fn deliver(convs: &mut Conversations, from: &str, out: &Outgoing, now_ms: u64) -> (Vec<Outcome>, Ctx) {
+        let mut ctx = Ctx::new();
+        ctx.set_from(from);
+        ctx.set_now(now_ms);
+        let done = convs.on_message(&out.unl, &out.body, &mut (), &mut ctx).unwrap();
+        (done, ctx)
+    }
+
+    #[test]
+    fn many_conversations_share_one_timer_slot() {
+        let mut convs = Conversations::new(SLOT);
+        let mut ctx = Ctx::new();
+        let (a, _) = convs.start("fipa-request", ask("s1", 1_000), &mut ctx);
+        let (b, _) = convs.start("fipa-request", ask("s2", 3_000), &mut ctx);
+        assert_ne!(a, b);
+        assert_eq!(convs.len(), 2);
+        // one slot, armed once, for the sooner deadline
+        assert_eq!(ctx.take_timers(), [TimerOp::Set { id: SLOT, delay_ms: 1_000 }]);
+        let sent = ctx.take();
+        assert_eq!((sent[0].to.as_str(), sent[0].unl.as_str()), ("s1", "obj(request, LtG)"));
+
+        // the first times out; the slot moves on to the second
+        let done = convs.on_tick(SLOT, 1_000, &mut (), &mut ctx).unwrap();
+        assert_eq!(done, [Outcome { cid: a, pid: "fipa-request".into(), result: Err("timeout".into()) }]);
+        assert_eq!(ctx.take_timers(), [TimerOp::Set { id: SLOT, delay_ms: 2_000 }]);
+
+        // the second is answered in time; nothing is left to wait for
+        let mut s2 = Conversations::new(SLOT);
+        s2.respond("fipa-request", |_| Box::new(Answer));
+        let (_, mut s2ctx) = deliver(&mut s2, "ba", &sent[1], 1_500);
+        let reply = &s2ctx.take()[0];
+        assert_eq!((reply.to.as_str(), reply.unl.as_str()), ("ba", "obj(inform, LtG)"));
+        let (done, mut back) = deliver(&mut convs, "s2", reply, 1_500);
+        assert_eq!(done, [Outcome { cid: b, pid: "fipa-request".into(), result: Ok(json!(999)) }]);
+        assert_eq!(back.take_timers(), [TimerOp::Cancel { id: SLOT }]);
+        assert!(convs.is_empty());
+    }
+
+    /// `to`'s `inform` answering `req` with `result`.
+    fn inform(req: &Outgoing, result: Value) -> Outgoing {
+        let (acl, _) = Acl::split(&req.body).unwrap();
+        Outgoing { to: req.to.clone(), unl: "obj(inform, LtG)".into(), body: acl.reply("inform").join(json!({ "result": result })) }
+    }
+
+    #[test]
+    fn a_follow_up_composes_into_one_outcome() {
+        let mut convs = Conversations::new(SLOT);
+        let mut ctx = Ctx::new();
+        let (award, _) = convs.start("fipa-request", ask("s1", 1_000), &mut ctx);
+        assert!(convs.then(&award, "fipa-request", |won| {
+            assert_eq!(won, &json!(999));
+            Some(ask("pa", 5_000))
+        }));
+        let cfp = ctx.take().remove(0);
+
+        // the award finishes, the purchase starts under it, nothing surfaces yet
+        let (done, mut ctx) = deliver(&mut convs, "s1", &inform(&cfp, json!(999)), 500);
+        assert!(done.is_empty());
+        let escrow = ctx.take().remove(0);
+        assert_eq!(escrow.to, "pa");
+        let (acl, _) = Acl::split(&escrow.body).unwrap();
+        assert_eq!(acl.parent_cid.as_deref(), Some(award.as_str()));
+        assert!(convs.contains(&award));
+        assert_eq!(convs.children(&award), std::slice::from_ref(&acl.cid));
+
+        let (done, _) = deliver(&mut convs, "pa", &inform(&escrow, json!("held")), 600);
+        let children = json!([{ "cid": acl.cid, "pid": "fipa-request", "result": "held" }]);
+        let composed = json!({ "result": 999, "children": children });
+        assert_eq!(done, [Outcome { cid: award, pid: "fipa-request".into(), result: Ok(composed) }]);
+        assert!(convs.is_empty());
+    }
+
+    #[test]
+    fn a_parent_timing_out_cancels_its_children() {
+        let mut convs = Conversations::new(SLOT);
+        let mut ctx = Ctx::new();
+        let (parent, _) = convs.start("fipa-request", ask("s1", 1_000), &mut ctx);
+        let (child, _) = convs.start_child(&parent, "fipa-request", ask("pa", 5_000), &mut ctx).unwrap();
+        let (grandchild, _) = convs.start_child(&child, "fipa-request", ask("bank", 9_000), &mut ctx).unwrap();
+        assert!(convs.start_child("c-none", "fipa-request", ask("pa", 1), &mut ctx).is_none());
+        assert_eq!(convs.len(), 3);
+        ctx.take_timers();
+
+        // one outcome — the parent's; the whole family is gone with it
+        let done = convs.on_tick(SLOT, 1_000, &mut (), &mut ctx).unwrap();
+        assert_eq!(done, [Outcome { cid: parent, pid: "fipa-request".into(), result: Err("timeout".into()) }]);
+        assert!(convs.is_empty() && !convs.contains(&child) && !convs.contains(&grandchild));
+        assert!(ctx.take_timers().is_empty()); // the slot just fired; nothing to re-arm
+    }
+
+    #[test]
+    fn a_failed_child_fails_the_composed_outcome() {
+        let mut convs = Conversations::new(SLOT);
+        let mut ctx = Ctx::new();
+        let (parent, _) = convs.start("fipa-request", ask("s1", 1_000), &mut ctx);
+        let (child, _) = convs.start_child(&parent, "fipa-request", ask("pa", 500), &mut ctx).unwrap();
+        let sent = ctx.take();
+
+        // the child times out first; the parent carries on, then finishes
+        assert_eq!(convs.on_tick(SLOT, 500, &mut (), &mut ctx), Some(vec![]));
+        let (done, _) = deliver(&mut convs, "s1", &inform(&sent[0], json!(999)), 600);
+        let why = format!("fipa-request {child}: timeout");
+        assert_eq!(done, [Outcome { cid: parent, pid: "fipa-request".into(), result: Err(why) }]);
+    }
+
+    #[test]
+    fn other_traffic_passes_through() {
+        let mut convs = Conversations::new(SLOT);
+        convs.respond("fipa-request", |_| Box::new(Answer));
+        let mut ctx = Ctx::new();
+        assert!(convs.on_message("obj(provide, bookselling)", b"[\"s1\"]", &mut (), &mut ctx).is_none());
+        assert!(convs.on_tick(7, 0, &mut (), &mut ctx).is_none()); // the agent's own timer
+        // an `_acl` reply for no live conversation is dropped…
+        let stray = Acl::new("c9", "fipa-query", "inform").join(json!({}));
+        assert_eq!(convs.on_message("obj(inform, x)", &stray, &mut (), &mut ctx), Some(vec![]));
+        assert!(ctx.take().is_empty() && convs.is_empty());
+        // …but one opening a protocol nobody here answers is not understood
+        ctx.set_from("s1");
+        let cfp = Acl::new("c9", "fipa-contract-net", "cfp").join(json!({}));
+        convs.on_message("obj(cfp, LtG)", &cfp, &mut (), &mut ctx);
+        let nu = ctx.take();
+        assert_eq!((nu[0].to.as_str(), nu[0].unl.as_str()), ("s1", "obj(nu, LtG)"));
+    }
+
+    #[test]
+    fn a_reply_from_a_peer_never_asked_is_dropped() {
+        let mut convs = Conversations::new(SLOT);
+        let mut ctx = Ctx::new();
+        let (cid, _) = convs.start("fipa-request", ask("s1", 1_000), &mut ctx);
+        let req = ctx.take().remove(0);
+
+        let (done, _) = deliver(&mut convs, "mallory", &inform(&req, json!(1)), 500);
+        assert!(done.is_empty());
+        assert!(convs.contains(&cid)); // still waiting for s1
+        let (done, _) = deliver(&mut convs, "s1", &inform(&req, json!(999)), 600);
+        assert_eq!(done, [Outcome { cid, pid: "fipa-request".into(), result: Ok(json!(999)) }]);
+    }
+
+    #[test]
+    fn only_a_protocols_opening_performative_opens_a_responder() {
+        let mut convs = Conversations::new(SLOT);
+        convs.respond("fipa-request", |_| Box::new(Answer));
+        let mut ctx = Ctx::new();
+        ctx.set_from("s1");
+        for perf in ["inform", "agree", "cfp"] {
+            let body = Acl::new("c1", "fipa-request", perf).join(json!({}));
+            convs.on_message(&format!("obj({perf}, LtG)"), &body, &mut (), &mut ctx);
+        }
+        assert!(convs.is_empty());
+        let sent = ctx.take(); // the stray `cfp` is not understood; the rest are dropped
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].unl, "obj(nu, LtG)");
+
+        let body = Acl::new("c1", "fipa-request", "request").join(json!({}));
+        convs.on_message("obj(request, LtG)", &body, &mut (), &mut ctx);
+        assert_eq!(ctx.take()[0].unl, "obj(inform, LtG)"); // answered
+    }
+
+    /// Responder: never answers, so its conversation stays open.
+    struct Mute;
+
+    impl Fsm for Mute {
+        fn on_message(&mut self, _msg: &Msg, _now_ms: u64, _: &mut (), _out: &mut Vec<Msg>) -> Step {
+            Step::Continue
+        }
+        fn on_timeout(&mut self, _now_ms: u64, _: &mut (), _out: &mut Vec<Msg>) -> Step {
+            Step::Continue
+        }
+        fn deadline(&self) -> Option<u64> {
+            None
+        }
+    }
+
+    #[test]
+    fn a_full_table_refuses_new_conversations() {
+        let mut convs = Conversations::new(SLOT);
+        convs.respond("fipa-request", |_| Box::new(Mute));
+        let mut ctx = Ctx::new();
+        ctx.set_from("s1");
+        for i in 0..=MAX_CONVERSATIONS {
+            let body = Acl::new(format!("c{i}"), "fipa-request", "request").join(json!({}));
+            convs.on_message("obj(request, LtG)", &body, &mut (), &mut ctx);
+        }
+        assert_eq!(convs.len(), MAX_CONVERSATIONS);
+        let refused = ctx.take();
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].unl, "obj(refuse, LtG)");
+    }
+}
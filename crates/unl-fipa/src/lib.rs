@@ -15,21 +15,44 @@
 //! [`AclMessage::verify_content`] replies [`AclMessage::not_understood`] —
 //! cleanly, by construction. That is the property neither raw-NL nor raw-JSON
 //! A2A can offer.
+//!
+//! Between agents on the node, the same performatives travel as the `_acl`
+//! header of a JSON body ([`Acl`]); [`Conversations`] runs each agent's protocol
+//! FSMs over it (`docs/INTERACTION_PROTOCOLS.md`). The standard protocols come
+//! ready-made: [`Requester`]/[`Responder`] for request and query,
+//! [`ContractNet`]/[`Participant`] for (iterated) contract-net.
 
+mod acl;
+mod contract_net;
+mod conversation;
 mod performative;
+mod request;
+#[cfg(feature = "envelope")]
 mod sexpr;
 
+pub use acl::{unl_for, unl_verb, Acl, ACL_KEY};
+pub use contract_net::{
+    lowest_price, ContractNet, Contractor, Decision, Participant, Proposal, DEFAULT_HOLD_MS,
+    DEFAULT_MAX_ROUNDS, FIPA_CONTRACT_NET, FIPA_ITERATED_CONTRACT_NET,
+};
+pub use conversation::{Conversations, Fsm, Msg, Outcome, Step};
 pub use performative::{Performative, UnknownPerformative};
+pub use request::{Handler, Refusal, Requester, Responder, FIPA_QUERY, FIPA_REQUEST};
 // Addressing types are shared with the A2A layer.
+#[cfg(feature = "envelope")]
 pub use unl_a2a::{AgentId, ConversationId};
 
-use serde::{Deserialize, Serialize};
-use smol_str::SmolStr;
-use unl_core::UnlGraph;
-use unl_kb::KnowledgeBase;
-use unl_validator::{Diagnostic, Severity, Validate};
+#[cfg(feature = "envelope")]
+use {
+    serde::{Deserialize, Serialize},
+    smol_str::SmolStr,
+    unl_core::UnlGraph,
+    unl_kb::KnowledgeBase,
+    unl_validator::{Diagnostic, Severity, Validate},
+};
 
 /// A FIPA ACL message whose content language is UNL.
+#[cfg(feature = "envelope")]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AclMessage {
     pub performative: Performative,
@@ -43,6 +66,7 @@ pub struct AclMessage {
     pub content: UnlGraph,
 }
 
+#[cfg(feature = "envelope")]
 impl AclMessage {
     pub const CONTENT_LANGUAGE: &'static str = "UNL";
 
@@ -154,12 +178,14 @@ impl AclMessage {
     }
 }
 
+#[cfg(feature = "envelope")]
 fn write_agent(agent: &AgentId, out: &mut String) {
     out.push_str("(agent-identifier :name ");
     out.push_str(&agent.0);
     out.push(')');
 }
 
+#[cfg(feature = "envelope")]
 fn escape_into(s: &str, out: &mut String) {
     for ch in s.chars() {
         if ch == '\\' || ch == '"' {
@@ -169,6 +195,7 @@ fn escape_into(s: &str, out: &mut String) {
     }
 }
 
+#[cfg(feature = "envelope")]
 #[derive(Debug, thiserror::Error)]
 pub enum FipaError {
     #[error("FIPA syntax error: {0}")]
@@ -181,7 +208,7 @@ pub enum FipaError {
     Content(#[from] unl_parser::ParseError),
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "envelope"))]
 mod tests {
     use super::*;
     use unl_core::{Relation, RelationTag, Uci, Uw};
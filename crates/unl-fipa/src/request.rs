@@ -0,0 +1,274 @@
+//! `fipa-request` and `fipa-query` (`docs/INTERACTION_PROTOCOLS.md` §7, §8).
+//!
+//! Both are one question and one answer, so one initiator and one responder
+//! serve both: [`Requester`] asks (`request`, `query-if` or `query-ref`) and
+//! finishes on `inform`; [`Responder`] hands the question to the agent's
+//! [`Handler`] and answers with its verdict.
+
+use serde_json::{json, Value};
+
+use crate::acl::Acl;
+use crate::conversation::{Fsm, Msg, Step};
+
+pub const FIPA_REQUEST: &str = "fipa-request";
+pub const FIPA_QUERY: &str = "fipa-query";
+
+/// Why a responder did not produce a result; each goes back as its own
+/// performative with a `reason`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Refusal {
+    /// Understood, but will not do it (`refuse`).
+    Refuse(String),
+    /// Tried, and it did not work (`failure`).
+    Failure(String),
+    /// Could not make sense of the content (`not-understood`).
+    NotUnderstood(String),
+}
+
+impl Refusal {
+    pub fn perf(&self) -> &'static str {
+        match self {
+            Refusal::Refuse(_) => "refuse",
+            Refusal::Failure(_) => "failure",
+            Refusal::NotUnderstood(_) => "not-understood",
+        }
+    }
+
+    pub fn reason(&self) -> &str {
+        match self {
+            Refusal::Refuse(r) | Refusal::Failure(r) | Refusal::NotUnderstood(r) => r,
+        }
+    }
+
+    /// The refusal a peer sent, if `msg` is one.
+    pub fn of(msg: &Msg) -> Option<Refusal> {
+        let reason = reason_of(msg);
+        match msg.acl.perf.as_str() {
+            "refuse" => Some(Refusal::Refuse(reason)),
+            "failure" => Some(Refusal::Failure(reason)),
+            "not-understood" => Some(Refusal::NotUnderstood(reason)),
+            _ => None,
+        }
+    }
+
+    /// The reply carrying this refusal back to the sender of `msg`.
+    pub fn reply_to(&self, msg: &Msg) -> Msg {
+        msg.reply(self.perf(), json!({ "reason": self.reason() }))
+    }
+}
+
+/// The `reason` of a refusal-like message, defaulting to its performative.
+pub(crate) fn reason_of(msg: &Msg) -> String {
+    msg.content.get("reason").and_then(Value::as_str).unwrap_or(&msg.acl.perf).to_string()
+}
+
+/// Initiator of a request or query. Finishes with the `result` of the peer's
+/// `inform` (`null` if it carried none), or fails with the reason of a
+/// `refuse`/`failure`/`not-understood`, `"timeout"`, or `"cancelled"`.
+///
+/// `rb_ms` bounds the wait for the first answer; an `agree` restarts it for the
+/// `inform`. On timeout or cancel the peer is sent `cancel` so it can unwind.
+pub struct Requester {
+    to: String,
+    subject: String,
+    perf: &'static str,
+    content: Value,
+    rb_ms: u64,
+    sent: Option<Acl>,
+    deadline: Option<u64>,
+}
+
+impl Requester {
+    /// `fipa-request`: ask `to` to perform `action` about `subject`.
+    pub fn request(to: impl Into<String>, subject: impl Into<String>, action: Value, rb_ms: u64) -> Self {
+        Requester::new(to, subject, "request", json!({ "action": action }), rb_ms)
+    }
+
+    /// `fipa-query` (`query-ref`): ask `to` for the value of `expr`.
+    pub fn query(to: impl Into<String>, subject: impl Into<String>, expr: Value, rb_ms: u64) -> Self {
+        Requester::new(to, subject, "query-ref", json!({ "expr": expr }), rb_ms)
+    }
+
+    /// `fipa-query` (`query-if`): ask `to` whether `prop` holds.
+    pub fn query_if(to: impl Into<String>, subject: impl Into<String>, prop: Value, rb_ms: u64) -> Self {
+        Requester::new(to, subject, "query-if", json!({ "prop": prop }), rb_ms)
+    }
+
+    fn new(to: impl Into<String>, subject: impl Into<String>, perf: &'static str, content: Value, rb_ms: u64) -> Self {
+        Requester { to: to.into(), subject: subject.into(), perf, content, rb_ms, sent: None, deadline: None }
+    }
+
+    /// The protocol this initiator speaks, for [`Conversations::start`](crate::Conversations::start).
+    pub fn pid(&self) -> &'static str {
+        if self.perf == "request" { FIPA_REQUEST } else { FIPA_QUERY }
+    }
+
+    fn cancel(&self, out: &mut Vec<Msg>) {
+        if let Some(acl) = &self.sent {
+            out.push(Msg::new(self.to.clone(), acl.reply("cancel"), self.subject.clone(), json!({})));
+        }
+    }
+}
+
+impl<A> Fsm<A> for Requester {
+    fn on_start(&mut self, cid: &str, now_ms: u64, out: &mut Vec<Msg>) -> Step {
+        let mut acl = Acl::new(cid, self.pid(), self.perf);
+        acl.rb_ms = Some(self.rb_ms);
+        out.push(Msg::new(self.to.clone(), acl.clone(), self.subject.clone(), self.content.clone()));
+        self.sent = Some(acl);
+        self.deadline = Some(now_ms + self.rb_ms);
+        Step::Continue
+    }
+
+    fn on_message(&mut self, msg: &Msg, now_ms: u64, _agent: &mut A, _out: &mut Vec<Msg>) -> Step {
+        if msg.peer != self.to {
+            return Step::Continue; // not our counterpart
+        }
+        if let Some(refusal) = Refusal::of(msg) {
+            return Step::Failed(refusal.reason().to_string());
+        }
+        match msg.acl.perf.as_str() {
+            "agree" => {
+                self.deadline = Some(now_ms + self.rb_ms);
+                Step::Continue
+            }
+            "inform" => Step::Done(msg.content.get("result").cloned().unwrap_or(Value::Null)),
+            _ => Step::Continue,
+        }
+    }
+
+    fn on_timeout(&mut self, _now_ms: u64, _agent: &mut A, out: &mut Vec<Msg>) -> Step {
+        self.cancel(out);
+        Step::Failed("timeout".into())
+    }
+
+    fn on_cancel(&mut self, _now_ms: u64, _agent: &mut A, out: &mut Vec<Msg>) -> Step {
+        self.cancel(out);
+        Step::Failed("cancelled".into())
+    }
+
+    fn deadline(&self) -> Option<u64> {
+        self.deadline
+    }
+}
+
+/// What an agent answering requests and queries implements.
+pub trait Handler {
+    /// Decide `msg` — a `request` (content `action`), `query-ref` (`expr`) or
+    /// `query-if` (`prop`) — and produce the result to `inform` back.
+    fn handle(&mut self, msg: &Msg) -> Result<Value, Refusal>;
+}
+
+/// Responder side of a request or query: one decision by the agent's
+/// [`Handler`], answered at once. A request that succeeds is answered `agree`
+/// then `inform`; a query just `inform`. The outcome is the result (or reason)
+/// it answered with.
+#[derive(Default)]
+pub struct Responder;
+
+impl Responder {
+    /// A factory for [`Conversations::respond`](crate::Conversations::respond).
+    pub fn factory<A: Handler>() -> impl Fn(&Msg) -> Box<dyn Fsm<A>> + Send + 'static {
+        |_| Box::new(Responder)
+    }
+}
+
+impl<A: Handler> Fsm<A> for Responder {
+    fn on_message(&mut self, msg: &Msg, _now_ms: u64, agent: &mut A, out: &mut Vec<Msg>) -> Step {
+        match msg.acl.perf.as_str() {
+            "request" | "query-ref" | "query-if" => match agent.handle(msg) {
+                Ok(result) => {
+                    if msg.acl.perf == "request" {
+                        out.push(msg.reply("agree", json!({})));
+                    }
+                    out.push(msg.reply("inform", json!({ "result": result.clone() })));
+                    Step::Done(result)
+                }
+                Err(refusal) => {
+                    out.push(refusal.reply_to(msg));
+                    Step::Failed(refusal.reason().to_string())
+                }
+            },
+            other => {
+                out.push(msg.reply("not-understood", json!({ "reason": format!("unexpected {other}") })));
+                Step::Failed(format!("unexpected {other}"))
+            }
+        }
+    }
+
+    fn on_timeout(&mut self, _now_ms: u64, _agent: &mut A, _out: &mut Vec<Msg>) -> Step {
+        Step::Failed("timeout".into())
+    }
+
+    fn deadline(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::{Conversations, Outcome};
+    use unl_agent::{Ctx, Outgoing, TimerOp};
+
+    const SLOT: u64 = 99;
+
+    /// A price list that answers `query-ref`s and refuses unknown titles.
+    struct Prices;
+
+    impl Handler for Prices {
+        fn handle(&mut self, msg: &Msg) -> Result<Value, Refusal> {
+            match msg.content["expr"].as_str() {
+                Some("LtG") => Ok(json!(10_000)),
+                Some(_) => Err(Refusal::Refuse("book-not-found".into())),
+                None => Err(Refusal::NotUnderstood("no expr".into())),
+            }
+        }
+    }
+
+    fn deliver<A>(convs: &mut Conversations<A>, agent: &mut A, from: &str, out: &Outgoing, now_ms: u64) -> (Vec<Outcome>, Vec<Outgoing>) {
+        let mut ctx = Ctx::new();
+        ctx.set_from(from);
+        ctx.set_now(now_ms);
+        let done = convs.on_message(&out.unl, &out.body, agent, &mut ctx).unwrap();
+        (done, ctx.take())
+    }
+
+    #[test]
+    fn a_query_is_answered_or_refused() {
+        let mut seller: Conversations<Prices> = Conversations::new(SLOT);
+        seller.respond(FIPA_QUERY, Responder::factory());
+        for (title, want) in [("LtG", Ok(json!(10_000))), ("Dune", Err("book-not-found".to_string()))] {
+            let mut buyer = Conversations::new(SLOT);
+            let mut ctx = Ctx::new();
+            let (cid, _) = buyer.start(FIPA_QUERY, Box::new(Requester::query("bs", "price", json!(title), 500)), &mut ctx);
+            let ask = ctx.take();
+            assert_eq!(ask[0].unl, "obj(query, price)");
+            let (_, answer) = deliver(&mut seller, &mut Prices, "ba", &ask[0], 10);
+            let (done, _) = deliver(&mut buyer, &mut (), "bs", &answer[0], 20);
+            assert_eq!(done, [Outcome { cid, pid: FIPA_QUERY.into(), result: want }]);
+        }
+        assert!(seller.is_empty());
+    }
+
+    #[test]
+    fn an_unanswered_request_times_out_and_cancels() {
+        let mut convs = Conversations::new(SLOT);
+        let mut ctx = Ctx::new();
+        let req = Requester::request("pa", "escrow", json!({ "reserve": 10 }), 1_000);
+        let (cid, _) = convs.start(FIPA_REQUEST, Box::new(req), &mut ctx);
+        ctx.take();
+        // an `agree` pushes the deadline out for the `inform`
+        let agree = Acl::new(cid.clone(), FIPA_REQUEST, "agree").join(json!({}));
+        let agree = Outgoing { to: "ba".into(), unl: "obj(agree, escrow)".into(), body: agree };
+        let (done, _) = deliver(&mut convs, &mut (), "pa", &agree, 600);
+        assert!(done.is_empty());
+        let mut tick = Ctx::new();
+        assert_eq!(convs.on_tick(SLOT, 1_000, &mut (), &mut tick), Some(vec![])); // a stale wake-up
+        assert_eq!(tick.take_timers(), [TimerOp::Set { id: SLOT, delay_ms: 600 }]);
+        let done = convs.on_tick(SLOT, 1_600, &mut (), &mut tick).unwrap();
+        assert_eq!(done[0].result, Err("timeout".into()));
+        let cancel = tick.take();
+        assert_eq!((cancel[0].to.as_str(), cancel[0].unl.as_str()), ("pa", "obj(cancel, escrow)"));
+    }
+}